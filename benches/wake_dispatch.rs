@@ -0,0 +1,160 @@
+//! Measures how a slow waker affects the driver I/O threads with and without a
+//! wake dispatcher.
+//!
+//! The benchmark plays the part of a driver I/O thread completing a request
+//! every `--interval` microseconds. For every request it runs the future
+//! callback of the crate, which wakes a task whose waker takes `--wake-cost`
+//! microseconds, either inline as it does by default or through a
+//! [`ThreadDispatcher`]. It reports the time the I/O
+//! thread spends per completion, i.e. the delay added to every other connection
+//! served by the thread, and the latency from the completion to the end of the
+//! wake-up. Once the wake-ups take longer than the interval, the inline ones
+//! delay the completions and the dispatched ones queue up on the dispatcher
+//! thread.
+//!
+//! ```text
+//! cargo bench --features bench --bench wake_dispatch -- --requests 20000 --interval 50 --wake-cost 20
+//! ```
+
+use std::sync::{
+    mpsc,
+    Arc,
+};
+use std::task::Waker;
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use cassander::{
+    PendingFutureCallback,
+    ThreadDispatcher,
+    WakeDispatcher,
+};
+use futures::task::{
+    waker,
+    ArcWake,
+};
+
+/// A waker that busy-waits to imitate an expensive wake-up, e.g. an executor
+/// that polls the task inline, then reports when it is done.
+struct SlowWaker {
+    /// The time the wake-up takes.
+    cost: Duration,
+    /// Receives the instants the wake-ups complete at.
+    done: mpsc::Sender<Instant>,
+}
+
+impl ArcWake for SlowWaker {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        let start = Instant::now();
+        while start.elapsed() < arc_self.cost {
+            std::hint::spin_loop();
+        }
+        let _ = arc_self.done.send(Instant::now());
+    }
+}
+
+/// The latencies of a run, in microseconds.
+struct Latencies {
+    /// The time the I/O thread spent per completion.
+    io_thread:  Vec<f64>,
+    /// The time from the completion to the end of the wake-up.
+    end_to_end: Vec<f64>,
+}
+
+/// Completes the requests at the given interval, waking the task with the
+/// dispatcher or inline.
+fn run(
+    requests: usize,
+    interval: Duration,
+    cost: Duration,
+    dispatcher: Option<Arc<dyn WakeDispatcher>>,
+) -> Latencies {
+    let (sender, receiver) = mpsc::channel();
+    let task: Waker = waker(Arc::new(SlowWaker {
+        cost,
+        done: sender,
+    }));
+
+    let mut completed = Vec::with_capacity(requests);
+    let mut io_thread = Vec::with_capacity(requests);
+    let mut next = Instant::now();
+    for _ in 0..requests {
+        while Instant::now() < next {
+            std::hint::spin_loop();
+        }
+        next += interval;
+        let pending =
+            PendingFutureCallback::new(task.clone(), dispatcher.clone());
+
+        let start = Instant::now();
+        pending.complete();
+        io_thread.push(micros(start.elapsed()));
+        completed.push(start);
+    }
+
+    let end_to_end = completed
+        .into_iter()
+        .map(|start| micros(receiver.recv().unwrap() - start))
+        .collect();
+
+    Latencies {
+        io_thread,
+        end_to_end,
+    }
+}
+
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1e6
+}
+
+/// Returns the value at the given percentile of the sorted values.
+fn percentile(sorted: &[f64], percentile: f64) -> f64 {
+    let index = ((sorted.len() - 1) as f64 * percentile).round() as usize;
+
+    sorted[index]
+}
+
+fn report(name: &str, latencies: Latencies) {
+    for (metric, mut values) in [
+        ("I/O thread", latencies.io_thread),
+        ("end-to-end", latencies.end_to_end),
+    ] {
+        values.sort_by(f64::total_cmp);
+        println!(
+            "{:<8} {:<11} p50 {:>10.2} µs  p99 {:>10.2} µs  max {:>10.2} µs",
+            name,
+            metric,
+            percentile(&values, 0.5),
+            percentile(&values, 0.99),
+            values[values.len() - 1],
+        );
+    }
+}
+
+/// Returns the value of the `--name value` argument.
+fn argument(name: &str, default: u64) -> u64 {
+    let arguments: Vec<String> = std::env::args().collect();
+    arguments
+        .iter()
+        .position(|argument| argument == name)
+        .and_then(|index| arguments.get(index + 1))
+        .map(|value| value.parse().expect("the argument must be a number"))
+        .unwrap_or(default)
+}
+
+fn main() {
+    let requests = argument("--requests", 10_000) as usize;
+    let interval = Duration::from_micros(argument("--interval", 50));
+    let cost = Duration::from_micros(argument("--wake-cost", 20));
+    println!(
+        "{} requests, one per {:?}, {:?} per wake-up",
+        requests, interval, cost
+    );
+
+    report("inline", run(requests, interval, cost, None));
+
+    let dispatcher = Arc::new(ThreadDispatcher::new());
+    report("thread", run(requests, interval, cost, Some(dispatcher)));
+}
@@ -0,0 +1,663 @@
+mod plain;
+mod token;
+
+use std::any::Any;
+use std::ffi::{
+    c_char,
+    c_void,
+};
+use std::marker::PhantomData;
+use std::panic::{
+    catch_unwind,
+    AssertUnwindSafe,
+};
+use std::slice;
+
+pub use plain::*;
+use thiserror::Error;
+pub use token::*;
+use zeroize::Zeroize;
+
+#[cfg(test)]
+use self::tests::fake_driver::{
+    cass_authenticator_address,
+    cass_authenticator_class_name,
+    cass_authenticator_exchange_data,
+    cass_authenticator_hostname,
+    cass_authenticator_set_error_n,
+    cass_authenticator_set_exchange_data,
+    cass_authenticator_set_response,
+};
+use crate::cql::CqlInet;
+#[cfg(not(test))]
+use crate::ffi::{
+    cass_authenticator_address,
+    cass_authenticator_class_name,
+    cass_authenticator_exchange_data,
+    cass_authenticator_hostname,
+    cass_authenticator_set_error_n,
+    cass_authenticator_set_exchange_data,
+    cass_authenticator_set_response,
+};
+use crate::ffi::{
+    struct_CassAuthenticatorCallbacks_,
+    struct_CassAuthenticator_,
+    struct_CassInet_,
+};
+
+/// An error that occurs when an [`Authenticator`] rejects the authentication
+/// exchange.
+///
+/// The error message is sent to the driver which fails the connection attempt
+/// with [`DriverErrorKind::ServerBadCredentials`].
+///
+/// [`DriverErrorKind::ServerBadCredentials`]: crate::DriverErrorKind::ServerBadCredentials
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{0}")]
+pub struct AuthenticatorError(String);
+
+impl AuthenticatorError {
+    /// Creates a new authenticator error with the given message.
+    pub fn new<T>(message: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self(message.into())
+    }
+
+    /// Returns the error message.
+    pub fn message(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A custom authentication provider.
+///
+/// The driver runs a separate authentication exchange for every connection it
+/// opens. Each exchange starts with [`Authenticator::initial_response`], may
+/// continue with any number of [`Authenticator::evaluate_challenge`] calls,
+/// and ends with [`Authenticator::on_success`] if the server accepted the
+/// client. [`Authenticator::cleanup`] is always called at the end of the
+/// exchange.
+///
+/// The methods are called from the driver's I/O threads and must not block.
+/// The state of a single exchange can be kept with
+/// [`AuthenticatorExchange::set_state`].
+pub trait Authenticator: Send + Sync + 'static {
+    /// Returns the initial response token sent to the server.
+    fn initial_response(
+        &self,
+        exchange: &mut AuthenticatorExchange<'_>,
+    ) -> Result<Vec<u8>, AuthenticatorError>;
+
+    /// Evaluates the challenge token sent by the server and returns the
+    /// response token.
+    ///
+    /// The default implementation rejects any challenge.
+    fn evaluate_challenge(
+        &self,
+        exchange: &mut AuthenticatorExchange<'_>,
+        token: &[u8],
+    ) -> Result<Vec<u8>, AuthenticatorError> {
+        let _ = (exchange, token);
+
+        Err(AuthenticatorError::new(
+            "unexpected authentication challenge",
+        ))
+    }
+
+    /// Called when the server accepted the client.
+    ///
+    /// The `token` is the final token sent by the server, it may be empty.
+    fn on_success(
+        &self,
+        exchange: &mut AuthenticatorExchange<'_>,
+        token: &[u8],
+    ) {
+        let _ = (exchange, token);
+    }
+
+    /// Called at the end of the exchange, whether it succeeded or not.
+    fn cleanup(&self, exchange: &mut AuthenticatorExchange<'_>) {
+        let _ = exchange;
+    }
+}
+
+/// A single authentication exchange between the driver and a node.
+pub struct AuthenticatorExchange<'a> {
+    inner:     *mut struct_CassAuthenticator_,
+    _callback: PhantomData<&'a mut struct_CassAuthenticator_>,
+}
+
+impl AuthenticatorExchange<'_> {
+    /// Creates a new exchange from the driver object.
+    fn from_driver(inner: *mut struct_CassAuthenticator_) -> Self {
+        Self {
+            inner,
+            _callback: PhantomData,
+        }
+    }
+
+    /// Returns the raw pointer to the authenticator object.
+    fn inner(&self) -> *mut struct_CassAuthenticator_ {
+        self.inner
+    }
+
+    /// Returns the address of the node.
+    pub fn address(&self) -> CqlInet {
+        let mut address = struct_CassInet_ {
+            address:        [0; 16],
+            address_length: 0,
+        };
+        unsafe { cass_authenticator_address(self.inner(), &mut address) };
+
+        CqlInet::from_driver(address)
+    }
+
+    /// Returns the hostname of the node.
+    ///
+    /// The hostname is only available if hostname resolution is enabled,
+    /// otherwise the method returns an empty string.
+    pub fn hostname(&self) -> String {
+        let mut len = 0;
+        let ptr =
+            unsafe { cass_authenticator_hostname(self.inner(), &mut len) };
+
+        to_string_lossy(ptr, len)
+    }
+
+    /// Returns the class name of the server-side authenticator, e.g.
+    /// `org.apache.cassandra.auth.PasswordAuthenticator`.
+    pub fn class_name(&self) -> String {
+        let mut len = 0;
+        let ptr =
+            unsafe { cass_authenticator_class_name(self.inner(), &mut len) };
+
+        to_string_lossy(ptr, len)
+    }
+
+    /// Stores the state of the exchange replacing the previous one.
+    ///
+    /// The state is dropped at the end of the exchange, after
+    /// [`Authenticator::cleanup`] is called.
+    pub fn set_state<T>(&mut self, state: T)
+    where
+        T: Any + Send,
+    {
+        drop(self.take_state_box());
+
+        let state: Box<Box<dyn Any + Send>> = Box::new(Box::new(state));
+        let data = Box::into_raw(state) as *mut c_void;
+        unsafe { cass_authenticator_set_exchange_data(self.inner(), data) };
+    }
+
+    /// Returns the state of the exchange.
+    ///
+    /// Returns [`None`] if there is no state or the state is not of type `T`.
+    pub fn state_mut<T>(&mut self) -> Option<&mut T>
+    where
+        T: Any + Send,
+    {
+        let data = unsafe { cass_authenticator_exchange_data(self.inner()) };
+        if data.is_null() {
+            return None;
+        }
+
+        let state = unsafe { &mut *(data as *mut Box<dyn Any + Send>) };
+
+        state.downcast_mut()
+    }
+
+    /// Removes the state of the exchange and returns it.
+    fn take_state_box(&mut self) -> Option<Box<Box<dyn Any + Send>>> {
+        let data = unsafe { cass_authenticator_exchange_data(self.inner()) };
+        if data.is_null() {
+            return None;
+        }
+
+        unsafe {
+            cass_authenticator_set_exchange_data(
+                self.inner(),
+                std::ptr::null_mut(),
+            )
+        };
+
+        Some(unsafe { Box::from_raw(data as *mut Box<dyn Any + Send>) })
+    }
+
+    /// Sends the response token to the server.
+    fn set_response(&mut self, response: &[u8]) {
+        let ptr = response.as_ptr() as *const c_char;
+        unsafe {
+            cass_authenticator_set_response(self.inner(), ptr, response.len())
+        };
+    }
+
+    /// Fails the exchange with the given message.
+    fn set_error(&mut self, message: &str) {
+        let ptr = message.as_ptr() as *const c_char;
+        unsafe {
+            cass_authenticator_set_error_n(self.inner(), ptr, message.len())
+        };
+    }
+
+    /// Sends either the response or the error to the driver.
+    ///
+    /// The response is wiped from memory once the driver copied it, since it
+    /// usually contains credentials.
+    fn respond(&mut self, response: Result<Vec<u8>, AuthenticatorError>) {
+        match response {
+            Ok(mut response) => {
+                self.set_response(&response);
+                response.zeroize();
+            }
+            Err(error) => self.set_error(error.message()),
+        }
+    }
+}
+
+/// The target of the authenticator callbacks.
+pub(crate) struct AuthenticatorTarget(Box<dyn Authenticator>);
+
+impl AuthenticatorTarget {
+    /// Creates a new callback target for the given authenticator.
+    pub(crate) fn new<A>(authenticator: A) -> Self
+    where
+        A: Authenticator,
+    {
+        Self(Box::new(authenticator))
+    }
+}
+
+/// The authenticator callbacks passed to the driver.
+pub(crate) static AUTHENTICATOR_CALLBACKS: struct_CassAuthenticatorCallbacks_ =
+    struct_CassAuthenticatorCallbacks_ {
+        initial_callback:   Some(authenticator_initial_callback),
+        challenge_callback: Some(authenticator_challenge_callback),
+        success_callback:   Some(authenticator_success_callback),
+        cleanup_callback:   Some(authenticator_cleanup_callback),
+    };
+
+/// Calls the authenticator with the exchange built from the callback
+/// arguments.
+///
+/// A panic of the authenticator fails the exchange instead of unwinding into
+/// the driver.
+unsafe fn with_authenticator<F>(
+    auth: *mut struct_CassAuthenticator_,
+    data: *mut c_void,
+    f: F,
+) where
+    F: FnOnce(&dyn Authenticator, &mut AuthenticatorExchange<'_>),
+{
+    if auth.is_null() || data.is_null() {
+        return;
+    }
+
+    let target = unsafe { &*(data as *const AuthenticatorTarget) };
+    let mut exchange = AuthenticatorExchange::from_driver(auth);
+
+    let result =
+        catch_unwind(AssertUnwindSafe(|| f(target.0.as_ref(), &mut exchange)));
+    if result.is_err() {
+        exchange.set_error("the authenticator panicked");
+    }
+}
+
+unsafe extern "C" fn authenticator_initial_callback(
+    auth: *mut struct_CassAuthenticator_,
+    data: *mut c_void,
+) {
+    with_authenticator(auth, data, |authenticator, exchange| {
+        let response = authenticator.initial_response(exchange);
+        exchange.respond(response);
+    });
+}
+
+unsafe extern "C" fn authenticator_challenge_callback(
+    auth: *mut struct_CassAuthenticator_,
+    data: *mut c_void,
+    token: *const c_char,
+    token_size: usize,
+) {
+    let token = to_bytes(token, token_size);
+    with_authenticator(auth, data, |authenticator, exchange| {
+        let response = authenticator.evaluate_challenge(exchange, token);
+        exchange.respond(response);
+    });
+}
+
+unsafe extern "C" fn authenticator_success_callback(
+    auth: *mut struct_CassAuthenticator_,
+    data: *mut c_void,
+    token: *const c_char,
+    token_size: usize,
+) {
+    let token = to_bytes(token, token_size);
+    with_authenticator(auth, data, |authenticator, exchange| {
+        authenticator.on_success(exchange, token);
+    });
+}
+
+unsafe extern "C" fn authenticator_cleanup_callback(
+    auth: *mut struct_CassAuthenticator_,
+    data: *mut c_void,
+) {
+    with_authenticator(auth, data, |authenticator, exchange| {
+        authenticator.cleanup(exchange);
+    });
+
+    if !auth.is_null() {
+        drop(AuthenticatorExchange::from_driver(auth).take_state_box());
+    }
+}
+
+fn to_bytes<'a>(ptr: *const c_char, len: usize) -> &'a [u8] {
+    if ptr.is_null() {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(ptr as *const u8, len) }
+    }
+}
+
+fn to_string_lossy(ptr: *const c_char, len: usize) -> String {
+    String::from_utf8_lossy(to_bytes(ptr, len)).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    };
+    use std::sync::Arc;
+
+    use parking_lot::Mutex;
+
+    use super::*;
+
+    /// Stand-ins for the driver functions that operate on a
+    /// [`FakeExchange`](fake_driver::FakeExchange) instead of the driver's
+    /// authenticator object.
+    pub(super) mod fake_driver {
+        use super::*;
+
+        pub(crate) struct FakeExchange {
+            pub(crate) class_name: String,
+            pub(crate) data:       *mut c_void,
+            pub(crate) response:   Option<Vec<u8>>,
+            pub(crate) error:      Option<String>,
+        }
+
+        impl FakeExchange {
+            pub(crate) fn new(class_name: &str) -> Self {
+                Self {
+                    class_name: class_name.to_string(),
+                    data:       std::ptr::null_mut(),
+                    response:   None,
+                    error:      None,
+                }
+            }
+
+            pub(crate) fn as_driver(
+                &mut self,
+            ) -> *mut struct_CassAuthenticator_ {
+                self as *mut Self as *mut struct_CassAuthenticator_
+            }
+        }
+
+        fn fake<'a>(
+            auth: *const struct_CassAuthenticator_,
+        ) -> &'a mut FakeExchange {
+            unsafe { &mut *(auth as *mut FakeExchange) }
+        }
+
+        pub(crate) unsafe fn cass_authenticator_address(
+            _auth: *const struct_CassAuthenticator_,
+            address: *mut struct_CassInet_,
+        ) {
+            let address = unsafe { &mut *address };
+            address.address[..4].copy_from_slice(&[127, 0, 0, 1]);
+            address.address_length = 4;
+        }
+
+        pub(crate) unsafe fn cass_authenticator_hostname(
+            _auth: *const struct_CassAuthenticator_,
+            length: *mut usize,
+        ) -> *const c_char {
+            unsafe { *length = 0 };
+
+            std::ptr::null()
+        }
+
+        pub(crate) unsafe fn cass_authenticator_class_name(
+            auth: *const struct_CassAuthenticator_,
+            length: *mut usize,
+        ) -> *const c_char {
+            let class_name = &fake(auth).class_name;
+            unsafe { *length = class_name.len() };
+
+            class_name.as_ptr() as *const c_char
+        }
+
+        pub(crate) unsafe fn cass_authenticator_exchange_data(
+            auth: *mut struct_CassAuthenticator_,
+        ) -> *mut c_void {
+            fake(auth).data
+        }
+
+        pub(crate) unsafe fn cass_authenticator_set_exchange_data(
+            auth: *mut struct_CassAuthenticator_,
+            data: *mut c_void,
+        ) {
+            fake(auth).data = data;
+        }
+
+        pub(crate) unsafe fn cass_authenticator_set_response(
+            auth: *mut struct_CassAuthenticator_,
+            response: *const c_char,
+            response_size: usize,
+        ) {
+            fake(auth).response =
+                Some(to_bytes(response, response_size).to_vec());
+        }
+
+        pub(crate) unsafe fn cass_authenticator_set_error_n(
+            auth: *mut struct_CassAuthenticator_,
+            message: *const c_char,
+            message_length: usize,
+        ) {
+            fake(auth).error = Some(to_string_lossy(message, message_length));
+        }
+    }
+
+    use fake_driver::FakeExchange;
+
+    /// Counts its live instances.
+    struct Tracked(Arc<AtomicUsize>);
+
+    impl Tracked {
+        fn new(live: &Arc<AtomicUsize>) -> Self {
+            live.fetch_add(1, Ordering::SeqCst);
+
+            Self(live.clone())
+        }
+    }
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Answers every challenge with the challenge prefixed by the number of
+    /// the previous rounds, kept in the exchange state.
+    struct CountingAuthenticator {
+        live:    Arc<AtomicUsize>,
+        success: Arc<Mutex<Option<Vec<u8>>>>,
+    }
+
+    impl Authenticator for CountingAuthenticator {
+        fn initial_response(
+            &self,
+            exchange: &mut AuthenticatorExchange<'_>,
+        ) -> Result<Vec<u8>, AuthenticatorError> {
+            exchange.set_state((0u8, Tracked::new(&self.live)));
+
+            Ok(exchange.class_name().into_bytes())
+        }
+
+        fn evaluate_challenge(
+            &self,
+            exchange: &mut AuthenticatorExchange<'_>,
+            token: &[u8],
+        ) -> Result<Vec<u8>, AuthenticatorError> {
+            let (round, _) = exchange
+                .state_mut::<(u8, Tracked)>()
+                .ok_or_else(|| AuthenticatorError::new("no state"))?;
+            *round += 1;
+
+            Ok([&[*round], token].concat())
+        }
+
+        fn on_success(
+            &self,
+            _exchange: &mut AuthenticatorExchange<'_>,
+            token: &[u8],
+        ) {
+            *self.success.lock() = Some(token.to_vec());
+        }
+    }
+
+    struct FailingAuthenticator;
+
+    impl Authenticator for FailingAuthenticator {
+        fn initial_response(
+            &self,
+            exchange: &mut AuthenticatorExchange<'_>,
+        ) -> Result<Vec<u8>, AuthenticatorError> {
+            if exchange.address().to_string() != "127.0.0.1" {
+                panic!("unexpected address");
+            }
+
+            Err(AuthenticatorError::new("no credentials"))
+        }
+    }
+
+    struct PanickingAuthenticator;
+
+    impl Authenticator for PanickingAuthenticator {
+        fn initial_response(
+            &self,
+            _exchange: &mut AuthenticatorExchange<'_>,
+        ) -> Result<Vec<u8>, AuthenticatorError> {
+            panic!("boom")
+        }
+    }
+
+    fn target_data(target: &AuthenticatorTarget) -> *mut c_void {
+        target as *const AuthenticatorTarget as *mut c_void
+    }
+
+    #[test]
+    fn test_challenge_exchange() {
+        let live = Arc::new(AtomicUsize::new(0));
+        let success = Arc::new(Mutex::new(None));
+        let target = AuthenticatorTarget::new(CountingAuthenticator {
+            live:    live.clone(),
+            success: success.clone(),
+        });
+        let data = target_data(&target);
+        let mut fake = FakeExchange::new("com.example.Auth");
+        let auth = fake.as_driver();
+
+        unsafe { authenticator_initial_callback(auth, data) };
+        assert_eq!(fake.response.take(), Some(b"com.example.Auth".to_vec()));
+        assert_eq!(live.load(Ordering::SeqCst), 1);
+
+        for round in 1..=2u8 {
+            let token = b"abc";
+            unsafe {
+                authenticator_challenge_callback(
+                    auth,
+                    data,
+                    token.as_ptr() as *const c_char,
+                    token.len(),
+                )
+            };
+            assert_eq!(
+                fake.response.take(),
+                Some(vec![round, b'a', b'b', b'c'])
+            );
+        }
+
+        let token = b"done";
+        unsafe {
+            authenticator_success_callback(
+                auth,
+                data,
+                token.as_ptr() as *const c_char,
+                token.len(),
+            );
+            authenticator_cleanup_callback(auth, data);
+        }
+        assert_eq!(*success.lock(), Some(b"done".to_vec()));
+        assert_eq!(fake.error, None);
+        assert!(fake.data.is_null());
+        assert_eq!(live.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_errors() {
+        let target = AuthenticatorTarget::new(FailingAuthenticator);
+        let mut fake = FakeExchange::new("");
+        let auth = fake.as_driver();
+
+        unsafe { authenticator_initial_callback(auth, target_data(&target)) };
+        assert_eq!(fake.error.take().as_deref(), Some("no credentials"));
+
+        unsafe {
+            authenticator_challenge_callback(
+                auth,
+                target_data(&target),
+                std::ptr::null(),
+                0,
+            )
+        };
+        assert_eq!(
+            fake.error.take().as_deref(),
+            Some("unexpected authentication challenge")
+        );
+        assert_eq!(fake.response, None);
+
+        let target = AuthenticatorTarget::new(PanickingAuthenticator);
+        unsafe { authenticator_initial_callback(auth, target_data(&target)) };
+        assert_eq!(
+            fake.error.take().as_deref(),
+            Some("the authenticator panicked")
+        );
+    }
+
+    #[test]
+    fn test_null_arguments() {
+        let target = AuthenticatorTarget::new(PanickingAuthenticator);
+        let mut fake = FakeExchange::new("");
+
+        unsafe {
+            authenticator_initial_callback(
+                std::ptr::null_mut(),
+                target_data(&target),
+            );
+            authenticator_initial_callback(
+                fake.as_driver(),
+                std::ptr::null_mut(),
+            );
+            authenticator_cleanup_callback(
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            );
+        }
+        assert_eq!(fake.error, None);
+        assert_eq!(fake.response, None);
+    }
+}
@@ -0,0 +1,239 @@
+use std::fmt::{
+    Debug,
+    Formatter,
+};
+
+use zeroize::Zeroize;
+
+use crate::{
+    Authenticator,
+    AuthenticatorError,
+    AuthenticatorExchange,
+    Secret,
+};
+
+/// The class name of the DataStax Enterprise unified authenticator.
+const DSE_AUTHENTICATOR: &str =
+    "com.datastax.bdp.cassandra.auth.DseAuthenticator";
+
+/// The SASL mechanism the DataStax Enterprise authenticator is asked to use.
+const PLAIN_MECHANISM: &[u8] = b"PLAIN";
+
+/// The challenge the DataStax Enterprise authenticator sends to start the
+/// `PLAIN` exchange.
+const PLAIN_START_CHALLENGE: &[u8] = b"PLAIN-START";
+
+/// An authenticator that uses the SASL `PLAIN` mechanism
+/// ([RFC 4616](https://www.rfc-editor.org/rfc/rfc4616)).
+///
+/// Unlike [`Cluster::set_credentials`], it supports the authorization
+/// identity, which allows a proxy user to act on behalf of another user
+/// ("login as"), and keeps the password in a [`Secret`].
+///
+/// The authenticator works with both the Apache Cassandra
+/// `PasswordAuthenticator` and the DataStax Enterprise `DseAuthenticator`,
+/// but the authorization identity is only supported by the latter.
+///
+/// [`Cluster::set_credentials`]: crate::Cluster::set_credentials
+#[derive(Clone)]
+pub struct PlainAuthenticator {
+    /// The user whose credentials are checked.
+    username:         String,
+    /// The password of the user.
+    password:         Secret,
+    /// The user to act as.
+    authorization_id: Option<String>,
+}
+
+impl PlainAuthenticator {
+    /// Creates a new authenticator with the given credentials.
+    pub fn new<A, B>(username: A, password: B) -> Self
+    where
+        A: Into<String>,
+        B: Into<Secret>,
+    {
+        Self {
+            username:         username.into(),
+            password:         password.into(),
+            authorization_id: None,
+        }
+    }
+
+    /// Sets the authorization identity, i.e. the user to act as after the
+    /// credentials of the authenticating user are checked.
+    ///
+    /// The authorization identity is only supported by the DataStax
+    /// Enterprise `DseAuthenticator`, which requires the authenticating user
+    /// to be granted the `PROXY.LOGIN` permission on it. Authentication fails
+    /// against any other server-side authenticator, e.g. the Apache Cassandra
+    /// `PasswordAuthenticator`, rather than silently logging in as the
+    /// authenticating user.
+    pub fn with_authorization_id<T>(mut self, authorization_id: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.authorization_id = Some(authorization_id.into());
+
+        self
+    }
+
+    /// Returns the `PLAIN` mechanism message:
+    /// `[authorization_id] NUL username NUL password`.
+    fn credentials(&self) -> Vec<u8> {
+        let authorization_id =
+            self.authorization_id.as_deref().unwrap_or_default();
+        let password = self.password.expose();
+
+        let mut message = Vec::with_capacity(
+            authorization_id.len() + self.username.len() + password.len() + 2,
+        );
+        message.extend_from_slice(authorization_id.as_bytes());
+        message.push(0);
+        message.extend_from_slice(self.username.as_bytes());
+        message.push(0);
+        message.extend_from_slice(password.as_bytes());
+
+        message
+    }
+}
+
+impl Authenticator for PlainAuthenticator {
+    fn initial_response(
+        &self,
+        exchange: &mut AuthenticatorExchange<'_>,
+    ) -> Result<Vec<u8>, AuthenticatorError> {
+        let class_name = exchange.class_name();
+
+        if class_name == DSE_AUTHENTICATOR {
+            // The unified authenticator expects the mechanism name first and
+            // then asks for the credentials with a challenge.
+            Ok(PLAIN_MECHANISM.to_vec())
+        } else if self
+            .authorization_id
+            .as_deref()
+            .is_some_and(|id| !id.is_empty())
+        {
+            Err(AuthenticatorError::new(format!(
+                "the authorization identity is not supported by the '{}' \
+                 authenticator",
+                class_name
+            )))
+        } else {
+            Ok(self.credentials())
+        }
+    }
+
+    fn evaluate_challenge(
+        &self,
+        _exchange: &mut AuthenticatorExchange<'_>,
+        token: &[u8],
+    ) -> Result<Vec<u8>, AuthenticatorError> {
+        if token == PLAIN_START_CHALLENGE {
+            Ok(self.credentials())
+        } else {
+            Err(AuthenticatorError::new(format!(
+                "unexpected PLAIN authentication challenge '{}'",
+                String::from_utf8_lossy(token)
+            )))
+        }
+    }
+}
+
+impl Debug for PlainAuthenticator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlainAuthenticator")
+            .field("username", &self.username)
+            .field("password", &self.password)
+            .field("authorization_id", &self.authorization_id)
+            .finish()
+    }
+}
+
+impl Drop for PlainAuthenticator {
+    fn drop(&mut self) {
+        self.username.zeroize();
+        self.authorization_id.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authenticator::tests::fake_driver::FakeExchange;
+
+    /// Returns the initial response of the authenticator to a server using
+    /// the given authenticator class.
+    fn initial_response(
+        authenticator: &PlainAuthenticator,
+        class_name: &str,
+    ) -> Result<Vec<u8>, AuthenticatorError> {
+        let mut fake = FakeExchange::new(class_name);
+        let mut exchange = AuthenticatorExchange::from_driver(fake.as_driver());
+
+        authenticator.initial_response(&mut exchange)
+    }
+
+    #[test]
+    fn test_credentials() {
+        let authenticator = PlainAuthenticator::new("proxy", "secret");
+
+        assert_eq!(authenticator.credentials(), b"\0proxy\0secret");
+    }
+
+    #[test]
+    fn test_credentials_with_authorization_id() {
+        let authenticator = PlainAuthenticator::new("proxy", "secret")
+            .with_authorization_id("alice");
+
+        assert_eq!(authenticator.credentials(), b"alice\0proxy\0secret");
+    }
+
+    #[test]
+    fn test_debug_does_not_expose_password() {
+        let authenticator = PlainAuthenticator::new("proxy", "secret");
+
+        assert!(!format!("{:?}", authenticator).contains("secret"));
+    }
+
+    #[test]
+    fn test_initial_response() {
+        let authenticator = PlainAuthenticator::new("proxy", "secret");
+
+        assert_eq!(
+            initial_response(&authenticator, DSE_AUTHENTICATOR).unwrap(),
+            PLAIN_MECHANISM
+        );
+        assert_eq!(
+            initial_response(
+                &authenticator,
+                "org.apache.cassandra.auth.PasswordAuthenticator"
+            )
+            .unwrap(),
+            b"\0proxy\0secret"
+        );
+    }
+
+    #[test]
+    fn test_authorization_id_requires_dse() {
+        let authenticator = PlainAuthenticator::new("proxy", "secret")
+            .with_authorization_id("alice");
+
+        assert_eq!(
+            initial_response(&authenticator, DSE_AUTHENTICATOR).unwrap(),
+            PLAIN_MECHANISM
+        );
+        assert!(initial_response(
+            &authenticator,
+            "org.apache.cassandra.auth.PasswordAuthenticator"
+        )
+        .is_err());
+
+        let authenticator = PlainAuthenticator::new("proxy", "secret")
+            .with_authorization_id("");
+        assert!(initial_response(
+            &authenticator,
+            "org.apache.cassandra.auth.PasswordAuthenticator"
+        )
+        .is_ok());
+    }
+}
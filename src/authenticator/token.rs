@@ -0,0 +1,65 @@
+use std::fmt::{
+    Debug,
+    Formatter,
+};
+
+use crate::{
+    Authenticator,
+    AuthenticatorError,
+    AuthenticatorExchange,
+    Secret,
+};
+
+/// An authenticator that sends a token as the initial response.
+///
+/// The token is fetched by the user-supplied closure for every connection, so
+/// short-lived tokens are always fresh. The closure is called from the
+/// driver's I/O threads and must not block for long: fetch the token from a
+/// cache that is refreshed in the background if obtaining it is expensive.
+///
+/// The server must accept the token without any further challenges.
+pub struct TokenAuthenticator<F> {
+    fetch_token: F,
+}
+
+impl<F> TokenAuthenticator<F>
+where
+    F: Fn(&AuthenticatorExchange<'_>) -> Result<Secret, AuthenticatorError>
+        + Send
+        + Sync
+        + 'static,
+{
+    /// Creates a new authenticator that fetches the token with the given
+    /// closure.
+    ///
+    /// The closure receives the exchange to tell which node the connection is
+    /// made to.
+    pub fn new(fetch_token: F) -> Self {
+        Self {
+            fetch_token,
+        }
+    }
+}
+
+impl<F> Authenticator for TokenAuthenticator<F>
+where
+    F: Fn(&AuthenticatorExchange<'_>) -> Result<Secret, AuthenticatorError>
+        + Send
+        + Sync
+        + 'static,
+{
+    fn initial_response(
+        &self,
+        exchange: &mut AuthenticatorExchange<'_>,
+    ) -> Result<Vec<u8>, AuthenticatorError> {
+        let token = (self.fetch_token)(exchange)?;
+
+        Ok(token.expose().as_bytes().to_vec())
+    }
+}
+
+impl<F> Debug for TokenAuthenticator<F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TokenAuthenticator").finish_non_exhaustive()
+    }
+}
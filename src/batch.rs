@@ -0,0 +1,299 @@
+use std::borrow::Cow;
+use std::ffi::c_char;
+use std::sync::atomic::AtomicBool;
+
+#[rustfmt::skip]
+use crate::ffi::{
+    cass_batch_add_statement,
+    cass_batch_free,
+    cass_batch_new,
+    cass_batch_set_consistency,
+    cass_batch_set_custom_payload,
+    cass_batch_set_execution_profile_n,
+    cass_batch_set_is_idempotent,
+    cass_batch_set_keyspace_n,
+    cass_batch_set_request_timeout,
+    cass_batch_set_retry_policy,
+    cass_batch_set_serial_consistency,
+    cass_batch_set_timestamp,
+    cass_batch_set_tracing,
+    enum_CassBatchType_,
+    enum_CassBatchType__CASS_BATCH_TYPE_COUNTER  as COUNTER,
+    enum_CassBatchType__CASS_BATCH_TYPE_LOGGED   as LOGGED,
+    enum_CassBatchType__CASS_BATCH_TYPE_UNLOGGED as UNLOGGED,
+    struct_CassBatch_,
+};
+use crate::request::{
+    RequestInfo,
+    RequestKind,
+};
+use crate::{
+    to_result,
+    Consistency,
+    CustomPayload,
+    DriverError,
+    DriverErrorKind,
+    RetryPolicy,
+    Statement,
+};
+
+/// The type of a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BatchType {
+    /// The batch is written to the batch log first to guarantee that either
+    /// all or none of its statements are applied.
+    Logged,
+    /// The batch is not written to the batch log.
+    Unlogged,
+    /// The batch contains counter updates only.
+    Counter,
+}
+
+impl BatchType {
+    /// Converts this `BatchType` to the driver's object.
+    #[rustfmt::skip]
+    pub(crate) fn to_driver(self) -> enum_CassBatchType_ {
+        match self {
+            BatchType::Logged   => LOGGED,
+            BatchType::Unlogged => UNLOGGED,
+            BatchType::Counter  => COUNTER,
+        }
+    }
+}
+
+/// A group of statements that are executed as a single request.
+///
+/// The batch settings override the settings of the execution profile and the
+/// cluster. The settings of the statements added to the batch are ignored.
+pub struct Batch {
+    /// The driver's batch object.
+    inner:               *mut struct_CassBatch_,
+    /// The query strings of the statements in the batch.
+    queries:             Vec<String>,
+    /// The consistency level set on the batch.
+    consistency:         Option<Consistency>,
+    /// The keyspace set on the batch.
+    keyspace:            Option<String>,
+    /// Whether the last execution of the batch failed.
+    last_attempt_failed: AtomicBool,
+}
+
+impl Batch {
+    /// Creates a new batch of the given type.
+    pub fn new(batch_type: BatchType) -> Self {
+        let inner = unsafe { cass_batch_new(batch_type.to_driver()) };
+
+        Self {
+            inner,
+            queries: Vec::new(),
+            consistency: None,
+            keyspace: None,
+            last_attempt_failed: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the raw pointer to the batch object.
+    pub(crate) fn inner(&self) -> *mut struct_CassBatch_ {
+        self.inner
+    }
+
+    /// Returns the query strings of the statements in the batch.
+    pub fn queries(&self) -> &[String] {
+        &self.queries
+    }
+
+    /// Returns the description of the batch execution request.
+    pub(crate) fn request_info(&self) -> RequestInfo<'_> {
+        RequestInfo {
+            kind:                RequestKind::Batch,
+            statement:           Cow::Owned(self.queries.join("; ")),
+            keyspace:            self.keyspace.as_deref(),
+            consistency:         self.consistency,
+            page_number:         None,
+            last_attempt_failed: Some(&self.last_attempt_failed),
+        }
+    }
+
+    /// Adds a statement to the batch.
+    ///
+    /// The batch keeps its own reference to the statement, so the statement
+    /// can be dropped or reused after this call.
+    pub fn add_statement(
+        &mut self,
+        statement: &Statement,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_batch_add_statement(self.inner(), statement.inner())
+        };
+        to_result::<()>(code)?;
+
+        self.queries.push(statement.query().to_string());
+
+        Ok(())
+    }
+
+    /// Sets the batch's keyspace.
+    ///
+    /// This is only supported when using protocol v5 or DSE protocol v2.
+    pub fn set_keyspace<T>(&mut self, keyspace: T) -> Result<(), DriverError>
+    where
+        T: AsRef<str>,
+    {
+        let keyspace = keyspace.as_ref();
+        let keyspace_len = keyspace.len();
+        let keyspace_ptr = keyspace.as_ptr() as *const c_char;
+        let code = unsafe {
+            cass_batch_set_keyspace_n(self.inner(), keyspace_ptr, keyspace_len)
+        };
+        to_result::<()>(code)?;
+
+        self.keyspace = Some(keyspace.to_string());
+
+        Ok(())
+    }
+
+    /// Sets the batch's consistency level.
+    pub fn set_consistency(
+        &mut self,
+        consistency: Consistency,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_batch_set_consistency(self.inner(), consistency.to_driver())
+        };
+        to_result::<()>(code)?;
+
+        self.consistency = Some(consistency);
+
+        Ok(())
+    }
+
+    /// Sets the batch's serial consistency level.
+    pub fn set_serial_consistency(
+        &mut self,
+        consistency: Consistency,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_batch_set_serial_consistency(
+                self.inner(),
+                consistency.to_driver(),
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Sets the batch's timestamp in microseconds since the Unix epoch.
+    pub fn set_timestamp(&mut self, timestamp: i64) -> Result<(), DriverError> {
+        let code = unsafe { cass_batch_set_timestamp(self.inner(), timestamp) };
+
+        to_result(code)
+    }
+
+    /// Sets the batch's timeout in milliseconds for waiting for a response
+    /// from a node.
+    ///
+    /// A zero timeout disables it.
+    pub fn set_request_timeout(
+        &mut self,
+        timeout: i64,
+    ) -> Result<(), DriverError> {
+        let timeout = timeout.try_into().map_err(|_| {
+            DriverError::with_kind(DriverErrorKind::LibBadParams)
+        })?;
+
+        let code =
+            unsafe { cass_batch_set_request_timeout(self.inner(), timeout) };
+
+        to_result(code)
+    }
+
+    /// Sets whether the statements in the batch are idempotent.
+    ///
+    /// Idempotent batches are able to be automatically retried after
+    /// timeouts/errors and can be speculatively executed.
+    ///
+    /// The default value is `false`.
+    pub fn set_is_idempotent(
+        &mut self,
+        is_idempotent: bool,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_batch_set_is_idempotent(self.inner(), is_idempotent.into())
+        };
+
+        to_result(code)
+    }
+
+    /// Sets the batch's retry policy.
+    pub fn set_retry_policy(
+        &mut self,
+        policy: &RetryPolicy,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_batch_set_retry_policy(self.inner(), policy.inner())
+        };
+
+        to_result(code)
+    }
+
+    /// Sets the custom payload sent with the batch.
+    ///
+    /// The payload is copied, so it can be modified or reused once this
+    /// method is called.
+    pub fn set_custom_payload(
+        &mut self,
+        payload: &CustomPayload,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_batch_set_custom_payload(self.inner(), payload.inner())
+        };
+
+        to_result(code)
+    }
+
+    /// Sets whether the batch is traced by the cluster.
+    ///
+    /// See [`Statement::set_tracing`].
+    ///
+    /// The default value is `false`.
+    pub fn set_tracing(&mut self, enabled: bool) -> Result<(), DriverError> {
+        let code =
+            unsafe { cass_batch_set_tracing(self.inner(), enabled.into()) };
+
+        to_result(code)
+    }
+
+    /// Sets the execution profile to execute the batch with.
+    ///
+    /// The profile must be registered on the cluster with
+    /// [`Cluster::set_execution_profile`], otherwise the execution fails.
+    /// Passing an empty name resets the batch to the default profile.
+    ///
+    /// [`Cluster::set_execution_profile`]: crate::Cluster::set_execution_profile
+    pub fn set_execution_profile<T>(
+        &mut self,
+        name: T,
+    ) -> Result<(), DriverError>
+    where
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+        let name_len = name.len();
+        let name_ptr = name.as_ptr() as *const c_char;
+        let code = unsafe {
+            cass_batch_set_execution_profile_n(self.inner(), name_ptr, name_len)
+        };
+
+        to_result(code)
+    }
+}
+
+unsafe impl Send for Batch {}
+unsafe impl Sync for Batch {}
+
+impl Drop for Batch {
+    fn drop(&mut self) {
+        unsafe { cass_batch_free(self.inner()) }
+    }
+}
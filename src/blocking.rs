@@ -0,0 +1,293 @@
+//! Blocking API for the Cassandra driver.
+//!
+//! This module provides blocking counterparts of [`Cluster`] and [`Session`]
+//! for the applications that do not use async, e.g. CLI tools and batch jobs.
+//! The calls block the current thread until the driver completes the request,
+//! no async runtime is involved, so they can be made from any thread including
+//! the threads of a thread pool. They must not be made from the threads of an
+//! async executor, use the async API there.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use cassander::blocking::BlockingCluster;
+//! use cassander::{
+//!     Cluster,
+//!     Statement,
+//! };
+//!
+//! let mut cluster = Cluster::default();
+//! cluster.set_contact_points("127.0.0.1").unwrap();
+//!
+//! let session = BlockingCluster::new(cluster).connect().unwrap();
+//! let statement =
+//!     Statement::new("SELECT release_version FROM system.local", 0);
+//! for row in session.rows(statement) {
+//!     println!("{:?}", row.unwrap());
+//! }
+//! ```
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::cql::CqlValue;
+use crate::{
+    Batch,
+    Cluster,
+    DriverError,
+    Prepared,
+    QueryResult,
+    Session,
+    Statement,
+    TableScan,
+};
+
+/// A [`Cluster`] that connects the sessions blocking the current thread.
+pub struct BlockingCluster {
+    /// The cluster configuration.
+    cluster: Cluster,
+    /// The time to wait for a session to connect.
+    timeout: Option<Duration>,
+}
+
+impl BlockingCluster {
+    /// Creates a blocking cluster with the given configuration.
+    pub fn new(cluster: Cluster) -> Self {
+        Self {
+            cluster,
+            timeout: None,
+        }
+    }
+
+    /// Sets the time to wait for a session to connect, see
+    /// [`Cluster::set_connect_timeout`] to limit the time to connect to a
+    /// single node.
+    ///
+    /// By default, the connect calls wait until the driver gives up.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+
+        self
+    }
+
+    /// Returns the cluster configuration.
+    pub fn cluster(&self) -> &Cluster {
+        &self.cluster
+    }
+
+    /// Returns the cluster configuration to be modified.
+    pub fn cluster_mut(&mut self) -> &mut Cluster {
+        &mut self.cluster
+    }
+
+    /// Connects to the cluster and returns a session.
+    pub fn connect(&self) -> Result<BlockingSession, DriverError> {
+        let session = self.cluster.connect_blocking(None, self.timeout)?;
+
+        Ok(BlockingSession::new(session))
+    }
+
+    /// Connects to the cluster and returns a session with the specified
+    /// keyspace set as default.
+    pub fn connect_keyspace<T>(
+        &self,
+        keyspace: T,
+    ) -> Result<BlockingSession, DriverError>
+    where
+        T: AsRef<str>,
+    {
+        let session = self
+            .cluster
+            .connect_blocking(Some(keyspace.as_ref()), self.timeout)?;
+
+        Ok(BlockingSession::new(session))
+    }
+}
+
+impl From<Cluster> for BlockingCluster {
+    /// Converts the cluster into a blocking cluster.
+    fn from(cluster: Cluster) -> Self {
+        Self::new(cluster)
+    }
+}
+
+/// A [`Session`] that executes the requests blocking the current thread.
+///
+/// The session can be cloned and shared between threads, the clones share
+/// the connections.
+#[derive(Clone)]
+pub struct BlockingSession {
+    /// The session executing the requests.
+    session: Session,
+    /// The time to wait for a request to complete.
+    timeout: Option<Duration>,
+}
+
+impl BlockingSession {
+    /// Creates a blocking session executing the requests with the given
+    /// session.
+    pub fn new(session: Session) -> Self {
+        Self {
+            session,
+            timeout: None,
+        }
+    }
+
+    /// Sets the time to wait for a request to complete, the request fails
+    /// with [`LibRequestTimedOut`] once the time elapses.
+    ///
+    /// The request keeps running in the background, see
+    /// [`Statement::set_request_timeout`] to limit the time the driver spends
+    /// on a request. By default, the calls wait until the driver completes
+    /// the request.
+    ///
+    /// [`LibRequestTimedOut`]: crate::DriverErrorKind::LibRequestTimedOut
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+
+        self
+    }
+
+    /// Returns the session executing the requests, e.g. to read its metrics.
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// Executes the statement and returns its result, see
+    /// [`Session::execute`].
+    pub fn execute(
+        &self,
+        statement: &Statement,
+    ) -> Result<QueryResult, DriverError> {
+        self.session.execute_blocking(statement, self.timeout)
+    }
+
+    /// Executes the batch and returns its result, see
+    /// [`Session::execute_batch`].
+    pub fn execute_batch(
+        &self,
+        batch: &Batch,
+    ) -> Result<QueryResult, DriverError> {
+        self.session.execute_batch_blocking(batch, self.timeout)
+    }
+
+    /// Prepares the query on the cluster, see [`Session::prepare`].
+    pub fn prepare<T>(&self, query: T) -> Result<Prepared, DriverError>
+    where
+        T: Into<String>,
+    {
+        self.session.prepare_blocking(query, self.timeout)
+    }
+
+    /// Returns an iterator over the result pages of the statement.
+    ///
+    /// The pages are fetched as the iterator advances, the iteration stops
+    /// after the first error.
+    pub fn pages(&self, statement: Statement) -> Pages<'_> {
+        Pages {
+            session:   self,
+            statement: Some(statement),
+            error:     None,
+        }
+    }
+
+    /// Returns an iterator over the values of the rows of the statement
+    /// result, across all of its pages.
+    ///
+    /// The pages are fetched as the iterator advances, the iteration stops
+    /// after the first error.
+    pub fn rows(&self, statement: Statement) -> Rows<'_> {
+        Rows {
+            pages: self.pages(statement),
+            rows:  VecDeque::new(),
+        }
+    }
+
+    /// Returns a scan of all the rows of the table, see
+    /// [`Session::scan_table`] and [`TableScan::rows_blocking`].
+    pub fn scan_table<K, T>(&self, keyspace: K, table: T) -> TableScan
+    where
+        K: Into<String>,
+        T: Into<String>,
+    {
+        self.session.scan_table(keyspace, table)
+    }
+
+    /// Closes the session, see [`Session::close`].
+    pub fn close(&self, timeout: Duration) -> Result<(), DriverError> {
+        self.session.close_blocking(timeout)
+    }
+}
+
+impl From<Session> for BlockingSession {
+    /// Converts the session into a blocking session.
+    fn from(session: Session) -> Self {
+        Self::new(session)
+    }
+}
+
+/// An iterator over the result pages of a statement, see
+/// [`BlockingSession::pages`].
+pub struct Pages<'a> {
+    /// The session executing the statement.
+    session:   &'a BlockingSession,
+    /// The statement to fetch the next page with, [`None`] once the last page
+    /// is fetched or a request failed.
+    statement: Option<Statement>,
+    /// The error to return after the page fetched with it.
+    error:     Option<DriverError>,
+}
+
+impl Iterator for Pages<'_> {
+    type Item = Result<QueryResult, DriverError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.error.take() {
+            return Some(Err(error));
+        }
+        let mut statement = self.statement.take()?;
+
+        let result = match self.session.execute(&statement) {
+            Ok(result) => result,
+            Err(error) => return Some(Err(error)),
+        };
+        if let Some(token) = result.paging_state_token() {
+            match statement.set_paging_state_token(&token) {
+                Ok(()) => self.statement = Some(statement),
+                Err(error) => self.error = Some(error),
+            }
+        }
+
+        Some(Ok(result))
+    }
+}
+
+/// An iterator over the values of the rows of a statement result, see
+/// [`BlockingSession::rows`].
+pub struct Rows<'a> {
+    /// The pages of the result.
+    pages: Pages<'a>,
+    /// The rows of the current page.
+    rows:  VecDeque<Result<Vec<CqlValue>, DriverError>>,
+}
+
+impl Iterator for Rows<'_> {
+    type Item = Result<Vec<CqlValue>, DriverError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(row) = self.rows.pop_front() {
+                return Some(row);
+            }
+
+            // The rows borrow the page, so the values of the page are read
+            // at once.
+            match self.pages.next()? {
+                Ok(page) => {
+                    self.rows.extend(page.rows().map(|row| row.values()))
+                }
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
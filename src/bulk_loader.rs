@@ -0,0 +1,742 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::pin;
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use futures::stream::{
+    self,
+    Stream,
+    StreamExt,
+};
+
+use crate::blocking::BlockingSession;
+use crate::concurrent::{
+    is_transient,
+    next_retry_delay,
+    retry_on_queue_full,
+};
+use crate::cql::CqlValue;
+use crate::timer::sleep;
+use crate::{
+    Batch,
+    BatchType,
+    Consistency,
+    DriverError,
+    Prepared,
+    QueryResult,
+    Session,
+    Statement,
+};
+
+/// A record that can be loaded with a [`BulkLoader`].
+pub trait BulkRecord {
+    /// Binds the values of the record to the statement of the loader.
+    fn bind(&self, statement: &mut Statement) -> Result<(), DriverError>;
+
+    /// Returns the partition key of the record.
+    ///
+    /// The records with the same partition key may be written with a single
+    /// unlogged batch, the records without the key are written one by one.
+    /// The key is only compared, so any unique encoding of the partition key
+    /// values will do.
+    fn partition_key(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// The values of the bind variables in order.
+///
+/// The values do not tell which of them make up the partition key, so
+/// [`BulkRecord::partition_key`] returns [`None`] and the records are never
+/// batched, each of them is written with its own request. Implement
+/// [`BulkRecord`] for a type that knows its partition key to batch the
+/// records of a partition.
+impl BulkRecord for Vec<CqlValue> {
+    /// Binds the values to the bind variables in order.
+    fn bind(&self, statement: &mut Statement) -> Result<(), DriverError> {
+        for (index, value) in self.iter().enumerate() {
+            statement.bind(index, value.clone())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A record that failed to load.
+#[derive(Debug)]
+pub struct FailedRecord<T> {
+    /// The record.
+    pub record: T,
+    /// The error of the last attempt to write the record.
+    pub error:  DriverError,
+}
+
+/// The outcome of a bulk load.
+#[derive(Debug)]
+pub struct BulkLoadReport<T> {
+    /// The number of the records written.
+    loaded:  u64,
+    /// The number of the batches written.
+    batches: u64,
+    /// The number of the retried requests.
+    retries: u64,
+    /// The records that failed to load.
+    failed:  Vec<FailedRecord<T>>,
+}
+
+impl<T> BulkLoadReport<T> {
+    /// Returns the number of the records written.
+    pub fn loaded(&self) -> u64 {
+        self.loaded
+    }
+
+    /// Returns the number of the unlogged batches written.
+    pub fn batches(&self) -> u64 {
+        self.batches
+    }
+
+    /// Returns the number of the requests retried after an error.
+    pub fn retries(&self) -> u64 {
+        self.retries
+    }
+
+    /// Returns the records that failed to load.
+    pub fn failed(&self) -> &[FailedRecord<T>] {
+        &self.failed
+    }
+
+    /// Returns the records that failed to load.
+    pub fn into_failed(self) -> Vec<FailedRecord<T>> {
+        self.failed
+    }
+
+    /// Returns whether all the records were loaded.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Adds the outcome of a request to the report.
+    fn add(&mut self, outcome: Outcome<T>) {
+        self.loaded += outcome.loaded;
+        self.batches += outcome.batches;
+        self.retries += outcome.retries;
+        self.failed.extend(outcome.failed);
+    }
+}
+
+impl<T> Default for BulkLoadReport<T> {
+    fn default() -> Self {
+        Self {
+            loaded:  0,
+            batches: 0,
+            retries: 0,
+            failed:  Vec::new(),
+        }
+    }
+}
+
+/// Loads a stream of records with a prepared statement, e.g. an `INSERT`.
+///
+/// The loader groups the records of the same partition, see
+/// [`BulkRecord::partition_key`], into unlogged batches, which are written
+/// by the node that owns the partition at once. The records of a partition
+/// are only grouped if they are close to each other in the stream, within the
+/// window set with [`BulkLoader::with_batch_window`].
+///
+/// The loader limits the number of the requests in flight and the number of
+/// the records written per second. The requests rejected because the request
+/// queue of the driver is full are retried. The other failed requests are
+/// retried only if the statement is idempotent, see
+/// [`BulkLoader::with_idempotent`]. The records that fail to load are returned
+/// with their errors in the [`BulkLoadReport`], they do not stop the load.
+///
+/// The settings of the prepared statement, e.g. the consistency level, apply to
+/// the single records. The batches get the consistency level set with
+/// [`BulkLoader::with_consistency`] or the one of the execution profile.
+#[derive(Clone)]
+pub struct BulkLoader {
+    /// The statement that writes a record.
+    prepared:        Prepared,
+    /// The maximum number of the requests in flight.
+    max_in_flight:   usize,
+    /// The maximum number of the records written per second.
+    rows_per_second: Option<u32>,
+    /// The maximum number of the records in a batch.
+    max_batch_size:  usize,
+    /// The number of the consecutive records grouped into batches.
+    batch_window:    usize,
+    /// Whether the statement is idempotent.
+    idempotent:      bool,
+    /// The maximum number of the retries of a failed request.
+    max_retries:     u32,
+    /// The delay before the first retry, doubled for every next one, up to a
+    /// minute.
+    retry_delay:     Duration,
+    /// The consistency level of the requests.
+    consistency:     Option<Consistency>,
+}
+
+impl BulkLoader {
+    /// Creates a loader that writes the records with the prepared statement.
+    pub fn new(prepared: Prepared) -> Self {
+        Self {
+            prepared,
+            max_in_flight: 256,
+            rows_per_second: None,
+            max_batch_size: 20,
+            batch_window: 1000,
+            idempotent: false,
+            max_retries: 3,
+            retry_delay: Duration::from_millis(100),
+            consistency: None,
+        }
+    }
+
+    /// Sets the maximum number of the requests in flight.
+    ///
+    /// The default value is 256.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+
+        self
+    }
+
+    /// Sets the maximum number of the records written per second.
+    ///
+    /// By default, the throughput is not limited.
+    pub fn with_rows_per_second(mut self, rows_per_second: u32) -> Self {
+        self.rows_per_second = Some(rows_per_second).filter(|rows| *rows > 0);
+
+        self
+    }
+
+    /// Sets the maximum number of the records in a batch, `1` disables the
+    /// batches.
+    ///
+    /// The batches should be kept small, the default value is 20.
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size.max(1);
+
+        self
+    }
+
+    /// Sets the maximum number of the consecutive records of the stream
+    /// grouped into batches by the partition key.
+    ///
+    /// Only the records the stream has ready are grouped, the loader does not
+    /// wait for the window to fill up.
+    ///
+    /// The default value is 1000.
+    pub fn with_batch_window(mut self, batch_window: usize) -> Self {
+        self.batch_window = batch_window.max(1);
+
+        self
+    }
+
+    /// Sets whether the statement is idempotent, i.e. whether it can be
+    /// retried after a timeout or any other error.
+    ///
+    /// The default value is `false`.
+    pub fn with_idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+
+        self
+    }
+
+    /// Sets the maximum number of the retries of an idempotent request and
+    /// the delay before the first retry, doubled for every next one, up to a
+    /// minute.
+    ///
+    /// The default values are 3 retries and 100 milliseconds.
+    pub fn with_retries(mut self, max_retries: u32, delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_delay = delay;
+
+        self
+    }
+
+    /// Sets the consistency level of the requests.
+    pub fn with_consistency(mut self, consistency: Consistency) -> Self {
+        self.consistency = Some(consistency);
+
+        self
+    }
+
+    /// Loads the records and returns the report of the load.
+    pub async fn load<S, T>(
+        &self,
+        session: &Session,
+        records: S,
+    ) -> BulkLoadReport<T>
+    where
+        S: Stream<Item = T>,
+        T: BulkRecord,
+    {
+        let mut limiter = self.rows_per_second.map(RateLimiter::new);
+
+        let mut outcomes =
+            pin!(requests(records, self.batch_window, self.max_batch_size)
+                .then(|request| {
+                    let delay = limiter
+                        .as_mut()
+                        .map(|limiter| limiter.reserve(request.len()));
+                    async move {
+                        let waited = match delay {
+                            Some(delay) => sleep(delay).await,
+                            None => Ok(()),
+                        };
+
+                        (request, waited)
+                    }
+                })
+                .map(|(request, waited)| async move {
+                    match waited {
+                        Ok(()) => self.write(session, request).await,
+                        Err(error) => Outcome::failed(request, error),
+                    }
+                })
+                .buffer_unordered(self.max_in_flight));
+
+        let mut report = BulkLoadReport::default();
+        while let Some(outcome) = outcomes.next().await {
+            report.add(outcome);
+        }
+
+        report
+    }
+
+    /// Loads the records blocking the current thread, see
+    /// [`BulkLoader::load`].
+    ///
+    /// The load is driven on the current thread, so the method must not be
+    /// called from the threads of an async executor.
+    pub fn load_blocking<I, T>(
+        &self,
+        session: &BlockingSession,
+        records: I,
+    ) -> BulkLoadReport<T>
+    where
+        I: IntoIterator<Item = T>,
+        T: BulkRecord,
+    {
+        futures::executor::block_on(
+            self.load(session.session(), stream::iter(records)),
+        )
+    }
+
+    /// Writes the records of the request.
+    async fn write<T>(
+        &self,
+        session: &Session,
+        request: Request<T>,
+    ) -> Outcome<T>
+    where
+        T: BulkRecord,
+    {
+        match request {
+            Request::Single(record) => self.write_single(session, record).await,
+            Request::Batch(records) => self.write_batch(session, records).await,
+        }
+    }
+
+    /// Writes a single record.
+    async fn write_single<T>(&self, session: &Session, record: T) -> Outcome<T>
+    where
+        T: BulkRecord,
+    {
+        let mut outcome = Outcome::default();
+
+        let statement = match self.statement(&record) {
+            Ok(statement) => statement,
+            Err(error) => {
+                outcome.failed.push(FailedRecord {
+                    record,
+                    error,
+                });
+                return outcome;
+            }
+        };
+
+        let result = self
+            .retry(&mut outcome, || session.execute(&statement))
+            .await;
+        match result {
+            Ok(_) => outcome.loaded += 1,
+            Err(error) => outcome.failed.push(FailedRecord {
+                record,
+                error,
+            }),
+        }
+
+        outcome
+    }
+
+    /// Writes the records of a partition with an unlogged batch.
+    ///
+    /// If the batch fails and the statement is idempotent, the records are
+    /// written one by one to report the errors of the single records.
+    async fn write_batch<T>(
+        &self,
+        session: &Session,
+        records: Vec<T>,
+    ) -> Outcome<T>
+    where
+        T: BulkRecord,
+    {
+        let mut outcome = Outcome::default();
+
+        let mut batch = Batch::new(BatchType::Unlogged);
+        let mut batched = Vec::with_capacity(records.len());
+        for record in records {
+            let added = self
+                .statement(&record)
+                .and_then(|statement| batch.add_statement(&statement));
+            match added {
+                Ok(()) => batched.push(record),
+                Err(error) => outcome.failed.push(FailedRecord {
+                    record,
+                    error,
+                }),
+            }
+        }
+        if batched.is_empty() {
+            return outcome;
+        }
+
+        let result = match self.configure_batch(&mut batch) {
+            Ok(()) => {
+                self.retry(&mut outcome, || session.execute_batch(&batch))
+                    .await
+            }
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok(_) => {
+                outcome.loaded += batched.len() as u64;
+                outcome.batches += 1;
+            }
+            Err(_) if self.idempotent => {
+                for record in batched {
+                    let single = Box::pin(self.write_single(session, record));
+                    outcome.add(single.await);
+                }
+            }
+            Err(error) => {
+                outcome.failed.extend(batched.into_iter().map(|record| {
+                    FailedRecord {
+                        record,
+                        error: error.clone(),
+                    }
+                }));
+            }
+        }
+
+        outcome
+    }
+
+    /// Creates the statement that writes the record.
+    fn statement<T>(&self, record: &T) -> Result<Statement, DriverError>
+    where
+        T: BulkRecord,
+    {
+        let mut statement = self.prepared.bind();
+        record.bind(&mut statement)?;
+        statement.set_is_idempotent(self.idempotent)?;
+        if let Some(consistency) = self.consistency {
+            statement.set_consistency(consistency)?;
+        }
+
+        Ok(statement)
+    }
+
+    /// Applies the settings of the loader to the batch.
+    fn configure_batch(&self, batch: &mut Batch) -> Result<(), DriverError> {
+        batch.set_is_idempotent(self.idempotent)?;
+        if let Some(consistency) = self.consistency {
+            batch.set_consistency(consistency)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends the request, retrying it with an exponential backoff if it is
+    /// idempotent and failed with a transient error.
+    async fn retry<T, F, Fut>(
+        &self,
+        outcome: &mut Outcome<T>,
+        mut send: F,
+    ) -> Result<QueryResult, DriverError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<QueryResult, DriverError>>,
+    {
+        let mut delay = self.retry_delay;
+        let mut retries = 0;
+        loop {
+            match retry_on_queue_full(&mut send).await {
+                Err(error)
+                    if self.idempotent
+                        && retries < self.max_retries
+                        && is_transient(error.kind) =>
+                {
+                    retries += 1;
+                    outcome.retries += 1;
+                    sleep(delay).await?;
+                    delay = next_retry_delay(delay);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Groups the records of the stream into the requests, see [`group`].
+///
+/// The records are grouped as they become ready, up to the window size, so a
+/// slow stream does not hold back the records it has already produced.
+fn requests<S, T>(
+    records: S,
+    batch_window: usize,
+    max_batch_size: usize,
+) -> impl Stream<Item = Request<T>>
+where
+    S: Stream<Item = T>,
+    T: BulkRecord,
+{
+    records
+        .ready_chunks(batch_window)
+        .flat_map(move |records| stream::iter(group(records, max_batch_size)))
+}
+
+/// Groups the records of the same partition into batches of at most the given
+/// size, keeping the order of the partitions.
+fn group<T>(records: Vec<T>, max_batch_size: usize) -> Vec<Request<T>>
+where
+    T: BulkRecord,
+{
+    if max_batch_size == 1 {
+        return records.into_iter().map(Request::Single).collect();
+    }
+
+    let mut requests = Vec::new();
+    let mut partitions: HashMap<Vec<u8>, usize> = HashMap::new();
+    for record in records {
+        let Some(key) = record.partition_key() else {
+            requests.push(Request::Single(record));
+            continue;
+        };
+
+        match partitions.get(&key).map(|index| &mut requests[*index]) {
+            Some(Request::Batch(batch)) if batch.len() < max_batch_size => {
+                batch.push(record);
+            }
+            _ => {
+                partitions.insert(key, requests.len());
+                requests.push(Request::Batch(vec![record]));
+            }
+        }
+    }
+
+    requests
+        .into_iter()
+        .map(|request| match request {
+            Request::Batch(mut records) if records.len() == 1 => {
+                Request::Single(records.remove(0))
+            }
+            request => request,
+        })
+        .collect()
+}
+
+/// A request of a bulk load.
+#[derive(Debug, PartialEq)]
+enum Request<T> {
+    /// A single record.
+    Single(T),
+    /// The records of a partition written with an unlogged batch.
+    Batch(Vec<T>),
+}
+
+impl<T> Request<T> {
+    /// Returns the number of the records of the request.
+    fn len(&self) -> usize {
+        match self {
+            Request::Single(_) => 1,
+            Request::Batch(records) => records.len(),
+        }
+    }
+}
+
+/// The outcome of a request of a bulk load.
+struct Outcome<T> {
+    /// The number of the records written.
+    loaded:  u64,
+    /// The number of the batches written.
+    batches: u64,
+    /// The number of the retried requests.
+    retries: u64,
+    /// The records that failed to load.
+    failed:  Vec<FailedRecord<T>>,
+}
+
+impl<T> Outcome<T> {
+    /// Returns the outcome of a request that could not be sent.
+    fn failed(request: Request<T>, error: DriverError) -> Self {
+        let records = match request {
+            Request::Single(record) => vec![record],
+            Request::Batch(records) => records,
+        };
+
+        Self {
+            failed: records
+                .into_iter()
+                .map(|record| FailedRecord {
+                    record,
+                    error: error.clone(),
+                })
+                .collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Adds the outcome of another request.
+    fn add(&mut self, other: Outcome<T>) {
+        self.loaded += other.loaded;
+        self.batches += other.batches;
+        self.retries += other.retries;
+        self.failed.extend(other.failed);
+    }
+}
+
+impl<T> Default for Outcome<T> {
+    fn default() -> Self {
+        Self {
+            loaded:  0,
+            batches: 0,
+            retries: 0,
+            failed:  Vec::new(),
+        }
+    }
+}
+
+/// Limits the number of the records written per second.
+struct RateLimiter {
+    /// The time it takes to write a record at the limit.
+    interval: Duration,
+    /// The instant the next records may be written at.
+    next:     Instant,
+}
+
+impl RateLimiter {
+    /// Creates a limiter of the given number of the records per second.
+    fn new(rows_per_second: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / rows_per_second,
+            next:     Instant::now(),
+        }
+    }
+
+    /// Reserves the time to write the records and returns the delay before
+    /// the records may be written.
+    fn reserve(&mut self, rows: usize) -> Duration {
+        let now = Instant::now();
+        let start = self.next.max(now);
+        self.next = start + self.interval * rows as u32;
+
+        start - now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt;
+
+    use super::*;
+
+    /// A record with a partition key.
+    #[derive(Debug, PartialEq)]
+    struct Record(Option<u8>, u32);
+
+    impl BulkRecord for Record {
+        fn bind(&self, _statement: &mut Statement) -> Result<(), DriverError> {
+            Ok(())
+        }
+
+        fn partition_key(&self) -> Option<Vec<u8>> {
+            self.0.map(|key| vec![key])
+        }
+    }
+
+    #[test]
+    fn test_group_records_by_partition() {
+        let requests = group(
+            vec![
+                Record(Some(1), 1),
+                Record(Some(2), 2),
+                Record(None, 3),
+                Record(Some(1), 4),
+                Record(Some(1), 5),
+                Record(Some(3), 6),
+            ],
+            2,
+        );
+
+        assert_eq!(
+            requests,
+            vec![
+                Request::Batch(vec![Record(Some(1), 1), Record(Some(1), 4)]),
+                Request::Single(Record(Some(2), 2)),
+                Request::Single(Record(None, 3)),
+                Request::Single(Record(Some(1), 5)),
+                Request::Single(Record(Some(3), 6)),
+            ]
+        );
+
+        let requests = group(vec![Record(Some(1), 1), Record(Some(1), 2)], 1);
+        assert_eq!(requests.len(), 2);
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let mut limiter = RateLimiter::new(1000);
+
+        assert_eq!(limiter.reserve(10), Duration::ZERO);
+        let delay = limiter.reserve(5);
+        assert!(
+            delay > Duration::from_millis(8)
+                && delay <= Duration::from_millis(10)
+        );
+        let delay = limiter.reserve(1);
+        assert!(
+            delay > Duration::from_millis(13)
+                && delay <= Duration::from_millis(15)
+        );
+    }
+
+    #[test]
+    fn test_requests_do_not_wait_for_a_slow_stream() {
+        let records = stream::iter(vec![
+            Record(Some(1), 1),
+            Record(Some(1), 2),
+            Record(Some(2), 3),
+        ])
+        .chain(stream::pending());
+        let mut requests = pin!(requests(records, 1000, 20));
+
+        assert_eq!(
+            requests.next().now_or_never(),
+            Some(Some(Request::Batch(vec![
+                Record(Some(1), 1),
+                Record(Some(1), 2)
+            ])))
+        );
+        assert_eq!(
+            requests.next().now_or_never(),
+            Some(Some(Request::Single(Record(Some(2), 3))))
+        );
+        assert_eq!(requests.next().now_or_never(), None);
+    }
+}
@@ -0,0 +1,424 @@
+use std::fs::File;
+use std::io::{
+    Read,
+    Seek,
+};
+use std::path::Path;
+
+use thiserror::Error;
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::pem::{
+    pem_blocks,
+    pem_certificates,
+};
+use crate::{
+    DriverError,
+    DriverErrorKind,
+};
+
+/// The file with the connection settings of a bundle.
+const CONFIG_FILE: &str = "config.json";
+
+/// The file with the CA certificate of a bundle.
+const CA_CERT_FILE: &str = "ca.crt";
+
+/// The file with the client certificate of a bundle.
+const CERT_FILE: &str = "cert";
+
+/// The file with the private key of the client certificate of a bundle.
+const KEY_FILE: &str = "key";
+
+/// An error that occurs when a cloud secure connection bundle is invalid.
+#[derive(Error, Debug)]
+pub enum CloudBundleError {
+    /// The bundle cannot be read.
+    #[error("failed to read the cloud secure connection bundle: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The bundle is not a zip archive.
+    #[error("the cloud secure connection bundle is not a zip archive: {0}")]
+    Archive(String),
+
+    /// The bundle has no file that the driver requires.
+    #[error("the cloud secure connection bundle has no {0} file")]
+    MissingFile(&'static str),
+
+    /// A file of the bundle cannot be read or decompressed, or is not UTF-8.
+    #[error(
+        "failed to read {file} in the cloud secure connection bundle: {reason}"
+    )]
+    Unreadable {
+        /// The name of the file.
+        file:   &'static str,
+        /// The description of the error.
+        reason: String,
+    },
+
+    /// The connection settings of the bundle are invalid.
+    #[error(
+        "invalid {CONFIG_FILE} in the cloud secure connection bundle: {0}"
+    )]
+    InvalidConfig(String),
+
+    /// A certificate or the private key of the bundle is invalid.
+    #[error("invalid {file} in the cloud secure connection bundle: {reason}")]
+    InvalidPem {
+        /// The name of the file.
+        file:   &'static str,
+        /// The description of the error.
+        reason: String,
+    },
+}
+
+impl From<CloudBundleError> for DriverError {
+    /// Converts the bundle error into a driver error.
+    fn from(error: CloudBundleError) -> Self {
+        let kind = match &error {
+            CloudBundleError::InvalidPem {
+                file: KEY_FILE, ..
+            } => DriverErrorKind::SslInvalidPrivateKey,
+            CloudBundleError::InvalidPem {
+                ..
+            } => DriverErrorKind::SslInvalidCert,
+            _ => DriverErrorKind::LibBadParams,
+        };
+
+        DriverError::with_message(kind, error.to_string())
+    }
+}
+
+/// The connection settings of a cloud secure connection bundle, e.g. the one
+/// of a DataStax Astra database.
+///
+/// The bundle is a zip archive with the `config.json` file that describes the
+/// service to connect to and the `ca.crt`, `cert` and `key` files used to
+/// establish the SSL connections. The driver reads the bundle when a session
+/// connects and fails with the opaque
+/// [`LibUnableToConnect`](DriverErrorKind::LibUnableToConnect) error if the
+/// bundle is invalid. [`CloudBundle::inspect`] validates the bundle up front
+/// and reports the precise error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloudBundle {
+    /// The host of the metadata service.
+    host:     String,
+    /// The port of the metadata service.
+    port:     u16,
+    /// The username of the credentials in the bundle.
+    username: Option<String>,
+    /// The default keyspace.
+    keyspace: Option<String>,
+    /// The local data center.
+    local_dc: Option<String>,
+}
+
+impl CloudBundle {
+    /// Reads and validates the bundle at the given path.
+    pub fn inspect<P>(path: P) -> Result<Self, CloudBundleError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_reader(File::open(path)?)
+    }
+
+    /// Reads and validates the bundle.
+    pub fn from_reader<R>(reader: R) -> Result<Self, CloudBundleError>
+    where
+        R: Read + Seek,
+    {
+        let mut archive = ZipArchive::new(reader).map_err(archive_error)?;
+
+        let bundle = parse_config(&read_file(&mut archive, CONFIG_FILE)?)?;
+        validate_certificate(
+            CA_CERT_FILE,
+            &read_file(&mut archive, CA_CERT_FILE)?,
+        )?;
+        validate_certificate(CERT_FILE, &read_file(&mut archive, CERT_FILE)?)?;
+        validate_private_key(&read_file(&mut archive, KEY_FILE)?)?;
+
+        Ok(bundle)
+    }
+
+    /// Returns the host of the metadata service.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Returns the port of the metadata service.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Returns the username of the credentials in the bundle, if any.
+    pub fn username(&self) -> Option<&str> {
+        self.username.as_deref()
+    }
+
+    /// Returns the default keyspace, if any.
+    pub fn keyspace(&self) -> Option<&str> {
+        self.keyspace.as_deref()
+    }
+
+    /// Returns the local data center, if any.
+    pub fn local_dc(&self) -> Option<&str> {
+        self.local_dc.as_deref()
+    }
+}
+
+/// Converts the error of the zip archive.
+fn archive_error(error: ZipError) -> CloudBundleError {
+    match error {
+        ZipError::Io(error) => CloudBundleError::Io(error),
+        error => CloudBundleError::Archive(error.to_string()),
+    }
+}
+
+/// Reads the text file of the bundle.
+fn read_file<R>(
+    archive: &mut ZipArchive<R>,
+    name: &'static str,
+) -> Result<String, CloudBundleError>
+where
+    R: Read + Seek,
+{
+    let mut file = archive.by_name(name).map_err(|error| match error {
+        ZipError::FileNotFound => CloudBundleError::MissingFile(name),
+        error => archive_error(error),
+    })?;
+    let mut text = String::new();
+    file.read_to_string(&mut text).map_err(|error| {
+        CloudBundleError::Unreadable {
+            file:   name,
+            reason: error.to_string(),
+        }
+    })?;
+
+    Ok(text)
+}
+
+/// Parses the connection settings of the bundle.
+///
+/// The driver requires the `host` and `port` settings, the other ones are
+/// optional.
+fn parse_config(text: &str) -> Result<CloudBundle, CloudBundleError> {
+    let invalid = |reason: &str| CloudBundleError::InvalidConfig(reason.into());
+
+    let config: serde_json::Value = serde_json::from_str(text)
+        .map_err(|error| CloudBundleError::InvalidConfig(error.to_string()))?;
+    let config = config
+        .as_object()
+        .ok_or_else(|| invalid("expected a JSON object"))?;
+
+    let host = match config.get("host") {
+        Some(serde_json::Value::String(host)) if !host.is_empty() => {
+            host.clone()
+        }
+        Some(_) => return Err(invalid("host must be a non-empty string")),
+        None => return Err(invalid("missing host")),
+    };
+    let port = match config.get("port") {
+        Some(port) => port
+            .as_u64()
+            .and_then(|port| u16::try_from(port).ok())
+            .filter(|port| *port != 0)
+            .ok_or_else(|| invalid("port must be a number from 1 to 65535"))?,
+        None => return Err(invalid("missing port")),
+    };
+    let optional = |name: &str| -> Result<Option<String>, CloudBundleError> {
+        match config.get(name) {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(serde_json::Value::String(value)) => Ok(Some(value.clone())),
+            Some(_) => Err(CloudBundleError::InvalidConfig(format!(
+                "{} must be a string",
+                name
+            ))),
+        }
+    };
+
+    Ok(CloudBundle {
+        host,
+        port,
+        username: optional("username")?,
+        keyspace: optional("keyspace")?,
+        local_dc: optional("localDC")?,
+    })
+}
+
+/// Validates that the file contains PEM certificates.
+fn validate_certificate(
+    file: &'static str,
+    text: &str,
+) -> Result<(), CloudBundleError> {
+    let invalid = |reason: &str| CloudBundleError::InvalidPem {
+        file,
+        reason: reason.to_string(),
+    };
+
+    let certs = pem_certificates(text);
+    if certs.is_empty() {
+        return Err(invalid("no PEM certificates"));
+    }
+    if !certs.iter().all(|cert| cert.is_base64()) {
+        return Err(invalid("the certificate is not base64-encoded"));
+    }
+
+    Ok(())
+}
+
+/// Validates that the file contains a PEM private key.
+fn validate_private_key(text: &str) -> Result<(), CloudBundleError> {
+    let invalid = |reason: &str| CloudBundleError::InvalidPem {
+        file:   KEY_FILE,
+        reason: reason.to_string(),
+    };
+
+    let key = pem_blocks(text)
+        .into_iter()
+        .find(|block| block.label.ends_with("PRIVATE KEY"))
+        .ok_or_else(|| invalid("no PEM private key"))?;
+    if !key.is_base64() {
+        return Err(invalid("the private key is not base64-encoded"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{
+        Cursor,
+        Write,
+    };
+
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    use super::*;
+
+    const CERT: &str = "-----BEGIN \
+                        CERTIFICATE-----\nMIIBszCCAVmgAwIBAgIU\n-----END \
+                        CERTIFICATE-----\n";
+    const KEY: &str = "-----BEGIN PRIVATE \
+                       KEY-----\nMIGHAgEAMBMGByqGSM49\n-----END PRIVATE \
+                       KEY-----\n";
+
+    /// Creates a bundle with the given files.
+    fn bundle(files: &[(&str, &str)]) -> Cursor<Vec<u8>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in files {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+
+        Cursor::new(writer.finish().unwrap().into_inner())
+    }
+
+    #[test]
+    fn test_inspect_valid_bundle() {
+        let config = r#"{
+            "host": "abc-us-east1.db.astra.datastax.com",
+            "port": 29080,
+            "keyspace": "app",
+            "localDC": "us-east1"
+        }"#;
+
+        let bundle = CloudBundle::from_reader(bundle(&[
+            (CONFIG_FILE, config),
+            (CA_CERT_FILE, CERT),
+            (CERT_FILE, CERT),
+            (KEY_FILE, KEY),
+        ]))
+        .unwrap();
+
+        assert_eq!(bundle.host(), "abc-us-east1.db.astra.datastax.com");
+        assert_eq!(bundle.port(), 29080);
+        assert_eq!(bundle.keyspace(), Some("app"));
+        assert_eq!(bundle.local_dc(), Some("us-east1"));
+        assert_eq!(bundle.username(), None);
+    }
+
+    #[test]
+    fn test_inspect_invalid_bundles() {
+        let config = r#"{ "host": "localhost", "port": 29080 }"#;
+
+        let error = CloudBundle::from_reader(bundle(&[
+            (CONFIG_FILE, config),
+            (CA_CERT_FILE, CERT),
+            (CERT_FILE, CERT),
+        ]))
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the cloud secure connection bundle has no key file"
+        );
+
+        let error = CloudBundle::from_reader(bundle(&[
+            (CONFIG_FILE, r#"{ "host": "localhost" }"#),
+            (CA_CERT_FILE, CERT),
+            (CERT_FILE, CERT),
+            (KEY_FILE, KEY),
+        ]))
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid config.json in the cloud secure connection bundle: \
+             missing port"
+        );
+
+        let error = CloudBundle::from_reader(bundle(&[
+            (CONFIG_FILE, config),
+            (CA_CERT_FILE, "not a certificate"),
+            (CERT_FILE, CERT),
+            (KEY_FILE, KEY),
+        ]))
+        .unwrap_err();
+        assert_eq!(
+            DriverError::from(error).kind,
+            DriverErrorKind::SslInvalidCert
+        );
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file(CONFIG_FILE, FileOptions::default())
+            .unwrap();
+        writer.write_all(&[0xff, 0xfe]).unwrap();
+        let reader = Cursor::new(writer.finish().unwrap().into_inner());
+        let error = CloudBundle::from_reader(reader).unwrap_err();
+        assert!(matches!(
+            error,
+            CloudBundleError::Unreadable {
+                file: CONFIG_FILE,
+                ..
+            }
+        ));
+        assert_eq!(
+            DriverError::from(error).kind,
+            DriverErrorKind::LibBadParams
+        );
+
+        let error =
+            CloudBundle::from_reader(Cursor::new(b"PK".to_vec())).unwrap_err();
+        assert!(matches!(error, CloudBundleError::Archive(_)));
+    }
+
+    #[test]
+    fn test_cluster_rejects_invalid_bundle() {
+        let path = std::env::temp_dir()
+            .join(format!("cassander-bundle-{}.zip", std::process::id()));
+        std::fs::write(&path, bundle(&[(CONFIG_FILE, "{}")]).into_inner())
+            .unwrap();
+
+        let error = crate::Cluster::new()
+            .set_cloud_secure_connection_bundle(&path)
+            .unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(error.kind, DriverErrorKind::LibBadParams);
+        assert_eq!(
+            error.message,
+            "invalid config.json in the cloud secure connection bundle: \
+             missing host"
+        );
+    }
+}
@@ -1,15 +1,33 @@
-use std::ffi::c_char;
+use std::any::Any;
+use std::ffi::{
+    c_char,
+    c_void,
+};
 use std::num::NonZeroI64;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use futures::channel::oneshot;
+use itertools::Itertools;
 
+use crate::authenticator::{
+    AuthenticatorTarget,
+    AUTHENTICATOR_CALLBACKS,
+};
 use crate::cql::CqlUuid;
 use crate::ffi::{
     cass_cluster_free,
     cass_cluster_new,
     cass_cluster_set_application_name_n,
     cass_cluster_set_application_version_n,
+    cass_cluster_set_authenticator_callbacks,
     cass_cluster_set_blacklist_dc_filtering_n,
     cass_cluster_set_blacklist_filtering_n,
     cass_cluster_set_client_id,
+    cass_cluster_set_cloud_secure_connection_bundle_n,
+    cass_cluster_set_cloud_secure_connection_bundle_no_ssl_lib_init_n,
     cass_cluster_set_connect_timeout,
     cass_cluster_set_connection_heartbeat_interval,
     cass_cluster_set_connection_idle_timeout,
@@ -18,7 +36,9 @@ use crate::ffi::{
     cass_cluster_set_contact_points_n,
     cass_cluster_set_core_connections_per_host,
     cass_cluster_set_credentials_n,
+    cass_cluster_set_execution_profile_n,
     cass_cluster_set_histogram_refresh_interval,
+    cass_cluster_set_host_listener_callback,
     cass_cluster_set_latency_aware_routing,
     cass_cluster_set_latency_aware_routing_settings,
     cass_cluster_set_load_balance_dc_aware_n,
@@ -62,41 +82,110 @@ use crate::ffi::{
     struct_CassCluster_,
 };
 use crate::future::DriverFuture;
+use crate::host_event::{
+    host_listener_callback,
+    HostListener,
+};
+use crate::trace::TraceSettings;
 use crate::{
     to_result,
+    Authenticator,
     Consistency,
+    ContactPoint,
+    ContactPointSource,
     DriverError,
     DriverErrorKind,
+    ExecutionProfile,
+    HostEventStream,
     ProtocolVersion,
     RetryPolicy,
     Session,
+    SpanStatementMode,
     Ssl,
     TimestampGen,
+    WakeDispatcher,
 };
 
-// TODO: `cass_cluster_set_authenticator_callbacks`
-// TODO: `cass_cluster_set_execution_profile`
-// TODO: `cass_cluster_set_host_listener_callback`
-// TODO: `cass_cluster_set_cloud_secure_connection_bundle_n`
-// TODO: `cass_cluster_set_cloud_secure_connection_bundle_no_ssl_lib_init_n`
-
 /// A cluster object describes the configuration of the Cassandra cluster and is
 /// used to construct a session instance.
 ///
 /// Unlike other DataStax drivers the cluster object does not maintain the
 /// control connection.
-#[repr(transparent)]
-pub struct Cluster(*mut struct_CassCluster_);
+pub struct Cluster {
+    /// The driver's cluster object.
+    inner:                *mut struct_CassCluster_,
+    /// The targets of the callbacks installed on the cluster.
+    ///
+    /// The driver copies the cluster configuration, including the callback
+    /// targets, into every session it connects. So the targets must live as
+    /// long as the cluster and all of its sessions.
+    callback_data:        Vec<CallbackData>,
+    /// The application name, the sessions report their metrics with it.
+    application_name:     Option<String>,
+    /// How the statement text is recorded in the request spans.
+    span_statement_mode:  SpanStatementMode,
+    /// How the sessions read the query traces.
+    trace_settings:       TraceSettings,
+    /// The source of the contact points resolved on every connect.
+    contact_point_source: Option<Arc<dyn ContactPointSource>>,
+    /// Wakes the tasks waiting for the requests of the sessions.
+    wake_dispatcher:      Option<Arc<dyn WakeDispatcher>>,
+    /// The size of the request queue, [`None`] if it is the default one.
+    queue_size_io:        Option<usize>,
+}
+
+/// The target of a callback installed on a [`Cluster`].
+pub(crate) type CallbackData = Arc<dyn Any + Send + Sync>;
 
 impl Cluster {
     /// Creates a new cluster object.
     pub fn new() -> Self {
-        unsafe { Cluster(cass_cluster_new()) }
+        Self {
+            inner:                unsafe { cass_cluster_new() },
+            callback_data:        Vec::new(),
+            application_name:     None,
+            span_statement_mode:  SpanStatementMode::default(),
+            trace_settings:       TraceSettings::default(),
+            contact_point_source: None,
+            wake_dispatcher:      None,
+            queue_size_io:        None,
+        }
     }
 
     /// Returns the raw pointer to the cluster object.
     pub(crate) fn inner(&self) -> *mut struct_CassCluster_ {
-        self.0
+        self.inner
+    }
+
+    /// Returns the targets of the callbacks installed on the cluster.
+    pub(crate) fn callback_data(&self) -> &[CallbackData] {
+        &self.callback_data
+    }
+
+    /// Returns the application name.
+    pub(crate) fn application_name(&self) -> Option<&str> {
+        self.application_name.as_deref()
+    }
+
+    /// Returns how the statement text is recorded in the request spans.
+    pub(crate) fn span_statement_mode(&self) -> SpanStatementMode {
+        self.span_statement_mode
+    }
+
+    /// Returns how the sessions read the query traces.
+    pub(crate) fn trace_settings(&self) -> TraceSettings {
+        self.trace_settings
+    }
+
+    /// Returns the size of the request queue, [`None`] if it is the default
+    /// one.
+    pub(crate) fn queue_size_io(&self) -> Option<usize> {
+        self.queue_size_io
+    }
+
+    /// Returns the dispatcher of the wake-ups of the sessions, if any.
+    pub(crate) fn wake_dispatcher(&self) -> Option<&Arc<dyn WakeDispatcher>> {
+        self.wake_dispatcher.as_ref()
     }
 
     /// Sets/Appends contact points.
@@ -123,6 +212,110 @@ impl Cluster {
         to_result(code)
     }
 
+    /// Sets the source of the contact points, e.g. the DNS SRV records, see
+    /// [`SrvContactPoints`](crate::SrvContactPoints).
+    ///
+    /// The source is resolved every time a session connects and its contact
+    /// points replace the ones set with [`Cluster::set_contact_points`]. The
+    /// driver supports a single port, so the contact points with a port must
+    /// all have the same port, which replaces the one set with
+    /// [`Cluster::set_port`].
+    pub fn set_contact_point_source<S>(&mut self, source: S)
+    where
+        S: ContactPointSource + 'static,
+    {
+        self.contact_point_source = Some(Arc::new(source));
+    }
+
+    /// Resolves the source of the contact points, if any, and sets the
+    /// contact points and the port of the cluster.
+    async fn resolve_contact_points(&self) -> Result<(), DriverError> {
+        let Some(source) = self.contact_point_source.clone() else {
+            return Ok(());
+        };
+
+        // The source may block, e.g. on a DNS query, so it is resolved on a
+        // separate thread.
+        let (sender, receiver) = oneshot::channel();
+        thread::spawn(move || {
+            let _ = sender.send(source.resolve());
+        });
+        let contact_points = receiver.await.map_err(|_| {
+            DriverError::with_kind(DriverErrorKind::LibHostResolution)
+        })??;
+
+        self.set_resolved_contact_points(&contact_points)
+    }
+
+    /// Resolves the source of the contact points, if any, on the current
+    /// thread, see [`Cluster::resolve_contact_points`].
+    fn resolve_contact_points_blocking(&self) -> Result<(), DriverError> {
+        match &self.contact_point_source {
+            Some(source) => {
+                self.set_resolved_contact_points(&source.resolve()?)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Sets the contact points and the port of the cluster to the resolved
+    /// ones.
+    fn set_resolved_contact_points(
+        &self,
+        contact_points: &[ContactPoint],
+    ) -> Result<(), DriverError> {
+        let mut ports: Vec<_> = contact_points
+            .iter()
+            .filter_map(|contact_point| contact_point.port)
+            .collect();
+        ports.sort_unstable();
+        ports.dedup();
+        if ports.len() > 1 {
+            return Err(DriverError::with_message(
+                DriverErrorKind::LibBadParams,
+                format!(
+                    "the contact points have different ports {}, the driver \
+                     supports a single port",
+                    ports.iter().join(", ")
+                ),
+            ));
+        }
+
+        let hosts = contact_points
+            .iter()
+            .map(|contact_point| contact_point.host.to_string())
+            .join(",");
+        // An empty string clears the previous contact points.
+        for hosts in ["", hosts.as_str()] {
+            let code = unsafe {
+                cass_cluster_set_contact_points_n(
+                    self.inner(),
+                    hosts.as_ptr() as *const c_char,
+                    hosts.len(),
+                )
+            };
+            to_result::<()>(code)?;
+        }
+        if let Some(port) = ports.first() {
+            let code =
+                unsafe { cass_cluster_set_port(self.inner(), *port as i32) };
+            to_result::<()>(code)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the dispatcher that wakes the tasks waiting for the requests of
+    /// the sessions, see [`WakeDispatcher`].
+    ///
+    /// By default, the tasks are woken on the driver I/O threads.
+    pub fn set_wake_dispatcher<D>(&mut self, dispatcher: D)
+    where
+        D: WakeDispatcher + 'static,
+    {
+        self.wake_dispatcher = Some(Arc::new(dispatcher));
+    }
+
     /// Sets the port.
     ///
     /// The default value is 9042.
@@ -219,14 +412,17 @@ impl Cluster {
         &mut self,
         queue_size: usize,
     ) -> Result<(), DriverError> {
-        let queue_size = queue_size.try_into().map_err(|_| {
+        let size = queue_size.try_into().map_err(|_| {
             DriverError::with_kind(DriverErrorKind::LibBadParams)
         })?;
 
         let code =
-            unsafe { cass_cluster_set_queue_size_io(self.inner(), queue_size) };
+            unsafe { cass_cluster_set_queue_size_io(self.inner(), size) };
+        to_result::<()>(code)?;
 
-        to_result(code)
+        self.queue_size_io = Some(queue_size);
+
+        Ok(())
     }
 
     /// Sets the size of the fixed size queue that stores events.
@@ -402,6 +598,8 @@ impl Cluster {
     /// Sets the maximum time in milliseconds to wait for tracing data to become
     /// available.
     ///
+    /// This is also the time [`Session::fetch_trace`] waits for a trace.
+    ///
     /// The default value is 15ms.
     pub fn set_tracing_max_wait_time(
         &mut self,
@@ -414,6 +612,8 @@ impl Cluster {
         unsafe {
             cass_cluster_set_tracing_max_wait_time(self.inner(), wait_time)
         };
+        self.trace_settings.max_wait_time =
+            Duration::from_millis(wait_time.into());
 
         Ok(())
     }
@@ -421,6 +621,9 @@ impl Cluster {
     /// Sets the amount of time to wait between attempts to check to see if
     /// tracing is available.
     ///
+    /// This is also the time [`Session::fetch_trace`] waits between the
+    /// attempts to read a trace.
+    ///
     /// The default value is 3ms.
     pub fn set_tracing_retry_wait_time(
         &mut self,
@@ -433,6 +636,8 @@ impl Cluster {
         unsafe {
             cass_cluster_set_tracing_retry_wait_time(self.inner(), wait_time)
         };
+        self.trace_settings.retry_wait_time =
+            Duration::from_millis(wait_time.into());
 
         Ok(())
     }
@@ -440,6 +645,8 @@ impl Cluster {
     /// Sets the consistency level to use for checking to see if tracing data is
     /// available.
     ///
+    /// [`Session::fetch_trace`] reads the traces with this consistency level.
+    ///
     /// The default value is [`Consistency::One`].
     pub fn set_tracing_consistency(
         &mut self,
@@ -451,11 +658,16 @@ impl Cluster {
                 consistency.to_driver(),
             )
         };
+        self.trace_settings.consistency = consistency;
 
         Ok(())
     }
 
     /// Sets credentials for plain text authentication.
+    ///
+    /// Use [`PlainAuthenticator`](crate::PlainAuthenticator) with
+    /// [`Cluster::set_authenticator`] to keep the password in a
+    /// [`Secret`](crate::Secret) or to authenticate on behalf of another user.
     pub fn set_credentials<A, B>(
         &mut self,
         username: A,
@@ -486,6 +698,35 @@ impl Cluster {
         Ok(())
     }
 
+    /// Sets a custom authenticator.
+    ///
+    /// The authenticator replaces the plain text authentication configured
+    /// with [`Cluster::set_credentials`] and vice versa.
+    pub fn set_authenticator<A>(
+        &mut self,
+        authenticator: A,
+    ) -> Result<(), DriverError>
+    where
+        A: Authenticator,
+    {
+        let target = Arc::new(AuthenticatorTarget::new(authenticator));
+        let data = Arc::as_ptr(&target) as *mut c_void;
+
+        let code = unsafe {
+            cass_cluster_set_authenticator_callbacks(
+                self.inner(),
+                &AUTHENTICATOR_CALLBACKS,
+                None,
+                data,
+            )
+        };
+        to_result::<()>(code)?;
+
+        self.callback_data.push(target);
+
+        Ok(())
+    }
+
     /// Configures the cluster to use round-robin load balancing.
     ///
     /// The driver discovers all nodes in a cluster and cycles through them per
@@ -1017,11 +1258,78 @@ impl Cluster {
         to_result(code)
     }
 
+    /// Sets the secure connection bundle path for processing DBaaS
+    /// credentials, e.g. the bundle of a DataStax Astra database.
+    ///
+    /// The bundle configures the contact points, the SSL and, if present, the
+    /// credentials. The contact points and the SSL must not be set
+    /// explicitly. The driver reads the bundle when a session connects.
+    ///
+    /// When the `cloud` feature is enabled, the bundle is validated with
+    /// [`CloudBundle::inspect`] first, so an invalid bundle fails with a
+    /// precise error rather than with the opaque
+    /// [`LibUnableToConnect`](DriverErrorKind::LibUnableToConnect) error on
+    /// connect.
+    ///
+    /// This function initializes the underlying SSL library, see
+    /// [`Cluster::set_cloud_secure_connection_bundle_no_ssl_lib_init`].
+    ///
+    /// [`CloudBundle::inspect`]: crate::CloudBundle::inspect
+    pub fn set_cloud_secure_connection_bundle<P>(
+        &mut self,
+        path: P,
+    ) -> Result<(), DriverError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = bundle_path(path.as_ref())?;
+        let len = path.len();
+        let ptr = path.as_ptr() as *const c_char;
+        let code = unsafe {
+            cass_cluster_set_cloud_secure_connection_bundle_n(
+                self.inner(),
+                ptr,
+                len,
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Same as [`Cluster::set_cloud_secure_connection_bundle`] but does not
+    /// initialize the underlying SSL library.
+    ///
+    /// <div class="warning">
+    /// The integrating application is responsible for initializing the
+    /// underlying SSL library, see [`Ssl::new_no_lib_init`].
+    /// </div>
+    pub fn set_cloud_secure_connection_bundle_no_ssl_lib_init<P>(
+        &mut self,
+        path: P,
+    ) -> Result<(), DriverError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = bundle_path(path.as_ref())?;
+        let len = path.len();
+        let ptr = path.as_ptr() as *const c_char;
+        let code = unsafe {
+            cass_cluster_set_cloud_secure_connection_bundle_no_ssl_lib_init_n(
+                self.inner(),
+                ptr,
+                len,
+            )
+        };
+
+        to_result(code)
+    }
+
     /// Sets the application name.
     ///
     /// This is optional; however it provides the server with the application
     /// name that can aid in debugging issues with larger clusters where there
-    /// are a lot of client (or application) connections.
+    /// are a lot of client (or application) connections. The name is also
+    /// used to label the metrics of the sessions.
     pub fn set_application_name<T>(
         &mut self,
         name: T,
@@ -1034,6 +1342,8 @@ impl Cluster {
         let ptr = name.as_ptr() as *const c_char;
         unsafe { cass_cluster_set_application_name_n(self.inner(), ptr, len) };
 
+        self.application_name = Some(name.to_string());
+
         Ok(())
     }
 
@@ -1133,14 +1443,93 @@ impl Cluster {
         Ok(())
     }
 
+    /// Sets how the statement text is recorded in the request spans.
+    ///
+    /// Every statement execution, batch execution and statement preparation
+    /// creates a `tracing` span with the `cassander::request` target. The span
+    /// fields follow the OpenTelemetry database semantic conventions, so the
+    /// spans can be exported with `tracing-opentelemetry`. The address of the
+    /// coordinator node is recorded when it is known, see
+    /// [`Coordinator::address`].
+    ///
+    /// The default value is [`SpanStatementMode::Redacted`].
+    ///
+    /// [`Coordinator::address`]: crate::Coordinator::address
+    pub fn set_span_statement_mode(
+        &mut self,
+        mode: SpanStatementMode,
+    ) -> Result<(), DriverError> {
+        self.span_statement_mode = mode;
+
+        Ok(())
+    }
+
+    /// Registers the execution profile under the given name.
+    ///
+    /// The profile is copied by the cluster, so changing it after this call
+    /// has no effect. Registering a profile under an existing name replaces
+    /// the previous one.
+    ///
+    /// The requests select the profile by name with
+    /// [`Statement::set_execution_profile`] or
+    /// [`Batch::set_execution_profile`].
+    ///
+    /// [`Statement::set_execution_profile`]: crate::Statement::set_execution_profile
+    /// [`Batch::set_execution_profile`]: crate::Batch::set_execution_profile
+    pub fn set_execution_profile<T>(
+        &mut self,
+        name: T,
+        profile: &ExecutionProfile,
+    ) -> Result<(), DriverError>
+    where
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+        let name_len = name.len();
+        let name_ptr = name.as_ptr() as *const c_char;
+        let code = unsafe {
+            cass_cluster_set_execution_profile_n(
+                self.inner(),
+                name_ptr,
+                name_len,
+                profile.inner(),
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Installs a listener of the cluster node state changes and returns the
+    /// stream of its events.
+    ///
+    /// Only the events of the sessions connected after this call are
+    /// reported. Calling this method again replaces the listener: the streams
+    /// returned by the previous calls receive the events of the sessions that
+    /// have already been connected only.
+    pub fn host_events(&mut self) -> Result<HostEventStream, DriverError> {
+        let (listener, stream) = HostListener::new();
+        let listener = Arc::new(listener);
+        let data = Arc::as_ptr(&listener) as *mut c_void;
+
+        let code = unsafe {
+            cass_cluster_set_host_listener_callback(
+                self.inner(),
+                Some(host_listener_callback),
+                data,
+            )
+        };
+        to_result::<()>(code)?;
+
+        self.callback_data.push(listener);
+
+        Ok(stream)
+    }
+
     /// Connects to the cluster and returns a session.
     pub async fn connect(self) -> Result<Session, DriverError> {
-        let session = Session::new();
-        let future =
-            unsafe { cass_session_connect(session.inner(), self.inner()) };
-        let future = DriverFuture::new(future, session);
+        self.resolve_contact_points().await?;
 
-        future.await
+        self.connect_future(None).await
     }
 
     /// Connects to the cluster and returns a session with the specified
@@ -1152,21 +1541,47 @@ impl Cluster {
     where
         T: AsRef<str>,
     {
-        let session = Session::new();
-        let keyspace = keyspace.as_ref();
-        let keyspace_len = keyspace.len();
-        let keyspace_ptr = keyspace.as_ptr() as *const c_char;
-        let future = unsafe {
-            cass_session_connect_keyspace_n(
-                session.inner(),
-                self.inner(),
-                keyspace_ptr,
-                keyspace_len,
-            )
+        self.resolve_contact_points().await?;
+
+        self.connect_future(Some(keyspace.as_ref())).await
+    }
+
+    /// Connects to the cluster blocking the current thread and returns a
+    /// session with the specified keyspace set as default, if any.
+    pub(crate) fn connect_blocking(
+        &self,
+        keyspace: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> Result<Session, DriverError> {
+        self.resolve_contact_points_blocking()?;
+
+        let future = self.connect_future(keyspace);
+        match timeout {
+            Some(timeout) => future.with_timeout(timeout).wait_for_result(),
+            None => future.wait_for_result(),
+        }
+    }
+
+    /// Starts connecting a new session with the specified keyspace set as
+    /// default, if any.
+    fn connect_future(&self, keyspace: Option<&str>) -> DriverFuture<Session> {
+        let session = Session::for_cluster(self, keyspace);
+        session.set_started();
+        let future = match keyspace {
+            Some(keyspace) => unsafe {
+                cass_session_connect_keyspace_n(
+                    session.inner(),
+                    self.inner(),
+                    keyspace.as_ptr() as *const c_char,
+                    keyspace.len(),
+                )
+            },
+            None => unsafe {
+                cass_session_connect(session.inner(), self.inner())
+            },
         };
-        let future = DriverFuture::new(future, session);
 
-        future.await
+        DriverFuture::new(future, session)
     }
 }
 
@@ -1181,3 +1596,17 @@ impl Drop for Cluster {
         unsafe { cass_cluster_free(self.inner()) }
     }
 }
+
+/// Returns the path of the cloud secure connection bundle as a string,
+/// validating the bundle if the `cloud` feature is enabled.
+fn bundle_path(path: &Path) -> Result<&str, DriverError> {
+    #[cfg(feature = "cloud")]
+    crate::CloudBundle::inspect(path)?;
+
+    path.to_str().ok_or_else(|| {
+        DriverError::with_message(
+            DriverErrorKind::LibBadParams,
+            format!("the bundle path {} is not valid UTF-8", path.display()),
+        )
+    })
+}
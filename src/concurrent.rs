@@ -0,0 +1,122 @@
+use std::future::Future;
+use std::time::Duration;
+
+use crate::timer::sleep;
+use crate::{
+    DriverError,
+    DriverErrorKind,
+};
+
+/// The default size of the request queue of the driver, see
+/// [`Cluster::set_queue_size_io`].
+///
+/// [`Cluster::set_queue_size_io`]: crate::Cluster::set_queue_size_io
+pub(crate) const DEFAULT_QUEUE_SIZE_IO: usize = 8192;
+
+/// The delay before the first retry of a request rejected because the request
+/// queue is full.
+const MIN_BACKOFF: Duration = Duration::from_millis(1);
+
+/// The maximum delay between the retries of a request rejected because the
+/// request queue is full.
+const MAX_BACKOFF: Duration = Duration::from_millis(100);
+
+/// The maximum delay between the retries of a failed request, see
+/// [`next_retry_delay`].
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Returns the delay before the next retry of a failed request: the delay
+/// doubled, up to a minute, or the delay itself if it is already longer.
+pub(crate) fn next_retry_delay(delay: Duration) -> Duration {
+    delay.saturating_mul(2).min(MAX_RETRY_DELAY).max(delay)
+}
+
+/// Sends the request until the driver accepts it, backing off exponentially
+/// while the request queue is full.
+///
+/// The driver rejects the request with
+/// [`DriverErrorKind::LibRequestQueueFull`] before sending it, so retrying it
+/// is safe even if it is not idempotent.
+pub(crate) async fn retry_on_queue_full<F, Fut, R>(
+    mut send: F,
+) -> Result<R, DriverError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<R, DriverError>>,
+{
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        match send().await {
+            Err(error)
+                if error.kind == DriverErrorKind::LibRequestQueueFull =>
+            {
+                sleep(backoff).await?;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Returns whether a request that failed with the error may succeed if
+/// retried.
+pub(crate) fn is_transient(kind: DriverErrorKind) -> bool {
+    matches!(
+        kind,
+        DriverErrorKind::ServerReadTimeout
+            | DriverErrorKind::ServerWriteTimeout
+            | DriverErrorKind::ServerUnavailable
+            | DriverErrorKind::ServerOverloaded
+            | DriverErrorKind::ServerIsBootstrapping
+            | DriverErrorKind::LibRequestTimedOut
+            | DriverErrorKind::LibNoHostsAvailable
+            | DriverErrorKind::LibUnableToConnect
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use futures::executor::block_on;
+
+    use super::*;
+
+    #[test]
+    fn test_retry_on_queue_full() {
+        let attempts = Cell::new(0);
+        let send = || {
+            attempts.set(attempts.get() + 1);
+            let attempt = attempts.get();
+            async move {
+                match attempt {
+                    1 | 2 => Err(DriverError::with_kind(
+                        DriverErrorKind::LibRequestQueueFull,
+                    )),
+                    _ => Ok(attempt),
+                }
+            }
+        };
+
+        assert_eq!(block_on(retry_on_queue_full(send)).unwrap(), 3);
+
+        let result: Result<(), _> = block_on(retry_on_queue_full(|| async {
+            Err(DriverError::with_kind(DriverErrorKind::LibRequestTimedOut))
+        }));
+        assert_eq!(
+            result.unwrap_err().kind,
+            DriverErrorKind::LibRequestTimedOut
+        );
+    }
+
+    #[test]
+    fn test_next_retry_delay() {
+        let delay = Duration::from_millis(100);
+        assert_eq!(next_retry_delay(delay), Duration::from_millis(200));
+        assert_eq!(next_retry_delay(Duration::from_secs(40)), MAX_RETRY_DELAY);
+        assert_eq!(next_retry_delay(Duration::MAX), Duration::MAX);
+
+        let delay = (0..100).fold(delay, |delay, _| next_retry_delay(delay));
+        assert_eq!(delay, MAX_RETRY_DELAY);
+    }
+}
@@ -1,12 +1,43 @@
+#[cfg(feature = "serde")]
+mod config_load_error;
+#[cfg(feature = "serde")]
+mod config_loader;
 mod contact_point;
+mod contact_point_source;
+mod credentials;
+mod dns;
 mod driver_config;
 mod driver_config_builder;
+mod driver_config_error;
+#[cfg(feature = "serde")]
+mod env;
+mod execution_profile_config;
 mod host;
+mod load_balancing_policy;
+mod retry_policy_kind;
+mod secret_source;
+mod speculative_execution_policy;
+mod ssl_config;
+mod timestamp_gen_kind;
 
 #[cfg(feature = "serde")]
 pub(crate) mod serialization;
 
+#[cfg(feature = "serde")]
+pub use config_load_error::*;
+#[cfg(feature = "serde")]
+pub use config_loader::*;
 pub use contact_point::*;
+pub use contact_point_source::*;
+pub use credentials::*;
 pub use driver_config::*;
 pub use driver_config_builder::*;
+pub use driver_config_error::*;
+pub use execution_profile_config::*;
 pub use host::*;
+pub use load_balancing_policy::*;
+pub use retry_policy_kind::*;
+pub use secret_source::*;
+pub use speculative_execution_policy::*;
+pub use ssl_config::*;
+pub use timestamp_gen_kind::*;
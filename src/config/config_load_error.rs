@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// An error that occurs when the driver configuration is loaded by the
+/// [`ConfigLoader`].
+///
+/// The error points at the source and the key of the setting that failed.
+///
+/// [`ConfigLoader`]: crate::ConfigLoader
+#[derive(Error, Debug)]
+pub enum ConfigLoadError {
+    /// The configuration file cannot be read.
+    #[error("failed to read the configuration file {}: {source}", .path.display())]
+    Io {
+        /// The path of the file.
+        path:   PathBuf,
+        /// The error that occurred.
+        source: std::io::Error,
+    },
+
+    /// The format of the configuration file is not supported.
+    #[error(
+        "the format of the configuration file {} is not supported, use a \
+         .toml, .yaml, .yml or .json file with the matching crate feature \
+         enabled",
+        .path.display()
+    )]
+    UnsupportedFormat {
+        /// The path of the file.
+        path: PathBuf,
+    },
+
+    /// A setting in the configuration file is invalid.
+    #[error(
+        "invalid setting '{}' in the configuration file {}: {message}",
+        .key.as_deref().unwrap_or("."),
+        .path.display()
+    )]
+    File {
+        /// The path of the file.
+        path:    PathBuf,
+        /// The dotted path of the setting, [`None`] if the file cannot be
+        /// parsed at all.
+        key:     Option<String>,
+        /// The description of the error.
+        message: String,
+    },
+
+    /// A setting in an environment variable is invalid.
+    #[error("invalid environment variable {variable}: {message}")]
+    Env {
+        /// The name of the variable.
+        variable: String,
+        /// The description of the error.
+        message:  String,
+    },
+}
@@ -0,0 +1,394 @@
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use crate::config::env::read_env;
+use crate::{
+    ConfigLoadError,
+    DriverConfig,
+};
+
+/// The default prefix of the environment variables.
+const DEFAULT_ENV_PREFIX: &str = "CASSANDER";
+
+/// Loads the driver configuration from layered sources.
+///
+/// The settings are read from the following sources, each overriding the
+/// previous ones:
+///
+/// 1. the configuration file, see [`ConfigLoader::file`];
+/// 2. the environment variables, see [`ConfigLoader::env`];
+/// 3. the programmatic values, see [`ConfigLoader::overrides`].
+///
+/// ```no_run
+/// use cassander::{
+///     ConfigLoader,
+///     DriverConfig,
+/// };
+///
+/// let config = ConfigLoader::new()
+///     .file("cassander.toml")
+///     .env()
+///     .overrides(DriverConfig {
+///         application_name: Some("billing".to_string()),
+///         ..Default::default()
+///     })
+///     .load()?;
+/// # Ok::<(), cassander::ConfigLoadError>(())
+/// ```
+#[derive(Default)]
+pub struct ConfigLoader {
+    /// The path of the configuration file.
+    file:       Option<PathBuf>,
+    /// The prefix of the environment variables, [`None`] if the variables are
+    /// not read.
+    env_prefix: Option<String>,
+    /// The programmatic values.
+    overrides:  Option<DriverConfig>,
+}
+
+impl ConfigLoader {
+    /// Creates a loader that reads no sources.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the settings from the given file.
+    ///
+    /// The format is chosen by the file extension: `.toml` requires the
+    /// `toml` feature, `.yaml` and `.yml` require the `yaml` feature and
+    /// `.json` requires the `json` feature. The YAML files select the enum
+    /// variants with tags, e.g. `load_balancing: !DcAware { local_dc: dc1 }`.
+    /// An unknown key fails the loading.
+    pub fn file<P>(mut self, path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.file = Some(path.into());
+
+        self
+    }
+
+    /// Reads the settings from the `CASSANDER_*` environment variables.
+    ///
+    /// The variable `CASSANDER_<SETTING>` sets the setting, e.g.
+    /// `CASSANDER_REQUEST_TIMEOUT=5s`. Lists are separated by commas, e.g.
+    /// `CASSANDER_CONTACT_POINTS=a:9042,b:9042`. The nested settings and the
+    /// settings of the enum variants are separated by double underscores,
+    /// e.g. `CASSANDER_LOAD_BALANCING__DC_AWARE__LOCAL_DC=dc1`. The names are
+    /// case-insensitive.
+    ///
+    /// A variable with the prefix that does not name a setting fails the
+    /// loading, so a misspelled setting is not ignored silently. Use
+    /// [`ConfigLoader::env_prefix`] if the prefix is taken by other variables.
+    pub fn env(self) -> Self {
+        self.env_prefix(DEFAULT_ENV_PREFIX)
+    }
+
+    /// Reads the settings from the environment variables with the given
+    /// prefix instead of `CASSANDER`, see [`ConfigLoader::env`].
+    pub fn env_prefix<T>(mut self, prefix: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.env_prefix = Some(prefix.into());
+
+        self
+    }
+
+    /// Overrides the settings read from the other sources with the ones set
+    /// in the given configuration, see [`DriverConfig::merge`].
+    pub fn overrides(mut self, config: DriverConfig) -> Self {
+        self.overrides = Some(config);
+
+        self
+    }
+
+    /// Loads the configuration.
+    pub fn load(self) -> Result<DriverConfig, ConfigLoadError> {
+        let variables = std::env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        });
+
+        self.load_from(variables)
+    }
+
+    /// Loads the configuration with the given environment variables.
+    fn load_from<I>(self, variables: I) -> Result<DriverConfig, ConfigLoadError>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut config = match &self.file {
+            Some(path) => read_file(path)?,
+            None => DriverConfig::default(),
+        };
+
+        if let Some(prefix) = &self.env_prefix {
+            let env = read_env(prefix, variables).map_err(|error| {
+                ConfigLoadError::Env {
+                    variable: error.variable.unwrap_or_default(),
+                    message:  error.message,
+                }
+            })?;
+            config.merge(env);
+        }
+
+        if let Some(overrides) = self.overrides {
+            config.merge(overrides);
+        }
+
+        Ok(config)
+    }
+}
+
+/// Reads the configuration from the file.
+fn read_file(path: &Path) -> Result<DriverConfig, ConfigLoadError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_lowercase);
+
+    match extension.as_deref() {
+        #[cfg(feature = "toml")]
+        Some("toml") => {
+            let text = read_text(path)?;
+
+            deserialize(path, toml::Deserializer::new(&text))
+        }
+        #[cfg(feature = "yaml")]
+        Some("yaml" | "yml") => {
+            let text = read_text(path)?;
+
+            deserialize(path, serde_yaml::Deserializer::from_str(&text))
+        }
+        #[cfg(feature = "json")]
+        Some("json") => {
+            let text = read_text(path)?;
+            let mut deserializer = serde_json::Deserializer::from_str(&text);
+            let config = deserialize(path, &mut deserializer)?;
+            deserializer
+                .end()
+                .map_err(|error| file_error(path, None, error))?;
+
+            Ok(config)
+        }
+        _ => Err(ConfigLoadError::UnsupportedFormat {
+            path: path.to_path_buf(),
+        }),
+    }
+}
+
+/// Reads the text of the file.
+#[cfg(any(feature = "toml", feature = "yaml", feature = "json"))]
+fn read_text(path: &Path) -> Result<String, ConfigLoadError> {
+    std::fs::read_to_string(path).map_err(|source| ConfigLoadError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Deserializes the configuration tracking the key of the failed setting.
+#[cfg(any(feature = "toml", feature = "yaml", feature = "json"))]
+fn deserialize<'de, D>(
+    path: &Path,
+    deserializer: D,
+) -> Result<DriverConfig, ConfigLoadError>
+where
+    D: serde::Deserializer<'de>,
+{
+    serde_path_to_error::deserialize(deserializer).map_err(|error| {
+        let key = error.path().to_string();
+        let key = (key != ".").then_some(key);
+
+        file_error(path, key, error.into_inner())
+    })
+}
+
+/// Returns the error of the setting in the file.
+#[cfg(any(feature = "toml", feature = "yaml", feature = "json"))]
+fn file_error<E>(path: &Path, key: Option<String>, error: E) -> ConfigLoadError
+where
+    E: std::fmt::Display,
+{
+    ConfigLoadError::File {
+        path: path.to_path_buf(),
+        key,
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes the file to a temporary directory and returns its path.
+    #[cfg(any(feature = "toml", feature = "yaml", feature = "json"))]
+    fn write_file(name: &str, text: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("cassander-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, text).unwrap();
+
+        path
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_load_layers() {
+        use std::time::Duration;
+
+        let path = write_file(
+            "layers.toml",
+            r#"
+                contact_points = ["10.0.0.1:9042"]
+                request_timeout = "5s"
+                application_name = "file"
+
+                [load_balancing.DcAware]
+                local_dc = "dc1"
+            "#,
+        );
+
+        let config = ConfigLoader::new()
+            .file(&path)
+            .env()
+            .overrides(DriverConfig {
+                application_name: Some("code".to_string()),
+                ..Default::default()
+            })
+            .load_from(vec![
+                ("CASSANDER_REQUEST_TIMEOUT".to_string(), "2s".to_string()),
+                ("CASSANDER_APPLICATION_NAME".to_string(), "env".to_string()),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            config.contact_points,
+            vec!["10.0.0.1:9042".parse().unwrap()]
+        );
+        assert_eq!(config.request_timeout, Some(Duration::from_secs(2)));
+        assert_eq!(config.application_name.as_deref(), Some("code"));
+        assert!(config.load_balancing.is_some());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_load_credentials_profiles_and_bundle() {
+        use std::time::Duration;
+
+        use crate::{
+            Consistency,
+            SecretSource,
+        };
+
+        let path = write_file(
+            "deployment.toml",
+            r#"
+                cloud_secure_connect_bundle = "/etc/cassander/bundle.zip"
+
+                [credentials]
+                username = "cassandra"
+                password = { Env = "CASSANDRA_PASSWORD" }
+
+                [profiles.analytics]
+                request_timeout = "60s"
+                consistency = "LocalQuorum"
+            "#,
+        );
+
+        let config = ConfigLoader::new().file(&path).load().unwrap();
+
+        assert_eq!(
+            config.cloud_secure_connect_bundle,
+            Some(PathBuf::from("/etc/cassander/bundle.zip"))
+        );
+        assert_eq!(
+            config.credentials,
+            Some(crate::Credentials {
+                username: "cassandra".to_string(),
+                password: SecretSource::Env("CASSANDRA_PASSWORD".to_string()),
+            })
+        );
+        let analytics = &config.profiles["analytics"];
+        assert_eq!(analytics.request_timeout, Some(Duration::from_secs(60)));
+        assert_eq!(analytics.consistency, Some(Consistency::LocalQuorum));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_load_file_error_points_at_the_key() {
+        let path = write_file(
+            "invalid.json",
+            r#"{ "latency_aware_routing": { "min_measured": -1 } }"#,
+        );
+
+        let error = ConfigLoader::new().file(&path).load().err().unwrap();
+
+        match error {
+            ConfigLoadError::File {
+                key, ..
+            } => assert_eq!(
+                key.as_deref(),
+                Some("latency_aware_routing.min_measured")
+            ),
+            error => panic!("unexpected error: {}", error),
+        }
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_load_file_rejects_unknown_keys() {
+        let path = write_file(
+            "unknown.json",
+            r#"{ "latency_aware_routing": { "exclusion_treshold": 2.0 } }"#,
+        );
+
+        let error = ConfigLoader::new().file(&path).load().err().unwrap();
+
+        match error {
+            ConfigLoadError::File {
+                key, ..
+            } => assert_eq!(
+                key.as_deref(),
+                Some("latency_aware_routing.exclusion_treshold")
+            ),
+            error => panic!("unexpected error: {}", error),
+        }
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_load_yaml() {
+        let path = write_file(
+            "config.yml",
+            "contact_points: [\"10.0.0.1\"]\nspeculative_execution: \
+             !Constant\n  delay: 100ms\n  max_executions: 2\n",
+        );
+
+        let config = match ConfigLoader::new().file(&path).load() {
+            Ok(config) => config,
+            Err(error) => panic!("{}", error),
+        };
+
+        assert_eq!(
+            config.speculative_execution,
+            Some(crate::SpeculativeExecutionPolicy::Constant {
+                delay:          std::time::Duration::from_millis(100),
+                max_executions: 2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_load_unsupported_format() {
+        let error = ConfigLoader::new()
+            .file("cassander.ini")
+            .load()
+            .err()
+            .unwrap();
+
+        assert!(matches!(error, ConfigLoadError::UnsupportedFormat { .. }));
+    }
+}
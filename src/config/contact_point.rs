@@ -2,6 +2,10 @@ use std::fmt::{
     Display,
     Formatter,
 };
+use std::net::{
+    IpAddr,
+    Ipv6Addr,
+};
 use std::str::FromStr;
 
 use thiserror::Error;
@@ -62,9 +66,12 @@ impl From<Host> for ContactPoint {
 impl Display for ContactPoint {
     /// Writes the contact point to the formatter.
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match &self.port {
-            Some(port) => write!(f, "{}:{}", self.host, port),
-            None => write!(f, "{}", self.host),
+        match (&self.host, &self.port) {
+            (Host::IpAddr(IpAddr::V6(ip_addr)), Some(port)) => {
+                write!(f, "[{}]:{}", ip_addr, port)
+            }
+            (host, Some(port)) => write!(f, "{}:{}", host, port),
+            (host, None) => write!(f, "{}", host),
         }
     }
 }
@@ -104,25 +111,101 @@ impl Default for ContactPoint {
     }
 }
 
+/// Parses a contact point in the `host`, `host:port`, `ipv6`, `[ipv6]` or
+/// `[ipv6]:port` form.
 fn parse_contact_point<S>(s: S) -> Result<ContactPoint, ContactPointParseError>
 where
     S: Into<String>,
 {
     let s = s.into();
+    let error = || ContactPointParseError(s.clone());
+    let parse_port = |port: &str| port.parse().map_err(|_| error());
+
+    // An IPv6 address enclosed in brackets as per RFC 3986.
+    if let Some(rest) = s.strip_prefix('[') {
+        let (ip_addr, rest) = rest.split_once(']').ok_or_else(error)?;
+        let ip_addr: Ipv6Addr = ip_addr.parse().map_err(|_| error())?;
+        let port = match rest {
+            "" => None,
+            rest => {
+                Some(parse_port(rest.strip_prefix(':').ok_or_else(error)?)?)
+            }
+        };
+
+        return Ok(ContactPoint {
+            host: Host::IpAddr(ip_addr.into()),
+            port,
+        });
+    }
 
-    s.rsplit_once(':')
-        .map(|(host, port)| {
-            let host = host
-                .parse()
-                .map_err(|_| ContactPointParseError(s.clone()))?;
-            let port = port
-                .parse()
-                .map_err(|_| ContactPointParseError(s.clone()))?;
-            Ok(ContactPoint::new(host, port))
-        })
-        .unwrap_or_else(|| {
-            let host =
-                s.parse().map_err(|_| ContactPointParseError(s.clone()))?;
+    match s.rsplit_once(':') {
+        // An IPv6 address without a port, the port of an IPv6 address must be
+        // separated with the brackets.
+        Some((host, _)) if host.contains(':') => {
+            let ip_addr: Ipv6Addr = s.parse().map_err(|_| error())?;
+            Ok(ContactPoint::with_host(Host::IpAddr(ip_addr.into())))
+        }
+        Some((host, port)) => {
+            let host = host.parse().map_err(|_| error())?;
+            Ok(ContactPoint::new(host, parse_port(port)?))
+        }
+        None => {
+            let host = s.parse().map_err(|_| error())?;
             Ok(ContactPoint::with_host(host))
-        })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_contact_point() {
+        let ipv6: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        for (s, host, port) in [
+            (
+                "10.0.0.1",
+                Host::IpAddr(Ipv4Addr::new(10, 0, 0, 1).into()),
+                None,
+            ),
+            (
+                "10.0.0.1:9042",
+                Host::IpAddr(Ipv4Addr::new(10, 0, 0, 1).into()),
+                Some(9042),
+            ),
+            ("::1", Host::IpAddr(Ipv6Addr::LOCALHOST.into()), None),
+            ("[::1]", Host::IpAddr(Ipv6Addr::LOCALHOST.into()), None),
+            ("2001:db8::1", Host::IpAddr(ipv6.into()), None),
+            ("[2001:db8::1]:9042", Host::IpAddr(ipv6.into()), Some(9042)),
+            (
+                "cassandra.example.internal:9043",
+                Host::Domain("cassandra.example.internal".parse().unwrap()),
+                Some(9043),
+            ),
+        ] {
+            assert_eq!(
+                s.parse(),
+                Ok(ContactPoint {
+                    host,
+                    port
+                }),
+                "{}",
+                s
+            );
+        }
+
+        for s in ["[::1", "[::1]9042", "[10.0.0.1]:9042", "::1:x:9042", "a:b"] {
+            assert!(s.parse::<ContactPoint>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_display_contact_point() {
+        for s in ["10.0.0.1:9042", "[2001:db8::1]:9042", "2001:db8::1"] {
+            assert_eq!(s.parse::<ContactPoint>().unwrap().to_string(), s);
+        }
+    }
 }
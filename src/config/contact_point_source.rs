@@ -0,0 +1,630 @@
+use std::io::{
+    Read,
+    Write,
+};
+use std::net::{
+    IpAddr,
+    Ipv4Addr,
+    SocketAddr,
+    TcpStream,
+    UdpSocket,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::dns::{
+    decode_srv_response,
+    encode_srv_query,
+    random_u64,
+    SrvResponse,
+};
+use crate::{
+    ContactPoint,
+    DriverError,
+    DriverErrorKind,
+};
+
+/// The path of the resolver configuration on Unix systems.
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+
+/// The port of the DNS servers.
+const DNS_PORT: u16 = 53;
+
+/// The maximum size of a DNS response over UDP.
+const MAX_UDP_RESPONSE_LEN: usize = 512;
+
+/// The default minimum number of dots in a name for it to be queried as is
+/// before the search domains are appended to it.
+const DEFAULT_NDOTS: usize = 1;
+
+/// A source of the contact points of a cluster.
+///
+/// The source is resolved every time a session connects, see
+/// [`Cluster::set_contact_point_source`], so the contact points follow the
+/// changes of the source, e.g. of the DNS records.
+///
+/// [`Cluster::set_contact_point_source`]: crate::Cluster::set_contact_point_source
+pub trait ContactPointSource: Send + Sync {
+    /// Returns the contact points.
+    ///
+    /// The call may block, e.g. on a DNS query.
+    fn resolve(&self) -> Result<Vec<ContactPoint>, DriverError>;
+}
+
+impl ContactPointSource for Vec<ContactPoint> {
+    /// Returns the static list of contact points.
+    fn resolve(&self) -> Result<Vec<ContactPoint>, DriverError> {
+        Ok(self.clone())
+    }
+}
+
+/// A DNS SRV record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrvRecord {
+    /// The priority of the target, the lower the value the more preferred the
+    /// target.
+    pub priority: u16,
+    /// The relative weight of the targets with the same priority.
+    pub weight:   u16,
+    /// The port of the service on the target.
+    pub port:     u16,
+    /// The domain name of the target.
+    pub target:   String,
+}
+
+/// Resolves the DNS SRV records.
+pub trait SrvResolver: Send + Sync {
+    /// Returns the SRV records of the name, e.g. `_cql._tcp.example.internal`.
+    fn resolve_srv(&self, name: &str) -> Result<Vec<SrvRecord>, DriverError>;
+}
+
+/// The contact points listed in the DNS SRV records of a name, e.g.
+/// `_cql._tcp.example.internal`.
+///
+/// The contact points are ordered by the priority of the records, the more
+/// preferred first, and get the ports of the records. The records with the
+/// same priority are ordered by a weighted random selection as described in
+/// RFC 2782, so the connections are spread across the targets in proportion
+/// to their weights.
+#[derive(Clone)]
+pub struct SrvContactPoints {
+    /// The name of the SRV records.
+    name:     String,
+    /// The resolver of the records.
+    resolver: Arc<dyn SrvResolver>,
+}
+
+impl SrvContactPoints {
+    /// Creates the source that resolves the SRV records with the system DNS
+    /// servers, see [`DnsResolver::system`].
+    pub fn new<T>(name: T) -> Result<Self, DriverError>
+    where
+        T: Into<String>,
+    {
+        Ok(Self::with_resolver(name, DnsResolver::system()?))
+    }
+
+    /// Creates the source that resolves the SRV records with the given
+    /// resolver.
+    pub fn with_resolver<T, R>(name: T, resolver: R) -> Self
+    where
+        T: Into<String>,
+        R: SrvResolver + 'static,
+    {
+        Self {
+            name:     name.into(),
+            resolver: Arc::new(resolver),
+        }
+    }
+}
+
+impl ContactPointSource for SrvContactPoints {
+    fn resolve(&self) -> Result<Vec<ContactPoint>, DriverError> {
+        let records = self.resolver.resolve_srv(&self.name)?;
+        let records = weighted_order(records, |bound| {
+            (random_u64() % (u64::from(bound) + 1)) as u32
+        });
+
+        let contact_points = records
+            .into_iter()
+            // The `.` target means the service is not available.
+            .filter(|record| !record.target.is_empty() && record.target != ".")
+            .map(|record| {
+                let host = record.target.trim_end_matches('.');
+                let host = host.parse().map_err(|_| {
+                    DriverError::with_message(
+                        DriverErrorKind::LibHostResolution,
+                        format!(
+                            "the SRV record of {} has an invalid target {}",
+                            self.name, record.target
+                        ),
+                    )
+                })?;
+
+                Ok(ContactPoint::new(host, record.port))
+            })
+            .collect::<Result<Vec<_>, DriverError>>()?;
+
+        if contact_points.is_empty() {
+            return Err(DriverError::with_message(
+                DriverErrorKind::LibHostResolution,
+                format!("no SRV records found for {}", self.name),
+            ));
+        }
+
+        Ok(contact_points)
+    }
+}
+
+/// Orders the records by their priorities and the records with the same
+/// priority by the weighted random selection of RFC 2782.
+///
+/// `random` returns a random number between 0 and the given bound, both
+/// included.
+fn weighted_order<F>(
+    mut records: Vec<SrvRecord>,
+    mut random: F,
+) -> Vec<SrvRecord>
+where
+    F: FnMut(u32) -> u32,
+{
+    // The records with a zero weight come first, so that they have a small
+    // chance to be selected.
+    records.sort_by_key(|record| (record.priority, record.weight != 0));
+
+    let mut ordered = Vec::with_capacity(records.len());
+    let mut records = records.into_iter().peekable();
+    while let Some(first) = records.next() {
+        let mut group = vec![first];
+        while let Some(record) =
+            records.next_if(|record| record.priority == group[0].priority)
+        {
+            group.push(record);
+        }
+
+        while !group.is_empty() {
+            let total: u32 =
+                group.iter().map(|record| u32::from(record.weight)).sum();
+            let selected = random(total);
+            let mut running = 0;
+            let index = group
+                .iter()
+                .position(|record| {
+                    running += u32::from(record.weight);
+                    running >= selected
+                })
+                .unwrap_or(0);
+            ordered.push(group.remove(index));
+        }
+    }
+
+    ordered
+}
+
+/// A minimal DNS client that resolves the SRV records.
+///
+/// The queries are sent over UDP to the name servers in turn and are retried
+/// over TCP if a response is truncated. Every query has a random transaction
+/// identifier and the responses with another identifier are ignored.
+///
+/// The names with fewer dots than the `ndots` option are tried with the
+/// search domains appended first, the other names are tried as they are
+/// first, as the system resolver does. The names ending with a dot are never
+/// searched.
+#[derive(Debug, Clone)]
+pub struct DnsResolver {
+    /// The addresses of the name servers.
+    name_servers:   Vec<SocketAddr>,
+    /// The domains appended to the names that are not fully qualified.
+    search_domains: Vec<String>,
+    /// The minimum number of dots in a name for it to be tried as it is
+    /// before the search domains are appended to it.
+    ndots:          usize,
+    /// The time to wait for a response of a name server.
+    timeout:        Duration,
+}
+
+impl DnsResolver {
+    /// Creates a resolver that queries the given name servers.
+    pub fn new(name_servers: Vec<SocketAddr>) -> Self {
+        Self {
+            name_servers,
+            search_domains: Vec::new(),
+            ndots: DEFAULT_NDOTS,
+            timeout: Duration::from_secs(2),
+        }
+    }
+
+    /// Creates a resolver that queries the name servers listed in
+    /// `/etc/resolv.conf`, with the search domains and the `ndots` option of
+    /// the file.
+    pub fn system() -> Result<Self, DriverError> {
+        let conf = std::fs::read_to_string(RESOLV_CONF).map_err(|error| {
+            DriverError::with_message(
+                DriverErrorKind::LibHostResolution,
+                format!("failed to read {}: {}", RESOLV_CONF, error),
+            )
+        })?;
+        let name_servers: Vec<_> = conf
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("nameserver"))
+            .filter_map(|address| address.trim().parse::<IpAddr>().ok())
+            .map(|address| SocketAddr::new(address, DNS_PORT))
+            .collect();
+
+        if name_servers.is_empty() {
+            return Err(DriverError::with_message(
+                DriverErrorKind::LibHostResolution,
+                format!("no name servers found in {}", RESOLV_CONF),
+            ));
+        }
+
+        // The last `search` or `domain` line wins, as with the system
+        // resolver.
+        let mut search_domains = Vec::new();
+        let mut ndots = DEFAULT_NDOTS;
+        for line in conf.lines() {
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("search" | "domain") => {
+                    search_domains = words.map(str::to_string).collect();
+                }
+                Some("options") => {
+                    if let Some(value) = words
+                        .find_map(|option| option.strip_prefix("ndots:"))
+                        .and_then(|value| value.parse().ok())
+                    {
+                        ndots = value;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self::new(name_servers)
+            .with_search_domains(search_domains)
+            .with_ndots(ndots))
+    }
+
+    /// Sets the domains appended to the names that are not fully qualified.
+    ///
+    /// The default value is no domains.
+    pub fn with_search_domains(mut self, domains: Vec<String>) -> Self {
+        self.search_domains = domains;
+
+        self
+    }
+
+    /// Sets the minimum number of dots in a name for it to be tried as it is
+    /// before the search domains are appended to it.
+    ///
+    /// The default value is 1.
+    pub fn with_ndots(mut self, ndots: usize) -> Self {
+        self.ndots = ndots;
+
+        self
+    }
+
+    /// Returns the names to query for the given name, in order.
+    fn candidate_names(&self, name: &str) -> Vec<String> {
+        if name.ends_with('.') {
+            return vec![name.to_string()];
+        }
+
+        let searched = self
+            .search_domains
+            .iter()
+            .map(|domain| format!("{}.{}", name, domain.trim_end_matches('.')));
+        if name.matches('.').count() >= self.ndots {
+            std::iter::once(name.to_string()).chain(searched).collect()
+        } else {
+            searched.chain(std::iter::once(name.to_string())).collect()
+        }
+    }
+
+    /// Sets the time to wait for a response of a name server.
+    ///
+    /// The default value is 2 seconds.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+
+        self
+    }
+
+    /// Queries the name server over UDP, then over TCP if the response is
+    /// truncated.
+    fn query(
+        &self,
+        name_server: SocketAddr,
+        id: u16,
+        query: &[u8],
+    ) -> std::io::Result<Result<Vec<SrvRecord>, String>> {
+        let local: SocketAddr = match name_server {
+            SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+            SocketAddr::V6(_) => (std::net::Ipv6Addr::UNSPECIFIED, 0).into(),
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.set_read_timeout(Some(self.timeout))?;
+        socket.connect(name_server)?;
+        socket.send(query)?;
+
+        let mut response = [0u8; MAX_UDP_RESPONSE_LEN];
+        let len = loop {
+            let len = socket.recv(&mut response)?;
+            // A response to another query, e.g. a late response to a
+            // previous one or a spoofed one, is ignored.
+            if response[..len].starts_with(&id.to_be_bytes()) {
+                break len;
+            }
+        };
+        match decode_srv_response(id, &response[..len]) {
+            Ok(SrvResponse::Truncated) => {}
+            Ok(SrvResponse::Records(records)) => return Ok(Ok(records)),
+            Err(error) => return Ok(Err(error)),
+        }
+
+        let mut stream =
+            TcpStream::connect_timeout(&name_server, self.timeout)?;
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        stream.write_all(&(query.len() as u16).to_be_bytes())?;
+        stream.write_all(query)?;
+
+        let mut len = [0u8; 2];
+        stream.read_exact(&mut len)?;
+        let mut response = vec![0u8; u16::from_be_bytes(len) as usize];
+        stream.read_exact(&mut response)?;
+
+        Ok(match decode_srv_response(id, &response) {
+            Ok(SrvResponse::Records(records)) => Ok(records),
+            Ok(SrvResponse::Truncated) => {
+                Err("the response is truncated over TCP".to_string())
+            }
+            Err(error) => Err(error),
+        })
+    }
+}
+
+impl SrvResolver for DnsResolver {
+    fn resolve_srv(&self, name: &str) -> Result<Vec<SrvRecord>, DriverError> {
+        let error = |reason: String| {
+            DriverError::with_message(
+                DriverErrorKind::LibHostResolution,
+                format!(
+                    "failed to resolve the SRV records of {}: {}",
+                    name, reason
+                ),
+            )
+        };
+
+        if self.name_servers.is_empty() {
+            return Err(error("no name servers".to_string()));
+        }
+
+        let mut failures = Vec::new();
+        for candidate in self.candidate_names(name) {
+            let mut candidate_failures = Vec::new();
+            for name_server in &self.name_servers {
+                let id = random_u64() as u16;
+                let query = encode_srv_query(id, &candidate).map_err(error)?;
+                let reason = match self.query(*name_server, id, &query) {
+                    Ok(Ok(records)) if records.is_empty() => {
+                        // The name has no records, the next one is tried.
+                        candidate_failures.clear();
+                        break;
+                    }
+                    Ok(Ok(records)) => return Ok(records),
+                    Ok(Err(reason)) => reason,
+                    Err(reason) => reason.to_string(),
+                };
+                candidate_failures.push(format!(
+                    "{} at {}: {}",
+                    candidate, name_server, reason
+                ));
+            }
+            failures.extend(candidate_failures);
+        }
+
+        if failures.is_empty() {
+            // No candidate name has records.
+            Ok(Vec::new())
+        } else {
+            Err(error(failures.join("; ")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::config::dns::tests::encode_srv_response;
+
+    /// Starts a name server stand-in that answers the queries of the given
+    /// name with the given records and the other queries with no records.
+    ///
+    /// Each answer is preceded by a response with another transaction
+    /// identifier that the client must ignore.
+    fn name_server(name: &'static str, records: Vec<SrvRecord>) -> SocketAddr {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        thread::spawn(move || loop {
+            let mut query = [0u8; MAX_UDP_RESPONSE_LEN];
+            let (len, client) = socket.recv_from(&mut query).unwrap();
+            let query = &query[..len];
+            let answer = if query_name(query) == name {
+                &records[..]
+            } else {
+                &[]
+            };
+
+            let mut decoy = encode_srv_response(query, &[]);
+            decoy[0] ^= 0xFF;
+            socket.send_to(&decoy, client).unwrap();
+            let response = encode_srv_response(query, answer);
+            socket.send_to(&response, client).unwrap();
+        });
+
+        address
+    }
+
+    /// Returns the name of the question of the query.
+    fn query_name(query: &[u8]) -> String {
+        let mut labels = Vec::new();
+        let mut position = 12;
+        while query[position] != 0 {
+            let len = query[position] as usize;
+            labels.push(
+                String::from_utf8_lossy(
+                    &query[position + 1..position + 1 + len],
+                )
+                .into_owned(),
+            );
+            position += 1 + len;
+        }
+
+        labels.join(".")
+    }
+
+    fn record(
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: &str,
+    ) -> SrvRecord {
+        SrvRecord {
+            priority,
+            weight,
+            port,
+            target: target.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_srv_contact_points() {
+        let address = name_server(
+            "_cql._tcp.example.internal",
+            vec![
+                record(20, 0, 9042, "node3.example.internal"),
+                record(10, 10, 9042, "node2.example.internal"),
+                record(10, 50, 9042, "node1.example.internal"),
+            ],
+        );
+        let source = SrvContactPoints::with_resolver(
+            "_cql._tcp.example.internal",
+            DnsResolver::new(vec![address]),
+        );
+
+        let contact_points: Vec<_> = source
+            .resolve()
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        // The records with the same priority are in a random order.
+        assert_eq!(
+            contact_points[..2].iter().sorted().collect::<Vec<_>>(),
+            vec!["node1.example.internal:9042", "node2.example.internal:9042"]
+        );
+        assert_eq!(contact_points[2], "node3.example.internal:9042");
+    }
+
+    #[test]
+    fn test_srv_contact_points_with_search_domains() {
+        let address = name_server(
+            "_cql._tcp.cassandra.example.internal",
+            vec![record(10, 0, 9142, "node1.example.internal")],
+        );
+        let resolver = DnsResolver::new(vec![address])
+            .with_search_domains(vec!["example.internal".to_string()]);
+        let source =
+            SrvContactPoints::with_resolver("_cql._tcp.cassandra", resolver);
+
+        let contact_points = source.resolve().unwrap();
+
+        assert_eq!(
+            contact_points,
+            vec!["node1.example.internal:9142".parse().unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_candidate_names() {
+        let resolver = DnsResolver::new(Vec::new())
+            .with_search_domains(vec![
+                "a.internal".to_string(),
+                "b.internal.".to_string(),
+            ])
+            .with_ndots(2);
+
+        assert_eq!(
+            resolver.candidate_names("_cql._tcp.cassandra"),
+            vec![
+                "_cql._tcp.cassandra",
+                "_cql._tcp.cassandra.a.internal",
+                "_cql._tcp.cassandra.b.internal",
+            ]
+        );
+        assert_eq!(
+            resolver.candidate_names("_cql.cassandra"),
+            vec![
+                "_cql.cassandra.a.internal",
+                "_cql.cassandra.b.internal",
+                "_cql.cassandra",
+            ]
+        );
+        assert_eq!(
+            resolver.candidate_names("_cql.cassandra."),
+            vec!["_cql.cassandra."]
+        );
+    }
+
+    #[test]
+    fn test_weighted_order() {
+        let records = vec![
+            record(20, 5, 9042, "node4"),
+            record(10, 10, 9042, "node2"),
+            record(10, 50, 9042, "node3"),
+            record(10, 0, 9042, "node1"),
+        ];
+        // The running sums of the weights are 0, 10, 60 for the first pick,
+        // then 0, 10 and then 10.
+        let mut picks = vec![60, 0, 5, 3].into_iter();
+        let mut bounds = Vec::new();
+
+        let ordered = weighted_order(records, |bound| {
+            bounds.push(bound);
+            picks.next().unwrap()
+        });
+
+        assert_eq!(
+            ordered
+                .iter()
+                .map(|record| record.target.as_str())
+                .collect::<Vec<_>>(),
+            vec!["node3", "node1", "node2", "node4"]
+        );
+        assert_eq!(bounds, vec![60, 10, 10, 5]);
+    }
+
+    #[test]
+    fn test_srv_contact_points_without_records() {
+        let address = name_server("_cql._tcp.example.internal", Vec::new());
+        let source = SrvContactPoints::with_resolver(
+            "_cql._tcp.example.internal",
+            DnsResolver::new(vec![address]),
+        );
+
+        let error = source.resolve().unwrap_err();
+
+        assert_eq!(error.kind, DriverErrorKind::LibHostResolution);
+        assert_eq!(
+            error.message,
+            "no SRV records found for _cql._tcp.example.internal"
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use crate::SecretSource;
+
+/// The credentials of the plain text authentication.
+///
+/// The password is read from its source when the cluster is created, see
+/// [`Cluster::set_credentials`](crate::Cluster::set_credentials).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub struct Credentials {
+    /// The name of the user.
+    pub username: String,
+    /// Where the password of the user is read from.
+    pub password: SecretSource,
+}
@@ -0,0 +1,279 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{
+    BuildHasher,
+    Hasher,
+};
+
+use crate::SrvRecord;
+
+/// The type of the SRV resource records.
+const TYPE_SRV: u16 = 33;
+
+/// The Internet class of the resource records.
+const CLASS_IN: u16 = 1;
+
+/// The "recursion desired" flag of a query.
+const FLAG_RD: u16 = 0x0100;
+
+/// The "response" flag of a message.
+const FLAG_QR: u16 = 0x8000;
+
+/// The "truncated" flag of a response.
+const FLAG_TC: u16 = 0x0200;
+
+/// The mask of the response code.
+const RCODE_MASK: u16 = 0x000F;
+
+/// The response code of a name that does not exist.
+const RCODE_NXDOMAIN: u16 = 3;
+
+/// The size of the message header.
+const HEADER_LEN: usize = 12;
+
+/// The maximum number of the compression pointers followed in a single name,
+/// guards against the pointer loops.
+const MAX_POINTERS: usize = 64;
+
+/// The result of decoding a response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SrvResponse {
+    /// The records of the answer section.
+    Records(Vec<SrvRecord>),
+    /// The response was truncated, the query must be retried over TCP.
+    Truncated,
+}
+
+/// Returns a random number.
+///
+/// The standard library seeds the keys of every [`RandomState`] from the
+/// operating system randomness, so the hash of an empty input is random
+/// enough for the transaction identifiers and the weighted SRV ordering,
+/// without a dependency on a random number generator.
+pub(crate) fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// Encodes the query of the SRV records of the name.
+pub(crate) fn encode_srv_query(id: u16, name: &str) -> Result<Vec<u8>, String> {
+    let mut query = Vec::with_capacity(HEADER_LEN + name.len() + 6);
+    for value in [id, FLAG_RD, 1, 0, 0, 0] {
+        query.extend_from_slice(&value.to_be_bytes());
+    }
+
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(format!("invalid name {}", name));
+        }
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    query.extend_from_slice(&TYPE_SRV.to_be_bytes());
+    query.extend_from_slice(&CLASS_IN.to_be_bytes());
+
+    Ok(query)
+}
+
+/// Decodes the response to the query with the given identifier.
+pub(crate) fn decode_srv_response(
+    id: u16,
+    response: &[u8],
+) -> Result<SrvResponse, String> {
+    let mut reader = Reader {
+        message:  response,
+        position: 0,
+    };
+
+    if reader.u16()? != id {
+        return Err("the response does not match the query".to_string());
+    }
+    let flags = reader.u16()?;
+    if flags & FLAG_QR == 0 {
+        return Err("the message is not a response".to_string());
+    }
+    if flags & FLAG_TC != 0 {
+        return Ok(SrvResponse::Truncated);
+    }
+    match flags & RCODE_MASK {
+        0 => {}
+        RCODE_NXDOMAIN => return Ok(SrvResponse::Records(Vec::new())),
+        rcode => return Err(format!("the server failed with code {}", rcode)),
+    }
+
+    let questions = reader.u16()?;
+    let answers = reader.u16()?;
+    reader.skip(4)?;
+
+    for _ in 0..questions {
+        reader.name()?;
+        reader.skip(4)?;
+    }
+
+    let mut records = Vec::new();
+    for _ in 0..answers {
+        reader.name()?;
+        let record_type = reader.u16()?;
+        let class = reader.u16()?;
+        reader.skip(4)?;
+        let len = reader.u16()? as usize;
+        let end = reader.position + len;
+
+        // Skips the CNAME and other records of the answer.
+        if record_type == TYPE_SRV && class == CLASS_IN {
+            records.push(SrvRecord {
+                priority: reader.u16()?,
+                weight:   reader.u16()?,
+                port:     reader.u16()?,
+                target:   reader.name()?,
+            });
+        }
+        if end > response.len() {
+            return Err("the response is truncated".to_string());
+        }
+        reader.position = end;
+    }
+
+    Ok(SrvResponse::Records(records))
+}
+
+/// Reads the fields of a message.
+struct Reader<'a> {
+    /// The whole message, the names may point to any part of it.
+    message:  &'a [u8],
+    /// The position of the next field.
+    position: usize,
+}
+
+impl Reader<'_> {
+    /// Reads a big-endian 16-bit number.
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Skips the given number of bytes.
+    fn skip(&mut self, len: usize) -> Result<(), String> {
+        self.take(len).map(|_| ())
+    }
+
+    /// Reads the given number of bytes.
+    fn take(&mut self, len: usize) -> Result<&[u8], String> {
+        let bytes = self
+            .message
+            .get(self.position..self.position + len)
+            .ok_or_else(|| "the response is truncated".to_string())?;
+        self.position += len;
+
+        Ok(bytes)
+    }
+
+    /// Reads a possibly compressed name.
+    fn name(&mut self) -> Result<String, String> {
+        let mut labels = Vec::new();
+        let mut position = self.position;
+        let mut end = None;
+        let mut pointers = 0;
+        loop {
+            let len = *self
+                .message
+                .get(position)
+                .ok_or_else(|| "the response is truncated".to_string())?
+                as usize;
+            match len {
+                0 => {
+                    end.get_or_insert(position + 1);
+                    break;
+                }
+                len if len & 0xC0 == 0xC0 => {
+                    let low =
+                        *self.message.get(position + 1).ok_or_else(|| {
+                            "the response is truncated".to_string()
+                        })? as usize;
+                    end.get_or_insert(position + 2);
+                    pointers += 1;
+                    if pointers > MAX_POINTERS {
+                        return Err("the response has a name loop".to_string());
+                    }
+                    position = ((len & 0x3F) << 8) | low;
+                }
+                len => {
+                    let label = self
+                        .message
+                        .get(position + 1..position + 1 + len)
+                        .ok_or_else(|| {
+                            "the response is truncated".to_string()
+                        })?;
+                    labels.push(String::from_utf8_lossy(label).into_owned());
+                    position += 1 + len;
+                }
+            }
+        }
+        self.position = end.unwrap_or(position);
+
+        Ok(labels.join("."))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Encodes the response to the query with the given records.
+    pub(crate) fn encode_srv_response(
+        query: &[u8],
+        records: &[SrvRecord],
+    ) -> Vec<u8> {
+        let mut response = query[..2].to_vec();
+        for value in [FLAG_QR | FLAG_RD | 0x0080, 1, records.len() as u16, 0, 0]
+        {
+            response.extend_from_slice(&value.to_be_bytes());
+        }
+        response.extend_from_slice(&query[HEADER_LEN..]);
+
+        for record in records {
+            // A pointer to the name of the question.
+            response.extend_from_slice(&[0xC0, HEADER_LEN as u8]);
+            response.extend_from_slice(&TYPE_SRV.to_be_bytes());
+            response.extend_from_slice(&CLASS_IN.to_be_bytes());
+            response.extend_from_slice(&300u32.to_be_bytes());
+
+            let mut data = Vec::new();
+            for value in [record.priority, record.weight, record.port] {
+                data.extend_from_slice(&value.to_be_bytes());
+            }
+            for label in record.target.split('.') {
+                data.push(label.len() as u8);
+                data.extend_from_slice(label.as_bytes());
+            }
+            data.push(0);
+
+            response.extend_from_slice(&(data.len() as u16).to_be_bytes());
+            response.extend_from_slice(&data);
+        }
+
+        response
+    }
+
+    #[test]
+    fn test_decode_srv_response() {
+        let query = encode_srv_query(7, "_cql._tcp.example.internal").unwrap();
+        let records = vec![SrvRecord {
+            priority: 10,
+            weight:   5,
+            port:     9042,
+            target:   "node1.example.internal".to_string(),
+        }];
+
+        let response = encode_srv_response(&query, &records);
+
+        assert_eq!(
+            decode_srv_response(7, &response),
+            Ok(SrvResponse::Records(records))
+        );
+        assert!(decode_srv_response(8, &response).is_err());
+        assert!(
+            decode_srv_response(7, &response[..response.len() - 3]).is_err()
+        );
+    }
+}
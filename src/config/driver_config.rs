@@ -1,22 +1,62 @@
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+};
+use std::num::NonZeroI64;
+use std::path::PathBuf;
 use std::time::Duration;
 
+use itertools::Itertools;
+
+use crate::cql::CqlUuid;
 use crate::{
+    Cluster,
     Consistency,
     ContactPoint,
+    Credentials,
     DriverConfigBuilder,
+    DriverConfigError,
+    DriverError,
+    DriverErrorKind,
+    ExecutionProfileConfig,
+    InvalidSetting,
+    LatencyAwareRouting,
+    LoadBalancingPolicy,
     ProtocolVersion,
+    RetryPolicyKind,
+    Session,
+    SpanStatementMode,
+    SpeculativeExecutionPolicy,
+    SrvContactPoints,
     Ssl,
+    SslConfig,
+    TimestampGenKind,
 };
 
 // TODO: Implement the `Debug` trait for `DriverConfig`.
 
 /// The driver configuration.
 #[derive(Default)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
 pub struct DriverConfig {
     /// The list of contact points to use to connect to the Cassandra cluster.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub contact_points: Vec<ContactPoint>,
 
+    /// The name of the DNS SRV records listing the contact points, e.g.
+    /// `_cql._tcp.example.internal`.
+    ///
+    /// The records are resolved with the system DNS servers every time a
+    /// session connects, see [`SrvContactPoints`]. Only one of
+    /// [`contact_points`] and this setting can be set.
+    ///
+    /// [`contact_points`]: DriverConfig::contact_points
+    pub srv_contact_points: Option<String>,
+
     /// The port to use for the control connection.
     ///
     /// If not set, the default port 9042 is used.
@@ -31,10 +71,36 @@ pub struct DriverConfig {
     /// While it is possible to serialize and deserialize the SSL configuration
     /// and then construct an instance of the [`Ssl`] type, it is unwise to do
     /// so because it is all about security. So, deserialize the driver
-    /// configuration and then set the SSL configuration manually.
+    /// configuration and then set the SSL configuration manually, or use
+    /// [`ssl_config`](DriverConfig::ssl_config) that references the
+    /// certificates and keys by their file paths.
     #[cfg_attr(feature = "serde", serde(skip))]
     pub ssl: Option<Ssl>,
 
+    /// The SSL configuration that references the certificates and keys by
+    /// their file paths.
+    ///
+    /// The files are read when the cluster is created. Unlike [`ssl`], this
+    /// field is available for serialization and deserialization. Only one of
+    /// them can be set.
+    ///
+    /// If not set, SSL is disabled unless [`ssl`] is set.
+    ///
+    /// [`ssl`]: DriverConfig::ssl
+    pub ssl_config: Option<SslConfig>,
+
+    /// The secure connect bundle of a DataStax Astra database.
+    ///
+    /// The bundle provides the contact points and the SSL configuration, so
+    /// none of them can be set with it, see
+    /// [`Cluster::set_cloud_secure_connection_bundle`].
+    pub cloud_secure_connect_bundle: Option<PathBuf>,
+
+    /// The credentials of the plain text authentication.
+    ///
+    /// If not set, the driver does not authenticate.
+    pub credentials: Option<Credentials>,
+
     /// The Apache Cassandra protocol version.
     ///
     /// This will automatically downgrade to the lowest supported protocol
@@ -84,9 +150,236 @@ pub struct DriverConfig {
     /// If not set, the default value is 2000 milliseconds.
     #[cfg_attr(
         feature = "serde",
-        serde(with = "crate::serialization::opt_duration_as_string")
+        serde(default, with = "crate::serialization::opt_duration_as_string")
     )]
     pub reconnect_wait_time: Option<Duration>,
+
+    /// The maximum number of connections that will be created concurrently.
+    ///
+    /// If not set, the default value is 1.
+    pub max_concurrent_creation: Option<usize>,
+
+    /// The timeout for connecting to a node.
+    ///
+    /// If not set, the default value is 5 seconds.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::serialization::opt_duration_as_string")
+    )]
+    pub connect_timeout: Option<Duration>,
+
+    /// The timeout for waiting for a response from a node.
+    ///
+    /// If not set, the default value is 12 seconds.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::serialization::opt_duration_as_string")
+    )]
+    pub request_timeout: Option<Duration>,
+
+    /// The timeout for waiting for DNS name resolution.
+    ///
+    /// If not set, the default value is 2 seconds.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::serialization::opt_duration_as_string")
+    )]
+    pub resolve_timeout: Option<Duration>,
+
+    /// The maximum time to wait for the schema agreement after a schema
+    /// change.
+    ///
+    /// If not set, the default value is 10 seconds.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::serialization::opt_duration_as_string")
+    )]
+    pub max_schema_wait_time: Option<Duration>,
+
+    /// The maximum time to wait for a trace to become available.
+    ///
+    /// If not set, the default value is 15 milliseconds.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::serialization::opt_duration_as_string")
+    )]
+    pub tracing_max_wait_time: Option<Duration>,
+
+    /// The time to wait between the attempts to read a trace.
+    ///
+    /// If not set, the default value is 3 milliseconds.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::serialization::opt_duration_as_string")
+    )]
+    pub tracing_retry_wait_time: Option<Duration>,
+
+    /// The consistency level the traces are read with.
+    ///
+    /// If not set, the default value is [`Consistency::One`].
+    pub tracing_consistency: Option<Consistency>,
+
+    /// The load balancing policy.
+    ///
+    /// If not set, the default value is [`LoadBalancingPolicy::DcAware`] with
+    /// the data center of the contact points as the local one.
+    pub load_balancing: Option<LoadBalancingPolicy>,
+
+    /// Whether the requests are routed to the replicas of their partition.
+    ///
+    /// If not set, the token-aware routing is enabled.
+    pub token_aware_routing: Option<bool>,
+
+    /// Whether the replicas selected by the token-aware routing are shuffled.
+    ///
+    /// If not set, the replicas are shuffled.
+    pub token_aware_routing_shuffle_replicas: Option<bool>,
+
+    /// The settings of the latency-aware routing.
+    ///
+    /// If not set, the latency-aware routing is disabled.
+    pub latency_aware_routing: Option<LatencyAwareRouting>,
+
+    /// The hosts the driver is allowed to connect to.
+    ///
+    /// If empty, the driver connects to all the hosts.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub whitelist_hosts: Vec<String>,
+
+    /// The hosts the driver is not allowed to connect to.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub blacklist_hosts: Vec<String>,
+
+    /// The data centers the driver is allowed to connect to.
+    ///
+    /// If empty, the driver connects to all the data centers.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub whitelist_dcs: Vec<String>,
+
+    /// The data centers the driver is not allowed to connect to.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub blacklist_dcs: Vec<String>,
+
+    /// Whether Nagle's algorithm is disabled on the connections.
+    ///
+    /// If not set, the default value is `true`.
+    pub tcp_nodelay: Option<bool>,
+
+    /// The TCP keep-alive delay of the connections, in whole seconds.
+    ///
+    /// If not set, the TCP keep-alive is disabled.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::serialization::opt_duration_as_string")
+    )]
+    pub tcp_keepalive: Option<Duration>,
+
+    /// The time between the heartbeat messages, in whole seconds.
+    ///
+    /// If not set, the default value is 30 seconds.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::serialization::opt_duration_as_string")
+    )]
+    pub connection_heartbeat_interval: Option<Duration>,
+
+    /// The time a connection is allowed to be without a successful heartbeat
+    /// response before it is reconnected, in whole seconds.
+    ///
+    /// If not set, the default value is 60 seconds.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::serialization::opt_duration_as_string")
+    )]
+    pub connection_idle_timeout: Option<Duration>,
+
+    /// The retry policy used for all the requests.
+    ///
+    /// If not set, the default value is [`RetryPolicyKind::Default`].
+    pub retry_policy: Option<RetryPolicyKind>,
+
+    /// Whether the schema metadata is retrieved and updated.
+    ///
+    /// If not set, the default value is `true`.
+    pub use_schema: Option<bool>,
+
+    /// Whether the contact points are randomized.
+    ///
+    /// If not set, the default value is `true`.
+    pub use_randomized_contact_points: Option<bool>,
+
+    /// The speculative execution policy.
+    ///
+    /// If not set, the default value is [`SpeculativeExecutionPolicy::None`].
+    pub speculative_execution: Option<SpeculativeExecutionPolicy>,
+
+    /// The maximum number of "pending write" objects saved for re-use.
+    ///
+    /// If not set, the default value is [`u32::MAX`].
+    pub max_reusable_write_objects: Option<usize>,
+
+    /// Whether the statements are prepared on all the hosts.
+    ///
+    /// If not set, the default value is `true`.
+    pub prepare_on_all_hosts: Option<bool>,
+
+    /// Whether the statements are prepared on the hosts that become available.
+    ///
+    /// If not set, the default value is `true`.
+    pub prepare_on_up_or_add_host: Option<bool>,
+
+    /// Whether the `NO_COMPACT` startup option is sent.
+    ///
+    /// If not set, the default value is `false`.
+    pub no_compact: Option<bool>,
+
+    /// The application name reported to the cluster.
+    pub application_name: Option<String>,
+
+    /// The application version reported to the cluster.
+    pub application_version: Option<String>,
+
+    /// The client identifier reported to the cluster.
+    ///
+    /// If not set, a random identifier is generated.
+    pub client_id: Option<CqlUuid>,
+
+    /// The time between the monitor reporting events, in whole seconds.
+    ///
+    /// If not set, the default value is 300 seconds.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::serialization::opt_duration_as_string")
+    )]
+    pub monitor_reporting_interval: Option<Duration>,
+
+    /// The time after which the metric histograms are refreshed.
+    ///
+    /// If not set, the histograms are never refreshed.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::serialization::opt_duration_as_string")
+    )]
+    pub histogram_refresh_interval: Option<Duration>,
+
+    /// The timestamp generator used for all the requests.
+    ///
+    /// If not set, the default value is [`TimestampGenKind::ServerSide`].
+    pub timestamp_gen: Option<TimestampGenKind>,
+
+    /// How the statement text is recorded in the request spans.
+    ///
+    /// If not set, the default value is [`SpanStatementMode::Redacted`].
+    pub span_statement_mode: Option<SpanStatementMode>,
+
+    /// The execution profiles by their names.
+    ///
+    /// The requests select a profile by its name with
+    /// [`Statement::set_execution_profile`].
+    ///
+    /// [`Statement::set_execution_profile`]: crate::Statement::set_execution_profile
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub profiles: BTreeMap<String, ExecutionProfileConfig>,
 }
 
 impl DriverConfig {
@@ -94,4 +387,905 @@ impl DriverConfig {
     pub fn builder() -> DriverConfigBuilder {
         DriverConfigBuilder::default()
     }
+
+    /// Overrides the settings with the ones set in the given configuration.
+    ///
+    /// A setting is overridden if it is [`Some`] or, for the lists, not empty
+    /// in the given configuration. The profiles are overridden by name.
+    pub fn merge(&mut self, overrides: DriverConfig) {
+        let DriverConfig {
+            contact_points,
+            srv_contact_points,
+            port,
+            ssl,
+            ssl_config,
+            cloud_secure_connect_bundle,
+            credentials,
+            protocol_version,
+            consistency,
+            serial_consistency,
+            io_threads_count,
+            io_queue_size,
+            event_queue_size,
+            core_connections_per_host,
+            max_connections_per_host,
+            reconnect_wait_time,
+            max_concurrent_creation,
+            connect_timeout,
+            request_timeout,
+            resolve_timeout,
+            max_schema_wait_time,
+            tracing_max_wait_time,
+            tracing_retry_wait_time,
+            tracing_consistency,
+            load_balancing,
+            token_aware_routing,
+            token_aware_routing_shuffle_replicas,
+            latency_aware_routing,
+            whitelist_hosts,
+            blacklist_hosts,
+            whitelist_dcs,
+            blacklist_dcs,
+            tcp_nodelay,
+            tcp_keepalive,
+            connection_heartbeat_interval,
+            connection_idle_timeout,
+            retry_policy,
+            use_schema,
+            use_randomized_contact_points,
+            speculative_execution,
+            max_reusable_write_objects,
+            prepare_on_all_hosts,
+            prepare_on_up_or_add_host,
+            no_compact,
+            application_name,
+            application_version,
+            client_id,
+            monitor_reporting_interval,
+            histogram_refresh_interval,
+            timestamp_gen,
+            span_statement_mode,
+            profiles,
+        } = overrides;
+
+        if !contact_points.is_empty() {
+            self.contact_points = contact_points;
+        }
+        self.srv_contact_points =
+            srv_contact_points.or(self.srv_contact_points.take());
+        self.port = port.or(self.port.take());
+        self.ssl = ssl.or(self.ssl.take());
+        self.ssl_config = ssl_config.or(self.ssl_config.take());
+        self.cloud_secure_connect_bundle = cloud_secure_connect_bundle
+            .or(self.cloud_secure_connect_bundle.take());
+        self.credentials = credentials.or(self.credentials.take());
+        self.protocol_version =
+            protocol_version.or(self.protocol_version.take());
+        self.consistency = consistency.or(self.consistency.take());
+        self.serial_consistency =
+            serial_consistency.or(self.serial_consistency.take());
+        self.io_threads_count =
+            io_threads_count.or(self.io_threads_count.take());
+        self.io_queue_size = io_queue_size.or(self.io_queue_size.take());
+        self.event_queue_size =
+            event_queue_size.or(self.event_queue_size.take());
+        self.core_connections_per_host =
+            core_connections_per_host.or(self.core_connections_per_host.take());
+        self.max_connections_per_host =
+            max_connections_per_host.or(self.max_connections_per_host.take());
+        self.reconnect_wait_time =
+            reconnect_wait_time.or(self.reconnect_wait_time.take());
+        self.max_concurrent_creation =
+            max_concurrent_creation.or(self.max_concurrent_creation.take());
+        self.connect_timeout = connect_timeout.or(self.connect_timeout.take());
+        self.request_timeout = request_timeout.or(self.request_timeout.take());
+        self.resolve_timeout = resolve_timeout.or(self.resolve_timeout.take());
+        self.max_schema_wait_time =
+            max_schema_wait_time.or(self.max_schema_wait_time.take());
+        self.tracing_max_wait_time =
+            tracing_max_wait_time.or(self.tracing_max_wait_time.take());
+        self.tracing_retry_wait_time =
+            tracing_retry_wait_time.or(self.tracing_retry_wait_time.take());
+        self.tracing_consistency =
+            tracing_consistency.or(self.tracing_consistency.take());
+        self.load_balancing = load_balancing.or(self.load_balancing.take());
+        self.token_aware_routing =
+            token_aware_routing.or(self.token_aware_routing.take());
+        self.token_aware_routing_shuffle_replicas =
+            token_aware_routing_shuffle_replicas
+                .or(self.token_aware_routing_shuffle_replicas.take());
+        self.latency_aware_routing =
+            latency_aware_routing.or(self.latency_aware_routing.take());
+        if !whitelist_hosts.is_empty() {
+            self.whitelist_hosts = whitelist_hosts;
+        }
+        if !blacklist_hosts.is_empty() {
+            self.blacklist_hosts = blacklist_hosts;
+        }
+        if !whitelist_dcs.is_empty() {
+            self.whitelist_dcs = whitelist_dcs;
+        }
+        if !blacklist_dcs.is_empty() {
+            self.blacklist_dcs = blacklist_dcs;
+        }
+        self.tcp_nodelay = tcp_nodelay.or(self.tcp_nodelay.take());
+        self.tcp_keepalive = tcp_keepalive.or(self.tcp_keepalive.take());
+        self.connection_heartbeat_interval = connection_heartbeat_interval
+            .or(self.connection_heartbeat_interval.take());
+        self.connection_idle_timeout =
+            connection_idle_timeout.or(self.connection_idle_timeout.take());
+        self.retry_policy = retry_policy.or(self.retry_policy.take());
+        self.use_schema = use_schema.or(self.use_schema.take());
+        self.use_randomized_contact_points = use_randomized_contact_points
+            .or(self.use_randomized_contact_points.take());
+        self.speculative_execution =
+            speculative_execution.or(self.speculative_execution.take());
+        self.max_reusable_write_objects = max_reusable_write_objects
+            .or(self.max_reusable_write_objects.take());
+        self.prepare_on_all_hosts =
+            prepare_on_all_hosts.or(self.prepare_on_all_hosts.take());
+        self.prepare_on_up_or_add_host =
+            prepare_on_up_or_add_host.or(self.prepare_on_up_or_add_host.take());
+        self.no_compact = no_compact.or(self.no_compact.take());
+        self.application_name =
+            application_name.or(self.application_name.take());
+        self.application_version =
+            application_version.or(self.application_version.take());
+        self.client_id = client_id.or(self.client_id.take());
+        self.monitor_reporting_interval = monitor_reporting_interval
+            .or(self.monitor_reporting_interval.take());
+        self.histogram_refresh_interval = histogram_refresh_interval
+            .or(self.histogram_refresh_interval.take());
+        self.timestamp_gen = timestamp_gen.or(self.timestamp_gen.take());
+        self.span_statement_mode =
+            span_statement_mode.or(self.span_statement_mode.take());
+        self.profiles.extend(profiles);
+    }
+
+    /// Creates a cluster from the configuration and connects a session to it.
+    ///
+    /// An invalid configuration fails with the
+    /// [`LibBadParams`](crate::DriverErrorKind::LibBadParams) error which
+    /// message lists all the invalid settings, see [`Cluster::try_from`].
+    pub async fn connect(self) -> Result<Session, DriverError> {
+        let cluster = Cluster::try_from(self)?;
+
+        cluster.connect().await
+    }
+
+    /// Returns the settings that are invalid on their own or in combination
+    /// with other settings.
+    fn validate(&self) -> Vec<InvalidSetting> {
+        let mut invalid = Vec::new();
+
+        let ports: BTreeSet<_> = self
+            .contact_points
+            .iter()
+            .filter_map(|contact_point| contact_point.port)
+            .collect();
+        if ports.len() > 1 {
+            invalid.push(InvalidSetting::new(
+                "contact_points",
+                format!(
+                    "the contact points use different ports ({}), the driver \
+                     uses a single port for all of them",
+                    ports.iter().join(", ")
+                ),
+            ));
+        }
+        if let (Some(port), Some(contact_points_port)) =
+            (self.port, ports.first())
+        {
+            if ports.len() == 1 && port != *contact_points_port {
+                invalid.push(InvalidSetting::new(
+                    "port",
+                    format!(
+                        "the port {} conflicts with the port {} of the \
+                         contact points",
+                        port, contact_points_port
+                    ),
+                ));
+            }
+        }
+
+        if self.ssl.is_some() && self.ssl_config.is_some() {
+            invalid.push(InvalidSetting::new(
+                "ssl_config",
+                "conflicts with ssl, set only one of them",
+            ));
+        }
+
+        if self.srv_contact_points.is_some() && !self.contact_points.is_empty()
+        {
+            invalid.push(InvalidSetting::new(
+                "srv_contact_points",
+                "conflicts with contact_points, set only one of them",
+            ));
+        }
+
+        if self.cloud_secure_connect_bundle.is_some() {
+            let conflicts: Vec<_> = [
+                ("contact_points", !self.contact_points.is_empty()),
+                ("srv_contact_points", self.srv_contact_points.is_some()),
+                ("port", self.port.is_some()),
+                ("ssl", self.ssl.is_some()),
+                ("ssl_config", self.ssl_config.is_some()),
+            ]
+            .into_iter()
+            .filter_map(|(setting, is_set)| is_set.then_some(setting))
+            .collect();
+            if !conflicts.is_empty() {
+                invalid.push(InvalidSetting::new(
+                    "cloud_secure_connect_bundle",
+                    format!(
+                        "conflicts with {}, the bundle provides them",
+                        conflicts.join(", ")
+                    ),
+                ));
+            }
+        }
+
+        for (setting, value) in [
+            ("io_threads_count", self.io_threads_count),
+            ("io_queue_size", self.io_queue_size),
+            ("event_queue_size", self.event_queue_size),
+            ("core_connections_per_host", self.core_connections_per_host),
+            ("max_connections_per_host", self.max_connections_per_host),
+        ] {
+            if value == Some(0) {
+                invalid.push(InvalidSetting::new(
+                    setting,
+                    "must be greater than 0",
+                ));
+            }
+        }
+
+        if let Some(consistency) = self.serial_consistency {
+            if !matches!(
+                consistency,
+                Consistency::Serial
+                    | Consistency::LocalSerial
+                    | Consistency::Any
+            ) {
+                invalid.push(InvalidSetting::new(
+                    "serial_consistency",
+                    format!(
+                        "{} is not a serial consistency level, use SERIAL or \
+                         LOCAL_SERIAL",
+                        consistency
+                    ),
+                ));
+            }
+        }
+
+        for (setting, value) in [
+            ("tcp_keepalive", self.tcp_keepalive),
+            (
+                "connection_heartbeat_interval",
+                self.connection_heartbeat_interval,
+            ),
+            ("connection_idle_timeout", self.connection_idle_timeout),
+            (
+                "monitor_reporting_interval",
+                self.monitor_reporting_interval,
+            ),
+        ] {
+            if value.is_some_and(|value| value.subsec_nanos() != 0) {
+                invalid.push(InvalidSetting::new(
+                    setting,
+                    "must be a whole number of seconds",
+                ));
+            }
+        }
+
+        if self
+            .histogram_refresh_interval
+            .is_some_and(|interval| interval.as_millis() == 0)
+        {
+            invalid.push(InvalidSetting::new(
+                "histogram_refresh_interval",
+                "must be at least 1 millisecond",
+            ));
+        }
+
+        for (setting, whitelist, blacklist) in [
+            (
+                "blacklist_hosts",
+                &self.whitelist_hosts,
+                &self.blacklist_hosts,
+            ),
+            ("blacklist_dcs", &self.whitelist_dcs, &self.blacklist_dcs),
+        ] {
+            let both: Vec<_> = blacklist
+                .iter()
+                .filter(|item| whitelist.contains(item))
+                .collect();
+            if !both.is_empty() {
+                invalid.push(InvalidSetting::new(
+                    setting,
+                    format!("{} are also whitelisted", both.iter().join(", ")),
+                ));
+            }
+        }
+
+        if let Some(LoadBalancingPolicy::DcAware {
+            local_dc, ..
+        }) = &self.load_balancing
+        {
+            if self.blacklist_dcs.contains(local_dc) {
+                invalid.push(InvalidSetting::new(
+                    "load_balancing",
+                    format!(
+                        "the local data center {} is blacklisted",
+                        local_dc
+                    ),
+                ));
+            }
+        }
+
+        invalid
+    }
+}
+
+impl TryFrom<DriverConfig> for Cluster {
+    type Error = DriverConfigError;
+
+    /// Creates a cluster with every setting of the configuration applied.
+    ///
+    /// The configuration is validated first, then every setting is applied.
+    /// The error lists all the invalid settings, including the ones rejected
+    /// by the driver.
+    fn try_from(config: DriverConfig) -> Result<Self, Self::Error> {
+        let mut invalid = config.validate();
+        let mut cluster = Cluster::new();
+        let mut apply = |setting, result: Result<(), DriverError>| {
+            if let Err(error) = result {
+                invalid.push(InvalidSetting::new(setting, error.message));
+            }
+        };
+
+        if let Some(path) = &config.cloud_secure_connect_bundle {
+            apply(
+                "cloud_secure_connect_bundle",
+                cluster.set_cloud_secure_connection_bundle(path),
+            );
+        } else {
+            let contact_points = if config.contact_points.is_empty() {
+                // Connect to localhost by default.
+                ContactPoint::default().host.to_string()
+            } else {
+                config
+                    .contact_points
+                    .iter()
+                    .map(|contact_point| contact_point.host.to_string())
+                    .join(",")
+            };
+            apply("contact_points", cluster.set_contact_points(contact_points));
+        }
+        if let Some(name) = &config.srv_contact_points {
+            apply(
+                "srv_contact_points",
+                SrvContactPoints::new(name)
+                    .map(|source| cluster.set_contact_point_source(source)),
+            );
+        }
+
+        let port = config.port.or_else(|| {
+            config
+                .contact_points
+                .iter()
+                .find_map(|contact_point| contact_point.port)
+        });
+        if let Some(port) = port {
+            apply("port", cluster.set_port(port));
+        }
+        if let Some(ssl) = &config.ssl {
+            apply("ssl", cluster.set_ssl(ssl));
+        }
+        if let Some(ssl_config) = &config.ssl_config {
+            apply(
+                "ssl_config",
+                ssl_config.build().and_then(|ssl| cluster.set_ssl(&ssl)),
+            );
+        }
+        if let Some(credentials) = &config.credentials {
+            apply(
+                "credentials",
+                credentials.password.read().and_then(|password| {
+                    cluster.set_credentials(
+                        &credentials.username,
+                        password.expose(),
+                    )
+                }),
+            );
+        }
+        if let Some(version) = config.protocol_version {
+            apply("protocol_version", cluster.set_protocol_version(version));
+        }
+        if let Some(consistency) = config.consistency {
+            apply("consistency", cluster.set_consistency(consistency));
+        }
+        if let Some(consistency) = config.serial_consistency {
+            apply(
+                "serial_consistency",
+                cluster.set_serial_consistency(consistency),
+            );
+        }
+        if let Some(count) = config.io_threads_count {
+            apply("io_threads_count", cluster.set_num_threads_io(count));
+        }
+        if let Some(size) = config.io_queue_size {
+            apply("io_queue_size", cluster.set_queue_size_io(size));
+        }
+        if let Some(size) = config.event_queue_size {
+            apply("event_queue_size", cluster.set_queue_size_event(size));
+        }
+        if let Some(count) = config.core_connections_per_host {
+            apply(
+                "core_connections_per_host",
+                cluster.set_core_connections_per_host(count),
+            );
+        }
+        if let Some(count) = config.max_connections_per_host {
+            apply(
+                "max_connections_per_host",
+                cluster.set_max_connections_per_host(count),
+            );
+        }
+        if let Some(wait_time) = config.reconnect_wait_time {
+            apply(
+                "reconnect_wait_time",
+                millis(wait_time)
+                    .and_then(|millis| cluster.set_reconnect_wait_time(millis)),
+            );
+        }
+        if let Some(count) = config.max_concurrent_creation {
+            apply(
+                "max_concurrent_creation",
+                cluster.set_max_concurrent_creation(count),
+            );
+        }
+        if let Some(timeout) = config.connect_timeout {
+            apply(
+                "connect_timeout",
+                millis(timeout)
+                    .and_then(|millis| cluster.set_connect_timeout(millis)),
+            );
+        }
+        if let Some(timeout) = config.request_timeout {
+            apply(
+                "request_timeout",
+                millis(timeout)
+                    .and_then(|millis| cluster.set_request_timeout(millis)),
+            );
+        }
+        if let Some(timeout) = config.resolve_timeout {
+            apply(
+                "resolve_timeout",
+                millis(timeout)
+                    .and_then(|millis| cluster.set_resolve_timeout(millis)),
+            );
+        }
+        if let Some(wait_time) = config.max_schema_wait_time {
+            apply(
+                "max_schema_wait_time",
+                millis(wait_time).and_then(|millis| {
+                    cluster.set_max_schema_wait_time(millis)
+                }),
+            );
+        }
+        if let Some(wait_time) = config.tracing_max_wait_time {
+            apply(
+                "tracing_max_wait_time",
+                millis(wait_time).and_then(|millis| {
+                    cluster.set_tracing_max_wait_time(millis)
+                }),
+            );
+        }
+        if let Some(wait_time) = config.tracing_retry_wait_time {
+            apply(
+                "tracing_retry_wait_time",
+                millis(wait_time).and_then(|millis| {
+                    cluster.set_tracing_retry_wait_time(millis)
+                }),
+            );
+        }
+        if let Some(consistency) = config.tracing_consistency {
+            apply(
+                "tracing_consistency",
+                cluster.set_tracing_consistency(consistency),
+            );
+        }
+        match &config.load_balancing {
+            Some(LoadBalancingPolicy::RoundRobin) => {
+                apply("load_balancing", cluster.set_load_balance_round_robin())
+            }
+            Some(LoadBalancingPolicy::DcAware {
+                local_dc,
+                used_hosts_per_remote_dc,
+                allow_remote_dcs_for_local_cl,
+            }) => apply(
+                "load_balancing",
+                cluster.set_load_balance_dc_aware(
+                    local_dc,
+                    *used_hosts_per_remote_dc,
+                    *allow_remote_dcs_for_local_cl,
+                ),
+            ),
+            None => {}
+        }
+        if let Some(enabled) = config.token_aware_routing {
+            apply(
+                "token_aware_routing",
+                cluster.set_token_aware_routing(enabled),
+            );
+        }
+        if let Some(enabled) = config.token_aware_routing_shuffle_replicas {
+            apply(
+                "token_aware_routing_shuffle_replicas",
+                cluster.set_token_aware_routing_shuffle_replicas(enabled),
+            );
+        }
+        if let Some(routing) = config.latency_aware_routing {
+            apply(
+                "latency_aware_routing",
+                cluster.set_latency_aware_routing(true).and_then(|_| {
+                    cluster.set_latency_aware_routing_settings(
+                        routing.exclusion_threshold,
+                        millis(routing.scale)?,
+                        millis(routing.retry_period)?,
+                        millis(routing.update_rate)?,
+                        routing.min_measured,
+                    )
+                }),
+            );
+        }
+        if !config.whitelist_hosts.is_empty() {
+            apply(
+                "whitelist_hosts",
+                cluster
+                    .set_whitelist_filtering(config.whitelist_hosts.join(",")),
+            );
+        }
+        if !config.blacklist_hosts.is_empty() {
+            apply(
+                "blacklist_hosts",
+                cluster
+                    .set_blacklist_filtering(config.blacklist_hosts.join(",")),
+            );
+        }
+        if !config.whitelist_dcs.is_empty() {
+            apply(
+                "whitelist_dcs",
+                cluster
+                    .set_whitelist_dc_filtering(config.whitelist_dcs.join(",")),
+            );
+        }
+        if !config.blacklist_dcs.is_empty() {
+            apply(
+                "blacklist_dcs",
+                cluster
+                    .set_blacklist_dc_filtering(config.blacklist_dcs.join(",")),
+            );
+        }
+        if let Some(enabled) = config.tcp_nodelay {
+            apply("tcp_nodelay", cluster.set_tcp_nodelay(enabled));
+        }
+        if let Some(delay) = config.tcp_keepalive {
+            apply(
+                "tcp_keepalive",
+                seconds(delay)
+                    .and_then(|delay| cluster.set_tcp_keepalive(Some(delay))),
+            );
+        }
+        if let Some(interval) = config.connection_heartbeat_interval {
+            apply(
+                "connection_heartbeat_interval",
+                seconds(interval).and_then(|interval| {
+                    cluster.set_connection_heartbeat_interval(interval)
+                }),
+            );
+        }
+        if let Some(timeout) = config.connection_idle_timeout {
+            apply(
+                "connection_idle_timeout",
+                seconds(timeout).and_then(|timeout| {
+                    cluster.set_connection_idle_timeout(timeout)
+                }),
+            );
+        }
+        if let Some(kind) = &config.retry_policy {
+            let result = match kind.create() {
+                Some(policy) => cluster.set_retry_policy(&policy),
+                None => Err(DriverError::with_message(
+                    DriverErrorKind::LibBadParams,
+                    "a logging retry policy cannot wrap another logging policy",
+                )),
+            };
+            apply("retry_policy", result);
+        }
+        if let Some(enabled) = config.use_schema {
+            apply("use_schema", cluster.set_use_schema(enabled));
+        }
+        if let Some(enabled) = config.use_randomized_contact_points {
+            apply(
+                "use_randomized_contact_points",
+                cluster.set_use_randomized_contact_points(enabled),
+            );
+        }
+        match config.speculative_execution {
+            Some(SpeculativeExecutionPolicy::None) => apply(
+                "speculative_execution",
+                cluster.set_no_speculative_execution_policy(),
+            ),
+            Some(SpeculativeExecutionPolicy::Constant {
+                delay,
+                max_executions,
+            }) => apply(
+                "speculative_execution",
+                millis(delay).and_then(|delay| {
+                    cluster.set_constant_speculative_execution_policy(
+                        delay,
+                        max_executions,
+                    )
+                }),
+            ),
+            None => {}
+        }
+        if let Some(count) = config.max_reusable_write_objects {
+            apply(
+                "max_reusable_write_objects",
+                cluster.set_max_reusable_write_objects(count),
+            );
+        }
+        if let Some(enabled) = config.prepare_on_all_hosts {
+            apply(
+                "prepare_on_all_hosts",
+                cluster.set_prepare_on_all_hosts(enabled),
+            );
+        }
+        if let Some(enabled) = config.prepare_on_up_or_add_host {
+            apply(
+                "prepare_on_up_or_add_host",
+                cluster.set_prepare_on_up_or_add_host(enabled),
+            );
+        }
+        if let Some(enabled) = config.no_compact {
+            apply("no_compact", cluster.set_no_compact(enabled));
+        }
+        if let Some(name) = &config.application_name {
+            apply("application_name", cluster.set_application_name(name));
+        }
+        if let Some(version) = &config.application_version {
+            apply(
+                "application_version",
+                cluster.set_application_version(version),
+            );
+        }
+        if let Some(id) = config.client_id {
+            apply("client_id", cluster.set_client_id(id));
+        }
+        if let Some(interval) = config.monitor_reporting_interval {
+            apply(
+                "monitor_reporting_interval",
+                seconds(interval).and_then(|interval| {
+                    cluster.set_monitor_reporting_interval(interval)
+                }),
+            );
+        }
+        if let Some(interval) = config
+            .histogram_refresh_interval
+            .filter(|interval| interval.as_millis() > 0)
+        {
+            apply(
+                "histogram_refresh_interval",
+                millis(interval).and_then(|interval| {
+                    let interval =
+                        NonZeroI64::new(interval).ok_or_else(|| {
+                            DriverError::with_kind(
+                                DriverErrorKind::LibBadParams,
+                            )
+                        })?;
+                    cluster.set_histogram_refresh_interval(interval)
+                }),
+            );
+        }
+        if let Some(kind) = &config.timestamp_gen {
+            apply("timestamp_gen", cluster.set_timestamp_gen(&kind.create()));
+        }
+        if let Some(mode) = config.span_statement_mode {
+            apply("span_statement_mode", cluster.set_span_statement_mode(mode));
+        }
+        for (name, profile) in &config.profiles {
+            let result = profile
+                .build()
+                .and_then(|profile| {
+                    cluster.set_execution_profile(name, &profile)
+                })
+                .map_err(|error| {
+                    DriverError::with_message(
+                        error.kind,
+                        format!("{}: {}", name, error.message),
+                    )
+                });
+            apply("profiles", result);
+        }
+
+        if invalid.is_empty() {
+            Ok(cluster)
+        } else {
+            Err(DriverConfigError::new(invalid))
+        }
+    }
+}
+
+/// Converts the duration to milliseconds.
+pub(super) fn millis(duration: Duration) -> Result<i64, DriverError> {
+    duration.as_millis().try_into().map_err(|_| {
+        DriverError::with_message(
+            DriverErrorKind::LibBadParams,
+            "the duration is too long",
+        )
+    })
+}
+
+/// Converts the duration to seconds.
+fn seconds(duration: Duration) -> Result<i64, DriverError> {
+    duration.as_secs().try_into().map_err(|_| {
+        DriverError::with_message(
+            DriverErrorKind::LibBadParams,
+            "the duration is too long",
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SecretSource;
+
+    #[test]
+    fn test_try_into_cluster_lists_all_invalid_settings() {
+        let config = DriverConfig::builder()
+            .contact_point("10.0.0.1:9042".parse().unwrap())
+            .contact_point("10.0.0.2:9043".parse().unwrap())
+            .io_threads_count(0)
+            .serial_consistency(Consistency::Quorum)
+            .build();
+
+        let error = Cluster::try_from(config).err().unwrap();
+
+        assert_eq!(
+            error
+                .invalid_settings()
+                .iter()
+                .map(|invalid| invalid.setting)
+                .collect::<Vec<_>>(),
+            vec!["contact_points", "io_threads_count", "serial_consistency",]
+        );
+        assert!(error.to_string().starts_with(
+            "invalid driver configuration: contact_points: the contact points \
+             use different ports (9042, 9043)"
+        ));
+    }
+
+    #[test]
+    fn test_try_into_cluster_validates_routing_and_intervals() {
+        let config = DriverConfig::builder()
+            .load_balancing(LoadBalancingPolicy::DcAware {
+                local_dc:                      "dc1".to_string(),
+                used_hosts_per_remote_dc:      0,
+                allow_remote_dcs_for_local_cl: false,
+            })
+            .blacklist_dc("dc1")
+            .whitelist_host("10.0.0.1")
+            .blacklist_host("10.0.0.1")
+            .tcp_keepalive(Duration::from_millis(1500))
+            .histogram_refresh_interval(Duration::ZERO)
+            .speculative_execution(SpeculativeExecutionPolicy::Constant {
+                delay:          Duration::from_millis(100),
+                max_executions: 2,
+            })
+            .build();
+
+        let error = Cluster::try_from(config).err().unwrap();
+
+        assert_eq!(
+            error.invalid_settings(),
+            &[
+                InvalidSetting::new(
+                    "tcp_keepalive",
+                    "must be a whole number of seconds"
+                ),
+                InvalidSetting::new(
+                    "histogram_refresh_interval",
+                    "must be at least 1 millisecond"
+                ),
+                InvalidSetting::new(
+                    "blacklist_hosts",
+                    "10.0.0.1 are also whitelisted"
+                ),
+                InvalidSetting::new(
+                    "load_balancing",
+                    "the local data center dc1 is blacklisted"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_try_into_cluster_applies_credentials_and_profiles() {
+        let password = std::env::temp_dir()
+            .join(format!("cassander-password-{}", std::process::id()));
+        std::fs::write(&password, "secret\n").unwrap();
+        let config = DriverConfig::builder()
+            .credentials("cassandra", SecretSource::File(password.clone()))
+            .profile(
+                "analytics",
+                ExecutionProfileConfig {
+                    request_timeout: Some(Duration::from_secs(60)),
+                    consistency: Some(Consistency::LocalQuorum),
+                    load_balancing: Some(LoadBalancingPolicy::DcAware {
+                        local_dc:                      "analytics".to_string(),
+                        used_hosts_per_remote_dc:      0,
+                        allow_remote_dcs_for_local_cl: false,
+                    }),
+                    ..Default::default()
+                },
+            )
+            .build();
+
+        let result = Cluster::try_from(config);
+        std::fs::remove_file(password).unwrap();
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_try_into_cluster_reports_credentials_profiles_and_bundle() {
+        let config = DriverConfig::builder()
+            .contact_point("10.0.0.1".parse().unwrap())
+            .cloud_secure_connect_bundle("/nonexistent/secure-connect.zip")
+            .credentials(
+                "cassandra",
+                SecretSource::Env("CASSANDER_TEST_UNSET_PASSWORD".to_string()),
+            )
+            .profile(
+                "analytics",
+                ExecutionProfileConfig {
+                    retry_policy: Some(RetryPolicyKind::Logging(Box::new(
+                        RetryPolicyKind::Logging(Box::new(
+                            RetryPolicyKind::Default,
+                        )),
+                    ))),
+                    ..Default::default()
+                },
+            )
+            .build();
+
+        let error = Cluster::try_from(config).err().unwrap();
+        let invalid = error.invalid_settings();
+
+        assert_eq!(
+            invalid
+                .iter()
+                .map(|invalid| invalid.setting)
+                .collect::<Vec<_>>(),
+            vec![
+                "cloud_secure_connect_bundle",
+                "cloud_secure_connect_bundle",
+                "credentials",
+                "profiles",
+            ]
+        );
+        assert_eq!(
+            invalid[0].reason,
+            "conflicts with contact_points, the bundle provides them"
+        );
+        assert_eq!(
+            invalid[3].reason,
+            "analytics: retry_policy: a logging retry policy cannot wrap \
+             another logging policy"
+        );
+    }
 }
@@ -1,9 +1,23 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::cql::CqlUuid;
 use crate::{
     Consistency,
     ContactPoint,
+    Credentials,
     DriverConfig,
+    ExecutionProfileConfig,
+    LatencyAwareRouting,
+    LoadBalancingPolicy,
     ProtocolVersion,
+    RetryPolicyKind,
+    SecretSource,
+    SpanStatementMode,
+    SpeculativeExecutionPolicy,
     Ssl,
+    SslConfig,
+    TimestampGenKind,
 };
 
 /// A builder for the driver configuration.
@@ -42,6 +56,18 @@ impl DriverConfigBuilder {
         self
     }
 
+    /// Sets the name of the DNS SRV records listing the contact points.
+    ///
+    /// The records replace the contact points, so none can be added.
+    pub fn srv_contact_points<T>(mut self, name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.config.srv_contact_points = Some(name.into());
+
+        self
+    }
+
     /// Sets the port to use for the control connection.
     ///
     /// If not set, the default port 9042 is used.
@@ -60,6 +86,44 @@ impl DriverConfigBuilder {
         self
     }
 
+    /// Sets the SSL configuration that references the certificates and keys
+    /// by their file paths.
+    ///
+    /// If not set, SSL is disabled unless [`ssl`](Self::ssl) is set.
+    pub fn ssl_config(mut self, config: SslConfig) -> Self {
+        self.config.ssl_config = Some(config);
+
+        self
+    }
+
+    /// Sets the secure connect bundle of a DataStax Astra database.
+    ///
+    /// The bundle provides the contact points and the SSL configuration, so
+    /// none of them can be set.
+    pub fn cloud_secure_connect_bundle<P>(mut self, path: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        self.config.cloud_secure_connect_bundle = Some(path.into());
+
+        self
+    }
+
+    /// Sets the credentials of the plain text authentication.
+    ///
+    /// The password is read from its source when the cluster is created.
+    pub fn credentials<T>(mut self, username: T, password: SecretSource) -> Self
+    where
+        T: Into<String>,
+    {
+        self.config.credentials = Some(Credentials {
+            username: username.into(),
+            password,
+        });
+
+        self
+    }
+
     /// Sets the Apache Cassandra protocol version.
     ///
     /// This will automatically downgrade to the lowest supported protocol
@@ -136,9 +200,378 @@ impl DriverConfigBuilder {
         self
     }
 
+    /// Sets the wait time before attempting to reconnect.
+    ///
+    /// The default value is 2000 milliseconds.
+    pub fn reconnect_wait_time(mut self, wait_time: Duration) -> Self {
+        self.config.reconnect_wait_time = Some(wait_time);
+
+        self
+    }
+
+    /// Sets the maximum number of connections that will be created
+    /// concurrently.
+    ///
+    /// The default value is 1.
+    pub fn max_concurrent_creation(mut self, count: usize) -> Self {
+        self.config.max_concurrent_creation = Some(count);
+
+        self
+    }
+
+    /// Sets the timeout for connecting to a node.
+    ///
+    /// The default value is 5 seconds.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connect_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Sets the timeout for waiting for a response from a node.
+    ///
+    /// The default value is 12 seconds.
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.config.request_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Sets the timeout for waiting for DNS name resolution.
+    ///
+    /// The default value is 2 seconds.
+    pub fn resolve_timeout(mut self, timeout: Duration) -> Self {
+        self.config.resolve_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Sets the maximum time to wait for the schema agreement after a schema
+    /// change.
+    ///
+    /// The default value is 10 seconds.
+    pub fn max_schema_wait_time(mut self, wait_time: Duration) -> Self {
+        self.config.max_schema_wait_time = Some(wait_time);
+
+        self
+    }
+
+    /// Sets the maximum time to wait for a trace to become available.
+    ///
+    /// The default value is 15 milliseconds.
+    pub fn tracing_max_wait_time(mut self, wait_time: Duration) -> Self {
+        self.config.tracing_max_wait_time = Some(wait_time);
+
+        self
+    }
+
+    /// Sets the time to wait between the attempts to read a trace.
+    ///
+    /// The default value is 3 milliseconds.
+    pub fn tracing_retry_wait_time(mut self, wait_time: Duration) -> Self {
+        self.config.tracing_retry_wait_time = Some(wait_time);
+
+        self
+    }
+
+    /// Sets the consistency level the traces are read with.
+    ///
+    /// The default value is [`Consistency::One`].
+    pub fn tracing_consistency(mut self, consistency: Consistency) -> Self {
+        self.config.tracing_consistency = Some(consistency);
+
+        self
+    }
+
+    /// Sets the load balancing policy.
+    ///
+    /// The default value is [`LoadBalancingPolicy::DcAware`] with the data
+    /// center of the contact points as the local one.
+    pub fn load_balancing(mut self, policy: LoadBalancingPolicy) -> Self {
+        self.config.load_balancing = Some(policy);
+
+        self
+    }
+
+    /// Enables/Disables the routing of the requests to the replicas of their
+    /// partition.
+    ///
+    /// The default value is `true`.
+    pub fn token_aware_routing(mut self, enabled: bool) -> Self {
+        self.config.token_aware_routing = Some(enabled);
+
+        self
+    }
+
+    /// Enables/Disables the shuffling of the replicas selected by the
+    /// token-aware routing.
+    ///
+    /// The default value is `true`.
+    pub fn token_aware_routing_shuffle_replicas(
+        mut self,
+        enabled: bool,
+    ) -> Self {
+        self.config.token_aware_routing_shuffle_replicas = Some(enabled);
+
+        self
+    }
+
+    /// Enables the latency-aware routing with the given settings.
+    ///
+    /// The latency-aware routing is disabled by default.
+    pub fn latency_aware_routing(
+        mut self,
+        routing: LatencyAwareRouting,
+    ) -> Self {
+        self.config.latency_aware_routing = Some(routing);
+
+        self
+    }
+
+    /// Adds a host the driver is allowed to connect to.
+    ///
+    /// If no hosts are added, the driver connects to all the hosts.
+    pub fn whitelist_host<T>(mut self, host: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.config.whitelist_hosts.push(host.into());
+
+        self
+    }
+
+    /// Adds a host the driver is not allowed to connect to.
+    pub fn blacklist_host<T>(mut self, host: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.config.blacklist_hosts.push(host.into());
+
+        self
+    }
+
+    /// Adds a data center the driver is allowed to connect to.
+    ///
+    /// If no data centers are added, the driver connects to all the data
+    /// centers.
+    pub fn whitelist_dc<T>(mut self, dc: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.config.whitelist_dcs.push(dc.into());
+
+        self
+    }
+
+    /// Adds a data center the driver is not allowed to connect to.
+    pub fn blacklist_dc<T>(mut self, dc: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.config.blacklist_dcs.push(dc.into());
+
+        self
+    }
+
+    /// Enables/Disables Nagle's algorithm on the connections.
+    ///
+    /// The default value is `true` (disables Nagle's algorithm).
+    pub fn tcp_nodelay(mut self, enabled: bool) -> Self {
+        self.config.tcp_nodelay = Some(enabled);
+
+        self
+    }
+
+    /// Enables the TCP keep-alive on the connections with the given delay in
+    /// whole seconds.
+    ///
+    /// The TCP keep-alive is disabled by default.
+    pub fn tcp_keepalive(mut self, delay: Duration) -> Self {
+        self.config.tcp_keepalive = Some(delay);
+
+        self
+    }
+
+    /// Sets the time between the heartbeat messages in whole seconds.
+    ///
+    /// The default value is 30 seconds.
+    pub fn connection_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.config.connection_heartbeat_interval = Some(interval);
+
+        self
+    }
+
+    /// Sets the time a connection is allowed to be without a successful
+    /// heartbeat response before it is reconnected, in whole seconds.
+    ///
+    /// The default value is 60 seconds.
+    pub fn connection_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connection_idle_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Sets the retry policy used for all the requests.
+    ///
+    /// The default value is [`RetryPolicyKind::Default`].
+    pub fn retry_policy(mut self, kind: RetryPolicyKind) -> Self {
+        self.config.retry_policy = Some(kind);
+
+        self
+    }
+
+    /// Enables/Disables retrieving and updating the schema metadata.
+    ///
+    /// The default value is `true`.
+    pub fn use_schema(mut self, enabled: bool) -> Self {
+        self.config.use_schema = Some(enabled);
+
+        self
+    }
+
+    /// Enables/Disables the randomization of the contact points.
+    ///
+    /// The default value is `true`.
+    pub fn use_randomized_contact_points(mut self, enabled: bool) -> Self {
+        self.config.use_randomized_contact_points = Some(enabled);
+
+        self
+    }
+
+    /// Sets the speculative execution policy.
+    ///
+    /// The default value is [`SpeculativeExecutionPolicy::None`].
+    pub fn speculative_execution(
+        mut self,
+        policy: SpeculativeExecutionPolicy,
+    ) -> Self {
+        self.config.speculative_execution = Some(policy);
+
+        self
+    }
+
+    /// Sets the maximum number of "pending write" objects saved for re-use.
+    ///
+    /// The default value is [`u32::MAX`].
+    pub fn max_reusable_write_objects(mut self, count: usize) -> Self {
+        self.config.max_reusable_write_objects = Some(count);
+
+        self
+    }
+
+    /// Enables/Disables preparing the statements on all the hosts.
+    ///
+    /// The default value is `true`.
+    pub fn prepare_on_all_hosts(mut self, enabled: bool) -> Self {
+        self.config.prepare_on_all_hosts = Some(enabled);
+
+        self
+    }
+
+    /// Enables/Disables preparing the statements on the hosts that become
+    /// available.
+    ///
+    /// The default value is `true`.
+    pub fn prepare_on_up_or_add_host(mut self, enabled: bool) -> Self {
+        self.config.prepare_on_up_or_add_host = Some(enabled);
+
+        self
+    }
+
+    /// Enables/Disables sending the `NO_COMPACT` startup option.
+    ///
+    /// The default value is `false`.
+    pub fn no_compact(mut self, enabled: bool) -> Self {
+        self.config.no_compact = Some(enabled);
+
+        self
+    }
+
+    /// Sets the application name reported to the cluster.
+    pub fn application_name<T>(mut self, name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.config.application_name = Some(name.into());
+
+        self
+    }
+
+    /// Sets the application version reported to the cluster.
+    pub fn application_version<T>(mut self, version: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.config.application_version = Some(version.into());
+
+        self
+    }
+
+    /// Sets the client identifier reported to the cluster.
+    ///
+    /// A random identifier is generated by default.
+    pub fn client_id(mut self, id: CqlUuid) -> Self {
+        self.config.client_id = Some(id);
+
+        self
+    }
+
+    /// Sets the time between the monitor reporting events in whole seconds.
+    ///
+    /// The default value is 300 seconds.
+    pub fn monitor_reporting_interval(mut self, interval: Duration) -> Self {
+        self.config.monitor_reporting_interval = Some(interval);
+
+        self
+    }
+
+    /// Sets the time after which the metric histograms are refreshed.
+    ///
+    /// The histograms are never refreshed by default.
+    pub fn histogram_refresh_interval(mut self, interval: Duration) -> Self {
+        self.config.histogram_refresh_interval = Some(interval);
+
+        self
+    }
+
+    /// Sets the timestamp generator used for all the requests.
+    ///
+    /// The default value is [`TimestampGenKind::ServerSide`].
+    pub fn timestamp_gen(mut self, kind: TimestampGenKind) -> Self {
+        self.config.timestamp_gen = Some(kind);
+
+        self
+    }
+
+    /// Sets how the statement text is recorded in the request spans.
+    ///
+    /// The default value is [`SpanStatementMode::Redacted`].
+    pub fn span_statement_mode(mut self, mode: SpanStatementMode) -> Self {
+        self.config.span_statement_mode = Some(mode);
+
+        self
+    }
+
+    /// Adds an execution profile, replacing the profile with the same name.
+    pub fn profile<T>(
+        mut self,
+        name: T,
+        profile: ExecutionProfileConfig,
+    ) -> Self
+    where
+        T: Into<String>,
+    {
+        self.config.profiles.insert(name.into(), profile);
+
+        self
+    }
+
     /// Builds the driver configuration consuming the builder.
     pub fn build(mut self) -> DriverConfig {
-        if self.config.contact_points.is_empty() {
+        if self.config.contact_points.is_empty()
+            && self.config.srv_contact_points.is_none()
+            && self.config.cloud_secure_connect_bundle.is_none()
+        {
             // Connect to localhost by default.
             self.config.contact_points.push(ContactPoint::default());
         }
@@ -0,0 +1,72 @@
+use std::fmt::{
+    Display,
+    Formatter,
+};
+
+use itertools::Itertools;
+use thiserror::Error;
+
+use crate::{
+    DriverError,
+    DriverErrorKind,
+};
+
+/// An invalid setting of a [`DriverConfig`](crate::DriverConfig).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidSetting {
+    /// The name of the configuration field.
+    pub setting: &'static str,
+    /// Why the setting is invalid.
+    pub reason:  String,
+}
+
+impl InvalidSetting {
+    /// Creates a new invalid setting.
+    pub fn new<T>(setting: &'static str, reason: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            setting,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl Display for InvalidSetting {
+    /// Writes the setting name and the reason to the formatter.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.setting, self.reason)
+    }
+}
+
+/// An error that occurs when a [`DriverConfig`](crate::DriverConfig) cannot
+/// be turned into a [`Cluster`](crate::Cluster).
+///
+/// The error lists all the invalid settings, not only the first one.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("invalid driver configuration: {}", .0.iter().join("; "))]
+pub struct DriverConfigError(Vec<InvalidSetting>);
+
+impl DriverConfigError {
+    /// Creates a new error from the list of invalid settings.
+    pub fn new(invalid_settings: Vec<InvalidSetting>) -> Self {
+        Self(invalid_settings)
+    }
+
+    /// Returns the invalid settings.
+    pub fn invalid_settings(&self) -> &[InvalidSetting] {
+        &self.0
+    }
+}
+
+impl From<DriverConfigError> for DriverError {
+    /// Converts the configuration error into a driver error with the
+    /// [`DriverErrorKind::LibBadParams`] kind.
+    fn from(error: DriverConfigError) -> Self {
+        DriverError::with_message(
+            DriverErrorKind::LibBadParams,
+            error.to_string(),
+        )
+    }
+}
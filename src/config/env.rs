@@ -0,0 +1,636 @@
+use std::collections::BTreeMap;
+use std::fmt::{
+    Display,
+    Formatter,
+};
+
+use serde::de::value::{
+    MapDeserializer,
+    SeqDeserializer,
+    StringDeserializer,
+};
+use serde::de::{
+    DeserializeSeed,
+    EnumAccess,
+    Error as _,
+    IntoDeserializer,
+    VariantAccess,
+    Visitor,
+};
+use serde::{
+    forward_to_deserialize_any,
+    Deserialize,
+    Deserializer,
+};
+
+use crate::DriverConfig;
+
+/// The separator of the nested keys in the variable names, e.g.
+/// `CASSANDER_LOAD_BALANCING__DC_AWARE__LOCAL_DC`.
+const NESTED_KEY_SEPARATOR: &str = "__";
+
+/// An error that occurs when a setting is read from an environment variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EnvError {
+    /// The name of the variable, [`None`] until the error is attributed to
+    /// one.
+    pub(crate) variable: Option<String>,
+    /// The description of the error.
+    pub(crate) message:  String,
+}
+
+impl EnvError {
+    /// Attributes the error to the variable unless it is attributed already.
+    fn at(mut self, variable: &str) -> Self {
+        if self.variable.is_none() {
+            self.variable = Some(variable.to_string());
+        }
+
+        self
+    }
+}
+
+impl Display for EnvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for EnvError {}
+
+impl serde::de::Error for EnvError {
+    fn custom<T: Display>(message: T) -> Self {
+        Self {
+            variable: None,
+            message:  message.to_string(),
+        }
+    }
+}
+
+/// Reads the driver configuration from the environment variables with the
+/// given prefix.
+///
+/// The variable `<PREFIX>_<SETTING>` sets the setting, e.g.
+/// `CASSANDER_REQUEST_TIMEOUT=5s`. Lists are separated by commas, e.g.
+/// `CASSANDER_CONTACT_POINTS=a:9042,b:9042`. The nested settings and the
+/// settings of the enum variants are separated by double underscores, e.g.
+/// `CASSANDER_LOAD_BALANCING__DC_AWARE__LOCAL_DC=dc1`. The names are
+/// case-insensitive.
+///
+/// Only the settings that have a variable are set in the returned
+/// configuration. A variable with the prefix that does not name a setting is
+/// an error.
+pub(crate) fn read_env<I>(
+    prefix: &str,
+    variables: I,
+) -> Result<DriverConfig, EnvError>
+where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let prefix = format!("{}_", prefix.to_uppercase());
+    let mut settings = BTreeMap::new();
+    for (variable, value) in variables {
+        let Some(key) =
+            variable.to_uppercase().strip_prefix(&prefix).map(|key| {
+                key.split(NESTED_KEY_SEPARATOR)
+                    .map(str::to_lowercase)
+                    .collect::<Vec<_>>()
+            })
+        else {
+            continue;
+        };
+
+        insert(
+            &mut settings,
+            &key,
+            EnvNode::Value {
+                variable,
+                value,
+            },
+        )?;
+    }
+
+    // Each setting is read on its own to attribute the errors to the
+    // variables.
+    let mut config = DriverConfig::default();
+    for (key, node) in settings {
+        let variable = node.variable(&prefix, &key);
+        let setting = MapDeserializer::new(std::iter::once((key, node)));
+        let setting =
+            DriverConfig::deserialize(setting).map_err(|e| e.at(&variable))?;
+        config.merge(setting);
+    }
+
+    Ok(config)
+}
+
+/// Inserts the value of a variable into the settings tree.
+fn insert(
+    table: &mut BTreeMap<String, EnvNode>,
+    key: &[String],
+    value: EnvNode,
+) -> Result<(), EnvError> {
+    let conflict = |variable: &str| EnvError {
+        variable: Some(variable.to_string()),
+        message:  "the setting is set both directly and by nested variables"
+            .to_string(),
+    };
+
+    let [name, rest @ ..] = key else {
+        return Ok(());
+    };
+    if rest.is_empty() {
+        if let Some(EnvNode::Table(_)) = table.get(name) {
+            if let EnvNode::Value {
+                variable, ..
+            } = &value
+            {
+                return Err(conflict(variable));
+            }
+        }
+        table.insert(name.clone(), value);
+
+        return Ok(());
+    }
+
+    let node = table
+        .entry(name.clone())
+        .or_insert_with(|| EnvNode::Table(BTreeMap::new()));
+    match node {
+        EnvNode::Table(table) => insert(table, rest, value),
+        EnvNode::Value {
+            variable, ..
+        } => Err(conflict(variable)),
+    }
+}
+
+/// Returns the name normalized for the case-insensitive comparison that
+/// ignores the underscores, e.g. `DC_AWARE` matches `DcAware`.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Returns the name from the list that matches the key.
+fn find_name(
+    key: &str,
+    names: &'static [&'static str],
+) -> Option<&'static str> {
+    let key = normalize(key);
+
+    names.iter().copied().find(|name| normalize(name) == key)
+}
+
+/// A node of the settings tree built from the environment variables.
+#[derive(Debug)]
+enum EnvNode {
+    /// The value of a variable.
+    Value {
+        /// The name of the variable.
+        variable: String,
+        /// The value of the variable.
+        value:    String,
+    },
+    /// The nested settings.
+    Table(BTreeMap<String, EnvNode>),
+}
+
+impl EnvNode {
+    /// Returns the name of the variable the errors of the node are attributed
+    /// to.
+    fn variable(&self, prefix: &str, key: &str) -> String {
+        match self {
+            EnvNode::Value {
+                variable, ..
+            } => variable.clone(),
+            EnvNode::Table(_) => format!(
+                "{}{}{}*",
+                prefix,
+                key.to_uppercase(),
+                NESTED_KEY_SEPARATOR
+            ),
+        }
+    }
+
+    /// Deserializes a table into a struct or a map, matching the keys to the
+    /// given field names if any.
+    fn visit_table<'de, V>(
+        table: BTreeMap<String, EnvNode>,
+        fields: Option<&'static [&'static str]>,
+        visitor: V,
+    ) -> Result<V::Value, EnvError>
+    where
+        V: Visitor<'de>,
+    {
+        let entries = table.into_iter().map(|(key, node)| {
+            let key = fields
+                .and_then(|fields| find_name(&key, fields))
+                .map(str::to_string)
+                .unwrap_or(key);
+
+            (key, node)
+        });
+
+        visitor.visit_map(MapDeserializer::new(entries))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, EnvError> for EnvNode {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+/// Parses the value of a variable and visits it with the given method.
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                match self {
+                    EnvNode::Value { variable, value } => value
+                        .trim()
+                        .parse()
+                        .map_err(|e| EnvError::custom(format!(
+                            "invalid value '{}': {}",
+                            value, e
+                        )))
+                        .and_then(|value| visitor.$visit(value))
+                        .map_err(|e| e.at(&variable)),
+                    node => node.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for EnvNode {
+    type Error = EnvError;
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 str string bytes byte_buf unit unit_struct tuple
+        tuple_struct identifier
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            EnvNode::Value {
+                variable,
+                value,
+            } => visitor
+                .visit_string::<EnvError>(value)
+                .map_err(|e| e.at(&variable)),
+            EnvNode::Table(table) => Self::visit_table(table, None, visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match &self {
+            EnvNode::Value {
+                value, ..
+            } if value.trim().is_empty() => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            EnvNode::Value {
+                variable,
+                value,
+            } => {
+                let items = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(|item| EnvNode::Value {
+                        variable: variable.clone(),
+                        value:    item.to_string(),
+                    });
+
+                visitor
+                    .visit_seq(SeqDeserializer::new(items))
+                    .map_err(|e| e.at(&variable))
+            }
+            EnvNode::Table(_) => Err(EnvError::custom(
+                "expected a comma-separated list, not nested variables",
+            )),
+        }
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            EnvNode::Value {
+                variable, ..
+            } => Err(EnvError {
+                variable: Some(variable.clone()),
+                message:  format!(
+                    "expected nested settings, set the variables \
+                     {}{}<SETTING> instead",
+                    variable, NESTED_KEY_SEPARATOR
+                ),
+            }),
+            EnvNode::Table(table) => {
+                Self::visit_table(table, Some(fields), visitor)
+            }
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            EnvNode::Value {
+                variable,
+                value,
+            } => {
+                let variant = find_name(value.trim(), variants)
+                    .map(str::to_string)
+                    .unwrap_or(value);
+                let variant: StringDeserializer<EnvError> =
+                    variant.into_deserializer();
+
+                visitor.visit_enum(variant).map_err(|e| e.at(&variable))
+            }
+            EnvNode::Table(table) => {
+                let mut entries = table.into_iter();
+                match (entries.next(), entries.next()) {
+                    (Some((key, node)), None) => {
+                        let variant = find_name(&key, variants)
+                            .map(str::to_string)
+                            .unwrap_or(key);
+
+                        visitor.visit_enum(EnvVariant {
+                            variant,
+                            node,
+                        })
+                    }
+                    _ => Err(EnvError::custom(format!(
+                        "expected exactly one of {}",
+                        variants.join(", ")
+                    ))),
+                }
+            }
+        }
+    }
+
+    fn deserialize_ignored_any<V>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+}
+
+/// An enum variant with the settings set by nested variables.
+struct EnvVariant {
+    /// The name of the variant.
+    variant: String,
+    /// The settings of the variant.
+    node:    EnvNode,
+}
+
+impl<'de> EnumAccess<'de> for EnvVariant {
+    type Error = EnvError;
+    type Variant = EnvNode;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, EnvNode), EnvError>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant: StringDeserializer<EnvError> =
+            self.variant.into_deserializer();
+
+        Ok((seed.deserialize(variant)?, self.node))
+    }
+}
+
+impl<'de> VariantAccess<'de> for EnvNode {
+    type Error = EnvError;
+
+    fn unit_variant(self) -> Result<(), EnvError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, EnvError>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, EnvError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, EnvError>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_struct("", fields, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        LoadBalancingPolicy,
+        RetryPolicyKind,
+        SpanStatementMode,
+        SslConfig,
+        SslVerifyFlags,
+    };
+
+    fn vars(vars: &[(&str, &str)]) -> Vec<(String, String)> {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_read_env() {
+        let config = read_env(
+            "CASSANDER",
+            vars(&[
+                ("CASSANDER_CONTACT_POINTS", "10.0.0.1:9042, 10.0.0.2:9042"),
+                ("CASSANDER_REQUEST_TIMEOUT", "5s"),
+                ("CASSANDER_TCP_NODELAY", "false"),
+                ("CASSANDER_LOAD_BALANCING__DC_AWARE__LOCAL_DC", "dc1"),
+                ("CASSANDER_RETRY_POLICY__LOGGING", "FALLTHROUGH"),
+                ("CASSANDER_SPAN_STATEMENT_MODE", "omitted"),
+                ("CASSANDER_WHITELIST_DCS", "dc1,dc2"),
+                (
+                    "CASSANDER_SSL_CONFIG__VERIFY_FLAGS",
+                    "peer_cert,PEER_IDENTITY",
+                ),
+                ("CASSANDER_SSL_CONFIG__TRUSTED_CERTS", "/etc/ssl/ca.pem"),
+                ("HOME", "/root"),
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.contact_points,
+            vec![
+                "10.0.0.1:9042".parse().unwrap(),
+                "10.0.0.2:9042".parse().unwrap()
+            ]
+        );
+        assert_eq!(config.request_timeout, Some(Duration::from_secs(5)));
+        assert_eq!(config.tcp_nodelay, Some(false));
+        assert_eq!(
+            config.load_balancing,
+            Some(LoadBalancingPolicy::DcAware {
+                local_dc:                      "dc1".to_string(),
+                used_hosts_per_remote_dc:      0,
+                allow_remote_dcs_for_local_cl: false,
+            })
+        );
+        assert_eq!(
+            config.retry_policy,
+            Some(RetryPolicyKind::Logging(Box::new(
+                RetryPolicyKind::Fallthrough
+            )))
+        );
+        assert_eq!(
+            config.span_statement_mode,
+            Some(SpanStatementMode::Omitted)
+        );
+        assert_eq!(config.whitelist_dcs, vec!["dc1", "dc2"]);
+        assert_eq!(
+            config.ssl_config,
+            Some(SslConfig {
+                trusted_certs: vec!["/etc/ssl/ca.pem".into()],
+                verify_flags: Some(
+                    SslVerifyFlags::PEER_CERT | SslVerifyFlags::PEER_IDENTITY
+                ),
+                ..Default::default()
+            })
+        );
+        assert_eq!(config.port, None);
+    }
+
+    #[test]
+    fn test_read_env_errors_point_at_the_variable() {
+        let error = read_env("CASSANDER", vars(&[("CASSANDER_PORT", "90420")]))
+            .err()
+            .unwrap();
+        assert_eq!(error.variable.as_deref(), Some("CASSANDER_PORT"));
+        assert!(error.message.starts_with("invalid value '90420'"));
+
+        let error = read_env(
+            "CASSANDER",
+            vars(&[("CASSANDER_LOAD_BALANCING__DC_AWARE__USED_HOSTS", "x")]),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.variable.as_deref(),
+            Some("CASSANDER_LOAD_BALANCING__*")
+        );
+    }
+
+    #[test]
+    fn test_read_env_rejects_unknown_variables() {
+        let error = read_env(
+            "CASSANDER",
+            vars(&[("CASSANDER_REQUEST_TIMEOUTS", "5s")]),
+        )
+        .err()
+        .unwrap();
+        assert_eq!(
+            error.variable.as_deref(),
+            Some("CASSANDER_REQUEST_TIMEOUTS")
+        );
+        assert!(error
+            .message
+            .starts_with("unknown field `request_timeouts`, expected one of"));
+
+        let error = read_env(
+            "CASSANDER",
+            vars(&[("CASSANDER_SSL_CONFIG__TRUSTED_CERT", "/etc/ssl/ca.pem")]),
+        )
+        .err()
+        .unwrap();
+        assert!(error.message.starts_with("unknown field `trusted_cert`"));
+    }
+}
@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+use super::driver_config::millis;
+use crate::{
+    Consistency,
+    DriverError,
+    DriverErrorKind,
+    ExecutionProfile,
+    LatencyAwareRouting,
+    LoadBalancingPolicy,
+    RetryPolicyKind,
+    SpeculativeExecutionPolicy,
+};
+
+/// The configuration of an [`ExecutionProfile`].
+///
+/// The settings that are not set fall back to the cluster settings, see
+/// [`ExecutionProfile`] for how the load balancing settings combine.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct ExecutionProfileConfig {
+    /// The timeout for waiting for a response from a node, a zero timeout
+    /// disables it.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::opt_duration_as_string")
+    )]
+    pub request_timeout: Option<Duration>,
+
+    /// The consistency level of the requests.
+    pub consistency: Option<Consistency>,
+
+    /// The serial consistency level of the requests.
+    pub serial_consistency: Option<Consistency>,
+
+    /// The load balancing policy.
+    pub load_balancing: Option<LoadBalancingPolicy>,
+
+    /// Whether the requests are routed to the replicas of their partition.
+    pub token_aware_routing: Option<bool>,
+
+    /// Whether the replicas selected by the token-aware routing are shuffled.
+    pub token_aware_routing_shuffle_replicas: Option<bool>,
+
+    /// The settings of the latency-aware routing.
+    pub latency_aware_routing: Option<LatencyAwareRouting>,
+
+    /// The hosts the requests are allowed to be sent to.
+    pub whitelist_hosts: Vec<String>,
+
+    /// The hosts the requests are not allowed to be sent to.
+    pub blacklist_hosts: Vec<String>,
+
+    /// The data centers the requests are allowed to be sent to.
+    pub whitelist_dcs: Vec<String>,
+
+    /// The data centers the requests are not allowed to be sent to.
+    pub blacklist_dcs: Vec<String>,
+
+    /// The retry policy.
+    pub retry_policy: Option<RetryPolicyKind>,
+
+    /// The speculative execution policy.
+    pub speculative_execution: Option<SpeculativeExecutionPolicy>,
+}
+
+impl ExecutionProfileConfig {
+    /// Builds the execution profile.
+    ///
+    /// The error message names the first setting the driver rejects.
+    pub fn build(&self) -> Result<ExecutionProfile, DriverError> {
+        let mut profile = ExecutionProfile::new();
+
+        if let Some(timeout) = self.request_timeout {
+            setting(
+                "request_timeout",
+                millis(timeout)
+                    .and_then(|millis| profile.set_request_timeout(millis)),
+            )?;
+        }
+        if let Some(consistency) = self.consistency {
+            setting("consistency", profile.set_consistency(consistency))?;
+        }
+        if let Some(consistency) = self.serial_consistency {
+            setting(
+                "serial_consistency",
+                profile.set_serial_consistency(consistency),
+            )?;
+        }
+        match &self.load_balancing {
+            Some(LoadBalancingPolicy::RoundRobin) => setting(
+                "load_balancing",
+                profile.set_load_balance_round_robin(),
+            )?,
+            Some(LoadBalancingPolicy::DcAware {
+                local_dc,
+                used_hosts_per_remote_dc,
+                allow_remote_dcs_for_local_cl,
+            }) => setting(
+                "load_balancing",
+                profile.set_load_balance_dc_aware(
+                    local_dc,
+                    *used_hosts_per_remote_dc,
+                    *allow_remote_dcs_for_local_cl,
+                ),
+            )?,
+            None => {}
+        }
+        if let Some(enabled) = self.token_aware_routing {
+            setting(
+                "token_aware_routing",
+                profile.set_token_aware_routing(enabled),
+            )?;
+        }
+        if let Some(enabled) = self.token_aware_routing_shuffle_replicas {
+            setting(
+                "token_aware_routing_shuffle_replicas",
+                profile.set_token_aware_routing_shuffle_replicas(enabled),
+            )?;
+        }
+        if let Some(routing) = self.latency_aware_routing {
+            setting(
+                "latency_aware_routing",
+                profile.set_latency_aware_routing(true).and_then(|_| {
+                    profile.set_latency_aware_routing_settings(
+                        routing.exclusion_threshold,
+                        millis(routing.scale)?,
+                        millis(routing.retry_period)?,
+                        millis(routing.update_rate)?,
+                        routing.min_measured,
+                    )
+                }),
+            )?;
+        }
+        if !self.whitelist_hosts.is_empty() {
+            setting(
+                "whitelist_hosts",
+                profile.set_whitelist_filtering(self.whitelist_hosts.join(",")),
+            )?;
+        }
+        if !self.blacklist_hosts.is_empty() {
+            setting(
+                "blacklist_hosts",
+                profile.set_blacklist_filtering(self.blacklist_hosts.join(",")),
+            )?;
+        }
+        if !self.whitelist_dcs.is_empty() {
+            setting(
+                "whitelist_dcs",
+                profile
+                    .set_whitelist_dc_filtering(self.whitelist_dcs.join(",")),
+            )?;
+        }
+        if !self.blacklist_dcs.is_empty() {
+            setting(
+                "blacklist_dcs",
+                profile
+                    .set_blacklist_dc_filtering(self.blacklist_dcs.join(",")),
+            )?;
+        }
+        if let Some(kind) = &self.retry_policy {
+            let result = match kind.create() {
+                Some(policy) => profile.set_retry_policy(&policy),
+                None => Err(DriverError::with_message(
+                    DriverErrorKind::LibBadParams,
+                    "a logging retry policy cannot wrap another logging policy",
+                )),
+            };
+            setting("retry_policy", result)?;
+        }
+        match self.speculative_execution {
+            Some(SpeculativeExecutionPolicy::None) => setting(
+                "speculative_execution",
+                profile.set_no_speculative_execution_policy(),
+            )?,
+            Some(SpeculativeExecutionPolicy::Constant {
+                delay,
+                max_executions,
+            }) => setting(
+                "speculative_execution",
+                millis(delay).and_then(|delay| {
+                    profile.set_constant_speculative_execution_policy(
+                        delay,
+                        max_executions,
+                    )
+                }),
+            )?,
+            None => {}
+        }
+
+        Ok(profile)
+    }
+}
+
+/// Prefixes the error message of the setting with its name.
+fn setting(
+    name: &str,
+    result: Result<(), DriverError>,
+) -> Result<(), DriverError> {
+    result.map_err(|error| {
+        DriverError::with_message(
+            error.kind,
+            format!("{}: {}", name, error.message),
+        )
+    })
+}
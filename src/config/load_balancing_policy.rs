@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+/// The policy that selects the nodes to send the requests to.
+///
+/// The policy is combined with the token-aware and latency-aware routing and
+/// the host filtering settings of the [`DriverConfig`].
+///
+/// [`DriverConfig`]: crate::DriverConfig
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub enum LoadBalancingPolicy {
+    /// Sends the requests to all the nodes of the cluster in turn.
+    RoundRobin,
+    /// Sends the requests to the nodes of the local data center in turn and
+    /// falls back to the remote data centers when the local nodes are down.
+    DcAware {
+        /// The name of the local data center.
+        ///
+        /// The data center of the contact points is used when empty.
+        local_dc:                      String,
+        /// The number of hosts used in each remote data center if no hosts
+        /// are available in the local data center.
+        #[cfg_attr(feature = "serde", serde(default))]
+        used_hosts_per_remote_dc:      usize,
+        /// Whether the remote hosts are used for the `LOCAL_ONE` and
+        /// `LOCAL_QUORUM` consistency levels.
+        #[cfg_attr(feature = "serde", serde(default))]
+        allow_remote_dcs_for_local_cl: bool,
+    },
+}
+
+/// The settings of the latency-aware routing.
+///
+/// See [`Cluster::set_latency_aware_routing_settings`] for the meaning of the
+/// settings.
+///
+/// [`Cluster::set_latency_aware_routing_settings`]: crate::Cluster::set_latency_aware_routing_settings
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default, deny_unknown_fields)
+)]
+pub struct LatencyAwareRouting {
+    /// How much worse the latency of a node must be compared to the best
+    /// average latency before it is penalized.
+    pub exclusion_threshold: f64,
+    /// The weight given to older latencies when computing the average latency
+    /// of a node.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::duration_as_string")
+    )]
+    pub scale:               Duration,
+    /// The time a penalized node waits before it is given a second chance.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::duration_as_string")
+    )]
+    pub retry_period:        Duration,
+    /// The rate the best average latency is recomputed at.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serialization::duration_as_string")
+    )]
+    pub update_rate:         Duration,
+    /// The minimum number of measurements of a node required to consider it.
+    pub min_measured:        usize,
+}
+
+impl Default for LatencyAwareRouting {
+    /// Returns the driver's default settings.
+    fn default() -> Self {
+        Self {
+            exclusion_threshold: 2.0,
+            scale:               Duration::from_millis(100),
+            retry_period:        Duration::from_secs(10),
+            update_rate:         Duration::from_millis(100),
+            min_measured:        50,
+        }
+    }
+}
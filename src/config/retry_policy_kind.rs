@@ -0,0 +1,30 @@
+use crate::RetryPolicy;
+
+/// The kind of the [`RetryPolicy`] of a cluster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RetryPolicyKind {
+    /// The policy created by [`RetryPolicy::new`].
+    Default,
+    /// The policy created by [`RetryPolicy::fallthrough`].
+    Fallthrough,
+    /// The policy created by [`RetryPolicy::logging`] that logs the decisions
+    /// of the child policy.
+    Logging(Box<RetryPolicyKind>),
+}
+
+impl RetryPolicyKind {
+    /// Creates the retry policy.
+    ///
+    /// Returns [`None`] if a logging policy wraps another logging policy.
+    pub fn create(&self) -> Option<RetryPolicy> {
+        match self {
+            RetryPolicyKind::Default => Some(RetryPolicy::new()),
+            RetryPolicyKind::Fallthrough => Some(RetryPolicy::fallthrough()),
+            RetryPolicyKind::Logging(child) => match child.as_ref() {
+                RetryPolicyKind::Logging(_) => None,
+                child => RetryPolicy::logging(&child.create()?),
+            },
+        }
+    }
+}
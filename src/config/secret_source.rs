@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+
+use crate::{
+    DriverError,
+    DriverErrorKind,
+    Secret,
+};
+
+/// Where a secret, e.g. a password, is read from.
+///
+/// The secret itself is never part of the configuration, so the configuration
+/// can be stored and logged safely.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SecretSource {
+    /// The secret is the value of the environment variable with the given
+    /// name.
+    Env(String),
+    /// The secret is the content of the file with the given path, without
+    /// the trailing line break.
+    File(PathBuf),
+}
+
+impl SecretSource {
+    /// Reads the secret.
+    pub fn read(&self) -> Result<Secret, DriverError> {
+        match self {
+            SecretSource::Env(name) => {
+                std::env::var(name).map(Secret::new).map_err(|error| {
+                    DriverError::with_message(
+                        DriverErrorKind::LibBadParams,
+                        format!(
+                            "failed to read the secret from the environment \
+                             variable {}: {}",
+                            name, error
+                        ),
+                    )
+                })
+            }
+            SecretSource::File(path) => {
+                let mut secret = Secret::new(
+                    std::fs::read_to_string(path).map_err(|error| {
+                        DriverError::with_message(
+                            DriverErrorKind::LibBadParams,
+                            format!(
+                                "failed to read the secret from {}: {}",
+                                path.display(),
+                                error
+                            ),
+                        )
+                    })?,
+                );
+                let len = secret.expose().trim_end_matches(['\r', '\n']).len();
+                if len < secret.expose().len() {
+                    secret = Secret::new(&secret.expose()[..len]);
+                }
+
+                Ok(secret)
+            }
+        }
+    }
+}
@@ -40,3 +40,39 @@ pub mod opt_duration_as_string {
         Ok(duration)
     }
 }
+
+pub mod duration_as_string {
+    use std::time::Duration;
+
+    use duration_string::{
+        self,
+        DurationString,
+    };
+    use serde::{
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+    };
+
+    ///  Serializes a `Duration` as a string.
+    pub fn serialize<S>(
+        duration: &Duration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        DurationString::new(*duration).serialize(serializer)
+    }
+
+    /// Deserializes a `Duration` from a string.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let duration = DurationString::deserialize(deserializer)?.into();
+
+        Ok(duration)
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+/// The policy that decides whether the driver sends the same request to other
+/// nodes before the first node replies.
+///
+/// Only idempotent statements are executed speculatively.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub enum SpeculativeExecutionPolicy {
+    /// The requests are never executed speculatively.
+    #[default]
+    None,
+    /// The request is sent to the next node after a constant delay until the
+    /// maximum number of speculative executions is reached.
+    Constant {
+        /// The delay between the executions, a zero delay sends all the
+        /// executions immediately.
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serialization::duration_as_string")
+        )]
+        delay:          Duration,
+        /// The maximum number of executions in addition to the original
+        /// request.
+        max_executions: usize,
+    },
+}
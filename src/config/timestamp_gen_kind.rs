@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use crate::TimestampGen;
+
+/// The kind of the [`TimestampGen`] of a cluster.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(deny_unknown_fields)
+)]
+pub enum TimestampGenKind {
+    /// The timestamps are assigned by the server.
+    #[default]
+    ServerSide,
+    /// The timestamps are assigned by a monotonically increasing client-side
+    /// generator.
+    Monotonic {
+        /// The clock skew that triggers a warning, [`None`] disables the
+        /// warnings.
+        #[cfg_attr(
+            feature = "serde",
+            serde(
+                default = "default_warning_threshold",
+                with = "crate::serialization::opt_duration_as_string"
+            )
+        )]
+        warning_threshold: Option<Duration>,
+        /// The time to wait before warning again about the clock skew.
+        #[cfg_attr(
+            feature = "serde",
+            serde(
+                default = "default_warning_interval",
+                with = "crate::serialization::duration_as_string"
+            )
+        )]
+        warning_interval:  Duration,
+    },
+}
+
+impl TimestampGenKind {
+    /// Returns the monotonic generator with the driver's default settings.
+    pub fn monotonic() -> Self {
+        TimestampGenKind::Monotonic {
+            warning_threshold: default_warning_threshold(),
+            warning_interval:  default_warning_interval(),
+        }
+    }
+
+    /// Creates the timestamp generator.
+    pub fn create(&self) -> TimestampGen {
+        match self {
+            TimestampGenKind::ServerSide => TimestampGen::new(),
+            TimestampGenKind::Monotonic {
+                warning_threshold,
+                warning_interval,
+            } => TimestampGen::monotonic_with_settings(
+                warning_threshold.map_or(-1, |threshold| {
+                    threshold.as_micros().try_into().unwrap_or(i64::MAX)
+                }),
+                warning_interval.as_millis().try_into().unwrap_or(i64::MAX),
+            ),
+        }
+    }
+}
+
+/// Returns the driver's default clock skew that triggers a warning.
+fn default_warning_threshold() -> Option<Duration> {
+    Some(Duration::from_secs(1))
+}
+
+/// Returns the driver's default time between the clock skew warnings.
+fn default_warning_interval() -> Duration {
+    Duration::from_secs(1)
+}
@@ -0,0 +1,190 @@
+use std::fmt::{
+    Debug,
+    Formatter,
+};
+use std::net::{
+    IpAddr,
+    SocketAddr,
+};
+use std::sync::Arc;
+
+use crate::ffi::struct_CassNode_;
+use crate::future::FutureHandle;
+
+/// The node that coordinated a request.
+///
+/// The coordinator is returned with [`QueryResult::coordinator`] and
+/// [`DriverError::coordinator`]. Use it with [`Statement::set_node`] to send
+/// a follow-up request to the same node, e.g. to read a trace or to query the
+/// node-local system tables.
+///
+/// The address of the node is returned with [`Coordinator::address`]. The
+/// address of the coordinator of a traced request is also available with
+/// [`QueryTrace::coordinator`].
+///
+/// [`QueryResult::coordinator`]: crate::QueryResult::coordinator
+/// [`DriverError::coordinator`]: crate::DriverError::coordinator
+/// [`Statement::set_node`]: crate::Statement::set_node
+/// [`QueryTrace::coordinator`]: crate::QueryTrace::coordinator
+#[derive(Clone)]
+pub struct Coordinator {
+    /// The future the node belongs to.
+    _future: Arc<FutureHandle>,
+    /// The driver's node object.
+    inner:   *const struct_CassNode_,
+}
+
+impl Coordinator {
+    /// Creates a new coordinator from the driver's node object owned by the
+    /// given future.
+    pub(crate) fn from_driver(
+        future: Arc<FutureHandle>,
+        inner: *const struct_CassNode_,
+    ) -> Self {
+        assert!(
+            !inner.is_null(),
+            "the driver's node object must not be null"
+        );
+
+        Self {
+            _future: future,
+            inner,
+        }
+    }
+
+    /// Returns the raw pointer to the node object.
+    pub(crate) fn inner(&self) -> *const struct_CassNode_ {
+        self.inner
+    }
+
+    /// Returns the address and the native protocol port of the node.
+    ///
+    /// The driver has no function that returns the address of a node, so it
+    /// is read from the driver's node object, which is the address the
+    /// request was sent to. Returns [`None`] if the object does not have the
+    /// layout of the version 2.17 of the driver built with `libstdc++` on a
+    /// 64-bit Linux, or if the node is known by a hostname that was not
+    /// resolved, e.g. behind the SNI proxy of a cloud bundle.
+    pub fn address(&self) -> Option<SocketAddr> {
+        // The node is owned by the future the coordinator keeps alive.
+        unsafe { read_node_address(self.inner as *const u8) }
+    }
+}
+
+/// The size of the driver's node object, a `datastax::internal::core::Address`:
+/// the address bytes or the hostname and the server name as two `libstdc++`
+/// strings, then the address family and the port as two 32-bit integers.
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+const NODE_SIZE: usize = 72;
+
+/// Reads the address of the driver's node object.
+///
+/// A `libstdc++` string is its data pointer, its length and a 16-byte buffer
+/// that holds the short strings, so the 4 bytes of an IPv4 address are in the
+/// object and the 16 bytes of an IPv6 address are allocated. The layout is
+/// checked before the IPv6 address is read through the pointer: the family
+/// and the length must match, and the data must not be in the buffer, which
+/// then holds the capacity of the allocation.
+///
+/// # Safety
+///
+/// The `node` must point to a node object returned by the driver.
+#[cfg(all(target_os = "linux", target_pointer_width = "64"))]
+unsafe fn read_node_address(node: *const u8) -> Option<SocketAddr> {
+    const IPV4: i32 = 1;
+    const IPV6: i32 = 2;
+
+    let bytes = std::ptr::read_unaligned(node as *const [u8; NODE_SIZE]);
+    let word = |offset: usize| {
+        u64::from_ne_bytes(bytes[offset..offset + 8].try_into().unwrap())
+            as usize
+    };
+    let int = |offset: usize| {
+        i32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    };
+
+    let (data, len, buffer) = (word(0), word(8), node as usize + 16);
+    let port = u16::try_from(int(68)).ok().filter(|port| *port != 0)?;
+    let ip = match (int(64), len) {
+        (IPV4, 4) if data == buffer => {
+            IpAddr::from(<[u8; 4]>::try_from(&bytes[16..20]).unwrap())
+        }
+        (IPV6, 16) if data != 0 && data != buffer => {
+            if !(16..=32).contains(&word(16)) {
+                return None;
+            }
+            IpAddr::from(std::ptr::read_unaligned(data as *const [u8; 16]))
+        }
+        _ => return None,
+    };
+
+    Some(SocketAddr::new(ip, port))
+}
+
+/// The layout of the driver's node object is only known for `libstdc++` on
+/// a 64-bit Linux.
+#[cfg(not(all(target_os = "linux", target_pointer_width = "64")))]
+unsafe fn read_node_address(_node: *const u8) -> Option<SocketAddr> {
+    None
+}
+
+impl Debug for Coordinator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Coordinator").finish_non_exhaustive()
+    }
+}
+
+// The node is read-only and can be safely shared between threads.
+unsafe impl Send for Coordinator {}
+unsafe impl Sync for Coordinator {}
+
+#[cfg(all(test, target_os = "linux", target_pointer_width = "64"))]
+mod tests {
+    use super::*;
+
+    /// Lays out a node object the way the driver does.
+    fn node(
+        family: i32,
+        port: i32,
+        address: &[u8],
+    ) -> (Box<[u8; 72]>, Vec<u8>) {
+        let mut node = Box::new([0; 72]);
+        let heap = address.to_vec();
+        let data = if address.len() < 16 {
+            node[16..16 + address.len()].copy_from_slice(address);
+            node.as_ptr() as usize + 16
+        } else {
+            node[16..24].copy_from_slice(&(address.len() as u64).to_ne_bytes());
+            heap.as_ptr() as usize
+        };
+        node[0..8].copy_from_slice(&(data as u64).to_ne_bytes());
+        node[8..16].copy_from_slice(&(address.len() as u64).to_ne_bytes());
+        node[64..68].copy_from_slice(&family.to_ne_bytes());
+        node[68..72].copy_from_slice(&port.to_ne_bytes());
+
+        (node, heap)
+    }
+
+    fn read(node: &[u8; 72]) -> Option<SocketAddr> {
+        unsafe { read_node_address(node.as_ptr()) }
+    }
+
+    #[test]
+    fn test_read_node_address() {
+        let (ipv4, _) = node(1, 9042, &[10, 0, 0, 1]);
+        assert_eq!(read(&ipv4), Some("10.0.0.1:9042".parse().unwrap()));
+
+        let ip = "2001:db8::1".parse::<std::net::Ipv6Addr>().unwrap();
+        let (ipv6, _heap) = node(2, 9142, &ip.octets());
+        assert_eq!(read(&ipv6), Some("[2001:db8::1]:9142".parse().unwrap()));
+
+        let (hostname, _) = node(0, 9042, b"node1");
+        assert_eq!(read(&hostname), None);
+        let (no_port, _) = node(1, 0, &[10, 0, 0, 1]);
+        assert_eq!(read(&no_port), None);
+
+        let (mut moved, _) = node(1, 9042, &[10, 0, 0, 1]);
+        moved[0..8].copy_from_slice(&1u64.to_ne_bytes());
+        assert_eq!(read(&moved), None);
+    }
+}
@@ -1,9 +1,18 @@
 //! CQL types and utilities.
 
+mod data_type;
 mod inet;
 mod uuid;
 mod uuid_gen;
+mod value;
 
+pub use data_type::*;
 pub use inet::*;
 pub use uuid::*;
 pub use uuid_gen::*;
+pub use value::*;
+
+/// Quotes the name of a keyspace, a table or a column for a CQL query.
+pub(crate) fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
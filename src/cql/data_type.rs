@@ -0,0 +1,261 @@
+use std::ffi::c_char;
+use std::fmt::{
+    self,
+    Display,
+    Formatter,
+};
+use std::slice;
+
+#[rustfmt::skip]
+use crate::ffi::{
+    cass_data_type_class_name,
+    cass_data_type_sub_data_type,
+    cass_data_type_sub_type_count,
+    cass_data_type_sub_type_name,
+    cass_data_type_type,
+    cass_data_type_type_name,
+    enum_CassError_,
+    enum_CassError__CASS_OK                            as CASS_OK,
+    enum_CassValueType__CASS_VALUE_TYPE_ASCII          as ASCII,
+    enum_CassValueType__CASS_VALUE_TYPE_BIGINT         as BIGINT,
+    enum_CassValueType__CASS_VALUE_TYPE_BLOB           as BLOB,
+    enum_CassValueType__CASS_VALUE_TYPE_BOOLEAN        as BOOLEAN,
+    enum_CassValueType__CASS_VALUE_TYPE_COUNTER        as COUNTER,
+    enum_CassValueType__CASS_VALUE_TYPE_DATE           as DATE,
+    enum_CassValueType__CASS_VALUE_TYPE_DECIMAL        as DECIMAL,
+    enum_CassValueType__CASS_VALUE_TYPE_DOUBLE         as DOUBLE,
+    enum_CassValueType__CASS_VALUE_TYPE_DURATION       as DURATION,
+    enum_CassValueType__CASS_VALUE_TYPE_FLOAT          as FLOAT,
+    enum_CassValueType__CASS_VALUE_TYPE_INET           as INET,
+    enum_CassValueType__CASS_VALUE_TYPE_INT            as INT,
+    enum_CassValueType__CASS_VALUE_TYPE_LIST           as LIST,
+    enum_CassValueType__CASS_VALUE_TYPE_MAP            as MAP,
+    enum_CassValueType__CASS_VALUE_TYPE_SET            as SET,
+    enum_CassValueType__CASS_VALUE_TYPE_SMALL_INT      as SMALL_INT,
+    enum_CassValueType__CASS_VALUE_TYPE_TEXT           as TEXT,
+    enum_CassValueType__CASS_VALUE_TYPE_TIME           as TIME,
+    enum_CassValueType__CASS_VALUE_TYPE_TIMESTAMP      as TIMESTAMP,
+    enum_CassValueType__CASS_VALUE_TYPE_TIMEUUID       as TIMEUUID,
+    enum_CassValueType__CASS_VALUE_TYPE_TINY_INT       as TINY_INT,
+    enum_CassValueType__CASS_VALUE_TYPE_TUPLE          as TUPLE,
+    enum_CassValueType__CASS_VALUE_TYPE_UDT            as UDT,
+    enum_CassValueType__CASS_VALUE_TYPE_UUID           as UUID,
+    enum_CassValueType__CASS_VALUE_TYPE_VARCHAR        as VARCHAR,
+    enum_CassValueType__CASS_VALUE_TYPE_VARINT         as VARINT,
+    struct_CassDataType_,
+};
+
+/// The data type of a CQL value, e.g. of a table column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CqlType {
+    /// An ASCII string.
+    Ascii,
+    /// A UTF-8 string, `text` or `varchar`.
+    Text,
+    /// A boolean.
+    Boolean,
+    /// An 8-bit signed integer.
+    TinyInt,
+    /// A 16-bit signed integer.
+    SmallInt,
+    /// A 32-bit signed integer.
+    Int,
+    /// A 64-bit signed integer.
+    BigInt,
+    /// A counter.
+    Counter,
+    /// A 32-bit floating point number.
+    Float,
+    /// A 64-bit floating point number.
+    Double,
+    /// A decimal number.
+    Decimal,
+    /// An arbitrary-precision integer.
+    Varint,
+    /// A UUID.
+    Uuid,
+    /// A version 1 (time-based) UUID.
+    TimeUuid,
+    /// An IPv4 or IPv6 address.
+    Inet,
+    /// Arbitrary bytes.
+    Blob,
+    /// A timestamp with millisecond precision.
+    Timestamp,
+    /// A date without a time.
+    Date,
+    /// A time of day with nanosecond precision.
+    Time,
+    /// A duration.
+    Duration,
+    /// A list of the values of the type.
+    List(Box<CqlType>),
+    /// A set of the values of the type.
+    Set(Box<CqlType>),
+    /// A map with the keys and the values of the types.
+    Map(Box<CqlType>, Box<CqlType>),
+    /// A tuple of the values of the types.
+    Tuple(Vec<CqlType>),
+    /// A user-defined type.
+    UserType {
+        /// The name of the type.
+        name:   String,
+        /// The names and the types of the fields.
+        fields: Vec<(String, CqlType)>,
+    },
+    /// A custom type, or a type the driver does not know.
+    Custom(String),
+}
+
+impl CqlType {
+    /// Reads the type from the driver object.
+    pub(crate) fn from_driver(data_type: *const struct_CassDataType_) -> Self {
+        if data_type.is_null() {
+            return CqlType::Custom(String::new());
+        }
+
+        let sub_type = |index| {
+            CqlType::from_driver(unsafe {
+                cass_data_type_sub_data_type(data_type, index)
+            })
+        };
+        let sub_types = || {
+            let count = unsafe { cass_data_type_sub_type_count(data_type) };
+            (0..count).map(sub_type).collect::<Vec<_>>()
+        };
+
+        match unsafe { cass_data_type_type(data_type) } {
+            ASCII => CqlType::Ascii,
+            TEXT | VARCHAR => CqlType::Text,
+            BOOLEAN => CqlType::Boolean,
+            TINY_INT => CqlType::TinyInt,
+            SMALL_INT => CqlType::SmallInt,
+            INT => CqlType::Int,
+            BIGINT => CqlType::BigInt,
+            COUNTER => CqlType::Counter,
+            FLOAT => CqlType::Float,
+            DOUBLE => CqlType::Double,
+            DECIMAL => CqlType::Decimal,
+            VARINT => CqlType::Varint,
+            UUID => CqlType::Uuid,
+            TIMEUUID => CqlType::TimeUuid,
+            INET => CqlType::Inet,
+            BLOB => CqlType::Blob,
+            TIMESTAMP => CqlType::Timestamp,
+            DATE => CqlType::Date,
+            TIME => CqlType::Time,
+            DURATION => CqlType::Duration,
+            LIST => CqlType::List(Box::new(sub_type(0))),
+            SET => CqlType::Set(Box::new(sub_type(0))),
+            MAP => CqlType::Map(Box::new(sub_type(0)), Box::new(sub_type(1))),
+            TUPLE => CqlType::Tuple(sub_types()),
+            UDT => {
+                let names = (0..sub_types().len()).map(|index| {
+                    get_string(|s, l| unsafe {
+                        cass_data_type_sub_type_name(data_type, index, s, l)
+                    })
+                });
+
+                CqlType::UserType {
+                    name:   get_string(|s, l| unsafe {
+                        cass_data_type_type_name(data_type, s, l)
+                    }),
+                    fields: names.zip(sub_types()).collect(),
+                }
+            }
+            _ => CqlType::Custom(get_string(|s, l| unsafe {
+                cass_data_type_class_name(data_type, s, l)
+            })),
+        }
+    }
+
+    /// Returns `true` if the type is a list, a set or a map.
+    pub fn is_collection(&self) -> bool {
+        matches!(self, CqlType::List(_) | CqlType::Set(_) | CqlType::Map(..))
+    }
+}
+
+impl Display for CqlType {
+    /// Formats the type as in CQL, e.g. `map<text, int>`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CqlType::Ascii => "ascii",
+            CqlType::Text => "text",
+            CqlType::Boolean => "boolean",
+            CqlType::TinyInt => "tinyint",
+            CqlType::SmallInt => "smallint",
+            CqlType::Int => "int",
+            CqlType::BigInt => "bigint",
+            CqlType::Counter => "counter",
+            CqlType::Float => "float",
+            CqlType::Double => "double",
+            CqlType::Decimal => "decimal",
+            CqlType::Varint => "varint",
+            CqlType::Uuid => "uuid",
+            CqlType::TimeUuid => "timeuuid",
+            CqlType::Inet => "inet",
+            CqlType::Blob => "blob",
+            CqlType::Timestamp => "timestamp",
+            CqlType::Date => "date",
+            CqlType::Time => "time",
+            CqlType::Duration => "duration",
+            CqlType::List(item) => return write!(f, "list<{}>", item),
+            CqlType::Set(item) => return write!(f, "set<{}>", item),
+            CqlType::Map(key, value) => {
+                return write!(f, "map<{}, {}>", key, value)
+            }
+            CqlType::Tuple(items) => {
+                let items: Vec<_> =
+                    items.iter().map(ToString::to_string).collect();
+                return write!(f, "tuple<{}>", items.join(", "));
+            }
+            CqlType::UserType {
+                name, ..
+            } => name,
+            CqlType::Custom(class_name) => {
+                return write!(f, "'{}'", class_name)
+            }
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// Copies a string returned by the driver.
+///
+/// Returns an empty string if the getter fails.
+fn get_string<F>(getter: F) -> String
+where
+    F: FnOnce(*mut *const c_char, *mut usize) -> enum_CassError_,
+{
+    let mut string = std::ptr::null();
+    let mut string_len = 0;
+    if getter(&mut string, &mut string_len) != CASS_OK || string.is_null() {
+        return String::new();
+    }
+
+    // The driver returns a pointer to its internal data. We should not free it.
+    let bytes =
+        unsafe { slice::from_raw_parts(string as *const u8, string_len) };
+
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let map = CqlType::Map(
+            Box::new(CqlType::Text),
+            Box::new(CqlType::List(Box::new(CqlType::Int))),
+        );
+        assert_eq!(map.to_string(), "map<text, list<int>>");
+
+        let tuple = CqlType::Tuple(vec![CqlType::Uuid, CqlType::Timestamp]);
+        assert_eq!(tuple.to_string(), "tuple<uuid, timestamp>");
+        assert!(!tuple.is_collection());
+        assert!(map.is_collection());
+    }
+}
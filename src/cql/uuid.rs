@@ -33,7 +33,15 @@ pub enum CqlUuidVersion {
 }
 
 /// Version 1 (time-based) or version 4 (random) UUID.
+///
+/// When the `serde` feature is enabled, the UUID is serialized into its string
+/// representation and deserialized from it.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
 #[repr(transparent)]
 pub struct CqlUuid(struct_CassUuid_);
 
@@ -203,6 +211,22 @@ impl FromStr for CqlUuid {
     }
 }
 
+impl TryFrom<String> for CqlUuid {
+    type Error = DriverError;
+
+    /// Parses a UUID from a string.
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<CqlUuid> for String {
+    /// Formats the UUID as a string.
+    fn from(uuid: CqlUuid) -> Self {
+        uuid.to_string()
+    }
+}
+
 impl From<struct_CassUuid_> for CqlUuid {
     /// Converts a driver UUID to a Rust UUID.
     fn from(value: struct_CassUuid_) -> Self {
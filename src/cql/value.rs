@@ -0,0 +1,1285 @@
+use std::ffi::c_char;
+use std::slice;
+
+#[rustfmt::skip]
+use crate::ffi::{
+    cass_collection_append_bool,
+    cass_collection_append_bytes,
+    cass_collection_append_collection,
+    cass_collection_append_decimal,
+    cass_collection_append_double,
+    cass_collection_append_duration,
+    cass_collection_append_float,
+    cass_collection_append_inet,
+    cass_collection_append_int16,
+    cass_collection_append_int32,
+    cass_collection_append_int64,
+    cass_collection_append_int8,
+    cass_collection_append_string_n,
+    cass_collection_append_tuple,
+    cass_collection_append_uint32,
+    cass_collection_append_user_type,
+    cass_collection_append_uuid,
+    cass_collection_free,
+    cass_collection_new,
+    cass_data_type_sub_data_type,
+    cass_data_type_sub_data_type_by_name_n,
+    cass_data_type_type,
+    cass_iterator_from_collection,
+    cass_iterator_from_map,
+    cass_iterator_from_tuple,
+    cass_iterator_fields_from_user_type,
+    cass_iterator_get_map_key,
+    cass_iterator_get_map_value,
+    cass_iterator_get_user_type_field_name,
+    cass_iterator_get_user_type_field_value,
+    cass_iterator_get_value,
+    cass_statement_bind_bool,
+    cass_statement_bind_bool_by_name_n,
+    cass_statement_bind_bytes,
+    cass_statement_bind_bytes_by_name_n,
+    cass_statement_bind_collection,
+    cass_statement_bind_collection_by_name_n,
+    cass_statement_bind_decimal,
+    cass_statement_bind_decimal_by_name_n,
+    cass_statement_bind_double,
+    cass_statement_bind_double_by_name_n,
+    cass_statement_bind_duration,
+    cass_statement_bind_duration_by_name_n,
+    cass_statement_bind_float,
+    cass_statement_bind_float_by_name_n,
+    cass_statement_bind_inet,
+    cass_statement_bind_inet_by_name_n,
+    cass_statement_bind_int16,
+    cass_statement_bind_int16_by_name_n,
+    cass_statement_bind_int32,
+    cass_statement_bind_int32_by_name_n,
+    cass_statement_bind_int64,
+    cass_statement_bind_int64_by_name_n,
+    cass_statement_bind_int8,
+    cass_statement_bind_int8_by_name_n,
+    cass_statement_bind_null,
+    cass_statement_bind_null_by_name_n,
+    cass_statement_bind_string_n,
+    cass_statement_bind_string_by_name_n,
+    cass_statement_bind_tuple,
+    cass_statement_bind_tuple_by_name_n,
+    cass_statement_bind_uint32,
+    cass_statement_bind_uint32_by_name_n,
+    cass_statement_bind_user_type,
+    cass_statement_bind_user_type_by_name_n,
+    cass_statement_bind_uuid,
+    cass_statement_bind_uuid_by_name_n,
+    cass_tuple_free,
+    cass_tuple_new,
+    cass_tuple_set_bool,
+    cass_tuple_set_bytes,
+    cass_tuple_set_collection,
+    cass_tuple_set_decimal,
+    cass_tuple_set_double,
+    cass_tuple_set_duration,
+    cass_tuple_set_float,
+    cass_tuple_set_inet,
+    cass_tuple_set_int16,
+    cass_tuple_set_int32,
+    cass_tuple_set_int64,
+    cass_tuple_set_int8,
+    cass_tuple_set_null,
+    cass_tuple_set_string_n,
+    cass_tuple_set_tuple,
+    cass_tuple_set_uint32,
+    cass_tuple_set_user_type,
+    cass_tuple_set_uuid,
+    cass_user_type_free,
+    cass_user_type_new_from_data_type,
+    cass_user_type_set_bool_by_name_n,
+    cass_user_type_set_bytes_by_name_n,
+    cass_user_type_set_collection_by_name_n,
+    cass_user_type_set_decimal_by_name_n,
+    cass_user_type_set_double_by_name_n,
+    cass_user_type_set_duration_by_name_n,
+    cass_user_type_set_float_by_name_n,
+    cass_user_type_set_inet_by_name_n,
+    cass_user_type_set_int16_by_name_n,
+    cass_user_type_set_int32_by_name_n,
+    cass_user_type_set_int64_by_name_n,
+    cass_user_type_set_int8_by_name_n,
+    cass_user_type_set_null_by_name_n,
+    cass_user_type_set_string_by_name_n,
+    cass_user_type_set_tuple_by_name_n,
+    cass_user_type_set_uint32_by_name_n,
+    cass_user_type_set_user_type_by_name_n,
+    cass_user_type_set_uuid_by_name_n,
+    cass_value_get_bool,
+    cass_value_get_bytes,
+    cass_value_get_decimal,
+    cass_value_get_double,
+    cass_value_get_duration,
+    cass_value_get_float,
+    cass_value_get_inet,
+    cass_value_get_int16,
+    cass_value_get_int32,
+    cass_value_get_int64,
+    cass_value_get_int8,
+    cass_value_get_string,
+    cass_value_get_uint32,
+    cass_value_get_uuid,
+    cass_value_is_null,
+    cass_value_type,
+    enum_CassCollectionType_,
+    enum_CassCollectionType__CASS_COLLECTION_TYPE_LIST as COLLECTION_LIST,
+    enum_CassCollectionType__CASS_COLLECTION_TYPE_MAP  as COLLECTION_MAP,
+    enum_CassCollectionType__CASS_COLLECTION_TYPE_SET  as COLLECTION_SET,
+    enum_CassError_,
+    enum_CassValueType__CASS_VALUE_TYPE_ASCII          as ASCII,
+    enum_CassValueType__CASS_VALUE_TYPE_BIGINT         as BIGINT,
+    enum_CassValueType__CASS_VALUE_TYPE_BLOB           as BLOB,
+    enum_CassValueType__CASS_VALUE_TYPE_BOOLEAN        as BOOLEAN,
+    enum_CassValueType__CASS_VALUE_TYPE_COUNTER        as COUNTER,
+    enum_CassValueType__CASS_VALUE_TYPE_CUSTOM         as CUSTOM,
+    enum_CassValueType__CASS_VALUE_TYPE_DATE           as DATE,
+    enum_CassValueType__CASS_VALUE_TYPE_DECIMAL        as DECIMAL,
+    enum_CassValueType__CASS_VALUE_TYPE_DOUBLE         as DOUBLE,
+    enum_CassValueType__CASS_VALUE_TYPE_DURATION       as DURATION,
+    enum_CassValueType__CASS_VALUE_TYPE_FLOAT          as FLOAT,
+    enum_CassValueType__CASS_VALUE_TYPE_INET           as INET,
+    enum_CassValueType__CASS_VALUE_TYPE_INT            as INT,
+    enum_CassValueType__CASS_VALUE_TYPE_LIST           as LIST,
+    enum_CassValueType__CASS_VALUE_TYPE_MAP            as MAP,
+    enum_CassValueType__CASS_VALUE_TYPE_SET            as SET,
+    enum_CassValueType__CASS_VALUE_TYPE_SMALL_INT      as SMALL_INT,
+    enum_CassValueType__CASS_VALUE_TYPE_TEXT           as TEXT,
+    enum_CassValueType__CASS_VALUE_TYPE_TIME           as TIME,
+    enum_CassValueType__CASS_VALUE_TYPE_TIMESTAMP      as TIMESTAMP,
+    enum_CassValueType__CASS_VALUE_TYPE_TIMEUUID       as TIMEUUID,
+    enum_CassValueType__CASS_VALUE_TYPE_TINY_INT       as TINY_INT,
+    enum_CassValueType__CASS_VALUE_TYPE_TUPLE          as TUPLE,
+    enum_CassValueType__CASS_VALUE_TYPE_UDT            as UDT,
+    enum_CassValueType__CASS_VALUE_TYPE_UUID           as UUID,
+    enum_CassValueType__CASS_VALUE_TYPE_VARCHAR        as VARCHAR,
+    enum_CassValueType__CASS_VALUE_TYPE_VARINT         as VARINT,
+    enum_cass_bool_t_cass_false                        as CASS_FALSE,
+    struct_CassCollection_,
+    struct_CassDataType_,
+    struct_CassStatement_,
+    struct_CassTuple_,
+    struct_CassUserType_,
+    struct_CassValue_,
+};
+use crate::cql::{
+    CqlInet,
+    CqlUuid,
+};
+use crate::iterator::DriverIterator;
+use crate::{
+    to_result,
+    DriverError,
+    DriverErrorKind,
+};
+
+/// Calls the driver function that matches the destination of a value.
+///
+/// The arguments are the destination, the statement binding function by
+/// index, the statement binding function by name, the collection append
+/// function, the tuple setter function, the user-defined type field setter
+/// function and the value arguments.
+macro_rules! write_value {
+    (
+        $sink:expr,
+        $bind:ident,
+        $bind_by_name:ident,
+        $append:ident,
+        $set:ident,
+        $set_field:ident,
+        $($arg:expr),+
+    ) => {
+        match $sink {
+            ValueSink::Index(statement, index) => unsafe {
+                $bind(statement, index, $($arg),+)
+            },
+            ValueSink::Name(statement, name) => unsafe {
+                $bind_by_name(
+                    statement,
+                    name.as_ptr() as *const c_char,
+                    name.len(),
+                    $($arg),+
+                )
+            },
+            ValueSink::Collection(collection) => unsafe {
+                $append(collection, $($arg),+)
+            },
+            ValueSink::Tuple(tuple, index) => unsafe {
+                $set(tuple, index, $($arg),+)
+            },
+            ValueSink::Field(user_type, name) => unsafe {
+                $set_field(
+                    user_type,
+                    name.as_ptr() as *const c_char,
+                    name.len(),
+                    $($arg),+
+                )
+            },
+        }
+    };
+}
+
+/// A CQL value read from a row or bound to a statement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CqlValue {
+    /// The null value.
+    Null,
+    /// An ASCII string.
+    Ascii(String),
+    /// A UTF-8 string, `text` or `varchar`.
+    Text(String),
+    /// A boolean.
+    Boolean(bool),
+    /// An 8-bit signed integer.
+    TinyInt(i8),
+    /// A 16-bit signed integer.
+    SmallInt(i16),
+    /// A 32-bit signed integer.
+    Int(i32),
+    /// A 64-bit signed integer.
+    BigInt(i64),
+    /// A counter value.
+    Counter(i64),
+    /// A 32-bit floating point number.
+    Float(f32),
+    /// A 64-bit floating point number.
+    Double(f64),
+    /// A decimal number: the unscaled value as a big-endian two's complement
+    /// integer and the scale.
+    Decimal {
+        /// The unscaled value.
+        unscaled: Vec<u8>,
+        /// The number of digits after the decimal point.
+        scale:    i32,
+    },
+    /// An arbitrary-precision integer as a big-endian two's complement
+    /// integer.
+    Varint(Vec<u8>),
+    /// A version 4 (random) UUID.
+    Uuid(CqlUuid),
+    /// A version 1 (time-based) UUID.
+    TimeUuid(CqlUuid),
+    /// An IPv4 or IPv6 address.
+    Inet(CqlInet),
+    /// Arbitrary bytes.
+    Blob(Vec<u8>),
+    /// The serialized value of a custom type.
+    Custom(Vec<u8>),
+    /// The number of milliseconds since the Unix epoch.
+    Timestamp(i64),
+    /// The number of days since the Unix epoch, shifted by 2^31 so the epoch
+    /// is at 2^31.
+    Date(u32),
+    /// The number of nanoseconds since midnight.
+    Time(i64),
+    /// A duration.
+    Duration {
+        /// The number of months.
+        months: i32,
+        /// The number of days.
+        days:   i32,
+        /// The number of nanoseconds.
+        nanos:  i64,
+    },
+    /// A list.
+    List(Vec<CqlValue>),
+    /// A set.
+    Set(Vec<CqlValue>),
+    /// A map as the list of its entries.
+    Map(Vec<(CqlValue, CqlValue)>),
+    /// A tuple.
+    Tuple(Vec<CqlValue>),
+    /// A user-defined type value as the list of its fields.
+    UserType(Vec<(String, CqlValue)>),
+}
+
+impl CqlValue {
+    /// Reads the value from the driver object.
+    ///
+    /// The null values and the missing values are read as [`CqlValue::Null`].
+    pub(crate) fn from_driver(
+        value: *const struct_CassValue_,
+    ) -> Result<Self, DriverError> {
+        if value.is_null() || unsafe { cass_value_is_null(value) } != CASS_FALSE
+        {
+            return Ok(CqlValue::Null);
+        }
+
+        let value_type = unsafe { cass_value_type(value) };
+        let value = match value_type {
+            ASCII => CqlValue::Ascii(get_string(value)?),
+            TEXT | VARCHAR => CqlValue::Text(get_string(value)?),
+            BOOLEAN => {
+                let output =
+                    get(value, |v, o| unsafe { cass_value_get_bool(v, o) })?;
+                CqlValue::Boolean(output != CASS_FALSE)
+            }
+            TINY_INT => CqlValue::TinyInt(get(value, |v, o| unsafe {
+                cass_value_get_int8(v, o)
+            })?),
+            SMALL_INT => CqlValue::SmallInt(get(value, |v, o| unsafe {
+                cass_value_get_int16(v, o)
+            })?),
+            INT => CqlValue::Int(get(value, |v, o| unsafe {
+                cass_value_get_int32(v, o)
+            })?),
+            BIGINT => CqlValue::BigInt(get(value, |v, o| unsafe {
+                cass_value_get_int64(v, o)
+            })?),
+            COUNTER => CqlValue::Counter(get(value, |v, o| unsafe {
+                cass_value_get_int64(v, o)
+            })?),
+            FLOAT => CqlValue::Float(get(value, |v, o| unsafe {
+                cass_value_get_float(v, o)
+            })?),
+            DOUBLE => CqlValue::Double(get(value, |v, o| unsafe {
+                cass_value_get_double(v, o)
+            })?),
+            DECIMAL => {
+                let mut varint = std::ptr::null();
+                let mut varint_len = 0;
+                let mut scale = 0;
+                let code = unsafe {
+                    cass_value_get_decimal(
+                        value,
+                        &mut varint,
+                        &mut varint_len,
+                        &mut scale,
+                    )
+                };
+                to_result::<()>(code)?;
+
+                CqlValue::Decimal {
+                    unscaled: to_vec(varint, varint_len),
+                    scale,
+                }
+            }
+            VARINT => CqlValue::Varint(get_bytes(value)?),
+            UUID => CqlValue::Uuid(CqlUuid::from_driver(get(
+                value,
+                |v, o| unsafe { cass_value_get_uuid(v, o) },
+            )?)),
+            TIMEUUID => CqlValue::TimeUuid(CqlUuid::from_driver(get(
+                value,
+                |v, o| unsafe { cass_value_get_uuid(v, o) },
+            )?)),
+            INET => CqlValue::Inet(CqlInet::from_driver(get(
+                value,
+                |v, o| unsafe { cass_value_get_inet(v, o) },
+            )?)),
+            BLOB => CqlValue::Blob(get_bytes(value)?),
+            CUSTOM => CqlValue::Custom(get_bytes(value)?),
+            TIMESTAMP => CqlValue::Timestamp(get(value, |v, o| unsafe {
+                cass_value_get_int64(v, o)
+            })?),
+            DATE => CqlValue::Date(get(value, |v, o| unsafe {
+                cass_value_get_uint32(v, o)
+            })?),
+            TIME => CqlValue::Time(get(value, |v, o| unsafe {
+                cass_value_get_int64(v, o)
+            })?),
+            DURATION => {
+                let mut months = 0;
+                let mut days = 0;
+                let mut nanos = 0;
+                let code = unsafe {
+                    cass_value_get_duration(
+                        value,
+                        &mut months,
+                        &mut days,
+                        &mut nanos,
+                    )
+                };
+                to_result::<()>(code)?;
+
+                CqlValue::Duration {
+                    months,
+                    days,
+                    nanos,
+                }
+            }
+            LIST => CqlValue::List(get_items(value)?),
+            SET => CqlValue::Set(get_items(value)?),
+            MAP => {
+                let mut iterator = DriverIterator::from_driver(unsafe {
+                    cass_iterator_from_map(value)
+                });
+                let mut entries = Vec::new();
+                while iterator.advance() {
+                    let key =
+                        unsafe { cass_iterator_get_map_key(iterator.inner()) };
+                    let value = unsafe {
+                        cass_iterator_get_map_value(iterator.inner())
+                    };
+                    entries.push((
+                        CqlValue::from_driver(key)?,
+                        CqlValue::from_driver(value)?,
+                    ));
+                }
+
+                CqlValue::Map(entries)
+            }
+            TUPLE => {
+                let mut iterator = DriverIterator::from_driver(unsafe {
+                    cass_iterator_from_tuple(value)
+                });
+                let mut items = Vec::new();
+                while iterator.advance() {
+                    let item =
+                        unsafe { cass_iterator_get_value(iterator.inner()) };
+                    items.push(CqlValue::from_driver(item)?);
+                }
+
+                CqlValue::Tuple(items)
+            }
+            UDT => {
+                let mut iterator = DriverIterator::from_driver(unsafe {
+                    cass_iterator_fields_from_user_type(value)
+                });
+                let mut fields = Vec::new();
+                while iterator.advance() {
+                    let mut name = std::ptr::null();
+                    let mut name_len = 0;
+                    let code = unsafe {
+                        cass_iterator_get_user_type_field_name(
+                            iterator.inner(),
+                            &mut name,
+                            &mut name_len,
+                        )
+                    };
+                    to_result::<()>(code)?;
+                    let name = to_vec(name as *const u8, name_len);
+                    let field = unsafe {
+                        cass_iterator_get_user_type_field_value(
+                            iterator.inner(),
+                        )
+                    };
+
+                    fields.push((
+                        String::from_utf8_lossy(&name).into_owned(),
+                        CqlValue::from_driver(field)?,
+                    ));
+                }
+
+                CqlValue::UserType(fields)
+            }
+            _ => {
+                return Err(DriverError::with_message(
+                    DriverErrorKind::LibInvalidValueType,
+                    format!("unsupported value type: {}", value_type),
+                ));
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Returns `true` if the value is null.
+    pub fn is_null(&self) -> bool {
+        matches!(self, CqlValue::Null)
+    }
+
+    /// Returns the string if the value is an ASCII or UTF-8 string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            CqlValue::Ascii(value) | CqlValue::Text(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Returns the integer if the value is an integer of any width.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            CqlValue::TinyInt(value) => Some((*value).into()),
+            CqlValue::SmallInt(value) => Some((*value).into()),
+            CqlValue::Int(value) => Some((*value).into()),
+            CqlValue::BigInt(value) | CqlValue::Counter(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the UUID if the value is a UUID or a time UUID.
+    pub fn as_uuid(&self) -> Option<CqlUuid> {
+        match self {
+            CqlValue::Uuid(value) | CqlValue::TimeUuid(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Returns the address if the value is an inet.
+    pub fn as_inet(&self) -> Option<CqlInet> {
+        match self {
+            CqlValue::Inet(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Writes the value to the given destination.
+    ///
+    /// The `data_type` is the data type of the destination, null if it is
+    /// unknown. It is required to write the user-defined type values, since
+    /// the driver builds them from their schema data type.
+    pub(crate) fn write_to(
+        &self,
+        sink: ValueSink<'_>,
+        data_type: *const struct_CassDataType_,
+    ) -> Result<(), DriverError> {
+        let code = match self {
+            CqlValue::Null => match sink {
+                ValueSink::Index(statement, index) => unsafe {
+                    cass_statement_bind_null(statement, index)
+                },
+                ValueSink::Name(statement, name) => unsafe {
+                    cass_statement_bind_null_by_name_n(
+                        statement,
+                        name.as_ptr() as *const c_char,
+                        name.len(),
+                    )
+                },
+                ValueSink::Tuple(tuple, index) => unsafe {
+                    cass_tuple_set_null(tuple, index)
+                },
+                ValueSink::Field(user_type, name) => unsafe {
+                    cass_user_type_set_null_by_name_n(
+                        user_type,
+                        name.as_ptr() as *const c_char,
+                        name.len(),
+                    )
+                },
+                ValueSink::Collection(_) => {
+                    return Err(DriverError::with_message(
+                        DriverErrorKind::LibBadParams,
+                        "collections cannot contain null values",
+                    ));
+                }
+            },
+            CqlValue::Ascii(value) | CqlValue::Text(value) => write_value!(
+                sink,
+                cass_statement_bind_string_n,
+                cass_statement_bind_string_by_name_n,
+                cass_collection_append_string_n,
+                cass_tuple_set_string_n,
+                cass_user_type_set_string_by_name_n,
+                value.as_ptr() as *const c_char,
+                value.len()
+            ),
+            CqlValue::Boolean(value) => write_value!(
+                sink,
+                cass_statement_bind_bool,
+                cass_statement_bind_bool_by_name_n,
+                cass_collection_append_bool,
+                cass_tuple_set_bool,
+                cass_user_type_set_bool_by_name_n,
+                (*value).into()
+            ),
+            CqlValue::TinyInt(value) => write_value!(
+                sink,
+                cass_statement_bind_int8,
+                cass_statement_bind_int8_by_name_n,
+                cass_collection_append_int8,
+                cass_tuple_set_int8,
+                cass_user_type_set_int8_by_name_n,
+                *value
+            ),
+            CqlValue::SmallInt(value) => write_value!(
+                sink,
+                cass_statement_bind_int16,
+                cass_statement_bind_int16_by_name_n,
+                cass_collection_append_int16,
+                cass_tuple_set_int16,
+                cass_user_type_set_int16_by_name_n,
+                *value
+            ),
+            CqlValue::Int(value) => write_value!(
+                sink,
+                cass_statement_bind_int32,
+                cass_statement_bind_int32_by_name_n,
+                cass_collection_append_int32,
+                cass_tuple_set_int32,
+                cass_user_type_set_int32_by_name_n,
+                *value
+            ),
+            CqlValue::BigInt(value)
+            | CqlValue::Counter(value)
+            | CqlValue::Timestamp(value)
+            | CqlValue::Time(value) => write_value!(
+                sink,
+                cass_statement_bind_int64,
+                cass_statement_bind_int64_by_name_n,
+                cass_collection_append_int64,
+                cass_tuple_set_int64,
+                cass_user_type_set_int64_by_name_n,
+                *value
+            ),
+            CqlValue::Float(value) => write_value!(
+                sink,
+                cass_statement_bind_float,
+                cass_statement_bind_float_by_name_n,
+                cass_collection_append_float,
+                cass_tuple_set_float,
+                cass_user_type_set_float_by_name_n,
+                *value
+            ),
+            CqlValue::Double(value) => write_value!(
+                sink,
+                cass_statement_bind_double,
+                cass_statement_bind_double_by_name_n,
+                cass_collection_append_double,
+                cass_tuple_set_double,
+                cass_user_type_set_double_by_name_n,
+                *value
+            ),
+            CqlValue::Decimal {
+                unscaled,
+                scale,
+            } => write_value!(
+                sink,
+                cass_statement_bind_decimal,
+                cass_statement_bind_decimal_by_name_n,
+                cass_collection_append_decimal,
+                cass_tuple_set_decimal,
+                cass_user_type_set_decimal_by_name_n,
+                unscaled.as_ptr(),
+                unscaled.len(),
+                *scale
+            ),
+            CqlValue::Varint(value)
+            | CqlValue::Blob(value)
+            | CqlValue::Custom(value) => write_value!(
+                sink,
+                cass_statement_bind_bytes,
+                cass_statement_bind_bytes_by_name_n,
+                cass_collection_append_bytes,
+                cass_tuple_set_bytes,
+                cass_user_type_set_bytes_by_name_n,
+                value.as_ptr(),
+                value.len()
+            ),
+            CqlValue::Uuid(value) | CqlValue::TimeUuid(value) => write_value!(
+                sink,
+                cass_statement_bind_uuid,
+                cass_statement_bind_uuid_by_name_n,
+                cass_collection_append_uuid,
+                cass_tuple_set_uuid,
+                cass_user_type_set_uuid_by_name_n,
+                value.inner()
+            ),
+            CqlValue::Inet(value) => write_value!(
+                sink,
+                cass_statement_bind_inet,
+                cass_statement_bind_inet_by_name_n,
+                cass_collection_append_inet,
+                cass_tuple_set_inet,
+                cass_user_type_set_inet_by_name_n,
+                *value.inner()
+            ),
+            CqlValue::Date(value) => write_value!(
+                sink,
+                cass_statement_bind_uint32,
+                cass_statement_bind_uint32_by_name_n,
+                cass_collection_append_uint32,
+                cass_tuple_set_uint32,
+                cass_user_type_set_uint32_by_name_n,
+                *value
+            ),
+            CqlValue::Duration {
+                months,
+                days,
+                nanos,
+            } => write_value!(
+                sink,
+                cass_statement_bind_duration,
+                cass_statement_bind_duration_by_name_n,
+                cass_collection_append_duration,
+                cass_tuple_set_duration,
+                cass_user_type_set_duration_by_name_n,
+                *months,
+                *days,
+                *nanos
+            ),
+            CqlValue::List(items) => {
+                let collection =
+                    Collection::new(COLLECTION_LIST, items, data_type)?;
+                write_collection(sink, &collection)
+            }
+            CqlValue::Set(items) => {
+                let collection =
+                    Collection::new(COLLECTION_SET, items, data_type)?;
+                write_collection(sink, &collection)
+            }
+            CqlValue::Map(entries) => {
+                let collection = Collection::new_map(entries, data_type)?;
+                write_collection(sink, &collection)
+            }
+            CqlValue::Tuple(items) => {
+                let tuple = Tuple::new(items, data_type)?;
+                write_value!(
+                    sink,
+                    cass_statement_bind_tuple,
+                    cass_statement_bind_tuple_by_name_n,
+                    cass_collection_append_tuple,
+                    cass_tuple_set_tuple,
+                    cass_user_type_set_tuple_by_name_n,
+                    tuple.0
+                )
+            }
+            CqlValue::UserType(fields) => {
+                let user_type = UserType::new(fields, data_type)?;
+                write_value!(
+                    sink,
+                    cass_statement_bind_user_type,
+                    cass_statement_bind_user_type_by_name_n,
+                    cass_collection_append_user_type,
+                    cass_tuple_set_user_type,
+                    cass_user_type_set_user_type_by_name_n,
+                    user_type.0
+                )
+            }
+        };
+
+        to_result(code)
+    }
+}
+
+/// The destination a value is written to.
+#[derive(Clone, Copy)]
+pub(crate) enum ValueSink<'a> {
+    /// The bind variable of a statement at the given index.
+    Index(*mut struct_CassStatement_, usize),
+    /// The bind variable of a statement with the given name.
+    Name(*mut struct_CassStatement_, &'a str),
+    /// The end of a collection.
+    Collection(*mut struct_CassCollection_),
+    /// The item of a tuple at the given index.
+    Tuple(*mut struct_CassTuple_, usize),
+    /// The field of a user-defined type value with the given name.
+    Field(*mut struct_CassUserType_, &'a str),
+}
+
+/// Writes a collection to the given destination.
+fn write_collection(
+    sink: ValueSink<'_>,
+    collection: &Collection,
+) -> enum_CassError_ {
+    write_value!(
+        sink,
+        cass_statement_bind_collection,
+        cass_statement_bind_collection_by_name_n,
+        cass_collection_append_collection,
+        cass_tuple_set_collection,
+        cass_user_type_set_collection_by_name_n,
+        collection.0
+    )
+}
+
+/// An owned driver collection.
+struct Collection(*mut struct_CassCollection_);
+
+impl Collection {
+    /// Creates a list or a set of the given data type with the given items.
+    fn new(
+        kind: enum_CassCollectionType_,
+        items: &[CqlValue],
+        data_type: *const struct_CassDataType_,
+    ) -> Result<Self, DriverError> {
+        let collection =
+            Self(unsafe { cass_collection_new(kind, items.len()) });
+        let item_type = sub_data_type(data_type, 0);
+        for item in items {
+            item.write_to(ValueSink::Collection(collection.0), item_type)?;
+        }
+
+        Ok(collection)
+    }
+
+    /// Creates a map of the given data type with the given entries.
+    fn new_map(
+        entries: &[(CqlValue, CqlValue)],
+        data_type: *const struct_CassDataType_,
+    ) -> Result<Self, DriverError> {
+        let collection =
+            Self(unsafe { cass_collection_new(COLLECTION_MAP, entries.len()) });
+        let key_type = sub_data_type(data_type, 0);
+        let value_type = sub_data_type(data_type, 1);
+        for (key, value) in entries {
+            key.write_to(ValueSink::Collection(collection.0), key_type)?;
+            value.write_to(ValueSink::Collection(collection.0), value_type)?;
+        }
+
+        Ok(collection)
+    }
+}
+
+impl Drop for Collection {
+    fn drop(&mut self) {
+        unsafe { cass_collection_free(self.0) }
+    }
+}
+
+/// An owned driver tuple.
+struct Tuple(*mut struct_CassTuple_);
+
+impl Tuple {
+    /// Creates a tuple of the given data type with the given items.
+    fn new(
+        items: &[CqlValue],
+        data_type: *const struct_CassDataType_,
+    ) -> Result<Self, DriverError> {
+        let tuple = Self(unsafe { cass_tuple_new(items.len()) });
+        for (index, item) in items.iter().enumerate() {
+            item.write_to(
+                ValueSink::Tuple(tuple.0, index),
+                sub_data_type(data_type, index),
+            )?;
+        }
+
+        Ok(tuple)
+    }
+}
+
+impl Drop for Tuple {
+    fn drop(&mut self) {
+        unsafe { cass_tuple_free(self.0) }
+    }
+}
+
+/// An owned driver user-defined type value.
+struct UserType(*mut struct_CassUserType_);
+
+impl UserType {
+    /// Creates a value of the given user-defined type with the given fields.
+    ///
+    /// The fields not listed are null.
+    fn new(
+        fields: &[(String, CqlValue)],
+        data_type: *const struct_CassDataType_,
+    ) -> Result<Self, DriverError> {
+        if data_type.is_null()
+            || unsafe { cass_data_type_type(data_type) } != UDT
+        {
+            return Err(DriverError::with_message(
+                DriverErrorKind::LibInvalidValueType,
+                "the user-defined type value can only be bound to a bind \
+                 variable of the user-defined type of a prepared statement",
+            ));
+        }
+
+        let user_type =
+            Self(unsafe { cass_user_type_new_from_data_type(data_type) });
+        for (name, value) in fields {
+            let field_type = unsafe {
+                cass_data_type_sub_data_type_by_name_n(
+                    data_type,
+                    name.as_ptr() as *const c_char,
+                    name.len(),
+                )
+            };
+            if field_type.is_null() {
+                return Err(DriverError::with_message(
+                    DriverErrorKind::LibNameDoesNotExist,
+                    format!(
+                        "the user-defined type has no field named {}",
+                        name
+                    ),
+                ));
+            }
+            value.write_to(ValueSink::Field(user_type.0, name), field_type)?;
+        }
+
+        Ok(user_type)
+    }
+}
+
+impl Drop for UserType {
+    fn drop(&mut self) {
+        unsafe { cass_user_type_free(self.0) }
+    }
+}
+
+/// Returns the sub-type of a collection or a tuple data type at the given
+/// index, null if the data type is unknown.
+fn sub_data_type(
+    data_type: *const struct_CassDataType_,
+    index: usize,
+) -> *const struct_CassDataType_ {
+    if data_type.is_null() {
+        return std::ptr::null();
+    }
+
+    unsafe { cass_data_type_sub_data_type(data_type, index) }
+}
+
+/// Reads a fixed size value with the given getter.
+fn get<T, F>(
+    value: *const struct_CassValue_,
+    getter: F,
+) -> Result<T, DriverError>
+where
+    F: FnOnce(*const struct_CassValue_, *mut T) -> enum_CassError_,
+{
+    let mut output = std::mem::MaybeUninit::<T>::zeroed();
+    let code = getter(value, output.as_mut_ptr());
+    to_result::<()>(code)?;
+
+    Ok(unsafe { output.assume_init() })
+}
+
+/// Reads a string value.
+fn get_string(value: *const struct_CassValue_) -> Result<String, DriverError> {
+    let mut string = std::ptr::null();
+    let mut string_len = 0;
+    let code =
+        unsafe { cass_value_get_string(value, &mut string, &mut string_len) };
+    to_result::<()>(code)?;
+
+    let bytes = to_vec(string as *const u8, string_len);
+
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Reads a bytes value.
+fn get_bytes(value: *const struct_CassValue_) -> Result<Vec<u8>, DriverError> {
+    let mut bytes = std::ptr::null();
+    let mut bytes_len = 0;
+    let code =
+        unsafe { cass_value_get_bytes(value, &mut bytes, &mut bytes_len) };
+    to_result::<()>(code)?;
+
+    Ok(to_vec(bytes, bytes_len))
+}
+
+/// Reads the items of a list or a set.
+fn get_items(
+    value: *const struct_CassValue_,
+) -> Result<Vec<CqlValue>, DriverError> {
+    let mut iterator = DriverIterator::from_driver(unsafe {
+        cass_iterator_from_collection(value)
+    });
+    let mut items = Vec::new();
+    while iterator.advance() {
+        let item = unsafe { cass_iterator_get_value(iterator.inner()) };
+        items.push(CqlValue::from_driver(item)?);
+    }
+
+    Ok(items)
+}
+
+/// Copies the bytes owned by the driver.
+fn to_vec(bytes: *const u8, len: usize) -> Vec<u8> {
+    if bytes.is_null() {
+        return Vec::new();
+    }
+
+    unsafe { slice::from_raw_parts(bytes, len) }.to_vec()
+}
+
+impl From<bool> for CqlValue {
+    fn from(value: bool) -> Self {
+        CqlValue::Boolean(value)
+    }
+}
+
+impl From<i8> for CqlValue {
+    fn from(value: i8) -> Self {
+        CqlValue::TinyInt(value)
+    }
+}
+
+impl From<i16> for CqlValue {
+    fn from(value: i16) -> Self {
+        CqlValue::SmallInt(value)
+    }
+}
+
+impl From<i32> for CqlValue {
+    fn from(value: i32) -> Self {
+        CqlValue::Int(value)
+    }
+}
+
+impl From<i64> for CqlValue {
+    fn from(value: i64) -> Self {
+        CqlValue::BigInt(value)
+    }
+}
+
+impl From<f32> for CqlValue {
+    fn from(value: f32) -> Self {
+        CqlValue::Float(value)
+    }
+}
+
+impl From<f64> for CqlValue {
+    fn from(value: f64) -> Self {
+        CqlValue::Double(value)
+    }
+}
+
+impl From<&str> for CqlValue {
+    fn from(value: &str) -> Self {
+        CqlValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for CqlValue {
+    fn from(value: String) -> Self {
+        CqlValue::Text(value)
+    }
+}
+
+impl From<Vec<u8>> for CqlValue {
+    fn from(value: Vec<u8>) -> Self {
+        CqlValue::Blob(value)
+    }
+}
+
+impl From<CqlUuid> for CqlValue {
+    fn from(value: CqlUuid) -> Self {
+        CqlValue::Uuid(value)
+    }
+}
+
+impl From<CqlInet> for CqlValue {
+    fn from(value: CqlInet) -> Self {
+        CqlValue::Inet(value)
+    }
+}
+
+impl<T> From<Option<T>> for CqlValue
+where
+    T: Into<CqlValue>,
+{
+    fn from(value: Option<T>) -> Self {
+        value.map(Into::into).unwrap_or(CqlValue::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::{
+        Cluster,
+        Session,
+        Statement,
+    };
+
+    /// Connects to the node set with `CASSANDER_TEST_CONTACT_POINTS`, the
+    /// local node by default, and creates the tables of the tests.
+    fn connect() -> Session {
+        let contact_points = std::env::var("CASSANDER_TEST_CONTACT_POINTS")
+            .unwrap_or_else(|_| "127.0.0.1".to_string());
+        let mut cluster = Cluster::new();
+        cluster.set_contact_points(contact_points).unwrap();
+        let session = block_on(cluster.connect()).unwrap();
+
+        for query in [
+            "CREATE KEYSPACE IF NOT EXISTS cassander_test WITH replication = \
+             {'class': 'SimpleStrategy', 'replication_factor': 1}",
+            "CREATE TYPE IF NOT EXISTS cassander_test.address (street text, \
+             zip int)",
+            "CREATE TABLE IF NOT EXISTS cassander_test.value_types (k int \
+             PRIMARY KEY, ascii ascii, text text, boolean boolean, tinyint \
+             tinyint, smallint smallint, int int, bigint bigint, float float, \
+             double double, decimal decimal, varint varint, uuid uuid, \
+             timeuuid timeuuid, inet inet, blob blob, timestamp timestamp, \
+             date date, time time, duration duration, list list<int>, set \
+             set<text>, map map<text, int>, tuple tuple<int, text>, address \
+             frozen<address>, addresses list<frozen<address>>)",
+            "CREATE TABLE IF NOT EXISTS cassander_test.counters (k int \
+             PRIMARY KEY, counter counter)",
+        ] {
+            block_on(session.execute(&Statement::new(query, 0))).unwrap();
+        }
+
+        session
+    }
+
+    /// The columns of the `value_types` table.
+    const COLUMNS: &str = "k, ascii, text, boolean, tinyint, smallint, int, \
+                           bigint, float, double, decimal, varint, uuid, \
+                           timeuuid, inet, blob, timestamp, date, time, \
+                           duration, list, set, map, tuple, address, addresses";
+
+    /// Returns the columns of the row of the given table with the given key.
+    fn select(
+        session: &Session,
+        columns: &str,
+        table: &str,
+        k: i32,
+    ) -> Option<Vec<CqlValue>> {
+        let mut statement = Statement::new(
+            format!(
+                "SELECT {} FROM cassander_test.{} WHERE k = ?",
+                columns, table
+            ),
+            1,
+        );
+        statement.bind(0, k).unwrap();
+        let result = block_on(session.execute(&statement)).unwrap();
+
+        result.first_row().map(|row| row.values().unwrap())
+    }
+
+    #[test]
+    fn test_null_collection_item_is_rejected() {
+        let list = CqlValue::List(vec![CqlValue::Int(1), CqlValue::Null]);
+
+        let error = list
+            .write_to(
+                ValueSink::Index(std::ptr::null_mut(), 0),
+                std::ptr::null(),
+            )
+            .unwrap_err();
+
+        assert_eq!(error.kind, DriverErrorKind::LibBadParams);
+    }
+
+    #[test]
+    fn test_user_type_requires_data_type() {
+        let address =
+            CqlValue::UserType(vec![("zip".to_string(), CqlValue::Int(12345))]);
+
+        let error = address
+            .write_to(
+                ValueSink::Index(std::ptr::null_mut(), 0),
+                std::ptr::null(),
+            )
+            .unwrap_err();
+
+        assert_eq!(error.kind, DriverErrorKind::LibInvalidValueType);
+    }
+
+    #[test]
+    #[ignore = "requires a Cassandra node, see CASSANDER_TEST_CONTACT_POINTS"]
+    fn test_round_trip() {
+        let session = connect();
+        let address = CqlValue::UserType(vec![
+            ("street".to_string(), CqlValue::Text("Main St".to_string())),
+            ("zip".to_string(), CqlValue::Int(12345)),
+        ]);
+        let values = vec![
+            CqlValue::Int(1),
+            CqlValue::Ascii("ascii".to_string()),
+            CqlValue::Text("tèxt".to_string()),
+            CqlValue::Boolean(true),
+            CqlValue::TinyInt(-8),
+            CqlValue::SmallInt(-16),
+            CqlValue::Int(-32),
+            CqlValue::BigInt(-64),
+            CqlValue::Float(1.5),
+            CqlValue::Double(-2.25),
+            CqlValue::Decimal {
+                unscaled: vec![0x30, 0x39],
+                scale:    2,
+            },
+            CqlValue::Varint(vec![0x01, 0x00]),
+            CqlValue::Uuid(
+                "f47ac10b-58cc-4372-a567-0e02b2c3d479".parse().unwrap(),
+            ),
+            CqlValue::TimeUuid(
+                "50554d6e-29bb-11e5-b345-feff819cdc9f".parse().unwrap(),
+            ),
+            CqlValue::Inet("10.0.0.1".parse().unwrap()),
+            CqlValue::Blob(vec![0xca, 0xfe]),
+            CqlValue::Timestamp(1_700_000_000_000),
+            CqlValue::Date((1 << 31) + 19_000),
+            CqlValue::Time(3_600_000_000_000),
+            CqlValue::Duration {
+                months: 1,
+                days:   2,
+                nanos:  3,
+            },
+            CqlValue::List(vec![CqlValue::Int(3), CqlValue::Int(1)]),
+            CqlValue::Set(vec![
+                CqlValue::Text("a".to_string()),
+                CqlValue::Text("b".to_string()),
+            ]),
+            CqlValue::Map(vec![(
+                CqlValue::Text("a".to_string()),
+                CqlValue::Int(1),
+            )]),
+            CqlValue::Tuple(vec![
+                CqlValue::Int(1),
+                CqlValue::Text("one".to_string()),
+            ]),
+            address.clone(),
+            CqlValue::List(vec![address]),
+        ];
+        let markers = vec!["?"; values.len()].join(", ");
+        let prepared = block_on(session.prepare(format!(
+            "INSERT INTO cassander_test.value_types ({}) VALUES ({})",
+            COLUMNS, markers
+        )))
+        .unwrap();
+        let mut statement = prepared.bind();
+        for (index, value) in values.iter().enumerate() {
+            statement.bind(index, value.clone()).unwrap();
+        }
+        block_on(session.execute(&statement)).unwrap();
+
+        assert_eq!(select(&session, COLUMNS, "value_types", 1), Some(values));
+
+        let prepared = block_on(session.prepare(
+            "UPDATE cassander_test.counters SET counter = counter + ? WHERE k \
+             = ?",
+        ))
+        .unwrap();
+        let mut statement = prepared.bind();
+        statement.bind(0, CqlValue::Counter(5)).unwrap();
+        statement.bind(1, 1).unwrap();
+        let before = select(&session, "counter", "counters", 1)
+            .map(|values| values[0].clone());
+        block_on(session.execute(&statement)).unwrap();
+
+        let after = select(&session, "counter", "counters", 1)
+            .map(|values| values[0].clone());
+        let before = before.as_ref().and_then(CqlValue::as_i64).unwrap_or(0);
+        assert_eq!(after, Some(CqlValue::Counter(before + 5)));
+    }
+
+    #[test]
+    #[ignore = "requires a Cassandra node, see CASSANDER_TEST_CONTACT_POINTS"]
+    fn test_row_null_values() {
+        let session = connect();
+        let prepared = block_on(session.prepare(
+            "INSERT INTO cassander_test.value_types (k, text, list) VALUES \
+             (?, ?, ?)",
+        ))
+        .unwrap();
+        let mut statement = prepared.bind();
+        statement.bind(0, 2).unwrap();
+        statement.bind(1, CqlValue::Null).unwrap();
+        statement
+            .bind_by_name("list", CqlValue::List(Vec::new()))
+            .unwrap();
+        block_on(session.execute(&statement)).unwrap();
+
+        let statement = Statement::new(
+            "SELECT k, text, list, address FROM cassander_test.value_types \
+             WHERE k = 2",
+            0,
+        );
+        let result = block_on(session.execute(&statement)).unwrap();
+        let row = result.first_row().unwrap();
+
+        assert_eq!(row.get(0).unwrap(), CqlValue::Int(2));
+        // Null values and empty collections are both read as null.
+        assert_eq!(row.get(1).unwrap(), CqlValue::Null);
+        assert_eq!(row.get(2).unwrap(), CqlValue::Null);
+        assert_eq!(row.get_by_name("address").unwrap(), CqlValue::Null);
+        assert_eq!(
+            row.get(4).unwrap_err().kind,
+            DriverErrorKind::LibIndexOutOfBounds
+        );
+        assert_eq!(
+            row.get_by_name("missing").unwrap_err().kind,
+            DriverErrorKind::LibNameDoesNotExist
+        );
+    }
+}
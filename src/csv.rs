@@ -0,0 +1,177 @@
+//! CSV import and export of table data, in the spirit of the `COPY` command
+//! of `cqlsh`.
+//!
+//! The values are formatted the way `cqlsh` formats them: the collections,
+//! the tuples and the user-defined types as CQL literals, e.g.
+//! `{'a': [1, 2]}`, the blobs as `0x` prefixed hex strings, and the
+//! timestamps and the dates with the formats of the [`CsvFormat`].
+
+mod csv_error;
+mod datetime;
+mod exporter;
+mod importer;
+mod number;
+mod partition_key;
+mod record;
+mod text;
+
+pub use csv_error::*;
+pub use exporter::*;
+pub use importer::*;
+
+/// The format of CSV data.
+///
+/// The default format is the one of `cqlsh`: comma separated fields, double
+/// quotes, an empty string for null, a header row, `True`/`False` booleans
+/// and the `%Y-%m-%d %H:%M:%S.%f%z` timestamps.
+///
+/// The timestamp and the date formats support the `%Y`, `%m`, `%d`, `%H`,
+/// `%M`, `%S`, `%f` (6-digit microseconds, as in `cqlsh`), `%z` (`+HHMM`
+/// offset) and `%%` directives. The timestamps are formatted in UTC. When
+/// parsed, a space in the format also matches the `T` of an ISO 8601
+/// timestamp, and a timestamp that does not match the format is parsed as
+/// the number of milliseconds since the Unix epoch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvFormat {
+    /// The character that separates the fields.
+    delimiter:       char,
+    /// The character that quotes the fields.
+    quote:           char,
+    /// Whether all the fields are quoted, not only the ones that need it.
+    quote_all:       bool,
+    /// The representation of null.
+    null:            String,
+    /// Whether the first row holds the column names.
+    header:          bool,
+    /// The representation of `true`.
+    true_value:      String,
+    /// The representation of `false`.
+    false_value:     String,
+    /// The format of the timestamps.
+    datetime_format: String,
+    /// The format of the dates.
+    date_format:     String,
+}
+
+impl CsvFormat {
+    /// Creates the default CSV format.
+    pub fn new() -> Self {
+        Self {
+            delimiter:       ',',
+            quote:           '"',
+            quote_all:       false,
+            null:            String::new(),
+            header:          true,
+            true_value:      "True".to_string(),
+            false_value:     "False".to_string(),
+            datetime_format: "%Y-%m-%d %H:%M:%S.%f%z".to_string(),
+            date_format:     "%Y-%m-%d".to_string(),
+        }
+    }
+
+    /// Creates the default format with tab separated fields.
+    pub fn tsv() -> Self {
+        Self::new().with_delimiter('\t')
+    }
+
+    /// Sets the character that separates the fields.
+    ///
+    /// The default value is `,`.
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+
+        self
+    }
+
+    /// Sets the character that quotes the fields, a quote in a quoted field
+    /// is doubled.
+    ///
+    /// The default value is `"`.
+    pub fn with_quote(mut self, quote: char) -> Self {
+        self.quote = quote;
+
+        self
+    }
+
+    /// Sets whether all the fields are quoted when exported.
+    ///
+    /// By default, only the fields that contain the delimiter, the quote, a
+    /// line break or the null representation are quoted. The null values are
+    /// never quoted, a quoted field is never null.
+    pub fn with_quote_all(mut self, quote_all: bool) -> Self {
+        self.quote_all = quote_all;
+
+        self
+    }
+
+    /// Sets the representation of null.
+    ///
+    /// The default value is an empty string.
+    pub fn with_null<T>(mut self, null: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.null = null.into();
+
+        self
+    }
+
+    /// Sets whether the first row holds the column names.
+    ///
+    /// The default value is `true`.
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+
+        self
+    }
+
+    /// Sets the representations of `true` and `false`.
+    ///
+    /// The default values are `True` and `False`, `true` and `false` are
+    /// accepted on import in any case.
+    pub fn with_bool_style<T, F>(
+        mut self,
+        true_value: T,
+        false_value: F,
+    ) -> Self
+    where
+        T: Into<String>,
+        F: Into<String>,
+    {
+        self.true_value = true_value.into();
+        self.false_value = false_value.into();
+
+        self
+    }
+
+    /// Sets the format of the timestamps.
+    ///
+    /// The default value is `%Y-%m-%d %H:%M:%S.%f%z`.
+    pub fn with_datetime_format<T>(mut self, format: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.datetime_format = format.into();
+
+        self
+    }
+
+    /// Sets the format of the dates.
+    ///
+    /// The default value is `%Y-%m-%d`.
+    pub fn with_date_format<T>(mut self, format: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.date_format = format.into();
+
+        self
+    }
+}
+
+impl Default for CsvFormat {
+    /// Creates the default CSV format.
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,54 @@
+use thiserror::Error;
+
+use crate::cql::CqlType;
+use crate::DriverError;
+
+/// An error that stops a CSV import or export.
+#[derive(Error, Debug)]
+pub enum CsvError {
+    /// The CSV data cannot be read or written.
+    #[error("failed to read or write the CSV data: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A request to the cluster failed.
+    #[error(transparent)]
+    Driver(#[from] DriverError),
+
+    /// The table does not exist.
+    #[error("the table {keyspace}.{table} does not exist")]
+    UnknownTable {
+        /// The name of the keyspace.
+        keyspace: String,
+        /// The name of the table.
+        table:    String,
+    },
+
+    /// The column does not exist.
+    #[error("the table {table} has no column {column}")]
+    UnknownColumn {
+        /// The name of the table.
+        table:  String,
+        /// The name of the column.
+        column: String,
+    },
+
+    /// The values of the column cannot be imported: the columns of a
+    /// counter import must be counters or primary key columns.
+    #[error("the column {column} of type {data_type} cannot be imported")]
+    UnsupportedColumn {
+        /// The name of the column.
+        column:    String,
+        /// The type of the column.
+        data_type: CqlType,
+    },
+}
+
+/// A CSV record that cannot be parsed, the import skips it.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("line {line}: {message}")]
+pub struct CsvParseError {
+    /// The line the record starts at, counting from 1.
+    pub line:    u64,
+    /// The description of the error.
+    pub message: String,
+}
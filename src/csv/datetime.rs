@@ -0,0 +1,485 @@
+const MILLIS_PER_DAY: i64 = 86_400_000;
+const NANOS_PER_MICRO: i64 = 1_000;
+const NANOS_PER_MILLI: i64 = 1_000_000;
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const NANOS_PER_DAY: i64 = 86_400 * NANOS_PER_SECOND;
+
+/// The day of the Unix epoch in a CQL date.
+const DATE_EPOCH: i64 = 1 << 31;
+
+/// The units of a CQL duration: the name, whether it counts months, days or
+/// nanoseconds, and the amount.
+///
+/// The longer names come first so that `mo` and `ms` are not read as `m`.
+const DURATION_UNITS: [(&str, DurationPart, i64); 11] = [
+    ("mo", DurationPart::Months, 1),
+    ("ms", DurationPart::Nanos, NANOS_PER_MILLI),
+    ("us", DurationPart::Nanos, 1_000),
+    ("µs", DurationPart::Nanos, 1_000),
+    ("ns", DurationPart::Nanos, 1),
+    ("y", DurationPart::Months, 12),
+    ("w", DurationPart::Days, 7),
+    ("d", DurationPart::Days, 1),
+    ("h", DurationPart::Nanos, 3_600 * NANOS_PER_SECOND),
+    ("m", DurationPart::Nanos, 60 * NANOS_PER_SECOND),
+    ("s", DurationPart::Nanos, NANOS_PER_SECOND),
+];
+
+/// The units a duration is formatted with below a day.
+const NANOS_UNITS: [(&str, u64); 6] = [
+    ("h", 3_600 * NANOS_PER_SECOND as u64),
+    ("m", 60 * NANOS_PER_SECOND as u64),
+    ("s", NANOS_PER_SECOND as u64),
+    ("ms", NANOS_PER_MILLI as u64),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+/// The part of a duration a unit counts.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DurationPart {
+    Months,
+    Days,
+    Nanos,
+}
+
+/// A date and a time of day.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct DateTime {
+    year:   i64,
+    month:  u32,
+    day:    u32,
+    hour:   u32,
+    minute: u32,
+    second: u32,
+    nanos:  u32,
+    /// The offset from UTC in seconds.
+    offset: i64,
+}
+
+impl DateTime {
+    /// Returns the date and the time of the day since the Unix epoch.
+    fn from_days(days: i64, nanos: i64) -> Self {
+        let (year, month, day) = civil_from_days(days);
+        let seconds = nanos / NANOS_PER_SECOND;
+
+        Self {
+            year,
+            month,
+            day,
+            hour: (seconds / 3_600) as u32,
+            minute: (seconds / 60 % 60) as u32,
+            second: (seconds % 60) as u32,
+            nanos: (nanos % NANOS_PER_SECOND) as u32,
+            offset: 0,
+        }
+    }
+
+    /// Returns the number of days since the Unix epoch.
+    fn days(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day)
+    }
+
+    /// Returns the number of milliseconds since the Unix epoch.
+    fn millis(&self) -> i64 {
+        let seconds = i64::from(self.hour) * 3_600
+            + i64::from(self.minute) * 60
+            + i64::from(self.second)
+            - self.offset;
+
+        self.days() * MILLIS_PER_DAY
+            + seconds * 1_000
+            + i64::from(self.nanos) / NANOS_PER_MILLI
+    }
+
+    /// Formats the date and the time.
+    fn format(&self, format: &str) -> String {
+        let mut output = String::with_capacity(format.len() + 8);
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                output.push(c);
+                continue;
+            }
+
+            match chars.next() {
+                Some('Y') if (0..=9999).contains(&self.year) => {
+                    output.push_str(&format!("{:04}", self.year))
+                }
+                Some('Y') => output.push_str(&self.year.to_string()),
+                Some('m') => output.push_str(&format!("{:02}", self.month)),
+                Some('d') => output.push_str(&format!("{:02}", self.day)),
+                Some('H') => output.push_str(&format!("{:02}", self.hour)),
+                Some('M') => output.push_str(&format!("{:02}", self.minute)),
+                Some('S') => output.push_str(&format!("{:02}", self.second)),
+                Some('f') => output.push_str(&format!(
+                    "{:06}",
+                    i64::from(self.nanos) / NANOS_PER_MICRO
+                )),
+                Some('z') => output.push_str("+0000"),
+                Some('%') => output.push('%'),
+                Some(other) => {
+                    output.push('%');
+                    output.push(other);
+                }
+                None => output.push('%'),
+            }
+        }
+
+        output
+    }
+
+    /// Parses the date and the time with the format.
+    fn parse(text: &str, format: &str) -> Result<Self, String> {
+        let mismatch = || format!("'{}' does not match '{}'", text, format);
+
+        let mut input = Input(text);
+        let mut date_time = DateTime {
+            month: 1,
+            day: 1,
+            ..Default::default()
+        };
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            let parsed = match c {
+                '%' => match chars.next() {
+                    Some('Y') => input.year().map(|y| date_time.year = y),
+                    Some('m') => input.number(2).map(|m| date_time.month = m),
+                    Some('d') => input.number(2).map(|d| date_time.day = d),
+                    Some('H') => input.number(2).map(|h| date_time.hour = h),
+                    Some('M') => input.number(2).map(|m| date_time.minute = m),
+                    Some('S') => input.number(2).map(|s| date_time.second = s),
+                    Some('f') => input.fraction().map(|f| date_time.nanos = f),
+                    Some('z') => input.offset().map(|o| date_time.offset = o),
+                    Some(other) => input.literal(other),
+                    None => input.literal('%'),
+                },
+                ' ' => input.literal(' ').or_else(|| input.literal('T')),
+                c => input.literal(c),
+            };
+            parsed.ok_or_else(mismatch)?;
+        }
+        if !input.0.is_empty() {
+            return Err(mismatch());
+        }
+
+        let valid = (1..=12).contains(&date_time.month)
+            && (1..=days_in_month(date_time.year, date_time.month))
+                .contains(&date_time.day)
+            && date_time.hour < 24
+            && date_time.minute < 60
+            && date_time.second < 60;
+        if !valid {
+            return Err(format!("'{}' is not a valid date or time", text));
+        }
+
+        Ok(date_time)
+    }
+}
+
+/// The rest of the text being parsed.
+struct Input<'a>(&'a str);
+
+impl Input<'_> {
+    /// Consumes the character.
+    fn literal(&mut self, c: char) -> Option<()> {
+        self.0 = self.0.strip_prefix(c)?;
+
+        Some(())
+    }
+
+    /// Consumes up to `max` digits, at least one.
+    fn digits(&mut self, max: usize) -> Option<&str> {
+        let len = self
+            .0
+            .bytes()
+            .take(max)
+            .take_while(u8::is_ascii_digit)
+            .count();
+        if len == 0 {
+            return None;
+        }
+        let (digits, rest) = self.0.split_at(len);
+        self.0 = rest;
+
+        Some(digits)
+    }
+
+    fn number(&mut self, max: usize) -> Option<u32> {
+        self.digits(max)?.parse().ok()
+    }
+
+    fn year(&mut self) -> Option<i64> {
+        let negative = self.literal('-').is_some();
+        let year: i64 = self.digits(9)?.parse().ok()?;
+
+        Some(if negative { -year } else { year })
+    }
+
+    /// Consumes the digits of a fraction of a second, returns nanoseconds.
+    fn fraction(&mut self) -> Option<u32> {
+        let digits = self.digits(9)?;
+        let nanos: u32 = digits.parse().ok()?;
+
+        Some(nanos * 10u32.pow(9 - digits.len() as u32))
+    }
+
+    /// Consumes `Z`, `+HHMM` or `+HH:MM`, returns seconds.
+    fn offset(&mut self) -> Option<i64> {
+        if self.literal('Z').is_some() {
+            return Some(0);
+        }
+
+        let sign = if self.literal('-').is_some() {
+            -1
+        } else {
+            self.literal('+')?;
+            1
+        };
+        let hours = i64::from(self.number(2)?);
+        self.literal(':');
+        let minutes = i64::from(self.number(2)?);
+
+        Some(sign * (hours * 3_600 + minutes * 60))
+    }
+}
+
+/// Formats the number of milliseconds since the Unix epoch in UTC.
+pub(crate) fn format_timestamp(millis: i64, format: &str) -> String {
+    let days = millis.div_euclid(MILLIS_PER_DAY);
+    let nanos = millis.rem_euclid(MILLIS_PER_DAY) * NANOS_PER_MILLI;
+
+    DateTime::from_days(days, nanos).format(format)
+}
+
+/// Parses a timestamp with the format, or the number of milliseconds since
+/// the Unix epoch.
+pub(crate) fn parse_timestamp(text: &str, format: &str) -> Result<i64, String> {
+    match DateTime::parse(text, format) {
+        Ok(date_time) => Ok(date_time.millis()),
+        Err(error) => text.parse().map_err(|_| error),
+    }
+}
+
+/// Formats a CQL date.
+pub(crate) fn format_date(date: u32, format: &str) -> String {
+    DateTime::from_days(i64::from(date) - DATE_EPOCH, 0).format(format)
+}
+
+/// Parses a date with the format.
+pub(crate) fn parse_date(text: &str, format: &str) -> Result<u32, String> {
+    let days = DateTime::parse(text, format)?.days();
+
+    u32::try_from(days + DATE_EPOCH)
+        .map_err(|_| format!("the date '{}' is out of range", text))
+}
+
+/// Formats the number of nanoseconds since midnight as
+/// `HH:MM:SS.nnnnnnnnn`.
+pub(crate) fn format_time(nanos: i64) -> String {
+    let time = DateTime::from_days(0, nanos.rem_euclid(NANOS_PER_DAY));
+
+    format!(
+        "{:02}:{:02}:{:02}.{:09}",
+        time.hour, time.minute, time.second, time.nanos
+    )
+}
+
+/// Parses a time as `HH:MM:SS` with an optional fraction of a second, or the
+/// number of nanoseconds since midnight.
+pub(crate) fn parse_time(text: &str) -> Result<i64, String> {
+    if let Ok(nanos) = text.parse::<i64>() {
+        if !(0..NANOS_PER_DAY).contains(&nanos) {
+            return Err(format!("the time '{}' is out of range", text));
+        }
+        return Ok(nanos);
+    }
+
+    let time = DateTime::parse(text, "%H:%M:%S.%f")
+        .or_else(|_| DateTime::parse(text, "%H:%M:%S"))?;
+    let seconds = i64::from(time.hour) * 3_600
+        + i64::from(time.minute) * 60
+        + i64::from(time.second);
+
+    Ok(seconds * NANOS_PER_SECOND + i64::from(time.nanos))
+}
+
+/// Formats a CQL duration as e.g. `1y2mo3d4h5m6s7ms8us9ns`.
+pub(crate) fn format_duration(months: i32, days: i32, nanos: i64) -> String {
+    let negative = months < 0 || days < 0 || nanos < 0;
+    let months = i64::from(months).unsigned_abs();
+    let days = i64::from(days).unsigned_abs();
+    let mut nanos = nanos.unsigned_abs();
+
+    let mut output = String::new();
+    if negative {
+        output.push('-');
+    }
+    let mut push = |amount: u64, unit: &str| {
+        if amount > 0 {
+            output.push_str(&amount.to_string());
+            output.push_str(unit);
+        }
+    };
+    push(months / 12, "y");
+    push(months % 12, "mo");
+    push(days, "d");
+    for (unit, amount) in NANOS_UNITS {
+        push(nanos / amount, unit);
+        nanos %= amount;
+    }
+
+    if output.len() <= usize::from(negative) {
+        output = "0s".to_string();
+    }
+
+    output
+}
+
+/// Parses a CQL duration formatted as e.g. `1y2mo3w4d5h6m7s8ms9us10ns`.
+pub(crate) fn parse_duration(text: &str) -> Result<(i32, i32, i64), String> {
+    let invalid = || format!("'{}' is not a valid duration", text);
+
+    let (negative, mut rest) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+
+    let (mut months, mut days, mut nanos) = (0i64, 0i64, 0i64);
+    while !rest.is_empty() {
+        let mut input = Input(rest);
+        let amount: i64 = input
+            .digits(19)
+            .and_then(|d| d.parse().ok())
+            .ok_or_else(invalid)?;
+        let lowercase = input.0.to_lowercase();
+        let (unit, part, scale) = DURATION_UNITS
+            .iter()
+            .find(|(unit, ..)| lowercase.starts_with(unit))
+            .ok_or_else(invalid)?;
+        rest = &input.0[unit.len()..];
+
+        let total = match part {
+            DurationPart::Months => &mut months,
+            DurationPart::Days => &mut days,
+            DurationPart::Nanos => &mut nanos,
+        };
+        *total = amount
+            .checked_mul(*scale)
+            .and_then(|amount| total.checked_add(amount))
+            .ok_or_else(invalid)?;
+    }
+
+    if negative {
+        (months, days, nanos) = (-months, -days, -nanos);
+    }
+    let months = i32::try_from(months).map_err(|_| invalid())?;
+    let days = i32::try_from(days).map_err(|_| invalid())?;
+
+    Ok((months, days, nanos))
+}
+
+/// Returns the number of days since the Unix epoch of the date of the
+/// proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let month_from_march = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_from_march + 2) / 5 + i64::from(day) - 1;
+    let day_of_era =
+        year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the year, the month and the day of the number of days since the
+/// Unix epoch.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524
+        - day_of_era / 146_096)
+        / 365;
+    let day_of_year =
+        day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    } as u32;
+    let year = year_of_era + era * 400;
+
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORMAT: &str = "%Y-%m-%d %H:%M:%S.%f%z";
+
+    #[test]
+    fn test_timestamp() {
+        let millis = 1_700_000_000_123;
+        let text = format_timestamp(millis, FORMAT);
+        assert_eq!(text, "2023-11-14 22:13:20.123000+0000");
+        assert_eq!(parse_timestamp(&text, FORMAT), Ok(millis));
+
+        assert_eq!(
+            parse_timestamp("2023-11-15T00:13:20.123+02:00", FORMAT),
+            Ok(millis)
+        );
+        assert_eq!(parse_timestamp("1700000000123", FORMAT), Ok(millis));
+        assert_eq!(
+            format_timestamp(-1, FORMAT),
+            "1969-12-31 23:59:59.999000+0000"
+        );
+        assert!(parse_timestamp("2023-02-29 00:00:00.000Z", FORMAT).is_err());
+    }
+
+    #[test]
+    fn test_date_and_time() {
+        let date = (DATE_EPOCH + 19_675) as u32;
+        assert_eq!(format_date(date, "%Y-%m-%d"), "2023-11-14");
+        assert_eq!(parse_date("2023-11-14", "%Y-%m-%d"), Ok(date));
+        assert_eq!(format_date(0, "%Y-%m-%d"), "-5877641-06-23");
+
+        let nanos = 22 * 3_600 * NANOS_PER_SECOND + 123_456_789;
+        assert_eq!(format_time(nanos), "22:00:00.123456789");
+        assert_eq!(parse_time("22:00:00.123456789"), Ok(nanos));
+        assert_eq!(parse_time("22:00:00.1234"), Ok(nanos - 56_789));
+        assert_eq!(parse_time("22:00:00"), Ok(nanos - 123_456_789));
+        assert_eq!(parse_time(&nanos.to_string()), Ok(nanos));
+        assert!(parse_time("24:00:00").is_err());
+    }
+
+    #[test]
+    fn test_duration() {
+        let nanos = ((4 * 60 + 5) * 60 + 6) * NANOS_PER_SECOND + 7_008_009;
+        let text = format_duration(14, 3, nanos);
+        assert_eq!(text, "1y2mo3d4h5m6s7ms8us9ns");
+        assert_eq!(parse_duration(&text), Ok((14, 3, nanos)));
+        assert_eq!(parse_duration("-2W1MO"), Ok((-1, -14, 0)));
+        assert_eq!(format_duration(0, 0, 0), "0s");
+        assert_eq!(format_duration(0, -1, 0), "-1d");
+        assert!(parse_duration("1x").is_err());
+        assert!(parse_duration("-").is_err());
+    }
+}
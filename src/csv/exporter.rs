@@ -0,0 +1,263 @@
+use std::io::{
+    BufWriter,
+    Write,
+};
+
+use super::record::write_record;
+use super::text::format_field;
+use super::{
+    CsvError,
+    CsvFormat,
+};
+use crate::blocking::BlockingSession;
+use crate::cql::{
+    quote_identifier,
+    CqlValue,
+};
+use crate::{
+    QueryResult,
+    Session,
+    Statement,
+};
+
+/// Exports the rows of a query or a table to CSV.
+///
+/// The rows are fetched page by page and written as they arrive, so the
+/// export of a large table does not hold it in memory.
+#[derive(Debug, Clone, Default)]
+pub struct CsvExporter {
+    /// The format of the CSV data.
+    format:    CsvFormat,
+    /// The number of the rows fetched per page.
+    page_size: Option<usize>,
+}
+
+impl CsvExporter {
+    /// Creates an exporter that writes the CSV data with the format.
+    pub fn new(format: CsvFormat) -> Self {
+        Self {
+            format,
+            page_size: None,
+        }
+    }
+
+    /// Sets the number of the rows fetched per page.
+    ///
+    /// By default, the page size of the statement is used.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = Some(page_size);
+
+        self
+    }
+
+    /// Exports the rows of the statement and returns their number.
+    ///
+    /// The writer is written on the current task, use a writer that does not
+    /// block for long, e.g. a file.
+    pub async fn export<W>(
+        &self,
+        session: &Session,
+        mut statement: Statement,
+        writer: W,
+    ) -> Result<u64, CsvError>
+    where
+        W: Write,
+    {
+        self.configure(&mut statement)?;
+        let mut output = Output::new(writer, &self.format);
+        loop {
+            let page = session.execute(&statement).await?;
+            output.write_page(&page)?;
+
+            let Some(token) = page.paging_state_token() else {
+                break;
+            };
+            statement.set_paging_state_token(&token)?;
+        }
+
+        output.finish()
+    }
+
+    /// Exports all the rows of the table and returns their number.
+    pub async fn export_table<W>(
+        &self,
+        session: &Session,
+        keyspace: &str,
+        table: &str,
+        writer: W,
+    ) -> Result<u64, CsvError>
+    where
+        W: Write,
+    {
+        self.export(session, select_all(keyspace, table), writer)
+            .await
+    }
+
+    /// Exports the rows of the statement blocking the current thread, see
+    /// [`CsvExporter::export`].
+    pub fn export_blocking<W>(
+        &self,
+        session: &BlockingSession,
+        mut statement: Statement,
+        writer: W,
+    ) -> Result<u64, CsvError>
+    where
+        W: Write,
+    {
+        self.configure(&mut statement)?;
+        let mut output = Output::new(writer, &self.format);
+        for page in session.pages(statement) {
+            output.write_page(&page?)?;
+        }
+
+        output.finish()
+    }
+
+    /// Exports all the rows of the table blocking the current thread, see
+    /// [`CsvExporter::export_table`].
+    pub fn export_table_blocking<W>(
+        &self,
+        session: &BlockingSession,
+        keyspace: &str,
+        table: &str,
+        writer: W,
+    ) -> Result<u64, CsvError>
+    where
+        W: Write,
+    {
+        self.export_blocking(session, select_all(keyspace, table), writer)
+    }
+
+    /// Applies the settings of the exporter to the statement.
+    fn configure(&self, statement: &mut Statement) -> Result<(), CsvError> {
+        if let Some(page_size) = self.page_size {
+            statement.set_paging_size(Some(page_size))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the statement that selects all the rows of the table.
+fn select_all(keyspace: &str, table: &str) -> Statement {
+    let query = format!(
+        "SELECT * FROM {}.{}",
+        quote_identifier(keyspace),
+        quote_identifier(table)
+    );
+
+    Statement::new(query, 0)
+}
+
+/// Writes the pages of a result as CSV.
+struct Output<'a, W>
+where
+    W: Write,
+{
+    writer: BufWriter<W>,
+    format: &'a CsvFormat,
+    /// Whether the header is yet to be written.
+    header: bool,
+    /// The number of the rows written.
+    rows:   u64,
+}
+
+impl<'a, W> Output<'a, W>
+where
+    W: Write,
+{
+    fn new(writer: W, format: &'a CsvFormat) -> Self {
+        Self {
+            writer: BufWriter::new(writer),
+            format,
+            header: format.header,
+            rows: 0,
+        }
+    }
+
+    /// Writes the rows of the page, and the column names before the first
+    /// page.
+    fn write_page(&mut self, page: &QueryResult) -> Result<(), CsvError> {
+        if self.header {
+            let names = (0..page.column_count())
+                .map(|index| page.column_name(index).unwrap_or_default());
+            self.write_header(names)?;
+        }
+
+        for row in page.rows() {
+            self.write_row(&row.values()?)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_header<I>(&mut self, names: I) -> Result<(), CsvError>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        write_record(
+            &mut self.writer,
+            names.into_iter().map(Some),
+            self.format,
+        )?;
+        self.header = false;
+
+        Ok(())
+    }
+
+    fn write_row(&mut self, values: &[CqlValue]) -> Result<(), CsvError> {
+        let fields =
+            values.iter().map(|value| format_field(value, self.format));
+        write_record(&mut self.writer, fields, self.format)?;
+        self.rows += 1;
+
+        Ok(())
+    }
+
+    /// Flushes the output, returns the number of the rows written.
+    fn finish(mut self) -> Result<u64, CsvError> {
+        self.writer.flush()?;
+
+        Ok(self.rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(format: &CsvFormat, rows: &[Vec<CqlValue>]) -> String {
+        let mut buffer = Vec::new();
+        let mut output = Output::new(&mut buffer, format);
+        output
+            .write_header(vec!["k".to_string(), "v".to_string()])
+            .unwrap();
+        for row in rows {
+            output.write_row(row).unwrap();
+        }
+        assert_eq!(output.finish().unwrap(), rows.len() as u64);
+
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_export_rows() {
+        let rows = vec![
+            vec![CqlValue::Int(1), CqlValue::Text("a, \"b\"".into())],
+            vec![CqlValue::Int(2), CqlValue::Null],
+            vec![
+                CqlValue::Int(3),
+                CqlValue::List(vec![CqlValue::Text("x".into())]),
+            ],
+        ];
+
+        assert_eq!(
+            export(&CsvFormat::new(), &rows),
+            "k,v\n1,\"a, \"\"b\"\"\"\n2,\n3,['x']\n"
+        );
+        assert_eq!(
+            export(&CsvFormat::tsv().with_quote('\'').with_null("NULL"), &rows),
+            "k\tv\n1\ta, \"b\"\n2\tNULL\n3\t'[''x'']'\n"
+        );
+    }
+}
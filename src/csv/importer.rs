@@ -0,0 +1,535 @@
+use std::io::BufRead;
+use std::sync::Arc;
+
+use futures::stream;
+
+use super::partition_key::encode_partition_key;
+use super::record::{
+    Record,
+    RecordReader,
+};
+use super::text::parse_field;
+use super::{
+    CsvError,
+    CsvFormat,
+    CsvParseError,
+};
+use crate::blocking::BlockingSession;
+use crate::cql::{
+    quote_identifier,
+    CqlType,
+    CqlValue,
+};
+use crate::{
+    BulkLoadReport,
+    BulkLoader,
+    BulkRecord,
+    ColumnKind,
+    DriverError,
+    Session,
+    Statement,
+};
+
+/// Imports CSV data into a table.
+///
+/// The fields are parsed into the types of the table columns, read from the
+/// schema metadata of the session, and the rows are written with a
+/// [`BulkLoader`]. The records that cannot be parsed or written do not stop
+/// the import, they are returned in the [`CsvImportReport`].
+///
+/// The rows are written with an `INSERT`, or, like `cqlsh` does, with an
+/// `UPDATE` that adds the values to the counters if the columns include
+/// counters, e.g. `UPDATE ks.t SET c = c + ? WHERE k = ?`. The counter
+/// imports are not idempotent, a retried write may add the values twice.
+#[derive(Clone, Default)]
+pub struct CsvImporter {
+    /// The format of the CSV data.
+    format:    CsvFormat,
+    /// The columns of the fields, in order.
+    columns:   Option<Vec<String>>,
+    /// Configures the loader that writes the rows.
+    configure: Option<Arc<dyn Fn(BulkLoader) -> BulkLoader + Send + Sync>>,
+}
+
+impl CsvImporter {
+    /// Creates an importer that reads the CSV data with the format.
+    pub fn new(format: CsvFormat) -> Self {
+        Self {
+            format,
+            columns: None,
+            configure: None,
+        }
+    }
+
+    /// Sets the columns of the fields, in order.
+    ///
+    /// By default, the columns are read from the header, or are all the
+    /// columns of the table in the order of `SELECT *` if the data has no
+    /// header. The header is skipped if the columns are set.
+    pub fn with_columns<I, T>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.columns = Some(columns.into_iter().map(Into::into).collect());
+
+        self
+    }
+
+    /// Sets the function that configures the loader that writes the rows,
+    /// e.g. its concurrency or its rate limit.
+    ///
+    /// ```rust,ignore
+    /// let importer = CsvImporter::new(CsvFormat::new()).with_loader(|loader| {
+    ///     loader.with_max_in_flight(64).with_idempotent(true)
+    /// });
+    /// ```
+    pub fn with_loader<F>(mut self, configure: F) -> Self
+    where
+        F: Fn(BulkLoader) -> BulkLoader + Send + Sync + 'static,
+    {
+        self.configure = Some(Arc::new(configure));
+
+        self
+    }
+
+    /// Imports the CSV data into the table and returns the report of the
+    /// import.
+    ///
+    /// The reader is read on the current task, use a reader that does not
+    /// block for long, e.g. a file. The import fails if the table or a column
+    /// does not exist, or the data cannot be read.
+    pub async fn import<R>(
+        &self,
+        session: &Session,
+        keyspace: &str,
+        table: &str,
+        reader: R,
+    ) -> Result<CsvImportReport, CsvError>
+    where
+        R: BufRead,
+    {
+        let mut records = RecordReader::new(reader, &self.format);
+        let header = if self.format.header {
+            match records.read()? {
+                Some(record) => Some(record.fields),
+                None => return Ok(CsvImportReport::default()),
+            }
+        } else {
+            None
+        };
+        let names = self.columns.clone().or_else(|| {
+            header.map(|fields| {
+                fields
+                    .into_iter()
+                    .map(|field| field.text.trim().to_string())
+                    .collect()
+            })
+        });
+
+        let columns = table_columns(session, keyspace, table, names)?;
+        let (query, bind_order) = import_query(keyspace, table, &columns)?;
+        let bind_order: Arc<[usize]> = bind_order.into();
+        let mut loader = BulkLoader::new(session.prepare(query).await?);
+        if let Some(configure) = &self.configure {
+            loader = configure(loader);
+        }
+
+        let mut rows = 0;
+        let mut parse_errors = Vec::new();
+        let mut read_error = None;
+        let parsed = std::iter::from_fn(|| loop {
+            match records.read() {
+                Ok(Some(record)) => {
+                    rows += 1;
+                    match self.parse(&columns, &bind_order, record) {
+                        Ok(row) => return Some(row),
+                        Err(error) => parse_errors.push(error),
+                    }
+                }
+                Ok(None) => return None,
+                Err(error) => {
+                    read_error = Some(error);
+                    return None;
+                }
+            }
+        });
+        let load = loader.load(session, stream::iter(parsed)).await;
+        if let Some(error) = read_error {
+            return Err(error.into());
+        }
+
+        Ok(CsvImportReport {
+            rows,
+            parse_errors,
+            load,
+        })
+    }
+
+    /// Imports the CSV data into the table blocking the current thread, see
+    /// [`CsvImporter::import`].
+    pub fn import_blocking<R>(
+        &self,
+        session: &BlockingSession,
+        keyspace: &str,
+        table: &str,
+        reader: R,
+    ) -> Result<CsvImportReport, CsvError>
+    where
+        R: BufRead,
+    {
+        futures::executor::block_on(self.import(
+            session.session(),
+            keyspace,
+            table,
+            reader,
+        ))
+    }
+
+    /// Parses the fields of the record into the values of the columns.
+    fn parse(
+        &self,
+        columns: &[ImportColumn],
+        bind_order: &Arc<[usize]>,
+        record: Record,
+    ) -> Result<CsvRow, CsvParseError> {
+        let error = |message| CsvParseError {
+            line: record.line,
+            message,
+        };
+        if record.fields.len() != columns.len() {
+            return Err(error(format!(
+                "expected {} fields, found {}",
+                columns.len(),
+                record.fields.len()
+            )));
+        }
+
+        let mut values = Vec::with_capacity(columns.len());
+        for (column, field) in columns.iter().zip(&record.fields) {
+            let value = if !field.quoted && field.text == self.format.null {
+                CqlValue::Null
+            } else {
+                parse_field(&field.text, &column.data_type, &self.format)
+                    .map_err(|message| {
+                        error(format!("column {}: {}", column.name, message))
+                    })?
+            };
+            values.push(value);
+        }
+
+        let key: Vec<_> = columns
+            .iter()
+            .zip(&values)
+            .filter(|(column, _)| column.kind == ColumnKind::PartitionKey)
+            .map(|(column, value)| (value, &column.data_type))
+            .collect();
+        let partition_key = encode_partition_key(&key);
+
+        Ok(CsvRow {
+            line: record.line,
+            values,
+            bind_order: bind_order.clone(),
+            partition_key,
+        })
+    }
+}
+
+/// A column the CSV fields are imported into.
+struct ImportColumn {
+    name:      String,
+    data_type: CqlType,
+    kind:      ColumnKind,
+}
+
+/// Returns the columns with the given names, or all the columns of the table.
+fn table_columns(
+    session: &Session,
+    keyspace: &str,
+    table: &str,
+    names: Option<Vec<String>>,
+) -> Result<Vec<ImportColumn>, CsvError> {
+    let schema = session.get_schema_meta();
+    let table_meta = schema
+        .keyspace(keyspace)
+        .and_then(|keyspace| keyspace.table(table))
+        .ok_or_else(|| CsvError::UnknownTable {
+            keyspace: keyspace.to_string(),
+            table:    table.to_string(),
+        })?;
+
+    let columns = match names {
+        Some(names) => names
+            .into_iter()
+            .map(|name| {
+                table_meta.column(&name).ok_or(CsvError::UnknownColumn {
+                    table:  table.to_string(),
+                    column: name,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        None => table_meta.columns().collect(),
+    };
+
+    Ok(columns
+        .into_iter()
+        .map(|column| ImportColumn {
+            name:      column.name(),
+            data_type: column.data_type(),
+            kind:      column.kind(),
+        })
+        .collect())
+}
+
+/// Returns the query that writes the rows and the indexes of the columns in
+/// the order of its bind variables.
+///
+/// The rows are inserted, unless the columns include counters: the counters
+/// are then incremented by the values and the other columns must be the
+/// primary key columns.
+fn import_query(
+    keyspace: &str,
+    table: &str,
+    columns: &[ImportColumn],
+) -> Result<(String, Vec<usize>), CsvError> {
+    let table =
+        format!("{}.{}", quote_identifier(keyspace), quote_identifier(table));
+    let names = |indexes: &[usize], template: &str, separator: &str| {
+        indexes
+            .iter()
+            .map(|index| {
+                template.replace("{}", &quote_identifier(&columns[*index].name))
+            })
+            .collect::<Vec<_>>()
+            .join(separator)
+    };
+
+    let (counters, others): (Vec<_>, Vec<_>) = (0..columns.len())
+        .partition(|index| columns[*index].data_type == CqlType::Counter);
+    if counters.is_empty() {
+        let query = format!(
+            "INSERT INTO {} ({}) VALUES ({})",
+            table,
+            names(&others, "{}", ", "),
+            vec!["?"; others.len()].join(", ")
+        );
+
+        return Ok((query, others));
+    }
+
+    if let Some(column) =
+        others.iter().map(|index| &columns[*index]).find(|column| {
+            !matches!(
+                column.kind,
+                ColumnKind::PartitionKey | ColumnKind::ClusteringKey
+            )
+        })
+    {
+        return Err(CsvError::UnsupportedColumn {
+            column:    column.name.clone(),
+            data_type: column.data_type.clone(),
+        });
+    }
+
+    let query = format!(
+        "UPDATE {} SET {} WHERE {}",
+        table,
+        names(&counters, "{} = {} + ?", ", "),
+        names(&others, "{} = ?", " AND ")
+    );
+
+    Ok((query, counters.into_iter().chain(others).collect()))
+}
+
+/// A CSV record parsed into the values of the columns.
+#[derive(Debug, Clone)]
+pub struct CsvRow {
+    /// The line the record starts at.
+    line:          u64,
+    /// The values of the columns.
+    values:        Vec<CqlValue>,
+    /// The indexes of the values in the order of the bind variables.
+    bind_order:    Arc<[usize]>,
+    /// The encoded values of the partition key columns.
+    partition_key: Option<Vec<u8>>,
+}
+
+impl CsvRow {
+    /// Returns the line the record starts at, counting from 1.
+    pub fn line(&self) -> u64 {
+        self.line
+    }
+
+    /// Returns the values of the columns.
+    pub fn values(&self) -> &[CqlValue] {
+        &self.values
+    }
+}
+
+impl BulkRecord for CsvRow {
+    fn bind(&self, statement: &mut Statement) -> Result<(), DriverError> {
+        for (index, value) in self.bind_order.iter().enumerate() {
+            statement.bind(index, self.values[*value].clone())?;
+        }
+
+        Ok(())
+    }
+
+    fn partition_key(&self) -> Option<Vec<u8>> {
+        self.partition_key.clone()
+    }
+}
+
+/// The outcome of a CSV import.
+#[derive(Debug, Default)]
+pub struct CsvImportReport {
+    /// The number of the records read.
+    rows:         u64,
+    /// The records that cannot be parsed.
+    parse_errors: Vec<CsvParseError>,
+    /// The outcome of the load of the parsed records.
+    load:         BulkLoadReport<CsvRow>,
+}
+
+impl CsvImportReport {
+    /// Returns the number of the records read, without the header.
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    /// Returns the number of the rows written.
+    pub fn loaded(&self) -> u64 {
+        self.load.loaded()
+    }
+
+    /// Returns the records that cannot be parsed.
+    pub fn parse_errors(&self) -> &[CsvParseError] {
+        &self.parse_errors
+    }
+
+    /// Returns the outcome of the load, including the rows that failed to
+    /// be written.
+    pub fn load(&self) -> &BulkLoadReport<CsvRow> {
+        &self.load
+    }
+
+    /// Returns whether all the records were parsed and written.
+    pub fn is_success(&self) -> bool {
+        self.parse_errors.is_empty() && self.load.is_success()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv::record::RecordReader;
+
+    fn column(
+        name: &str,
+        data_type: CqlType,
+        kind: ColumnKind,
+    ) -> ImportColumn {
+        ImportColumn {
+            name: name.to_string(),
+            data_type,
+            kind,
+        }
+    }
+
+    fn parse(
+        columns: &[ImportColumn],
+        line: &str,
+    ) -> Result<CsvRow, CsvParseError> {
+        let format = CsvFormat::new().with_header(false);
+        let (_, bind_order) = import_query("ks", "t", columns).unwrap();
+        let record = RecordReader::new(line.as_bytes(), &format)
+            .read()
+            .unwrap()
+            .unwrap();
+
+        CsvImporter::new(format).parse(columns, &bind_order.into(), record)
+    }
+
+    #[test]
+    fn test_insert_query() {
+        let columns = [
+            column("k", CqlType::Int, ColumnKind::PartitionKey),
+            column("Value", CqlType::Text, ColumnKind::Regular),
+        ];
+        assert_eq!(
+            import_query("ks", "t", &columns).unwrap(),
+            (
+                r#"INSERT INTO "ks"."t" ("k", "Value") VALUES (?, ?)"#
+                    .to_string(),
+                vec![0, 1]
+            )
+        );
+    }
+
+    #[test]
+    fn test_counter_query() {
+        let columns = [
+            column("c", CqlType::Counter, ColumnKind::Regular),
+            column("k", CqlType::Int, ColumnKind::PartitionKey),
+            column("d", CqlType::Counter, ColumnKind::Static),
+            column("ck", CqlType::Text, ColumnKind::ClusteringKey),
+        ];
+        assert_eq!(
+            import_query("ks", "t", &columns).unwrap(),
+            (
+                r#"UPDATE "ks"."t" SET "c" = "c" + ?, "d" = "d" + ? WHERE "k" = ? AND "ck" = ?"#
+                    .to_string(),
+                vec![0, 2, 1, 3]
+            )
+        );
+
+        let columns = [
+            column("k", CqlType::Int, ColumnKind::PartitionKey),
+            column("c", CqlType::Counter, ColumnKind::Regular),
+            column("v", CqlType::Int, ColumnKind::Regular),
+        ];
+        assert!(matches!(
+            import_query("ks", "t", &columns),
+            Err(CsvError::UnsupportedColumn { column, .. }) if column == "v"
+        ));
+    }
+
+    #[test]
+    fn test_parse_record() {
+        let address = CqlType::UserType {
+            name:   "address".into(),
+            fields: vec![("zip".into(), CqlType::Int)],
+        };
+        let columns = [
+            column("k", CqlType::Text, ColumnKind::PartitionKey),
+            column("b", CqlType::Int, ColumnKind::PartitionKey),
+            column("a", address, ColumnKind::Regular),
+        ];
+
+        let row = parse(&columns, "x,1,{zip: 2}").unwrap();
+        assert_eq!(
+            row.values(),
+            [
+                CqlValue::Text("x".into()),
+                CqlValue::Int(1),
+                CqlValue::UserType(vec![("zip".into(), CqlValue::Int(2))]),
+            ]
+        );
+        assert_eq!(
+            row.partition_key(),
+            Some(vec![0, 1, b'x', 0, 0, 4, 0, 0, 0, 1, 0])
+        );
+
+        let row = parse(&columns, "x,,").unwrap();
+        assert_eq!(row.values()[2], CqlValue::Null);
+        assert_eq!(row.partition_key(), None);
+
+        let error = parse(&columns, "x,y,").unwrap_err();
+        assert_eq!(error.line, 1);
+        assert!(error.message.starts_with("column b: "), "{}", error);
+        assert_eq!(
+            parse(&columns, "x,1").unwrap_err().message,
+            "expected 3 fields, found 2"
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Conversions between the big-endian two's complement integers of the CQL
+//! `varint` and `decimal` values and their decimal text.
+
+/// Formats a big-endian two's complement integer.
+pub(crate) fn format_varint(bytes: &[u8]) -> String {
+    let negative = bytes.first().is_some_and(|byte| byte & 0x80 != 0);
+    let mut magnitude = bytes.to_vec();
+    if negative {
+        negate(&mut magnitude);
+    }
+
+    // Divides the magnitude by 10 until it is zero, collecting the remainders.
+    let mut digits = Vec::new();
+    while magnitude.iter().any(|byte| *byte != 0) {
+        let mut remainder = 0u32;
+        for byte in magnitude.iter_mut() {
+            let value = remainder * 256 + u32::from(*byte);
+            *byte = (value / 10) as u8;
+            remainder = value % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+    }
+    if digits.is_empty() {
+        digits.push(b'0');
+    }
+    if negative {
+        digits.push(b'-');
+    }
+    digits.reverse();
+
+    String::from_utf8(digits).expect("the digits are ASCII")
+}
+
+/// Parses an integer as a big-endian two's complement integer of the
+/// minimal length.
+pub(crate) fn parse_varint(text: &str) -> Result<Vec<u8>, String> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(format!("'{}' is not a valid integer", text));
+    }
+
+    // Multiplies the magnitude by 10 and adds the digit, the leading zero
+    // byte keeps the sign bit clear.
+    let mut bytes = vec![0u8];
+    for digit in digits.bytes() {
+        let mut carry = u32::from(digit - b'0');
+        for byte in bytes.iter_mut().rev() {
+            let value = u32::from(*byte) * 10 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry > 0 {
+            bytes.insert(0, carry as u8);
+        }
+        if bytes[0] & 0x80 != 0 {
+            bytes.insert(0, 0);
+        }
+    }
+    if negative {
+        negate(&mut bytes);
+    }
+
+    // Strips the leading bytes that only repeat the sign.
+    let redundant = bytes
+        .windows(2)
+        .take_while(|pair| {
+            (pair[0] == 0x00 && pair[1] & 0x80 == 0)
+                || (pair[0] == 0xFF && pair[1] & 0x80 != 0)
+        })
+        .count();
+    bytes.drain(..redundant);
+
+    Ok(bytes)
+}
+
+/// Formats a decimal with the unscaled big-endian two's complement integer
+/// and the scale.
+///
+/// The negative scales are formatted with an exponent, e.g. `12E+3`.
+pub(crate) fn format_decimal(unscaled: &[u8], scale: i32) -> String {
+    let digits = format_varint(unscaled);
+    if scale < 0 {
+        return format!("{}E+{}", digits, -i64::from(scale));
+    }
+
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", digits.as_str()),
+    };
+    let scale = scale as usize;
+    if scale == 0 {
+        format!("{}{}", sign, digits)
+    } else if digits.len() > scale {
+        let (integer, fraction) = digits.split_at(digits.len() - scale);
+        format!("{}{}.{}", sign, integer, fraction)
+    } else {
+        format!("{}0.{:0>width$}", sign, digits, width = scale)
+    }
+}
+
+/// Parses a decimal, e.g. `-1.25` or `1.5E-3`, as the unscaled big-endian
+/// two's complement integer and the scale.
+pub(crate) fn parse_decimal(text: &str) -> Result<(Vec<u8>, i32), String> {
+    let invalid = || format!("'{}' is not a valid decimal", text);
+
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => {
+            let exponent = exponent.strip_prefix('+').unwrap_or(exponent);
+            (mantissa, exponent.parse::<i64>().map_err(|_| invalid())?)
+        }
+        None => (text, 0),
+    };
+    let (integer, fraction) =
+        mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = integer.trim_start_matches(['-', '+']).len() + fraction.len();
+    if digits == 0 || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    let unscaled = parse_varint(&format!("{}{}", integer, fraction))
+        .map_err(|_| invalid())?;
+    let scale = i32::try_from(fraction.len() as i64 - exponent)
+        .map_err(|_| invalid())?;
+
+    Ok((unscaled, scale))
+}
+
+/// Negates a big-endian two's complement integer in place.
+fn negate(bytes: &mut [u8]) {
+    let mut carry = true;
+    for byte in bytes.iter_mut().rev() {
+        *byte = !*byte;
+        if carry {
+            (*byte, carry) = byte.overflowing_add(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint() {
+        for (text, bytes) in [
+            ("0", vec![0x00]),
+            ("127", vec![0x7F]),
+            ("128", vec![0x00, 0x80]),
+            ("-128", vec![0x80]),
+            ("-129", vec![0xFF, 0x7F]),
+            ("65535", vec![0x00, 0xFF, 0xFF]),
+            ("-1", vec![0xFF]),
+        ] {
+            assert_eq!(parse_varint(text), Ok(bytes.clone()), "{}", text);
+            assert_eq!(format_varint(&bytes), text);
+        }
+
+        let big = "123456789012345678901234567890";
+        assert_eq!(format_varint(&parse_varint(big).unwrap()), big);
+        assert_eq!(format_varint(&[]), "0");
+        assert!(parse_varint("1.5").is_err());
+        assert!(parse_varint("-").is_err());
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(parse_decimal("-1.25"), Ok((vec![0x83], 2)));
+        assert_eq!(format_decimal(&[0x83], 2), "-1.25");
+        assert_eq!(parse_decimal("1.5E-3"), Ok((vec![0x0F], 4)));
+        assert_eq!(format_decimal(&[0x0F], 4), "0.0015");
+        assert_eq!(parse_decimal("12E+3"), Ok((vec![0x0C], -3)));
+        assert_eq!(format_decimal(&[0x0C], -3), "12E+3");
+        assert_eq!(format_decimal(&[0x0C], 0), "12");
+        assert!(parse_decimal("1.2.3").is_err());
+        assert!(parse_decimal(".").is_err());
+    }
+}
@@ -0,0 +1,307 @@
+//! The serialized partition key of the imported rows, encoded the way
+//! Cassandra encodes the partition key of a table.
+
+use std::net::IpAddr;
+
+use crate::cql::{
+    CqlType,
+    CqlValue,
+};
+
+/// Encodes the values of the partition key columns, in order.
+///
+/// A single column key is the serialized value, a composite key is the
+/// serialized values each prefixed with its 16-bit length and followed by a
+/// zero byte. Returns [`None`] if there are no values, a value is null or a
+/// serialized value is longer than 65535 bytes.
+pub(crate) fn encode_partition_key(
+    components: &[(&CqlValue, &CqlType)],
+) -> Option<Vec<u8>> {
+    if components.is_empty()
+        || components.iter().any(|(value, _)| value.is_null())
+    {
+        return None;
+    }
+
+    if let [(value, data_type)] = components {
+        let mut output = Vec::new();
+        serialize(value, data_type, &mut output);
+
+        return Some(output);
+    }
+
+    let mut output = Vec::new();
+    for (value, data_type) in components {
+        let mut component = Vec::new();
+        serialize(value, data_type, &mut component);
+        let len = u16::try_from(component.len()).ok()?;
+        output.extend_from_slice(&len.to_be_bytes());
+        output.extend_from_slice(&component);
+        output.push(0);
+    }
+
+    Some(output)
+}
+
+/// Appends the value serialized as in the version 4 of the native protocol,
+/// without its length.
+fn serialize(value: &CqlValue, data_type: &CqlType, output: &mut Vec<u8>) {
+    match value {
+        CqlValue::Null => {}
+        CqlValue::Ascii(value) | CqlValue::Text(value) => {
+            output.extend_from_slice(value.as_bytes())
+        }
+        CqlValue::Boolean(value) => output.push(u8::from(*value)),
+        CqlValue::TinyInt(value) => {
+            output.extend_from_slice(&value.to_be_bytes())
+        }
+        CqlValue::SmallInt(value) => {
+            output.extend_from_slice(&value.to_be_bytes())
+        }
+        CqlValue::Int(value) => output.extend_from_slice(&value.to_be_bytes()),
+        CqlValue::BigInt(value)
+        | CqlValue::Counter(value)
+        | CqlValue::Timestamp(value)
+        | CqlValue::Time(value) => {
+            output.extend_from_slice(&value.to_be_bytes())
+        }
+        CqlValue::Float(value) => {
+            output.extend_from_slice(&value.to_be_bytes())
+        }
+        CqlValue::Double(value) => {
+            output.extend_from_slice(&value.to_be_bytes())
+        }
+        CqlValue::Decimal {
+            unscaled,
+            scale,
+        } => {
+            output.extend_from_slice(&scale.to_be_bytes());
+            output.extend_from_slice(unscaled);
+        }
+        CqlValue::Varint(value)
+        | CqlValue::Blob(value)
+        | CqlValue::Custom(value) => output.extend_from_slice(value),
+        CqlValue::Uuid(value) | CqlValue::TimeUuid(value) => {
+            // The driver keeps the time fields of the UUID in one integer,
+            // the time low, mid and high fields are stored in that order.
+            let time = value.time_and_version();
+            output.extend_from_slice(&(time as u32).to_be_bytes());
+            output.extend_from_slice(&((time >> 32) as u16).to_be_bytes());
+            output.extend_from_slice(&((time >> 48) as u16).to_be_bytes());
+            output.extend_from_slice(&value.clock_seq_and_node().to_be_bytes());
+        }
+        CqlValue::Inet(value) => match IpAddr::from(value) {
+            IpAddr::V4(address) => output.extend_from_slice(&address.octets()),
+            IpAddr::V6(address) => output.extend_from_slice(&address.octets()),
+        },
+        CqlValue::Date(value) => output.extend_from_slice(&value.to_be_bytes()),
+        CqlValue::Duration {
+            months,
+            days,
+            nanos,
+        } => {
+            write_vint(i64::from(*months), output);
+            write_vint(i64::from(*days), output);
+            write_vint(*nanos, output);
+        }
+        CqlValue::List(items) | CqlValue::Set(items) => {
+            let item_type = match data_type {
+                CqlType::List(item) | CqlType::Set(item) => item.as_ref(),
+                _ => data_type,
+            };
+            write_len(items.len(), output);
+            for item in items {
+                write_bytes(item, item_type, output);
+            }
+        }
+        CqlValue::Map(entries) => {
+            let (key_type, value_type) = match data_type {
+                CqlType::Map(key, value) => (key.as_ref(), value.as_ref()),
+                _ => (data_type, data_type),
+            };
+            write_len(entries.len(), output);
+            for (key, value) in entries {
+                write_bytes(key, key_type, output);
+                write_bytes(value, value_type, output);
+            }
+        }
+        CqlValue::Tuple(items) => {
+            let types = match data_type {
+                CqlType::Tuple(types) => types.as_slice(),
+                _ => &[],
+            };
+            for (index, item) in items.iter().enumerate() {
+                write_bytes(
+                    item,
+                    types.get(index).unwrap_or(data_type),
+                    output,
+                );
+            }
+        }
+        CqlValue::UserType(values) => {
+            // The fields are serialized in the order of the type definition,
+            // the missing ones as null.
+            let CqlType::UserType {
+                fields, ..
+            } = data_type
+            else {
+                for (_, value) in values {
+                    write_bytes(value, data_type, output);
+                }
+                return;
+            };
+            for (name, field_type) in fields {
+                let value = values
+                    .iter()
+                    .find(|(field, _)| field == name)
+                    .map_or(&CqlValue::Null, |(_, value)| value);
+                write_bytes(value, field_type, output);
+            }
+        }
+    }
+}
+
+/// Appends the value prefixed with its 32-bit length, -1 for null.
+fn write_bytes(value: &CqlValue, data_type: &CqlType, output: &mut Vec<u8>) {
+    if value.is_null() {
+        output.extend_from_slice(&(-1i32).to_be_bytes());
+        return;
+    }
+
+    let start = output.len();
+    output.extend_from_slice(&[0; 4]);
+    serialize(value, data_type, output);
+    let len = (output.len() - start - 4) as i32;
+    output[start..start + 4].copy_from_slice(&len.to_be_bytes());
+}
+
+/// Appends the number of the items of a collection.
+fn write_len(len: usize, output: &mut Vec<u8>) {
+    output.extend_from_slice(&(len as i32).to_be_bytes());
+}
+
+/// Appends a zigzag encoded variable length integer: the number of the
+/// leading one bits of the first byte is the number of the bytes that
+/// follow it.
+fn write_vint(value: i64, output: &mut Vec<u8>) {
+    let value = ((value << 1) ^ (value >> 63)) as u64;
+    let size = ((639 - (value | 1).leading_zeros() * 9) >> 6) as usize;
+    if size == 1 {
+        output.push(value as u8);
+        return;
+    }
+
+    let mut bytes = [0; 9];
+    bytes[1..].copy_from_slice(&value.to_be_bytes());
+    let bytes = &mut bytes[9 - size..];
+    bytes[0] |= (0xff00_u16 >> (size - 1)) as u8;
+    output.extend_from_slice(bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cql::CqlUuid;
+
+    fn encode(value: CqlValue, data_type: CqlType) -> Vec<u8> {
+        encode_partition_key(&[(&value, &data_type)]).unwrap()
+    }
+
+    #[test]
+    fn test_scalars() {
+        assert_eq!(encode(CqlValue::Text("ab".into()), CqlType::Text), b"ab");
+        assert_eq!(
+            encode(CqlValue::Int(-2), CqlType::Int),
+            [255, 255, 255, 254]
+        );
+        assert_eq!(
+            encode(CqlValue::BigInt(1), CqlType::BigInt),
+            [0, 0, 0, 0, 0, 0, 0, 1]
+        );
+        assert_eq!(encode(CqlValue::Boolean(true), CqlType::Boolean), [1]);
+        assert_eq!(
+            encode(
+                CqlValue::Decimal {
+                    unscaled: vec![1],
+                    scale:    2,
+                },
+                CqlType::Decimal
+            ),
+            [0, 0, 0, 2, 1]
+        );
+        assert_eq!(
+            encode(
+                CqlValue::Uuid(CqlUuid::from_components(
+                    0x4567_0123_89ab_cdef,
+                    0x0011_2233_4455_6677
+                )),
+                CqlType::Uuid
+            ),
+            [
+                0x89, 0xab, 0xcd, 0xef, 0x01, 0x23, 0x45, 0x67, 0x00, 0x11,
+                0x22, 0x33, 0x44, 0x55, 0x66, 0x77
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collections() {
+        let list = CqlType::List(Box::new(CqlType::SmallInt));
+        assert_eq!(
+            encode(
+                CqlValue::List(vec![CqlValue::SmallInt(1), CqlValue::Null]),
+                list
+            ),
+            [0, 0, 0, 2, 0, 0, 0, 2, 0, 1, 255, 255, 255, 255]
+        );
+
+        let address = CqlType::UserType {
+            name:   "address".into(),
+            fields: vec![
+                ("street".into(), CqlType::Text),
+                ("zip".into(), CqlType::TinyInt),
+            ],
+        };
+        assert_eq!(
+            encode(
+                CqlValue::UserType(vec![("zip".into(), CqlValue::TinyInt(7))]),
+                address
+            ),
+            [255, 255, 255, 255, 0, 0, 0, 1, 7]
+        );
+    }
+
+    #[test]
+    fn test_duration() {
+        let duration = CqlValue::Duration {
+            months: 1,
+            days:   -1,
+            nanos:  1_000,
+        };
+        assert_eq!(encode(duration, CqlType::Duration), [2, 1, 0x87, 0xd0]);
+
+        let mut output = Vec::new();
+        write_vint(i64::MIN, &mut output);
+        assert_eq!(output, [255; 9]);
+    }
+
+    #[test]
+    fn test_composite_key() {
+        let (text, int) = (CqlValue::Text("a".into()), CqlValue::Int(1));
+        assert_eq!(
+            encode_partition_key(&[
+                (&text, &CqlType::Text),
+                (&int, &CqlType::Int)
+            ]),
+            Some(vec![0, 1, b'a', 0, 0, 4, 0, 0, 0, 1, 0])
+        );
+        assert_eq!(
+            encode_partition_key(&[
+                (&text, &CqlType::Text),
+                (&CqlValue::Null, &CqlType::Int)
+            ]),
+            None
+        );
+        assert_eq!(encode_partition_key(&[]), None);
+    }
+}
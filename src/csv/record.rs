@@ -0,0 +1,224 @@
+use std::io::{
+    self,
+    BufRead,
+    Write,
+};
+
+use super::CsvFormat;
+
+/// A field of a CSV record.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Field {
+    /// The text of the field without the quotes.
+    pub(crate) text:   String,
+    /// Whether the field was quoted.
+    pub(crate) quoted: bool,
+}
+
+/// A CSV record.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Record {
+    /// The line the record starts at, counting from 1.
+    pub(crate) line:   u64,
+    /// The fields of the record.
+    pub(crate) fields: Vec<Field>,
+}
+
+/// Reads the records of CSV data.
+///
+/// The quoted fields may contain the delimiter, line breaks and doubled
+/// quotes. The empty lines are skipped.
+pub(crate) struct RecordReader<R> {
+    reader:    R,
+    delimiter: char,
+    quote:     char,
+    /// The number of the lines read.
+    line:      u64,
+}
+
+impl<R> RecordReader<R>
+where
+    R: BufRead,
+{
+    pub(crate) fn new(reader: R, format: &CsvFormat) -> Self {
+        Self {
+            reader,
+            delimiter: format.delimiter,
+            quote: format.quote,
+            line: 0,
+        }
+    }
+
+    /// Reads the next record.
+    ///
+    /// Returns [`None`] at the end of the data.
+    pub(crate) fn read(&mut self) -> io::Result<Option<Record>> {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut in_quotes = false;
+        let mut start = self.line + 1;
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                if in_quotes {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unterminated quoted field at line {}", start),
+                    ));
+                }
+                return Ok(None);
+            }
+            self.line += 1;
+
+            let mut chars = line.chars().peekable();
+            while let Some(c) = chars.next() {
+                if in_quotes {
+                    if c != self.quote || chars.next_if_eq(&c).is_some() {
+                        field.push(c);
+                    } else {
+                        in_quotes = false;
+                    }
+                } else if c == self.quote && field.is_empty() && !quoted {
+                    in_quotes = true;
+                    quoted = true;
+                } else if c == self.delimiter {
+                    fields.push(Field {
+                        text: std::mem::take(&mut field),
+                        quoted,
+                    });
+                    quoted = false;
+                } else if c == '\r' && chars.peek() == Some(&'\n') {
+                    continue;
+                } else if c != '\n' {
+                    field.push(c);
+                }
+            }
+            if in_quotes {
+                continue;
+            }
+
+            if fields.is_empty() && field.is_empty() && !quoted {
+                start = self.line + 1;
+                continue;
+            }
+            fields.push(Field {
+                text: field,
+                quoted,
+            });
+
+            return Ok(Some(Record {
+                line: start,
+                fields,
+            }));
+        }
+    }
+}
+
+/// Writes a CSV record, the [`None`] fields are null.
+pub(crate) fn write_record<W, I, T>(
+    writer: &mut W,
+    fields: I,
+    format: &CsvFormat,
+) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = Option<T>>,
+    T: AsRef<str>,
+{
+    let mut delimiter = [0; 4];
+    let delimiter = format.delimiter.encode_utf8(&mut delimiter);
+    let mut quote = [0; 4];
+    let quote = format.quote.encode_utf8(&mut quote);
+
+    for (index, field) in fields.into_iter().enumerate() {
+        if index > 0 {
+            writer.write_all(delimiter.as_bytes())?;
+        }
+        let Some(field) = field else {
+            writer.write_all(format.null.as_bytes())?;
+            continue;
+        };
+
+        let field = field.as_ref();
+        let needs_quotes = format.quote_all
+            || field == format.null
+            || field.contains([format.delimiter, format.quote, '\r', '\n']);
+        if needs_quotes {
+            let doubled = quote.repeat(2);
+            writer.write_all(quote.as_bytes())?;
+            writer.write_all(field.replace(&*quote, &doubled).as_bytes())?;
+            writer.write_all(quote.as_bytes())?;
+        } else {
+            writer.write_all(field.as_bytes())?;
+        }
+    }
+
+    writer.write_all(b"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(text: &str, quoted: bool) -> Field {
+        Field {
+            text: text.to_string(),
+            quoted,
+        }
+    }
+
+    #[test]
+    fn test_read_records() {
+        let data = "a,\"b,\"\"c\"\"\",\r\n\n\"multi\nline\",,\"\"\nlast";
+        let mut reader = RecordReader::new(data.as_bytes(), &CsvFormat::new());
+
+        let record = reader.read().unwrap().unwrap();
+        assert_eq!(record.line, 1);
+        assert_eq!(
+            record.fields,
+            vec![field("a", false), field("b,\"c\"", true), field("", false),]
+        );
+
+        let record = reader.read().unwrap().unwrap();
+        assert_eq!(record.line, 3);
+        assert_eq!(
+            record.fields,
+            vec![
+                field("multi\nline", true),
+                field("", false),
+                field("", true),
+            ]
+        );
+
+        let record = reader.read().unwrap().unwrap();
+        assert_eq!(record.line, 5);
+        assert_eq!(record.fields, vec![field("last", false)]);
+        assert_eq!(reader.read().unwrap(), None);
+
+        let mut reader =
+            RecordReader::new("\"open".as_bytes(), &CsvFormat::new());
+        assert!(reader.read().is_err());
+    }
+
+    #[test]
+    fn test_write_record() {
+        let mut output = Vec::new();
+        let fields = [Some("a"), None, Some(""), Some("b\t\"c\"")];
+        write_record(&mut output, fields, &CsvFormat::tsv()).unwrap();
+        assert_eq!(output, b"a\t\t\"\"\t\"b\t\"\"c\"\"\"\n");
+
+        let mut reader = RecordReader::new(&output[..], &CsvFormat::tsv());
+        assert_eq!(
+            reader.read().unwrap().unwrap().fields,
+            vec![
+                field("a", false),
+                field("", false),
+                field("", true),
+                field("b\t\"c\"", true),
+            ]
+        );
+    }
+}
@@ -0,0 +1,653 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::datetime::{
+    format_date,
+    format_duration,
+    format_time,
+    format_timestamp,
+    parse_date,
+    parse_duration,
+    parse_time,
+    parse_timestamp,
+};
+use super::number::{
+    format_decimal,
+    format_varint,
+    parse_decimal,
+    parse_varint,
+};
+use super::CsvFormat;
+use crate::cql::{
+    CqlInet,
+    CqlType,
+    CqlUuid,
+    CqlValue,
+};
+
+/// Formats the value of a CSV field.
+///
+/// Returns [`None`] if the value is null.
+pub(crate) fn format_field(
+    value: &CqlValue,
+    format: &CsvFormat,
+) -> Option<String> {
+    if value.is_null() {
+        return None;
+    }
+
+    let mut output = String::new();
+    format_value(value, format, false, &mut output);
+
+    Some(output)
+}
+
+/// Formats the value, the values nested in the collections, the tuples and
+/// the user-defined types are formatted as CQL literals.
+fn format_value(
+    value: &CqlValue,
+    format: &CsvFormat,
+    nested: bool,
+    output: &mut String,
+) {
+    let text = match value {
+        CqlValue::Null if nested => "null".to_string(),
+        CqlValue::Null => String::new(),
+        CqlValue::Ascii(value) | CqlValue::Text(value) => value.clone(),
+        CqlValue::Boolean(true) => format.true_value.clone(),
+        CqlValue::Boolean(false) => format.false_value.clone(),
+        CqlValue::TinyInt(value) => value.to_string(),
+        CqlValue::SmallInt(value) => value.to_string(),
+        CqlValue::Int(value) => value.to_string(),
+        CqlValue::BigInt(value) | CqlValue::Counter(value) => value.to_string(),
+        CqlValue::Float(value) => format_float(*value),
+        CqlValue::Double(value) => format_float(*value),
+        CqlValue::Decimal {
+            unscaled,
+            scale,
+        } => format_decimal(unscaled, *scale),
+        CqlValue::Varint(value) => format_varint(value),
+        CqlValue::Uuid(value) | CqlValue::TimeUuid(value) => value.to_string(),
+        CqlValue::Inet(value) => value.to_string(),
+        CqlValue::Blob(value) | CqlValue::Custom(value) => {
+            let hex: String =
+                value.iter().map(|byte| format!("{:02x}", byte)).collect();
+            format!("0x{}", hex)
+        }
+        CqlValue::Timestamp(value) => {
+            format_timestamp(*value, &format.datetime_format)
+        }
+        CqlValue::Date(value) => format_date(*value, &format.date_format),
+        CqlValue::Time(value) => format_time(*value),
+        CqlValue::Duration {
+            months,
+            days,
+            nanos,
+        } => format_duration(*months, *days, *nanos),
+        CqlValue::List(items) => {
+            return format_items(items, "[", "]", output, |v, o| {
+                format_value(v, format, true, o)
+            })
+        }
+        CqlValue::Set(items) => {
+            return format_items(items, "{", "}", output, |v, o| {
+                format_value(v, format, true, o)
+            })
+        }
+        CqlValue::Tuple(items) => {
+            return format_items(items, "(", ")", output, |v, o| {
+                format_value(v, format, true, o)
+            })
+        }
+        CqlValue::Map(entries) => {
+            return format_items(entries, "{", "}", output, |e, o| {
+                format_value(&e.0, format, true, o);
+                o.push_str(": ");
+                format_value(&e.1, format, true, o);
+            })
+        }
+        CqlValue::UserType(fields) => {
+            return format_items(fields, "{", "}", output, |f, o| {
+                o.push_str(&f.0);
+                o.push_str(": ");
+                format_value(&f.1, format, true, o);
+            })
+        }
+    };
+
+    let quoted = nested
+        && matches!(
+            value,
+            CqlValue::Ascii(_)
+                | CqlValue::Text(_)
+                | CqlValue::Inet(_)
+                | CqlValue::Timestamp(_)
+                | CqlValue::Date(_)
+                | CqlValue::Time(_)
+        );
+    if quoted {
+        output.push('\'');
+        output.push_str(&text.replace('\'', "''"));
+        output.push('\'');
+    } else {
+        output.push_str(&text);
+    }
+}
+
+/// Formats the items separated with commas between the brackets.
+fn format_items<T, F>(
+    items: &[T],
+    open: &str,
+    close: &str,
+    output: &mut String,
+    mut format_item: F,
+) where
+    F: FnMut(&T, &mut String),
+{
+    output.push_str(open);
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            output.push_str(", ");
+        }
+        format_item(item, output);
+    }
+    output.push_str(close);
+}
+
+/// Formats a floating point number as a CQL literal.
+fn format_float<T>(value: T) -> String
+where
+    T: Into<f64> + Copy + std::fmt::Debug,
+{
+    let double: f64 = value.into();
+    if double.is_infinite() {
+        return if double > 0.0 {
+            "Infinity"
+        } else {
+            "-Infinity"
+        }
+        .to_string();
+    }
+
+    format!("{:?}", value)
+}
+
+/// Parses the text of a CSV field as a value of the type.
+pub(crate) fn parse_field(
+    text: &str,
+    data_type: &CqlType,
+    format: &CsvFormat,
+) -> Result<CqlValue, String> {
+    match data_type {
+        CqlType::Ascii | CqlType::Text => parse_scalar(text, data_type, format),
+        CqlType::List(_)
+        | CqlType::Set(_)
+        | CqlType::Map(..)
+        | CqlType::Tuple(_)
+        | CqlType::UserType {
+            ..
+        } => {
+            let mut parser = LiteralParser {
+                text,
+                format,
+            };
+            let value = parser.value(data_type)?;
+            parser.skip_whitespace();
+            if !parser.text.is_empty() {
+                return Err(format!("unexpected '{}'", parser.text));
+            }
+
+            Ok(value)
+        }
+        _ => parse_scalar(text.trim(), data_type, format),
+    }
+}
+
+/// Parses a value that is not a collection, a tuple or a user-defined type.
+fn parse_scalar(
+    text: &str,
+    data_type: &CqlType,
+    format: &CsvFormat,
+) -> Result<CqlValue, String> {
+    let value = match data_type {
+        CqlType::Ascii if !text.is_ascii() => {
+            return Err(format!("'{}' is not an ASCII string", text));
+        }
+        CqlType::Ascii => CqlValue::Ascii(text.to_string()),
+        CqlType::Text => CqlValue::Text(text.to_string()),
+        CqlType::Boolean => CqlValue::Boolean(parse_bool(text, format)?),
+        CqlType::TinyInt => CqlValue::TinyInt(parse(text, data_type)?),
+        CqlType::SmallInt => CqlValue::SmallInt(parse(text, data_type)?),
+        CqlType::Int => CqlValue::Int(parse(text, data_type)?),
+        CqlType::BigInt => CqlValue::BigInt(parse(text, data_type)?),
+        CqlType::Counter => CqlValue::Counter(parse(text, data_type)?),
+        CqlType::Float => CqlValue::Float(parse(text, data_type)?),
+        CqlType::Double => CqlValue::Double(parse(text, data_type)?),
+        CqlType::Decimal => {
+            let (unscaled, scale) = parse_decimal(text)?;
+            CqlValue::Decimal {
+                unscaled,
+                scale,
+            }
+        }
+        CqlType::Varint => CqlValue::Varint(parse_varint(text)?),
+        CqlType::Uuid => CqlValue::Uuid(parse::<CqlUuid>(text, data_type)?),
+        CqlType::TimeUuid => {
+            CqlValue::TimeUuid(parse::<CqlUuid>(text, data_type)?)
+        }
+        CqlType::Inet => CqlValue::Inet(parse::<CqlInet>(text, data_type)?),
+        CqlType::Blob => CqlValue::Blob(parse_hex(text)?),
+        CqlType::Custom(_) => CqlValue::Custom(parse_hex(text)?),
+        CqlType::Timestamp => {
+            CqlValue::Timestamp(parse_timestamp(text, &format.datetime_format)?)
+        }
+        CqlType::Date => CqlValue::Date(parse_date(text, &format.date_format)?),
+        CqlType::Time => CqlValue::Time(parse_time(text)?),
+        CqlType::Duration => {
+            let (months, days, nanos) = parse_duration(text)?;
+            CqlValue::Duration {
+                months,
+                days,
+                nanos,
+            }
+        }
+        _ => return parse_field(text, data_type, format),
+    };
+
+    Ok(value)
+}
+
+/// Parses the value with its [`FromStr`] implementation.
+fn parse<T>(text: &str, data_type: &CqlType) -> Result<T, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    text.parse().map_err(|error| {
+        format!("'{}' is not a valid {}: {}", text, data_type, error)
+    })
+}
+
+fn parse_bool(text: &str, format: &CsvFormat) -> Result<bool, String> {
+    if text == format.true_value || text.eq_ignore_ascii_case("true") {
+        Ok(true)
+    } else if text == format.false_value || text.eq_ignore_ascii_case("false") {
+        Ok(false)
+    } else {
+        Err(format!("'{}' is not a valid boolean", text))
+    }
+}
+
+/// Parses the `0x` prefixed hex string of a blob.
+fn parse_hex(text: &str) -> Result<Vec<u8>, String> {
+    let invalid = || format!("'{}' is not a valid blob", text);
+
+    let hex = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .filter(|hex| hex.len() % 2 == 0 && hex.is_ascii())
+        .ok_or_else(invalid)?;
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&hex[index..index + 2], 16))
+        .collect::<Result<_, _>>()
+        .map_err(|_| invalid())
+}
+
+/// Parses the CQL literals of the collections, the tuples and the
+/// user-defined types.
+struct LiteralParser<'a> {
+    /// The rest of the text.
+    text:   &'a str,
+    format: &'a CsvFormat,
+}
+
+impl LiteralParser<'_> {
+    fn value(&mut self, data_type: &CqlType) -> Result<CqlValue, String> {
+        self.skip_whitespace();
+
+        let value = match data_type {
+            CqlType::List(item) => {
+                CqlValue::List(self.items('[', ']', |p| p.value(item))?)
+            }
+            CqlType::Set(item) => {
+                CqlValue::Set(self.items('{', '}', |p| p.value(item))?)
+            }
+            CqlType::Map(key, value) => {
+                CqlValue::Map(self.items('{', '}', |p| {
+                    let key = p.value(key)?;
+                    p.expect(':')?;
+                    Ok((key, p.value(value)?))
+                })?)
+            }
+            CqlType::Tuple(types) => {
+                let mut types = types.iter();
+                CqlValue::Tuple(self.items('(', ')', |p| {
+                    let data_type = types
+                        .next()
+                        .ok_or_else(|| "too many tuple fields".to_string())?;
+                    p.value(data_type)
+                })?)
+            }
+            CqlType::UserType {
+                fields, ..
+            } => CqlValue::UserType(self.items('{', '}', |p| {
+                p.skip_whitespace();
+                let name = p.token();
+                let data_type = fields
+                    .iter()
+                    .find(|(field, _)| *field == name)
+                    .map(|(_, data_type)| data_type)
+                    .ok_or_else(|| format!("unknown field '{}'", name))?;
+                p.expect(':')?;
+                Ok((name, p.value(data_type)?))
+            })?),
+            _ => {
+                let (text, quoted) = match self.text.strip_prefix('\'') {
+                    Some(_) => (self.quoted()?, true),
+                    None => (self.token(), false),
+                };
+                if !quoted && text.eq_ignore_ascii_case("null") {
+                    CqlValue::Null
+                } else {
+                    parse_scalar(&text, data_type, self.format)?
+                }
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Parses the items separated with commas between the brackets.
+    fn items<T, F>(
+        &mut self,
+        open: char,
+        close: char,
+        mut item: F,
+    ) -> Result<Vec<T>, String>
+    where
+        F: FnMut(&mut Self) -> Result<T, String>,
+    {
+        self.expect(open)?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.next_if(close) {
+            return Ok(items);
+        }
+
+        loop {
+            items.push(item(self)?);
+            self.skip_whitespace();
+            if self.next_if(close) {
+                return Ok(items);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    /// Reads a quoted string, the quotes in it are doubled.
+    fn quoted(&mut self) -> Result<String, String> {
+        let mut string = String::new();
+        let mut chars = self.text.char_indices().skip(1).peekable();
+        while let Some((index, c)) = chars.next() {
+            if c != '\'' || chars.next_if(|(_, c)| *c == '\'').is_some() {
+                string.push(c);
+            } else {
+                self.text = &self.text[index + 1..];
+                return Ok(string);
+            }
+        }
+
+        Err("unterminated quoted string".to_string())
+    }
+
+    /// Reads the text up to a delimiter of the literals.
+    fn token(&mut self) -> String {
+        let end = self
+            .text
+            .find(|c: char| c.is_whitespace() || ",:[]{}()".contains(c))
+            .unwrap_or(self.text.len());
+        let (token, rest) = self.text.split_at(end);
+        self.text = rest;
+
+        token.to_string()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.next_if(c) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}' at '{}'", c, self.text))
+        }
+    }
+
+    fn next_if(&mut self, c: char) -> bool {
+        match self.text.strip_prefix(c) {
+            Some(rest) => {
+                self.text = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.text = self.text.trim_start();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(value: CqlValue, data_type: CqlType, text: &str) {
+        let format = CsvFormat::new();
+        assert_eq!(format_field(&value, &format).as_deref(), Some(text));
+        assert_eq!(parse_field(text, &data_type, &format), Ok(value));
+    }
+
+    #[test]
+    fn test_scalars() {
+        round_trip(CqlValue::Text(" it's ".into()), CqlType::Text, " it's ");
+        round_trip(CqlValue::Boolean(true), CqlType::Boolean, "True");
+        round_trip(CqlValue::Int(-42), CqlType::Int, "-42");
+        round_trip(CqlValue::Double(0.1), CqlType::Double, "0.1");
+        round_trip(CqlValue::Float(1e20), CqlType::Float, "1e20");
+        round_trip(
+            CqlValue::Double(f64::NEG_INFINITY),
+            CqlType::Double,
+            "-Infinity",
+        );
+        round_trip(CqlValue::Blob(vec![0, 255]), CqlType::Blob, "0x00ff");
+        round_trip(
+            CqlValue::Timestamp(0),
+            CqlType::Timestamp,
+            "1970-01-01 00:00:00.000000+0000",
+        );
+
+        let format = CsvFormat::new();
+        assert_eq!(
+            parse_field(" false ", &CqlType::Boolean, &format),
+            Ok(CqlValue::Boolean(false))
+        );
+        assert!(parse_field("300", &CqlType::TinyInt, &format).is_err());
+        assert!(parse_field("0x0", &CqlType::Blob, &format).is_err());
+        assert!(parse_field("é", &CqlType::Ascii, &format).is_err());
+        assert_eq!(format_field(&CqlValue::Null, &format), None);
+    }
+
+    #[test]
+    fn test_numbers() {
+        round_trip(CqlValue::TinyInt(-128), CqlType::TinyInt, "-128");
+        round_trip(CqlValue::SmallInt(32767), CqlType::SmallInt, "32767");
+        round_trip(
+            CqlValue::BigInt(i64::MIN),
+            CqlType::BigInt,
+            &i64::MIN.to_string(),
+        );
+        round_trip(CqlValue::Counter(-7), CqlType::Counter, "-7");
+        round_trip(CqlValue::Float(-0.5), CqlType::Float, "-0.5");
+        round_trip(CqlValue::Float(f32::INFINITY), CqlType::Float, "Infinity");
+        round_trip(CqlValue::Double(1e-7), CqlType::Double, "1e-7");
+        round_trip(
+            CqlValue::Decimal {
+                unscaled: vec![0x04, 0xD2],
+                scale:    2,
+            },
+            CqlType::Decimal,
+            "12.34",
+        );
+        round_trip(CqlValue::Varint(vec![0xFF, 0x7F]), CqlType::Varint, "-129");
+
+        let format = CsvFormat::new();
+        assert!(parse_field("1.5", &CqlType::Int, &format).is_err());
+        assert!(parse_field("32768", &CqlType::SmallInt, &format).is_err());
+        assert!(parse_field("x", &CqlType::Varint, &format).is_err());
+    }
+
+    #[test]
+    fn test_bytes_and_strings() {
+        round_trip(CqlValue::Ascii("a,b".into()), CqlType::Ascii, "a,b");
+        round_trip(CqlValue::Blob(vec![]), CqlType::Blob, "0x");
+        round_trip(
+            CqlValue::Custom(vec![0xCA, 0xFE]),
+            CqlType::Custom("org.example.Type".into()),
+            "0xcafe",
+        );
+        round_trip(CqlValue::Boolean(false), CqlType::Boolean, "False");
+
+        let format = CsvFormat::new().with_bool_style("yes", "no");
+        assert_eq!(
+            format_field(&CqlValue::Boolean(true), &format).as_deref(),
+            Some("yes")
+        );
+        assert_eq!(
+            parse_field("no", &CqlType::Boolean, &format),
+            Ok(CqlValue::Boolean(false))
+        );
+        assert_eq!(
+            parse_field("0XCAFE", &CqlType::Blob, &format),
+            Ok(CqlValue::Blob(vec![0xCA, 0xFE]))
+        );
+    }
+
+    #[test]
+    fn test_dates_and_times() {
+        round_trip(
+            CqlValue::Timestamp(1_700_000_000_123),
+            CqlType::Timestamp,
+            "2023-11-14 22:13:20.123000+0000",
+        );
+        round_trip(
+            CqlValue::Date((1 << 31) + 19_675),
+            CqlType::Date,
+            "2023-11-14",
+        );
+        round_trip(
+            CqlValue::Time(3_600_000_000_001),
+            CqlType::Time,
+            "01:00:00.000000001",
+        );
+        round_trip(
+            CqlValue::Duration {
+                months: -14,
+                days:   -3,
+                nanos:  -1_000,
+            },
+            CqlType::Duration,
+            "-1y2mo3d1us",
+        );
+        round_trip(
+            CqlValue::Tuple(vec![CqlValue::Date(1 << 31), CqlValue::Time(0)]),
+            CqlType::Tuple(vec![CqlType::Date, CqlType::Time]),
+            "('1970-01-01', '00:00:00.000000000')",
+        );
+
+        let format = CsvFormat::new()
+            .with_datetime_format("%d/%m/%Y %H:%M")
+            .with_date_format("%d.%m.%Y");
+        assert_eq!(
+            format_field(&CqlValue::Timestamp(60_000), &format).as_deref(),
+            Some("01/01/1970 00:01")
+        );
+        assert_eq!(
+            parse_field("02.01.1970", &CqlType::Date, &format),
+            Ok(CqlValue::Date((1 << 31) + 1))
+        );
+    }
+
+    #[test]
+    #[ignore = "requires the DataStax C/C++ driver to parse and format the \
+                UUIDs and the addresses"]
+    fn test_uuids_and_addresses() {
+        let uuid = "550e8400-e29b-41d4-a716-446655440000";
+        round_trip(CqlValue::Uuid(uuid.parse().unwrap()), CqlType::Uuid, uuid);
+        let time_uuid = "d2177dd0-eaa2-11de-a572-001b779c76e3";
+        round_trip(
+            CqlValue::TimeUuid(time_uuid.parse().unwrap()),
+            CqlType::TimeUuid,
+            time_uuid,
+        );
+        round_trip(
+            CqlValue::Inet("::1".parse().unwrap()),
+            CqlType::Inet,
+            "::1",
+        );
+        round_trip(
+            CqlValue::Set(vec![CqlValue::Inet("127.0.0.1".parse().unwrap())]),
+            CqlType::Set(Box::new(CqlType::Inet)),
+            "{'127.0.0.1'}",
+        );
+    }
+
+    #[test]
+    fn test_literals() {
+        round_trip(
+            CqlValue::Map(vec![
+                (
+                    CqlValue::Text("a'b".into()),
+                    CqlValue::List(vec![CqlValue::Int(1), CqlValue::Int(2)]),
+                ),
+                (CqlValue::Text("c".into()), CqlValue::List(vec![])),
+            ]),
+            CqlType::Map(
+                Box::new(CqlType::Text),
+                Box::new(CqlType::List(Box::new(CqlType::Int))),
+            ),
+            "{'a''b': [1, 2], 'c': []}",
+        );
+        round_trip(
+            CqlValue::Tuple(vec![
+                CqlValue::Timestamp(0),
+                CqlValue::Null,
+                CqlValue::Set(vec![CqlValue::Boolean(false)]),
+            ]),
+            CqlType::Tuple(vec![
+                CqlType::Timestamp,
+                CqlType::Int,
+                CqlType::Set(Box::new(CqlType::Boolean)),
+            ]),
+            "('1970-01-01 00:00:00.000000+0000', null, {False})",
+        );
+        round_trip(
+            CqlValue::UserType(vec![("street".into(), CqlValue::Null)]),
+            CqlType::UserType {
+                name:   "address".into(),
+                fields: vec![("street".into(), CqlType::Text)],
+            },
+            "{street: null}",
+        );
+
+        let format = CsvFormat::new();
+        let list = CqlType::List(Box::new(CqlType::Int));
+        assert_eq!(
+            parse_field(" [ 1 ,2 ] ", &list, &format),
+            Ok(CqlValue::List(vec![CqlValue::Int(1), CqlValue::Int(2)]))
+        );
+        assert!(parse_field("[1, 2", &list, &format).is_err());
+        assert!(parse_field("[1] 2", &list, &format).is_err());
+        assert!(parse_field("['1']x", &list, &format).is_err());
+    }
+}
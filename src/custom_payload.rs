@@ -0,0 +1,82 @@
+use std::ffi::c_char;
+
+use crate::ffi::{
+    cass_custom_payload_free,
+    cass_custom_payload_new,
+    cass_custom_payload_remove_n,
+    cass_custom_payload_set_n,
+    struct_CassCustomPayload_,
+};
+
+/// A custom payload sent with a request.
+///
+/// The payload is a map of names to arbitrary bytes passed to the custom query
+/// handler of the cluster or to a proxy in front of it. It requires protocol
+/// version 4 or higher. The custom payload of the response is returned with
+/// [`QueryResult::custom_payload`] and [`DriverError::custom_payload`].
+///
+/// [`QueryResult::custom_payload`]: crate::QueryResult::custom_payload
+/// [`DriverError::custom_payload`]: crate::DriverError::custom_payload
+pub struct CustomPayload(*mut struct_CassCustomPayload_);
+
+impl CustomPayload {
+    /// Creates a new empty custom payload.
+    pub fn new() -> Self {
+        Self(unsafe { cass_custom_payload_new() })
+    }
+
+    /// Returns the raw pointer to the custom payload object.
+    pub(crate) fn inner(&self) -> *mut struct_CassCustomPayload_ {
+        self.0
+    }
+
+    /// Sets the item with the given name, replacing the existing one.
+    pub fn set<T>(&mut self, name: T, value: &[u8])
+    where
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+        let name_len = name.len();
+        let name_ptr = name.as_ptr() as *const c_char;
+
+        unsafe {
+            cass_custom_payload_set_n(
+                self.inner(),
+                name_ptr,
+                name_len,
+                value.as_ptr(),
+                value.len(),
+            )
+        }
+    }
+
+    /// Removes the item with the given name.
+    pub fn remove<T>(&mut self, name: T)
+    where
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+        let name_len = name.len();
+        let name_ptr = name.as_ptr() as *const c_char;
+
+        unsafe {
+            cass_custom_payload_remove_n(self.inner(), name_ptr, name_len)
+        }
+    }
+}
+
+impl Default for CustomPayload {
+    /// Creates a new empty custom payload.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for CustomPayload {
+    fn drop(&mut self) {
+        unsafe { cass_custom_payload_free(self.inner()) }
+    }
+}
+
+unsafe impl Send for CustomPayload {}
+unsafe impl Sync for CustomPayload {}
@@ -2,20 +2,29 @@ mod details;
 mod kind;
 mod write_type;
 
+use std::collections::HashMap;
+
 pub use details::*;
 pub use kind::*;
 use thiserror::Error;
 pub use write_type::*;
 
-#[derive(Debug, Error)]
+use crate::Coordinator;
+
+#[derive(Debug, Clone, Error)]
 #[error("{0}", .message)]
 pub struct DriverError {
     /// The category of the error.
-    pub kind:    DriverErrorKind,
+    pub kind:           DriverErrorKind,
     /// The error message.
-    pub message: String,
+    pub message:        String,
     /// The error details available for server errors only.
-    pub details: Option<DriverErrorDetails>,
+    pub details:        Option<DriverErrorDetails>,
+    /// The node that coordinated the failed request, [`None`] if the request
+    /// failed before a coordinator responded.
+    pub coordinator:    Option<Coordinator>,
+    /// The custom payload of the error response.
+    pub custom_payload: HashMap<String, Vec<u8>>,
 }
 
 impl DriverError {
@@ -32,6 +41,8 @@ impl DriverError {
             kind,
             message: message.into(),
             details,
+            coordinator: None,
+            custom_payload: HashMap::new(),
         }
     }
 
@@ -6,6 +6,7 @@ use std::fmt::{
     Formatter,
 };
 use std::slice;
+use std::sync::Arc;
 
 use crate::ffi::{
     cass_error_num_arg_types,
@@ -34,9 +35,10 @@ use crate::{
 
 /// Additional details about a driver error.
 ///
-/// It is available for server errors only.
-#[repr(transparent)]
-pub struct DriverErrorDetails(CassErrorResult);
+/// It is available for server errors only. The clones share the driver
+/// object.
+#[derive(Clone)]
+pub struct DriverErrorDetails(Arc<CassErrorResult>);
 
 impl DriverErrorDetails {
     /// Creates a `DriverErrorDetails` from the driver object.
@@ -48,7 +50,7 @@ impl DriverErrorDetails {
         if error.is_null() {
             None
         } else {
-            Some(Self(CassErrorResult::from_driver(error)))
+            Some(Self(Arc::new(CassErrorResult::from_driver(error))))
         }
     }
 
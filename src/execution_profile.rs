@@ -0,0 +1,489 @@
+use std::ffi::c_char;
+
+use crate::ffi::{
+    cass_execution_profile_free,
+    cass_execution_profile_new,
+    cass_execution_profile_set_blacklist_dc_filtering_n,
+    cass_execution_profile_set_blacklist_filtering_n,
+    cass_execution_profile_set_consistency,
+    cass_execution_profile_set_constant_speculative_execution_policy,
+    cass_execution_profile_set_latency_aware_routing,
+    cass_execution_profile_set_latency_aware_routing_settings,
+    cass_execution_profile_set_load_balance_dc_aware_n,
+    cass_execution_profile_set_load_balance_round_robin,
+    cass_execution_profile_set_no_speculative_execution_policy,
+    cass_execution_profile_set_request_timeout,
+    cass_execution_profile_set_retry_policy,
+    cass_execution_profile_set_serial_consistency,
+    cass_execution_profile_set_token_aware_routing,
+    cass_execution_profile_set_token_aware_routing_shuffle_replicas,
+    cass_execution_profile_set_whitelist_dc_filtering_n,
+    cass_execution_profile_set_whitelist_filtering_n,
+    struct_CassExecProfile_,
+};
+use crate::{
+    to_result,
+    Consistency,
+    DriverError,
+    DriverErrorKind,
+    RetryPolicy,
+};
+
+/// An execution profile is a named group of request settings that override
+/// the cluster-wide settings.
+///
+/// Profiles are registered on the cluster with
+/// [`Cluster::set_execution_profile`] and selected per request by name with
+/// [`Statement::set_execution_profile`] or [`Batch::set_execution_profile`].
+/// This allows, for example, to route the OLTP and analytics traffic of one
+/// session to different datacenters with different timeouts and consistency
+/// levels.
+///
+/// The settings that are not set on the profile fall back to the cluster
+/// settings. The load balancing settings of a profile are used together: if
+/// any of them is set, the profile has its own load balancing policy built
+/// from the profile settings only.
+///
+/// [`Cluster::set_execution_profile`]: crate::Cluster::set_execution_profile
+/// [`Statement::set_execution_profile`]: crate::Statement::set_execution_profile
+/// [`Batch::set_execution_profile`]: crate::Batch::set_execution_profile
+pub struct ExecutionProfile(*mut struct_CassExecProfile_);
+
+impl ExecutionProfile {
+    /// Creates a new execution profile.
+    pub fn new() -> Self {
+        let profile = unsafe { cass_execution_profile_new() };
+
+        Self(profile)
+    }
+
+    /// Returns the raw pointer to the execution profile object.
+    pub(crate) fn inner(&self) -> *mut struct_CassExecProfile_ {
+        self.0
+    }
+
+    /// Sets the timeout in milliseconds for waiting for a response from a node.
+    ///
+    /// The default value is 12000ms, a zero timeout disables it.
+    pub fn set_request_timeout(
+        &mut self,
+        timeout: i64,
+    ) -> Result<(), DriverError> {
+        let timeout = timeout.try_into().map_err(|_| {
+            DriverError::with_kind(DriverErrorKind::LibBadParams)
+        })?;
+
+        let code = unsafe {
+            cass_execution_profile_set_request_timeout(self.inner(), timeout)
+        };
+
+        to_result(code)
+    }
+
+    /// Sets the consistency level of the requests using the profile.
+    pub fn set_consistency(
+        &mut self,
+        consistency: Consistency,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_execution_profile_set_consistency(
+                self.inner(),
+                consistency.to_driver(),
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Sets the serial consistency level of the requests using the profile.
+    pub fn set_serial_consistency(
+        &mut self,
+        consistency: Consistency,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_execution_profile_set_serial_consistency(
+                self.inner(),
+                consistency.to_driver(),
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Configures the profile to use round-robin load balancing.
+    ///
+    /// The driver discovers all nodes in a cluster and cycles through them per
+    /// request. All are considered 'local'.
+    pub fn set_load_balance_round_robin(&mut self) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_execution_profile_set_load_balance_round_robin(self.inner())
+        };
+
+        to_result(code)
+    }
+
+    /// Configures the profile to use DC-aware load balancing.
+    ///
+    /// For each query, all live nodes in a primary 'local' datacenter are tried
+    /// first, followed by any node from other datacenters.
+    ///
+    /// See [`Cluster::set_load_balance_dc_aware`] for the details.
+    ///
+    /// [`Cluster::set_load_balance_dc_aware`]: crate::Cluster::set_load_balance_dc_aware
+    pub fn set_load_balance_dc_aware<T>(
+        &mut self,
+        local_dc: T,
+        used_hosts_per_remote_dc: usize,
+        allow_remote_dcs_for_local_cl: bool,
+    ) -> Result<(), DriverError>
+    where
+        T: AsRef<str>,
+    {
+        let local_dc = local_dc.as_ref();
+        let local_dc_len = local_dc.len();
+        let local_dc_ptr = local_dc.as_ptr() as *const c_char;
+
+        let used_hosts_per_remote_dc =
+            used_hosts_per_remote_dc.try_into().map_err(|_| {
+                DriverError::with_kind(DriverErrorKind::LibBadParams)
+            })?;
+
+        let code = unsafe {
+            cass_execution_profile_set_load_balance_dc_aware_n(
+                self.inner(),
+                local_dc_ptr,
+                local_dc_len,
+                used_hosts_per_remote_dc,
+                allow_remote_dcs_for_local_cl.into(),
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Configures the profile to use token-aware request routing.
+    ///
+    /// This routing policy composes the base routing policy, routing requests
+    /// first to replicas on nodes considered 'local' by the base load balancing
+    /// policy.
+    pub fn set_token_aware_routing(
+        &mut self,
+        enabled: bool,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_execution_profile_set_token_aware_routing(
+                self.inner(),
+                enabled.into(),
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Configures token-aware routing to randomly shuffle replicas.
+    ///
+    /// Token-aware routing must be enabled for the setting to have any effect.
+    pub fn set_token_aware_routing_shuffle_replicas(
+        &mut self,
+        enabled: bool,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_execution_profile_set_token_aware_routing_shuffle_replicas(
+                self.inner(),
+                enabled.into(),
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Configures the profile to use latency-aware routing.
+    ///
+    /// This routing policy is a top-level routing policy. It uses the base
+    /// routing policy to determine locality (dc-aware) and/or placement
+    /// (token-aware) before considering the latency.
+    pub fn set_latency_aware_routing(
+        &mut self,
+        enabled: bool,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_execution_profile_set_latency_aware_routing(
+                self.inner(),
+                enabled.into(),
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Configures the profile settings for latency-aware request routing.
+    ///
+    /// See [`Cluster::set_latency_aware_routing_settings`] for the meaning of
+    /// the settings and their default values.
+    ///
+    /// [`Cluster::set_latency_aware_routing_settings`]: crate::Cluster::set_latency_aware_routing_settings
+    pub fn set_latency_aware_routing_settings(
+        &mut self,
+        exclusion_threshold: f64,
+        scale: i64,
+        retry_period: i64,
+        update_rate: i64,
+        min_measured: usize,
+    ) -> Result<(), DriverError> {
+        let scale = scale.try_into().map_err(|_| {
+            DriverError::with_kind(DriverErrorKind::LibBadParams)
+        })?;
+
+        let retry_period = retry_period.try_into().map_err(|_| {
+            DriverError::with_kind(DriverErrorKind::LibBadParams)
+        })?;
+
+        let update_rate = update_rate.try_into().map_err(|_| {
+            DriverError::with_kind(DriverErrorKind::LibBadParams)
+        })?;
+
+        let min_measured = min_measured.try_into().map_err(|_| {
+            DriverError::with_kind(DriverErrorKind::LibBadParams)
+        })?;
+
+        let code = unsafe {
+            cass_execution_profile_set_latency_aware_routing_settings(
+                self.inner(),
+                exclusion_threshold,
+                scale,
+                retry_period,
+                update_rate,
+                min_measured,
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Sets/Appends whitelist hosts.
+    ///
+    /// The first call sets the whitelist hosts and any subsequent calls appends
+    /// additional hosts. Passing an empty string will clear and disable the
+    /// whitelist. White space is striped from the hosts.
+    ///
+    /// The requests using the profile are only sent to the whitelisted hosts.
+    pub fn set_whitelist_filtering<T>(
+        &mut self,
+        hosts: T,
+    ) -> Result<(), DriverError>
+    where
+        T: AsRef<str>,
+    {
+        let hosts = hosts.as_ref();
+        let len = hosts.len();
+        let ptr = hosts.as_ptr() as *const c_char;
+        let code = unsafe {
+            cass_execution_profile_set_whitelist_filtering_n(
+                self.inner(),
+                ptr,
+                len,
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Sets/Appends blacklist hosts.
+    ///
+    /// The first call sets the blacklist hosts and any subsequent calls appends
+    /// additional hosts. Passing an empty string will clear and disable the
+    /// blacklist. White space is striped from the hosts.
+    ///
+    /// The requests using the profile are never sent to the blacklisted hosts.
+    pub fn set_blacklist_filtering<T>(
+        &mut self,
+        hosts: T,
+    ) -> Result<(), DriverError>
+    where
+        T: AsRef<str>,
+    {
+        let hosts = hosts.as_ref();
+        let len = hosts.len();
+        let ptr = hosts.as_ptr() as *const c_char;
+        let code = unsafe {
+            cass_execution_profile_set_blacklist_filtering_n(
+                self.inner(),
+                ptr,
+                len,
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Same as
+    /// [`set_whitelist_filtering`](ExecutionProfile::set_whitelist_filtering)
+    /// but whitelists all hosts of a datacenter.
+    pub fn set_whitelist_dc_filtering<T>(
+        &mut self,
+        datacenters: T,
+    ) -> Result<(), DriverError>
+    where
+        T: AsRef<str>,
+    {
+        let dc = datacenters.as_ref();
+        let len = dc.len();
+        let ptr = dc.as_ptr() as *const c_char;
+        let code = unsafe {
+            cass_execution_profile_set_whitelist_dc_filtering_n(
+                self.inner(),
+                ptr,
+                len,
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Same as
+    /// [`set_blacklist_filtering`](ExecutionProfile::set_blacklist_filtering)
+    /// but blacklists all hosts of a datacenter.
+    pub fn set_blacklist_dc_filtering<T>(
+        &mut self,
+        datacenters: T,
+    ) -> Result<(), DriverError>
+    where
+        T: AsRef<str>,
+    {
+        let dc = datacenters.as_ref();
+        let len = dc.len();
+        let ptr = dc.as_ptr() as *const c_char;
+        let code = unsafe {
+            cass_execution_profile_set_blacklist_dc_filtering_n(
+                self.inner(),
+                ptr,
+                len,
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Sets the retry policy of the requests using the profile.
+    pub fn set_retry_policy(
+        &mut self,
+        policy: &RetryPolicy,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_execution_profile_set_retry_policy(
+                self.inner(),
+                policy.inner(),
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Enables constant speculative executions with the supplied settings.
+    ///
+    /// The `delay` between each speculative execution is measured in
+    /// milliseconds. A zero delay means it should immediately send
+    /// `max_speculative_executions` requests along with the original request.
+    pub fn set_constant_speculative_execution_policy(
+        &mut self,
+        delay: i64,
+        max_speculative_executions: usize,
+    ) -> Result<(), DriverError> {
+        let max_speculative_executions =
+            max_speculative_executions.try_into().map_err(|_| {
+                DriverError::with_kind(DriverErrorKind::LibBadParams)
+            })?;
+
+        let code = unsafe {
+            cass_execution_profile_set_constant_speculative_execution_policy(
+                self.inner(),
+                delay,
+                max_speculative_executions,
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Disables speculative executions for the requests using the profile.
+    pub fn set_no_speculative_execution_policy(
+        &mut self,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_execution_profile_set_no_speculative_execution_policy(
+                self.inner(),
+            )
+        };
+
+        to_result(code)
+    }
+}
+
+impl Default for ExecutionProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl Send for ExecutionProfile {}
+unsafe impl Sync for ExecutionProfile {}
+
+impl Drop for ExecutionProfile {
+    fn drop(&mut self) {
+        unsafe { cass_execution_profile_free(self.inner()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setters() {
+        let mut profile = ExecutionProfile::new();
+
+        profile.set_request_timeout(5000).unwrap();
+        profile.set_consistency(Consistency::LocalQuorum).unwrap();
+        profile
+            .set_serial_consistency(Consistency::LocalSerial)
+            .unwrap();
+        profile.set_load_balance_dc_aware("dc1", 2, false).unwrap();
+        profile.set_token_aware_routing(true).unwrap();
+        profile
+            .set_token_aware_routing_shuffle_replicas(true)
+            .unwrap();
+        profile.set_latency_aware_routing(true).unwrap();
+        profile
+            .set_latency_aware_routing_settings(2.0, 100, 10_000, 100, 50)
+            .unwrap();
+        profile
+            .set_whitelist_filtering("10.0.0.1, 10.0.0.2")
+            .unwrap();
+        profile.set_blacklist_dc_filtering("dc2").unwrap();
+        profile.set_retry_policy(&RetryPolicy::new()).unwrap();
+        profile
+            .set_constant_speculative_execution_policy(100, 2)
+            .unwrap();
+        profile.set_no_speculative_execution_policy().unwrap();
+    }
+
+    #[test]
+    fn test_setters_reject_out_of_range_values() {
+        let mut profile = ExecutionProfile::new();
+
+        let errors = [
+            profile.set_request_timeout(-1),
+            profile.set_load_balance_dc_aware("dc1", usize::MAX, false),
+            profile
+                .set_latency_aware_routing_settings(2.0, -1, 10_000, 100, 50),
+            profile.set_latency_aware_routing_settings(2.0, 100, -1, 100, 50),
+            profile
+                .set_latency_aware_routing_settings(2.0, 100, 10_000, -1, 50),
+            profile.set_constant_speculative_execution_policy(100, usize::MAX),
+        ];
+
+        for error in errors {
+            assert_eq!(error.unwrap_err().kind, DriverErrorKind::LibBadParams);
+        }
+    }
+}
@@ -1,14 +1,26 @@
 mod result;
 
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::ffi::c_void;
 use std::marker::PhantomData;
+use std::pin::Pin;
 use std::slice;
 use std::sync::Arc;
 use std::task::{
     Context,
     Poll,
+    Wake,
     Waker,
 };
+use std::thread::{
+    self,
+    Thread,
+};
+use std::time::{
+    Duration,
+    Instant,
+};
 
 use futures::Future;
 use parking_lot::{
@@ -19,6 +31,9 @@ pub(crate) use result::*;
 
 use crate::cql::CqlUuid;
 use crate::ffi::{
+    cass_future_coordinator,
+    cass_future_custom_payload_item,
+    cass_future_custom_payload_item_count,
     cass_future_error_code,
     cass_future_error_message,
     cass_future_free,
@@ -32,20 +47,21 @@ use crate::ffi::{
     struct_CassFuture_,
     struct_CassUuid_,
 };
+use crate::timer::{
+    sleep,
+    Delay,
+};
 use crate::{
     to_result,
     to_result_with_message,
+    Coordinator,
     DriverError,
     DriverErrorDetails,
     DriverErrorKind,
     Session,
+    WakeDispatcher,
 };
 
-// TODO: cass_future_get_prepared
-// TODO: cass_future_custom_payload_item_count
-// TODO: cass_future_custom_payload_item
-// TODO: cass_future_coordinator
-
 // The driver's future has a `cass_future_set_callback` function that allows
 // setting a callback to be called when the future is set. This is what we use
 // to implement the `Future` trait for the `CassFuture` type.
@@ -125,16 +141,27 @@ use crate::{
 ///
 /// The `R` type parameter represents the type of the successful completion of
 /// the future.
+///
+/// The driver has no way to cancel a request. Dropping the future, or its
+/// timeout expiring, only stops waiting for the request: the request keeps
+/// running in the background and its result is discarded. If the future was
+/// polled, the driver still calls the callback once the request completes, so
+/// the callback target lives until then, see [`future_callback`].
 #[must_use]
 pub struct DriverFuture<R> {
     /// The driver's future object.
-    inner:        *mut struct_CassFuture_,
+    inner:        Arc<FutureHandle>,
     /// The session that created the future.
     ///
     /// The future must not outlive the session.
     session:      Session,
     /// The future state which is also is the target of the future callback.
     state:        Arc<DriverFutureCallbackTarget>,
+    /// The time to wait for the future to be set, see
+    /// [`DriverFuture::with_timeout`].
+    timeout:      Option<Duration>,
+    /// The delay that expires the future, created on the first poll.
+    delay:        Option<Delay>,
     /// The type of the successful completion of the future.
     _result_type: PhantomData<R>,
 }
@@ -147,18 +174,34 @@ impl<R> DriverFuture<R> {
             "the driver's future object must not be null"
         );
 
-        let state = DriverFutureCallbackTarget::new_in_arc();
+        let state = DriverFutureCallbackTarget::new_in_arc(
+            session.wake_dispatcher().cloned(),
+        );
         Self {
-            inner,
+            inner: Arc::new(FutureHandle(inner)),
             session,
             state,
+            timeout: None,
+            delay: None,
             _result_type: PhantomData,
         }
     }
 
+    /// Sets the time to wait for the future to be set.
+    ///
+    /// The future fails with [`DriverErrorKind::LibRequestTimedOut`] once the
+    /// time elapses. Like dropping the future, the timeout does not cancel
+    /// the request, see [`DriverFuture`].
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self.delay = Some(sleep(timeout));
+
+        self
+    }
+
     /// Returns the raw pointer to the future object.
     pub(crate) fn inner(&self) -> *mut struct_CassFuture_ {
-        self.inner
+        self.inner.0
     }
 
     /// Checks whether the future has been completed.
@@ -175,20 +218,91 @@ impl<R> DriverFuture<R> {
         unsafe { cass_future_wait(self.inner()) }
     }
 
-    /// The same as [`CassFuture::wait`] but timeouts after the given number of
-    /// microseconds.
-    ///
-    /// Returns Ok(`false`) if returned due to timeout.
+    /// The same as [`DriverFuture::wait`] but gives up after the timeout.
     ///
-    /// Returns an error if the `timeout` overflows.
-    pub fn wait_with_timeout(&self, timeout: i64) -> Result<bool, DriverError> {
-        let timeout = timeout.try_into().map_err(|_| {
-            DriverError::with_kind(crate::DriverErrorKind::LibBadParams)
-        })?;
+    /// Returns `false` if the future is not set within the timeout.
+    pub fn wait_with_timeout(&self, timeout: Duration) -> bool {
+        let timeout = timeout.as_micros().try_into().unwrap_or(u64::MAX);
         let completed =
             unsafe { cass_future_wait_timed(self.inner(), timeout) };
 
-        Ok(completed != CASS_FALSE)
+        completed != CASS_FALSE
+    }
+
+    /// Blocks the current thread until the future is set and returns its
+    /// result.
+    ///
+    /// Fails with [`DriverErrorKind::LibRequestTimedOut`] if the future is not
+    /// set within the timeout set with [`DriverFuture::with_timeout`].
+    ///
+    /// The callback is installed as by the `poll` method, so that the
+    /// server-side warnings of the response are attached to the result, but
+    /// it unparks the thread directly instead of through the
+    /// [`WakeDispatcher`] of the session.
+    pub(crate) fn wait_for_result(mut self) -> Result<R, DriverError>
+    where
+        R: DriverFutureResult,
+    {
+        self.state = DriverFutureCallbackTarget::new_in_arc(None);
+        self.state.transition_to(DriverFutureState::NotSet {
+            waker:  Waker::from(Arc::new(ThreadWaker(thread::current()))),
+            target: self.state.clone(),
+        });
+        self.set_callback()?;
+
+        let deadline = self
+            .timeout
+            .and_then(|timeout| Instant::now().checked_add(timeout));
+        while !matches!(*self.state.state(), DriverFutureState::Set) {
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(timed_out(
+                            self.timeout.unwrap_or_default(),
+                        ));
+                    }
+                    thread::park_timeout(deadline - now);
+                }
+                None => thread::park(),
+            }
+        }
+
+        self.get_result()
+    }
+
+    /// Installs the future callback, the state must have been transitioned to
+    /// [`DriverFutureState::NotSet`].
+    ///
+    /// The callback must be set outside of the Rust lock to avoid deadlocks.
+    fn set_callback(&self) -> Result<(), DriverError> {
+        let target = self.state.as_ref() as *const _ as *mut c_void;
+        let code = unsafe {
+            cass_future_set_callback(
+                self.inner(),
+                Some(future_callback),
+                target,
+            )
+        };
+
+        to_result_with_message::<(), _>(code, "failed to set future callback")
+            .inspect_err(|_| {
+                // The callback will never be called, so the reference cycle
+                // that keeps the target alive for it must be broken.
+                self.state.transition_to(DriverFutureState::Created);
+            })
+    }
+
+    /// Gets the result of the future that has been set.
+    fn get_result(&self) -> Result<R, DriverError>
+    where
+        R: DriverFutureResult,
+    {
+        if let Some(error) = self.get_error() {
+            return Err(error);
+        }
+
+        R::get_driver_future_result(self.session.clone(), self)
     }
 
     /// Gets the error details from a future that failed as a result of a server
@@ -269,6 +383,72 @@ impl<R> DriverFuture<R> {
         to_result::<()>(code).map(|_| CqlUuid::from_driver(id))
     }
 
+    /// Gets the node that coordinated the request.
+    ///
+    /// Returns [`None`] if the future does not represent a request sent to a
+    /// Cassandra server or if the request failed before a coordinator
+    /// responded.
+    pub(crate) fn get_coordinator(&self) -> Option<Coordinator> {
+        let node = unsafe { cass_future_coordinator(self.inner()) };
+        if node.is_null() {
+            return None;
+        }
+
+        // The node is owned by the future, so the coordinator keeps the
+        // future alive.
+        Some(Coordinator::from_driver(self.inner.clone(), node))
+    }
+
+    /// Gets the custom payload of the response.
+    ///
+    /// Returns an empty map if the response has no custom payload.
+    pub(crate) fn get_custom_payload(&self) -> HashMap<String, Vec<u8>> {
+        let count =
+            unsafe { cass_future_custom_payload_item_count(self.inner()) };
+        let mut payload = HashMap::with_capacity(count);
+
+        for index in 0..count {
+            let mut name = std::ptr::null();
+            let mut name_len = 0;
+            let mut value = std::ptr::null();
+            let mut value_len = 0;
+            let code = unsafe {
+                cass_future_custom_payload_item(
+                    self.inner(),
+                    index,
+                    &mut name,
+                    &mut name_len,
+                    &mut value,
+                    &mut value_len,
+                )
+            };
+            if to_result::<()>(code).is_err() || name.is_null() {
+                continue;
+            }
+
+            let name =
+                unsafe { slice::from_raw_parts(name as *const u8, name_len) };
+            let value = if value.is_null() {
+                Vec::new()
+            } else {
+                unsafe { slice::from_raw_parts(value, value_len) }.to_vec()
+            };
+            payload.insert(String::from_utf8_lossy(name).into_owned(), value);
+        }
+
+        payload
+    }
+
+    /// Gets the warnings the server returned with the response, e.g. about
+    /// reading too many tombstones or about a large batch.
+    ///
+    /// The driver decodes the warnings of the response frames but does not
+    /// expose them, it only logs them, so they are captured from the driver
+    /// log by the future callback, see [`capture_server_warning`].
+    pub(crate) fn get_warnings(&self) -> Vec<String> {
+        self.state.warnings.lock().clone()
+    }
+
     /// Gets the error from the future if the future failed.
     ///
     /// If the future is not ready this method will block the current thread and
@@ -282,25 +462,37 @@ impl<R> DriverFuture<R> {
                     .get_error_message()
                     .unwrap_or_else(|| kind.to_string());
                 let details = self.get_error_details();
+                let mut error = DriverError::new(kind, message, details);
+                error.coordinator = self.get_coordinator();
+                error.custom_payload = self.get_custom_payload();
 
-                Some(DriverError::new(kind, message, details))
+                Some(error)
             }
             None => None,
         }
     }
 }
 
-impl<R> Drop for DriverFuture<R> {
+unsafe impl<R> Send for DriverFuture<R> {}
+unsafe impl<R> Sync for DriverFuture<R> {}
+
+/// An owned driver future object.
+///
+/// The handle is shared with the results and errors that borrow the data owned
+/// by the future, e.g. the [`Coordinator`].
+pub(crate) struct FutureHandle(*mut struct_CassFuture_);
+
+impl Drop for FutureHandle {
     /// Frees the future instance.
     ///
     /// A future can be freed anytime.
     fn drop(&mut self) {
-        unsafe { cass_future_free(self.inner()) };
+        unsafe { cass_future_free(self.0) };
     }
 }
 
-unsafe impl<R> Send for DriverFuture<R> {}
-unsafe impl<R> Sync for DriverFuture<R> {}
+unsafe impl Send for FutureHandle {}
+unsafe impl Sync for FutureHandle {}
 
 impl<R> Future for DriverFuture<R>
 where
@@ -309,19 +501,18 @@ where
     type Output = Result<R, DriverError>;
 
     /// Polls the future to check if it is ready.
-    fn poll(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut Context<'_>,
-    ) -> Poll<Self::Output> {
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
         // We install callback only once when the future is created. The
         // callback must be set ouside of the Rust lock to avoid deadlocks.
         {
-            let mut current_state = self.state.state();
+            let mut current_state = this.state.state();
             match *current_state {
                 DriverFutureState::Created => {
                     *current_state = DriverFutureState::NotSet {
                         waker:  cx.waker().clone(),
-                        target: self.state.clone(),
+                        target: this.state.clone(),
                     };
                     // The callback will be installed later in this function.
                 }
@@ -333,38 +524,58 @@ where
                     if !waker.will_wake(cx.waker()) {
                         *waker = cx.waker().clone();
                     }
+                    drop(current_state);
 
-                    return Poll::Pending;
+                    return this.poll_delay(cx);
                 }
                 DriverFutureState::Set => {
-                    // The future is ready, check if it has an error.
-                    if let Some(error) = self.get_error() {
-                        return Poll::Ready(Err(error));
-                    }
+                    drop(current_state);
 
-                    // There is no error, get the result.
-                    let result = R::get_driver_future_result(
-                        self.session.clone(),
-                        &self,
-                    )?;
-
-                    return Poll::Ready(Ok(result));
+                    // The future is ready, get its error or result.
+                    return Poll::Ready(this.get_result());
                 }
             };
         }
 
         // We are in the `Created` state and we need to set the callback.
-        let target = self.state.as_ref() as *const _ as *mut c_void;
-        let code = unsafe {
-            cass_future_set_callback(self.inner, Some(future_callback), target)
-        };
+        if let Err(error) = this.set_callback() {
+            return Poll::Ready(Err(error));
+        }
 
-        to_result_with_message(code, "failed to set future callback")?;
+        this.poll_delay(cx)
+    }
+}
 
-        Poll::Pending
+impl<R> DriverFuture<R> {
+    /// Polls the delay of the timeout, if any, while the future is not set.
+    fn poll_delay(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<R, DriverError>> {
+        match (self.timeout, &mut self.delay) {
+            (Some(timeout), Some(delay)) => {
+                Pin::new(delay).poll(cx).map(|result| match result {
+                    Ok(()) => Err(timed_out(timeout)),
+                    Err(error) => Err(error),
+                })
+            }
+            _ => Poll::Pending,
+        }
     }
 }
 
+// The future is not self-referential, the callback target is pinned by its
+// `Arc` instead.
+impl<R> Unpin for DriverFuture<R> {}
+
+/// Returns the error of a future that is not set within the timeout.
+fn timed_out(timeout: Duration) -> DriverError {
+    DriverError::with_message(
+        DriverErrorKind::LibRequestTimedOut,
+        format!("the request did not complete within {:?}", timeout),
+    )
+}
+
 /// The state of a future.
 #[derive(Debug)]
 enum DriverFutureState {
@@ -398,23 +609,39 @@ enum DriverFutureState {
 /// up the task that is waiting for the future to be set. The callback is called
 /// with the driver future and the user data. The user data is the target of the
 /// callback.
-#[derive(Debug)]
 struct DriverFutureCallbackTarget {
     /// The current state of the future automaton.
-    state: Mutex<DriverFutureState>,
+    state:      Mutex<DriverFutureState>,
+    /// Wakes the task instead of the driver I/O thread, if set.
+    dispatcher: Option<Arc<dyn WakeDispatcher>>,
+    /// The server-side warnings of the response, set by the callback.
+    warnings:   Mutex<Vec<String>>,
+}
+
+impl std::fmt::Debug for DriverFutureCallbackTarget {
+    /// Writes the target without its state, which refers back to the target.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DriverFutureCallbackTarget")
+            .field("dispatcher", &self.dispatcher.is_some())
+            .finish_non_exhaustive()
+    }
 }
 
 impl DriverFutureCallbackTarget {
     /// Creates a new callback target in the [`DriverFutureState::Created`].
-    pub fn new() -> Self {
+    pub fn new(dispatcher: Option<Arc<dyn WakeDispatcher>>) -> Self {
         Self {
             state: Mutex::new(DriverFutureState::Created),
+            dispatcher,
+            warnings: Mutex::new(Vec::new()),
         }
     }
 
     /// Same as [`DriverFutureCallbackTarget::new`] but wrapped in an [`Arc`].
-    pub fn new_in_arc() -> Arc<Self> {
-        Arc::new(Self::new())
+    pub fn new_in_arc(
+        dispatcher: Option<Arc<dyn WakeDispatcher>>,
+    ) -> Arc<Self> {
+        Arc::new(Self::new(dispatcher))
     }
 
     /// Transitions to the given state of the future automaton returning the
@@ -432,32 +659,256 @@ impl DriverFutureCallbackTarget {
     }
 }
 
+/// The prefix of the driver log messages that carry a server-side warning.
+const SERVER_WARNING_PREFIX: &str = "Server-side warning: ";
+
+thread_local! {
+    /// The server-side warnings logged on the current driver I/O thread since
+    /// the last future callback ran on it.
+    static SERVER_WARNINGS: RefCell<Vec<String>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// Captures the server-side warning carried by the driver log message, if
+/// any.
+///
+/// The driver logs the warnings of a response while decoding it on an I/O
+/// thread, and then sets the future of the response on the same thread. So
+/// the warnings logged on the thread are attached to the next future set on
+/// it by [`future_callback`].
+pub(crate) fn capture_server_warning(message: &str) {
+    if let Some(warning) = message.strip_prefix(SERVER_WARNING_PREFIX) {
+        SERVER_WARNINGS
+            .with(|warnings| warnings.borrow_mut().push(warning.to_string()));
+    }
+}
+
+/// Takes the server-side warnings captured on the current thread for the
+/// future being set.
+fn take_server_warnings() -> Vec<String> {
+    SERVER_WARNINGS.with(|warnings| warnings.take())
+}
+
+/// Wakes the thread that blocks in [`DriverFuture::wait_for_result`].
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    /// Unparks the thread.
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
 /// The callback function that is called when a future is set.
 ///
 /// The callback updates the Rust future state to [`DriverFutureState::Set`] and
-/// wakes up the task that is waiting for the future to be set.
+/// wakes up the task that is waiting for the future to be set, or hands the
+/// wake-up to the [`WakeDispatcher`] of the session. It also attaches the
+/// server-side warnings of the response captured from the driver log.
+///
+/// The callback target is kept alive by the reference cycle of the
+/// [`DriverFutureState::NotSet`] state even if the [`DriverFuture`] has been
+/// dropped, the cycle is broken once the callback is done with the target.
 unsafe extern "C" fn future_callback(
     _future: *mut struct_CassFuture_,
     data: *mut c_void,
 ) {
-    let target = data as *mut DriverFutureCallbackTarget;
-    let target = unsafe { &mut *target };
+    let target = data as *const DriverFutureCallbackTarget;
+    let target = unsafe { &*target };
+
+    let warnings = take_server_warnings();
+    if !warnings.is_empty() {
+        *target.warnings.lock() = warnings;
+    }
 
     let state = target.transition_to(DriverFutureState::Set);
 
-    match state {
+    match &state {
         DriverFutureState::NotSet {
-            ref waker, ..
-        } => {
-            waker.wake_by_ref();
-        }
-        _ => {
-            // This can never happen because this callback function is called
-            // only when it was set by the `poll` method of the `Future` trait.
-            unreachable!(
-                "the future callback was called before the callback was \
-                 installed from the Rust side"
+            waker, ..
+        } => match &target.dispatcher {
+            Some(dispatcher) => dispatcher.dispatch(waker.clone()),
+            None => waker.wake_by_ref(),
+        },
+        DriverFutureState::Created | DriverFutureState::Set => {
+            // The driver calls the callback once, only after it was installed
+            // by the `poll` method of the `Future` trait or by
+            // `DriverFuture::wait_for_result`. A panic would abort
+            // the process as it cannot unwind into the driver, so the
+            // unexpected call is reported and ignored.
+            tracing::error!(
+                target: "cassander::future",
+                ?state,
+                "the future callback was called before it was installed or \
+                 more than once"
             );
         }
     }
+
+    // The previous state may hold the last reference to the target.
+    drop(state);
+}
+
+/// A request waiting for its future to be set, with the waker installed as
+/// the `poll` method of a [`DriverFuture`] installs it.
+///
+/// Not part of the public API, it lets the benchmarks run the future
+/// callback without a Cassandra node and is only compiled with the `bench`
+/// feature.
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub struct PendingFutureCallback(Arc<DriverFutureCallbackTarget>);
+
+#[cfg(feature = "bench")]
+impl PendingFutureCallback {
+    /// Installs the waker of the task waiting for the request.
+    pub fn new(
+        waker: Waker,
+        dispatcher: Option<Arc<dyn WakeDispatcher>>,
+    ) -> Self {
+        let target = DriverFutureCallbackTarget::new_in_arc(dispatcher);
+        target.transition_to(DriverFutureState::NotSet {
+            waker,
+            target: target.clone(),
+        });
+
+        Self(target)
+    }
+
+    /// Runs the future callback as the driver I/O thread does when the
+    /// future is set.
+    pub fn complete(self) {
+        let data = Arc::as_ptr(&self.0) as *mut c_void;
+        unsafe { future_callback(std::ptr::null_mut(), data) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{
+        AtomicUsize,
+        Ordering,
+    };
+
+    use futures::task::{
+        waker,
+        ArcWake,
+    };
+
+    use super::*;
+
+    /// Counts the wake-ups of a task.
+    #[derive(Default)]
+    struct WakeCounter(AtomicUsize);
+
+    impl ArcWake for WakeCounter {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Calls the callback as the driver does when the future is set.
+    fn call_callback(target: *const DriverFutureCallbackTarget) {
+        unsafe { future_callback(std::ptr::null_mut(), target as *mut c_void) }
+    }
+
+    #[test]
+    fn test_callback_outlives_dropped_future() {
+        let counter = Arc::new(WakeCounter::default());
+        let target = DriverFutureCallbackTarget::new_in_arc(None);
+        target.transition_to(DriverFutureState::NotSet {
+            waker:  waker(counter.clone()),
+            target: target.clone(),
+        });
+
+        // The future is dropped before it is set, the state keeps the target
+        // alive for the callback.
+        let weak = Arc::downgrade(&target);
+        let ptr = Arc::as_ptr(&target);
+        drop(target);
+        assert!(weak.upgrade().is_some());
+
+        call_callback(ptr);
+
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn test_callback_in_unexpected_state() {
+        let target = DriverFutureCallbackTarget::new_in_arc(None);
+
+        call_callback(Arc::as_ptr(&target));
+        call_callback(Arc::as_ptr(&target));
+
+        assert!(matches!(*target.state(), DriverFutureState::Set));
+    }
+
+    #[test]
+    fn test_callback_hands_waker_to_dispatcher() {
+        let counter = Arc::new(WakeCounter::default());
+        let dispatched = Arc::new(Mutex::new(Vec::<Waker>::new()));
+        let dispatcher = {
+            let dispatched = dispatched.clone();
+            move |waker| dispatched.lock().push(waker)
+        };
+        let target =
+            DriverFutureCallbackTarget::new_in_arc(Some(Arc::new(dispatcher)));
+        target.transition_to(DriverFutureState::NotSet {
+            waker:  waker(counter.clone()),
+            target: target.clone(),
+        });
+
+        call_callback(Arc::as_ptr(&target));
+
+        assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+        dispatched.lock().drain(..).for_each(Waker::wake);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_callback_attaches_server_warnings() {
+        let target = DriverFutureCallbackTarget::new_in_arc(None);
+        let next = DriverFutureCallbackTarget::new_in_arc(None);
+
+        // The callbacks run on the driver I/O thread that logged the warnings.
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                capture_server_warning(
+                    "Server-side warning: Aggregation query used without \
+                     partition key",
+                );
+                capture_server_warning("Host 127.0.0.1 is down");
+                call_callback(Arc::as_ptr(&target));
+                call_callback(Arc::as_ptr(&next));
+            });
+        });
+
+        assert_eq!(
+            *target.warnings.lock(),
+            vec!["Aggregation query used without partition key"]
+        );
+        assert!(next.warnings.lock().is_empty());
+    }
+
+    #[test]
+    fn test_callback_unparks_blocked_thread() {
+        let target = DriverFutureCallbackTarget::new_in_arc(None);
+        target.transition_to(DriverFutureState::NotSet {
+            waker:  Waker::from(Arc::new(ThreadWaker(thread::current()))),
+            target: target.clone(),
+        });
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                capture_server_warning("Server-side warning: Batch too large");
+                call_callback(Arc::as_ptr(&target));
+            });
+            while !matches!(*target.state(), DriverFutureState::Set) {
+                thread::park();
+            }
+        });
+
+        assert_eq!(*target.warnings.lock(), vec!["Batch too large"]);
+    }
 }
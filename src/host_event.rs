@@ -0,0 +1,275 @@
+use std::ffi::c_void;
+use std::fmt::{
+    Display,
+    Formatter,
+};
+use std::pin::Pin;
+use std::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+use std::sync::Arc;
+use std::task::{
+    Context,
+    Poll,
+};
+
+use futures::channel::mpsc;
+use futures::{
+    Stream,
+    StreamExt,
+};
+use parking_lot::Mutex;
+
+use crate::cql::CqlInet;
+#[rustfmt::skip]
+use crate::ffi::{
+    enum_CassHostListenerEvent_,
+    enum_CassHostListenerEvent__CASS_HOST_LISTENER_EVENT_ADD    as ADD,
+    enum_CassHostListenerEvent__CASS_HOST_LISTENER_EVENT_DOWN   as DOWN,
+    enum_CassHostListenerEvent__CASS_HOST_LISTENER_EVENT_REMOVE as REMOVE,
+    enum_CassHostListenerEvent__CASS_HOST_LISTENER_EVENT_UP     as UP,
+    struct_CassInet_,
+};
+
+/// The size of the queue that stores host events not yet consumed from a
+/// [`HostEventStream`].
+///
+/// The events that do not fit into the queue are dropped and counted, see
+/// [`HostEventStream::dropped_events`].
+const HOST_EVENT_QUEUE_SIZE: usize = 256;
+
+/// A change of a cluster node state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostEvent {
+    /// The node has been added to the cluster.
+    Added(CqlInet),
+    /// The node has been removed from the cluster.
+    Removed(CqlInet),
+    /// The node is up.
+    Up(CqlInet),
+    /// The node is down.
+    Down(CqlInet),
+}
+
+impl HostEvent {
+    /// Creates a new host event from the driver's event and node address.
+    ///
+    /// Returns [`None`] if the event is unknown to this crate.
+    #[rustfmt::skip]
+    pub(crate) fn from_driver(
+        event: enum_CassHostListenerEvent_,
+        address: struct_CassInet_,
+    ) -> Option<Self> {
+        let address = CqlInet::from_driver(address);
+
+        match event {
+            ADD    => Some(HostEvent::Added(address)),
+            REMOVE => Some(HostEvent::Removed(address)),
+            UP     => Some(HostEvent::Up(address)),
+            DOWN   => Some(HostEvent::Down(address)),
+            _      => None,
+        }
+    }
+
+    /// Returns the address of the node.
+    pub fn address(&self) -> CqlInet {
+        match self {
+            HostEvent::Added(address)
+            | HostEvent::Removed(address)
+            | HostEvent::Up(address)
+            | HostEvent::Down(address) => *address,
+        }
+    }
+}
+
+impl Display for HostEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HostEvent::Added(address) => write!(f, "{} added", address),
+            HostEvent::Removed(address) => write!(f, "{} removed", address),
+            HostEvent::Up(address) => write!(f, "{} up", address),
+            HostEvent::Down(address) => write!(f, "{} down", address),
+        }
+    }
+}
+
+/// A stream of the cluster node state changes.
+///
+/// The stream ends when the cluster and all the sessions created from it are
+/// dropped.
+///
+/// The events are buffered in a bounded queue. If the stream is not polled
+/// fast enough and the queue is full, the new events are dropped, counted and
+/// a warning is logged. A consumer that tracks the node states should resync
+/// them, e.g. from the `system.peers` table, when
+/// [`HostEventStream::dropped_events`] grows.
+#[must_use = "streams do nothing unless polled"]
+pub struct HostEventStream {
+    receiver: mpsc::Receiver<HostEvent>,
+    /// The number of the events dropped because the queue was full.
+    dropped:  Arc<AtomicU64>,
+}
+
+impl HostEventStream {
+    /// Returns the number of the events dropped so far because the stream was
+    /// not polled fast enough.
+    pub fn dropped_events(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Stream for HostEventStream {
+    type Item = HostEvent;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+/// The target of the host listener callback.
+///
+/// The driver calls the callback from its I/O threads, so the listener must
+/// not block.
+pub(crate) struct HostListener {
+    sender:  Mutex<mpsc::Sender<HostEvent>>,
+    /// The number of the events dropped because the queue was full.
+    dropped: Arc<AtomicU64>,
+}
+
+impl HostListener {
+    /// Creates a new listener and the stream that receives its events.
+    pub(crate) fn new() -> (Self, HostEventStream) {
+        let (sender, receiver) = mpsc::channel(HOST_EVENT_QUEUE_SIZE);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let listener = Self {
+            sender:  Mutex::new(sender),
+            dropped: dropped.clone(),
+        };
+        let stream = HostEventStream {
+            receiver,
+            dropped,
+        };
+
+        (listener, stream)
+    }
+
+    /// Sends the event to the stream without blocking.
+    fn notify(&self, event: HostEvent) {
+        if let Err(error) = self.sender.lock().try_send(event) {
+            if error.is_full() {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!(
+                    target: "cassander::host_events",
+                    %event,
+                    "the host event queue is full, the event is dropped"
+                );
+            }
+            // Otherwise the stream has been dropped and nobody is interested
+            // in the events anymore.
+        }
+    }
+}
+
+/// The callback function that is called when a node state changes.
+pub(crate) unsafe extern "C" fn host_listener_callback(
+    event: enum_CassHostListenerEvent_,
+    address: struct_CassInet_,
+    data: *mut c_void,
+) {
+    if data.is_null() {
+        return;
+    }
+
+    let listener = unsafe { &*(data as *const HostListener) };
+    if let Some(event) = HostEvent::from_driver(event, address) {
+        listener.notify(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on_stream;
+
+    use super::*;
+
+    fn inet(octets: [u8; 4]) -> struct_CassInet_ {
+        let mut address = [0; 16];
+        address[..4].copy_from_slice(&octets);
+
+        struct_CassInet_ {
+            address,
+            address_length: 4,
+        }
+    }
+
+    #[test]
+    fn test_from_driver() {
+        let address = inet([10, 0, 0, 1]);
+        let expected = CqlInet::from_driver(address);
+
+        assert_eq!(
+            HostEvent::from_driver(ADD, address),
+            Some(HostEvent::Added(expected))
+        );
+        assert_eq!(
+            HostEvent::from_driver(REMOVE, address),
+            Some(HostEvent::Removed(expected))
+        );
+        assert_eq!(
+            HostEvent::from_driver(UP, address),
+            Some(HostEvent::Up(expected))
+        );
+        assert_eq!(
+            HostEvent::from_driver(DOWN, address),
+            Some(HostEvent::Down(expected))
+        );
+        assert_eq!(HostEvent::from_driver(42, address), None);
+    }
+
+    #[test]
+    fn test_listener_callback() {
+        let (listener, stream) = HostListener::new();
+        let data = &listener as *const HostListener as *mut c_void;
+
+        unsafe {
+            host_listener_callback(UP, inet([10, 0, 0, 1]), data);
+            host_listener_callback(42, inet([10, 0, 0, 2]), data);
+            host_listener_callback(DOWN, inet([10, 0, 0, 3]), data);
+            host_listener_callback(
+                UP,
+                inet([10, 0, 0, 4]),
+                std::ptr::null_mut(),
+            );
+        }
+        drop(listener);
+
+        let events: Vec<_> = block_on_stream(stream).collect();
+        assert_eq!(
+            events,
+            vec![
+                HostEvent::Up(CqlInet::from_driver(inet([10, 0, 0, 1]))),
+                HostEvent::Down(CqlInet::from_driver(inet([10, 0, 0, 3]))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_full_queue_drops_events() {
+        let (listener, stream) = HostListener::new();
+
+        // The channel holds one more message than its buffer size per sender.
+        for _ in 0..HOST_EVENT_QUEUE_SIZE + 11 {
+            listener.notify(HostEvent::Up(CqlInet::from_driver(inet([
+                10, 0, 0, 1,
+            ]))));
+        }
+
+        assert_eq!(stream.dropped_events(), 10);
+        drop(listener);
+        assert_eq!(block_on_stream(stream).count(), HOST_EVENT_QUEUE_SIZE + 1);
+    }
+}
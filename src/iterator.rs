@@ -0,0 +1,86 @@
+use std::marker::PhantomData;
+
+use crate::ffi::{
+    cass_iterator_free,
+    cass_iterator_next,
+    enum_cass_bool_t_cass_false as CASS_FALSE,
+    struct_CassIterator_,
+};
+
+/// An owned driver iterator.
+///
+/// The driver iterators are cursors: they must be advanced before the first
+/// item can be read and they are freed when dropped.
+#[repr(transparent)]
+pub(crate) struct DriverIterator(*mut struct_CassIterator_);
+
+impl DriverIterator {
+    /// Creates a new iterator from the driver object.
+    pub(crate) fn from_driver(iterator: *mut struct_CassIterator_) -> Self {
+        assert!(
+            !iterator.is_null(),
+            "the driver's iterator object must not be null"
+        );
+
+        Self(iterator)
+    }
+
+    /// Returns the raw pointer to the iterator object.
+    pub(crate) fn inner(&self) -> *mut struct_CassIterator_ {
+        self.0
+    }
+
+    /// Advances the iterator to the next item.
+    ///
+    /// Returns `false` if there are no more items.
+    pub(crate) fn advance(&mut self) -> bool {
+        let has_next = unsafe { cass_iterator_next(self.inner()) };
+
+        has_next != CASS_FALSE
+    }
+}
+
+impl Drop for DriverIterator {
+    fn drop(&mut self) {
+        unsafe { cass_iterator_free(self.inner()) }
+    }
+}
+
+/// An [`Iterator`] adapter over a driver iterator which items live as long as
+/// the `'a` lifetime.
+///
+/// The `get` function reads the current item of the driver iterator.
+pub(crate) struct DriverIter<'a, T> {
+    /// The underlying driver iterator.
+    iterator: DriverIterator,
+    /// Reads the current item of the driver iterator.
+    get:      fn(*const struct_CassIterator_) -> T,
+    /// The lifetime of the object the iterator was created from.
+    _owner:   PhantomData<&'a ()>,
+}
+
+impl<'a, T> DriverIter<'a, T> {
+    /// Creates a new iterator adapter.
+    pub(crate) fn new(
+        iterator: *mut struct_CassIterator_,
+        get: fn(*const struct_CassIterator_) -> T,
+    ) -> Self {
+        Self {
+            iterator: DriverIterator::from_driver(iterator),
+            get,
+            _owner: PhantomData,
+        }
+    }
+}
+
+impl<T> Iterator for DriverIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.iterator.advance() {
+            Some((self.get)(self.iterator.inner()))
+        } else {
+            None
+        }
+    }
+}
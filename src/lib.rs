@@ -4,33 +4,86 @@
 //! driver for Apache Cassandra. The crate is a work in progress and is not
 //! feature complete. The crate is not yet ready for production use.
 
+mod authenticator;
+mod batch;
+mod bulk_loader;
+#[cfg(feature = "cloud")]
+mod cloud_bundle;
 mod cluster;
+mod concurrent;
 mod config;
 mod consistency;
+mod coordinator;
+#[cfg(feature = "csv")]
+mod csv;
+mod custom_payload;
 mod error;
+mod execution_profile;
 pub(crate) mod ffi;
 pub(crate) mod future;
+mod host_event;
+pub(crate) mod iterator;
+mod metrics;
+mod pem;
+mod prepared;
+mod query_result;
+mod request;
+mod request_gate;
 mod retry_policy;
+mod row;
+mod schema;
+mod secret;
 mod session;
 mod ssl;
 mod ssl_verify_flags;
+mod statement;
+mod table_scan;
+mod timer;
 mod timestamp_gen;
+mod trace;
 mod version;
+mod wake_dispatcher;
 
 pub mod allocator;
+pub mod blocking;
 pub mod cql;
 pub mod logging;
 
+pub use authenticator::*;
+pub use batch::*;
+pub use bulk_loader::*;
+#[cfg(feature = "cloud")]
+pub use cloud_bundle::*;
 pub use cluster::*;
 pub use config::*;
 pub use consistency::*;
+pub use coordinator::*;
+#[cfg(feature = "csv")]
+pub use csv::*;
+pub use custom_payload::*;
 pub use error::*;
+pub use execution_profile::*;
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub use future::PendingFutureCallback;
+pub use host_event::*;
+pub use metrics::*;
+pub use prepared::*;
+pub use query_result::*;
+pub use request::*;
 pub use retry_policy::*;
+pub use row::*;
+pub use schema::*;
+pub use secret::*;
 pub use session::*;
 pub use ssl::*;
 pub use ssl_verify_flags::*;
+pub use statement::*;
+pub use table_scan::*;
 pub use timestamp_gen::*;
+pub use trace::*;
 pub use version::*;
+pub use wake_dispatcher::*;
 
 // TODO: How do we support different versions of the C++ driver? Some functions
 //       are only available in newer versions of the driver. We need to be able
@@ -149,6 +149,11 @@ pub fn set_log_level(level: Option<Level>) {
 }
 
 /// Sets the Cassandra driver logger to use the `tracing` crate.
+///
+/// The logger also captures the warnings the server returns with the
+/// responses, which the driver only logs, see [`QueryResult::warnings`].
+///
+/// [`QueryResult::warnings`]: crate::QueryResult::warnings
 pub fn use_tracing_logger() {
     unsafe {
         cass_log_set_callback(Some(logging_callback), std::ptr::null_mut())
@@ -194,6 +199,7 @@ unsafe extern "C" fn logging_callback(
 
 
     let message = LogMessage::from_driver(message);
+    crate::future::capture_server_warning(message.message());
 
     macro_rules! log {
         ($level:expr, $message:expr) => {
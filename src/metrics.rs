@@ -0,0 +1,175 @@
+mod counters;
+#[cfg(feature = "prometheus")]
+mod prometheus;
+
+pub use counters::*;
+#[cfg(feature = "prometheus")]
+pub use prometheus::*;
+
+use crate::ffi::{
+    struct_CassMetrics_,
+    struct_CassSpeculativeExecutionMetrics_,
+};
+
+/// A snapshot of the session performance metrics.
+///
+/// The latency histograms are refreshed according to
+/// [`Cluster::set_histogram_refresh_interval`], otherwise they accumulate the
+/// data over the life of the session.
+///
+/// [`Cluster::set_histogram_refresh_interval`]: crate::Cluster::set_histogram_refresh_interval
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SessionMetrics {
+    /// The request latencies and rates.
+    pub requests:               RequestMetrics,
+    /// The connection statistics.
+    pub connections:            ConnectionMetrics,
+    /// The timeout counters.
+    pub timeouts:               TimeoutMetrics,
+    /// The speculative execution latencies and counters.
+    pub speculative_executions: SpeculativeExecutionMetrics,
+}
+
+impl SessionMetrics {
+    /// Creates a new metrics snapshot from the driver's objects.
+    pub(crate) fn from_driver(
+        metrics: &struct_CassMetrics_,
+        speculative_executions: &struct_CassSpeculativeExecutionMetrics_,
+    ) -> Self {
+        let requests = &metrics.requests;
+        let stats = &metrics.stats;
+        let errors = &metrics.errors;
+
+        Self {
+            requests:               RequestMetrics {
+                latency:             LatencyHistogram {
+                    min:              requests.min,
+                    max:              requests.max,
+                    mean:             requests.mean,
+                    stddev:           requests.stddev,
+                    median:           requests.median,
+                    percentile_75th:  requests.percentile_75th,
+                    percentile_95th:  requests.percentile_95th,
+                    percentile_98th:  requests.percentile_98th,
+                    percentile_99th:  requests.percentile_99th,
+                    percentile_999th: requests.percentile_999th,
+                },
+                mean_rate:           requests.mean_rate,
+                one_minute_rate:     requests.one_minute_rate,
+                five_minute_rate:    requests.five_minute_rate,
+                fifteen_minute_rate: requests.fifteen_minute_rate,
+            },
+            connections:            ConnectionMetrics {
+                total_connections:                    stats.total_connections,
+                exceeded_pending_requests_water_mark: stats
+                    .exceeded_pending_requests_water_mark,
+                exceeded_write_bytes_water_mark:      stats
+                    .exceeded_write_bytes_water_mark,
+            },
+            timeouts:               TimeoutMetrics {
+                connection_timeouts:      errors.connection_timeouts,
+                pending_request_timeouts: errors.pending_request_timeouts,
+                request_timeouts:         errors.request_timeouts,
+            },
+            speculative_executions: SpeculativeExecutionMetrics {
+                latency:    LatencyHistogram {
+                    min:              speculative_executions.min,
+                    max:              speculative_executions.max,
+                    mean:             speculative_executions.mean,
+                    stddev:           speculative_executions.stddev,
+                    median:           speculative_executions.median,
+                    percentile_75th:  speculative_executions.percentile_75th,
+                    percentile_95th:  speculative_executions.percentile_95th,
+                    percentile_98th:  speculative_executions.percentile_98th,
+                    percentile_99th:  speculative_executions.percentile_99th,
+                    percentile_999th: speculative_executions.percentile_999th,
+                },
+                count:      speculative_executions.count,
+                percentage: speculative_executions.percentage,
+            },
+        }
+    }
+}
+
+/// A latency histogram snapshot.
+///
+/// All the values are in microseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LatencyHistogram {
+    /// The minimum latency.
+    pub min:              u64,
+    /// The maximum latency.
+    pub max:              u64,
+    /// The mean latency.
+    pub mean:             u64,
+    /// The standard deviation of the latency.
+    pub stddev:           u64,
+    /// The median latency.
+    pub median:           u64,
+    /// The 75th percentile latency.
+    pub percentile_75th:  u64,
+    /// The 95th percentile latency.
+    pub percentile_95th:  u64,
+    /// The 98th percentile latency.
+    pub percentile_98th:  u64,
+    /// The 99th percentile latency.
+    pub percentile_99th:  u64,
+    /// The 99.9th percentile latency.
+    pub percentile_999th: u64,
+}
+
+/// The request latencies and rates.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RequestMetrics {
+    /// The request latency histogram.
+    pub latency:             LatencyHistogram,
+    /// The mean rate of requests per second.
+    pub mean_rate:           f64,
+    /// The one-minute rate of requests per second.
+    pub one_minute_rate:     f64,
+    /// The five-minute rate of requests per second.
+    pub five_minute_rate:    f64,
+    /// The fifteen-minute rate of requests per second.
+    pub fifteen_minute_rate: f64,
+}
+
+/// The connection statistics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConnectionMetrics {
+    /// The total number of connections.
+    pub total_connections:                    u64,
+    /// The number of times the pending requests high water mark was exceeded.
+    pub exceeded_pending_requests_water_mark: u64,
+    /// The number of times the write bytes high water mark was exceeded.
+    pub exceeded_write_bytes_water_mark:      u64,
+}
+
+/// The timeout counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeoutMetrics {
+    /// The number of timed out connection attempts.
+    pub connection_timeouts:      u64,
+    /// The number of requests timed out waiting in the queue.
+    pub pending_request_timeouts: u64,
+    /// The number of requests timed out waiting for a response.
+    pub request_timeouts:         u64,
+}
+
+/// The speculative execution latencies and counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpeculativeExecutionMetrics {
+    /// The latency histogram of the speculative executions that completed the
+    /// request.
+    pub latency:    LatencyHistogram,
+    /// The number of aborted speculative retries.
+    pub count:      u64,
+    /// The fraction of requests that are aborted speculative retries, in
+    /// percent.
+    pub percentage: f64,
+}
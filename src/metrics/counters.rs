@@ -0,0 +1,169 @@
+use std::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    Consistency,
+    DriverErrorKind,
+};
+
+/// The consistency levels the requests are counted by.
+///
+/// The requests that do not set the consistency level are counted separately
+/// since the level is chosen by the execution profile or the cluster.
+const CONSISTENCIES: [Consistency; 11] = [
+    Consistency::One,
+    Consistency::Two,
+    Consistency::Three,
+    Consistency::Quorum,
+    Consistency::All,
+    Consistency::EachQuorum,
+    Consistency::LocalQuorum,
+    Consistency::LocalOne,
+    Consistency::LocalSerial,
+    Consistency::Serial,
+    Consistency::Any,
+];
+
+/// A snapshot of the request counters maintained by this crate on top of the
+/// driver metrics.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestCounters {
+    /// The number of requests by the consistency level set on the statement or
+    /// batch, [`None`] if the level was not set.
+    ///
+    /// Only the levels that have been used are listed.
+    pub requests:                 Vec<(Option<Consistency>, u64)>,
+    /// The number of failed requests by the error category.
+    pub errors:                   Vec<(DriverErrorKind, u64)>,
+    /// The number of executions of a statement or batch whose previous
+    /// execution failed.
+    ///
+    /// This is not the number of retries: the retries made by the driver's
+    /// retry policy are not visible to this crate and are not counted, and
+    /// the execution that follows a failure is counted whatever the reason
+    /// the application executes the request again.
+    pub executions_after_failure: u64,
+    /// The number of requests that fetched a page other than the first one.
+    pub paging_round_trips:       u64,
+}
+
+/// The request counters of a session.
+#[derive(Debug)]
+pub(crate) struct RequestCounterSet {
+    /// The number of requests by the index of the consistency level in
+    /// [`CONSISTENCIES`], the last counter is for the requests that do not set
+    /// the consistency level.
+    requests:                 [AtomicU64; CONSISTENCIES.len() + 1],
+    /// The number of failed requests by the error category.
+    ///
+    /// Failures are expected to be rare, so a list is enough.
+    errors:                   Mutex<Vec<(DriverErrorKind, u64)>>,
+    /// The number of executions that follow a failed execution.
+    executions_after_failure: AtomicU64,
+    /// The number of requests that fetched a subsequent page.
+    paging_round_trips:       AtomicU64,
+}
+
+impl RequestCounterSet {
+    /// Creates a new set of zeroed counters.
+    pub(crate) fn new() -> Self {
+        Self {
+            requests:                 std::array::from_fn(|_| {
+                AtomicU64::new(0)
+            }),
+            errors:                   Mutex::new(Vec::new()),
+            executions_after_failure: AtomicU64::new(0),
+            paging_round_trips:       AtomicU64::new(0),
+        }
+    }
+
+    /// Counts a request.
+    pub(crate) fn record_request(
+        &self,
+        consistency: Option<Consistency>,
+        after_failure: bool,
+        is_next_page: bool,
+    ) {
+        let index = consistency
+            .and_then(|consistency| {
+                CONSISTENCIES.iter().position(|c| *c == consistency)
+            })
+            .unwrap_or(CONSISTENCIES.len());
+        self.requests[index].fetch_add(1, Ordering::Relaxed);
+
+        if after_failure {
+            self.executions_after_failure
+                .fetch_add(1, Ordering::Relaxed);
+        }
+        if is_next_page {
+            self.paging_round_trips.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Counts a failed request.
+    pub(crate) fn record_error(&self, kind: DriverErrorKind) {
+        let mut errors = self.errors.lock();
+        match errors.iter_mut().find(|(k, _)| *k == kind) {
+            Some((_, count)) => *count += 1,
+            None => errors.push((kind, 1)),
+        }
+    }
+
+    /// Returns a snapshot of the counters.
+    pub(crate) fn snapshot(&self) -> RequestCounters {
+        let requests = CONSISTENCIES
+            .iter()
+            .map(|consistency| Some(*consistency))
+            .chain([None])
+            .zip(&self.requests)
+            .map(|(consistency, count)| {
+                (consistency, count.load(Ordering::Relaxed))
+            })
+            .filter(|(_, count)| *count > 0)
+            .collect();
+
+        RequestCounters {
+            requests,
+            errors: self.errors.lock().clone(),
+            executions_after_failure: self
+                .executions_after_failure
+                .load(Ordering::Relaxed),
+            paging_round_trips: self.paging_round_trips.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let counters = RequestCounterSet::new();
+        counters.record_request(Some(Consistency::LocalQuorum), false, false);
+        counters.record_request(Some(Consistency::LocalQuorum), true, true);
+        counters.record_request(None, false, false);
+        counters.record_error(DriverErrorKind::ServerReadTimeout);
+        counters.record_error(DriverErrorKind::ServerReadTimeout);
+
+        assert_eq!(
+            counters.snapshot(),
+            RequestCounters {
+                requests:                 vec![
+                    (Some(Consistency::LocalQuorum), 2),
+                    (None, 1)
+                ],
+                errors:                   vec![(
+                    DriverErrorKind::ServerReadTimeout,
+                    2
+                )],
+                executions_after_failure: 1,
+                paging_round_trips:       1,
+            }
+        );
+    }
+}
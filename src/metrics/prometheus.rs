@@ -0,0 +1,453 @@
+use std::fmt::{
+    Display,
+    Write,
+};
+
+use parking_lot::Mutex;
+
+use crate::session::WeakSession;
+use crate::{
+    LatencyHistogram,
+    RequestCounters,
+    Session,
+    SessionMetrics,
+};
+
+/// The content type of the rendered metrics.
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Renders the metrics of the registered sessions in the Prometheus text
+/// exposition format.
+///
+/// The exporter renders both the driver metrics ([`Session::metrics`]) and the
+/// counters maintained by this crate ([`Session::request_counters`]). The
+/// samples of every session are labelled with the session identifier
+/// ([`Session::id`]) and the application name set with
+/// [`Cluster::set_application_name`], so one exporter can serve the metrics of
+/// all the sessions of a process.
+///
+/// The driver only exposes a snapshot of the latency histograms, without the
+/// number and the sum of the observations, so every latency statistic is
+/// rendered as its own gauge, e.g.
+/// `cassander_request_latency_p99_microseconds`.
+///
+/// The exporter does not serve HTTP itself: put the output of
+/// [`PrometheusExporter::render`] into the response of the scrape endpoint
+/// with the [`PROMETHEUS_CONTENT_TYPE`] content type.
+///
+/// [`Cluster::set_application_name`]: crate::Cluster::set_application_name
+#[derive(Default)]
+pub struct PrometheusExporter {
+    /// The registered sessions by session identifier.
+    ///
+    /// The exporter does not keep the sessions alive, the dropped ones are
+    /// removed on the next render.
+    sessions: Mutex<Vec<(u64, WeakSession)>>,
+}
+
+impl PrometheusExporter {
+    /// Creates a new exporter without any sessions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the session, so its metrics are rendered.
+    ///
+    /// Registering a session again has no effect. The sessions without the
+    /// application name use an empty name.
+    pub fn register(&self, session: &Session) {
+        let mut sessions = self.sessions.lock();
+
+        if sessions.iter().all(|(id, _)| *id != session.id()) {
+            sessions.push((session.id(), session.downgrade()));
+        }
+    }
+
+    /// Renders the metrics of the registered sessions.
+    pub fn render(&self) -> String {
+        let snapshots: Vec<_> = {
+            let mut sessions = self.sessions.lock();
+            sessions.retain(|(_, session)| session.upgrade().is_some());
+            sessions
+                .iter()
+                .filter_map(|(id, session)| {
+                    let session = session.upgrade()?;
+                    let application =
+                        session.application_name().unwrap_or_default();
+
+                    Some(SessionSnapshot {
+                        id:          *id,
+                        application: escape_label_value(application),
+                        metrics:     session.metrics(),
+                        counters:    session.request_counters(),
+                    })
+                })
+                .collect()
+        };
+
+        render(&snapshots)
+    }
+}
+
+/// The metrics of a single session.
+struct SessionSnapshot {
+    /// The session identifier.
+    id:          u64,
+    /// The escaped application name.
+    application: String,
+    /// The driver metrics.
+    metrics:     SessionMetrics,
+    /// The counters maintained by this crate.
+    counters:    RequestCounters,
+}
+
+/// A sample of a metric family: the extra label and the value.
+type Sample = (Option<(&'static str, String)>, String);
+
+/// A statistic of a latency histogram: its name, its description and its
+/// value.
+type LatencyStatistic =
+    (&'static str, &'static str, fn(&LatencyHistogram) -> u64);
+
+/// The statistics of a latency histogram rendered as gauges.
+const LATENCY_STATISTICS: [LatencyStatistic; 10] = [
+    ("min", "The minimum", |latency| latency.min),
+    ("max", "The maximum", |latency| latency.max),
+    ("mean", "The mean", |latency| latency.mean),
+    ("stddev", "The standard deviation of the", |latency| {
+        latency.stddev
+    }),
+    ("median", "The median", |latency| latency.median),
+    ("p75", "The 75th percentile", |latency| {
+        latency.percentile_75th
+    }),
+    ("p95", "The 95th percentile", |latency| {
+        latency.percentile_95th
+    }),
+    ("p98", "The 98th percentile", |latency| {
+        latency.percentile_98th
+    }),
+    ("p99", "The 99th percentile", |latency| {
+        latency.percentile_99th
+    }),
+    ("p999", "The 99.9th percentile", |latency| {
+        latency.percentile_999th
+    }),
+];
+
+/// Renders the metric families of all the sessions.
+fn render(snapshots: &[SessionSnapshot]) -> String {
+    let mut out = String::new();
+
+    latency_families(
+        &mut out,
+        snapshots,
+        "cassander_request_latency",
+        "request latency",
+        |s| &s.metrics.requests.latency,
+    );
+    family(
+        &mut out,
+        snapshots,
+        "cassander_request_rate",
+        "gauge",
+        "The rate of requests per second.",
+        |s| {
+            let requests = &s.metrics.requests;
+            vec![
+                labelled("window", "mean", requests.mean_rate),
+                labelled("window", "1m", requests.one_minute_rate),
+                labelled("window", "5m", requests.five_minute_rate),
+                labelled("window", "15m", requests.fifteen_minute_rate),
+            ]
+        },
+    );
+    family(
+        &mut out,
+        snapshots,
+        "cassander_connections",
+        "gauge",
+        "The total number of connections.",
+        |s| vec![unlabelled(s.metrics.connections.total_connections)],
+    );
+    family(
+        &mut out,
+        snapshots,
+        "cassander_pending_requests_water_mark_exceeded_total",
+        "counter",
+        "The number of times the pending requests high water mark was \
+         exceeded.",
+        |s| {
+            vec![unlabelled(
+                s.metrics.connections.exceeded_pending_requests_water_mark,
+            )]
+        },
+    );
+    family(
+        &mut out,
+        snapshots,
+        "cassander_write_bytes_water_mark_exceeded_total",
+        "counter",
+        "The number of times the write bytes high water mark was exceeded.",
+        |s| {
+            vec![unlabelled(
+                s.metrics.connections.exceeded_write_bytes_water_mark,
+            )]
+        },
+    );
+    family(
+        &mut out,
+        snapshots,
+        "cassander_timeouts_total",
+        "counter",
+        "The number of timeouts by type.",
+        |s| {
+            let timeouts = &s.metrics.timeouts;
+            vec![
+                labelled("type", "connection", timeouts.connection_timeouts),
+                labelled(
+                    "type",
+                    "pending_request",
+                    timeouts.pending_request_timeouts,
+                ),
+                labelled("type", "request", timeouts.request_timeouts),
+            ]
+        },
+    );
+    latency_families(
+        &mut out,
+        snapshots,
+        "cassander_speculative_execution_latency",
+        "latency of the speculative executions",
+        |s| &s.metrics.speculative_executions.latency,
+    );
+    family(
+        &mut out,
+        snapshots,
+        "cassander_speculative_executions_aborted_total",
+        "counter",
+        "The number of aborted speculative executions.",
+        |s| vec![unlabelled(s.metrics.speculative_executions.count)],
+    );
+    family(
+        &mut out,
+        snapshots,
+        "cassander_speculative_executions_aborted_percent",
+        "gauge",
+        "The percentage of requests that are aborted speculative executions.",
+        |s| vec![unlabelled(s.metrics.speculative_executions.percentage)],
+    );
+    family(
+        &mut out,
+        snapshots,
+        "cassander_requests_total",
+        "counter",
+        "The number of requests by the consistency level set on the request.",
+        |s| {
+            s.counters
+                .requests
+                .iter()
+                .map(|(consistency, count)| {
+                    let consistency = consistency
+                        .map(|consistency| consistency.to_string())
+                        .unwrap_or_else(|| "DEFAULT".to_string());
+                    labelled("consistency", consistency, count)
+                })
+                .collect()
+        },
+    );
+    family(
+        &mut out,
+        snapshots,
+        "cassander_request_errors_total",
+        "counter",
+        "The number of failed requests by the error kind.",
+        |s| {
+            s.counters
+                .errors
+                .iter()
+                .map(|(kind, count)| {
+                    labelled("kind", format!("{:?}", kind), count)
+                })
+                .collect()
+        },
+    );
+    family(
+        &mut out,
+        snapshots,
+        "cassander_executions_after_failure_total",
+        "counter",
+        "The number of executions of a request whose previous execution \
+         failed.",
+        |s| vec![unlabelled(s.counters.executions_after_failure)],
+    );
+    family(
+        &mut out,
+        snapshots,
+        "cassander_paging_round_trips_total",
+        "counter",
+        "The number of requests that fetched a page other than the first one.",
+        |s| vec![unlabelled(s.counters.paging_round_trips)],
+    );
+
+    out
+}
+
+/// Writes a metric family with the samples of all the sessions.
+fn family<F>(
+    out: &mut String,
+    snapshots: &[SessionSnapshot],
+    name: &str,
+    kind: &str,
+    help: &str,
+    samples: F,
+) where
+    F: Fn(&SessionSnapshot) -> Vec<Sample>,
+{
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+
+    for snapshot in snapshots {
+        for (label, value) in samples(snapshot) {
+            let _ = write!(
+                out,
+                "{}{{session=\"{}\",application=\"{}\"",
+                name, snapshot.id, snapshot.application
+            );
+            if let Some((label, label_value)) = label {
+                let _ = write!(
+                    out,
+                    ",{}=\"{}\"",
+                    label,
+                    escape_label_value(&label_value)
+                );
+            }
+            let _ = writeln!(out, "}} {}", value);
+        }
+    }
+}
+
+/// Writes a gauge for every statistic of a latency histogram, named
+/// `<prefix>_<statistic>_microseconds`.
+fn latency_families<F>(
+    out: &mut String,
+    snapshots: &[SessionSnapshot],
+    prefix: &str,
+    subject: &str,
+    latency: F,
+) where
+    F: Fn(&SessionSnapshot) -> &LatencyHistogram,
+{
+    for (statistic, description, value) in LATENCY_STATISTICS {
+        family(
+            out,
+            snapshots,
+            &format!("{}_{}_microseconds", prefix, statistic),
+            "gauge",
+            &format!("{} {}.", description, subject),
+            |s| vec![unlabelled(value(latency(s)))],
+        );
+    }
+}
+
+/// Returns a sample without extra labels.
+fn unlabelled<V>(value: V) -> Sample
+where
+    V: Display,
+{
+    (None, value.to_string())
+}
+
+/// Returns a sample with an extra label.
+fn labelled<T, V>(label: &'static str, label_value: T, value: V) -> Sample
+where
+    T: Into<String>,
+    V: Display,
+{
+    (Some((label, label_value.into())), value.to_string())
+}
+
+/// Escapes a label value as required by the text exposition format.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Consistency,
+        DriverErrorKind,
+    };
+
+    #[test]
+    fn test_render() {
+        let mut metrics = SessionMetrics::default();
+        metrics.requests.latency.percentile_99th = 1200;
+        let snapshot = SessionSnapshot {
+            id: 7,
+            application: escape_label_value("orders \"api\""),
+            metrics,
+            counters: RequestCounters {
+                requests:                 vec![(
+                    Some(Consistency::LocalQuorum),
+                    3,
+                )],
+                errors:                   vec![(
+                    DriverErrorKind::ServerReadTimeout,
+                    1,
+                )],
+                executions_after_failure: 1,
+                paging_round_trips:       2,
+            },
+        };
+
+        let output = render(&[snapshot]);
+
+        assert!(output.contains(
+            "# TYPE cassander_requests_total \
+             counter\ncassander_requests_total{session=\"7\",application=\"\
+             orders \\\"api\\\"\",consistency=\"LOCAL_QUORUM\"} 3\n"
+        ));
+        assert!(output.contains(
+            "cassander_request_errors_total{session=\"7\",application=\"\
+             orders \\\"api\\\"\",kind=\"ServerReadTimeout\"} 1\n"
+        ));
+        assert!(output.contains(
+            "cassander_executions_after_failure_total{session=\"7\",\
+             application=\"orders \\\"api\\\"\"} 1\n"
+        ));
+        assert!(output.contains(
+            "cassander_paging_round_trips_total{session=\"7\",application=\"\
+             orders \\\"api\\\"\"} 2\n"
+        ));
+    }
+
+    #[test]
+    fn test_render_latency_gauges() {
+        let mut metrics = SessionMetrics::default();
+        metrics.requests.latency.percentile_99th = 1200;
+        let snapshot = SessionSnapshot {
+            id: 1,
+            application: String::new(),
+            metrics,
+            counters: RequestCounters::default(),
+        };
+
+        let output = render(&[snapshot]);
+
+        assert!(output.contains(
+            "# TYPE cassander_request_latency_p99_microseconds \
+             gauge\ncassander_request_latency_p99_microseconds{session=\"1\",\
+             application=\"\"} 1200\n"
+        ));
+        assert!(output.contains(
+            "# HELP cassander_speculative_execution_latency_p999_microseconds \
+             The 99.9th percentile latency of the speculative executions.\n"
+        ));
+        assert!(!output.contains("quantile"));
+    }
+}
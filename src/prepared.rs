@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use crate::ffi::{
+    cass_future_get_prepared,
+    cass_prepared_bind,
+    cass_prepared_free,
+    struct_CassPrepared_,
+};
+use crate::future::{
+    DriverFuture,
+    DriverFutureResult,
+};
+use crate::{
+    DriverError,
+    DriverErrorKind,
+    Session,
+    Statement,
+};
+
+/// A statement that has been prepared by the cluster.
+///
+/// A prepared statement is parsed once by the cluster and then executed many
+/// times with different values bound to it. The prepared statement is cheap
+/// to clone and can be shared between tasks.
+#[derive(Clone)]
+pub struct Prepared(Arc<PreparedWrapper>);
+
+impl Prepared {
+    /// Returns the raw pointer to the prepared statement object.
+    pub(crate) fn inner(&self) -> *const struct_CassPrepared_ {
+        self.0.inner
+    }
+
+    /// Returns the query string of the prepared statement.
+    pub fn query(&self) -> &str {
+        &self.0.query
+    }
+
+    /// Creates a bound statement from the prepared statement.
+    ///
+    /// The statement inherits the keyspace, the consistency level and the
+    /// other settings of the prepared statement.
+    pub fn bind(&self) -> Statement {
+        let statement = unsafe { cass_prepared_bind(self.inner()) };
+
+        Statement::from_prepared(statement, self)
+    }
+
+    /// Sets the query string of the prepared statement.
+    ///
+    /// The driver does not expose the query string of a prepared statement,
+    /// so it is kept on the Rust side.
+    pub(crate) fn with_query(mut self, query: String) -> Self {
+        if let Some(wrapper) = Arc::get_mut(&mut self.0) {
+            wrapper.query = query;
+        }
+
+        self
+    }
+}
+
+impl DriverFutureResult for Prepared {
+    /// Gets the successful result of a driver's future that returns a
+    /// [`Prepared`] statement.
+    fn get_driver_future_result(
+        _session: Session,
+        future: &DriverFuture<Self>,
+    ) -> Result<Self, DriverError> {
+        let prepared = unsafe { cass_future_get_prepared(future.inner()) };
+        if prepared.is_null() {
+            return Err(DriverError::with_message(
+                DriverErrorKind::LibUnexpectedResponse,
+                "the future has no prepared statement",
+            ));
+        }
+
+        Ok(Self(Arc::new(PreparedWrapper {
+            inner: prepared,
+            query: String::new(),
+        })))
+    }
+}
+
+struct PreparedWrapper {
+    /// The driver's prepared statement object.
+    inner: *const struct_CassPrepared_,
+    /// The query string of the prepared statement.
+    query: String,
+}
+
+impl Drop for PreparedWrapper {
+    fn drop(&mut self) {
+        unsafe { cass_prepared_free(self.inner) }
+    }
+}
+
+unsafe impl Send for PreparedWrapper {}
+unsafe impl Sync for PreparedWrapper {}
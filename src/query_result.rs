@@ -0,0 +1,507 @@
+use std::collections::HashMap;
+use std::ffi::c_char;
+use std::slice;
+
+use crate::cql::CqlUuid;
+use crate::ffi::{
+    cass_future_get_result,
+    cass_iterator_from_result,
+    cass_iterator_get_row,
+    cass_result_column_count,
+    cass_result_column_name,
+    cass_result_first_row,
+    cass_result_free,
+    cass_result_has_more_pages,
+    cass_result_paging_state_token,
+    cass_result_row_count,
+    enum_cass_bool_t_cass_false as CASS_FALSE,
+    struct_CassResult_,
+};
+use crate::future::{
+    DriverFuture,
+    DriverFutureResult,
+};
+use crate::iterator::DriverIter;
+use crate::{
+    to_result,
+    Coordinator,
+    DriverError,
+    DriverErrorKind,
+    Row,
+    Session,
+};
+
+/// The result of a query.
+///
+/// The result holds a single page of rows. Use
+/// [`QueryResult::paging_state_token`] with
+/// [`Statement::set_paging_state_token`] to fetch the next page.
+///
+/// [`Statement::set_paging_state_token`]: crate::Statement::set_paging_state_token
+pub struct QueryResult {
+    /// The driver's result object.
+    inner:          *const struct_CassResult_,
+    /// The tracing identifier of the request.
+    tracing_id:     Option<CqlUuid>,
+    /// The node that coordinated the request.
+    coordinator:    Option<Coordinator>,
+    /// The custom payload of the response.
+    custom_payload: HashMap<String, Vec<u8>>,
+    /// The warnings returned by the server.
+    warnings:       Vec<String>,
+}
+
+impl QueryResult {
+    /// Creates a new query result from the driver object.
+    pub(crate) fn from_driver(inner: *const struct_CassResult_) -> Self {
+        assert!(
+            !inner.is_null(),
+            "the driver's result object must not be null"
+        );
+
+        Self {
+            inner,
+            tracing_id: None,
+            coordinator: None,
+            custom_payload: HashMap::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Returns the raw pointer to the result object.
+    pub(crate) fn inner(&self) -> *const struct_CassResult_ {
+        self.inner
+    }
+
+    /// Returns the tracing identifier of the request.
+    ///
+    /// Returns [`None`] if tracing was not enabled with
+    /// [`Statement::set_tracing`] or [`Batch::set_tracing`]. Use the
+    /// identifier with [`Session::fetch_trace`] to read the trace.
+    ///
+    /// [`Statement::set_tracing`]: crate::Statement::set_tracing
+    /// [`Batch::set_tracing`]: crate::Batch::set_tracing
+    pub fn tracing_id(&self) -> Option<CqlUuid> {
+        self.tracing_id
+    }
+
+    /// Returns the node that coordinated the request.
+    pub fn coordinator(&self) -> Option<&Coordinator> {
+        self.coordinator.as_ref()
+    }
+
+    /// Returns the custom payload of the response.
+    ///
+    /// The payload is empty unless the cluster or a proxy in front of it sets
+    /// it, see [`CustomPayload`].
+    ///
+    /// [`CustomPayload`]: crate::CustomPayload
+    pub fn custom_payload(&self) -> &HashMap<String, Vec<u8>> {
+        &self.custom_payload
+    }
+
+    /// Returns the warnings the server returned with the response, e.g. about
+    /// reading too many tombstones, a large batch or an aggregation without a
+    /// partition key.
+    ///
+    /// The warnings are also emitted as `WARN` events of the
+    /// `cassander::request` target with the statement text.
+    ///
+    /// The driver does not expose the warnings, it logs them instead, so they
+    /// are captured from the driver log. The list is empty unless the driver
+    /// logs through [`logging::use_tracing_logger`] at the `WARN` level, the
+    /// default level of the driver, or a more verbose one.
+    ///
+    /// The log messages do not identify the request, the driver logs the
+    /// warnings of a response on an I/O thread right before it completes the
+    /// request on the same thread, so the warnings logged on the thread are
+    /// attached to the next request it completes. The warnings of a response
+    /// are therefore attached to another request, or lost, if the request
+    /// completes before it is first polled or waited for, or if the request
+    /// is one the driver sends on its own, e.g. to prepare a statement on the
+    /// other nodes or to refresh the schema.
+    ///
+    /// [`logging::use_tracing_logger`]: crate::logging::use_tracing_logger
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Returns the rows of the result page.
+    pub fn rows(&self) -> impl Iterator<Item = Row<'_>> {
+        let iterator = unsafe { cass_iterator_from_result(self.inner()) };
+
+        DriverIter::new(iterator, |iterator| {
+            Row::from_driver(unsafe { cass_iterator_get_row(iterator) })
+        })
+    }
+
+    /// Returns the first row of the result page.
+    ///
+    /// Returns [`None`] if the page is empty.
+    pub fn first_row(&self) -> Option<Row<'_>> {
+        let row = unsafe { cass_result_first_row(self.inner()) };
+        if row.is_null() {
+            return None;
+        }
+
+        Some(Row::from_driver(row))
+    }
+
+    /// Returns the number of rows in the result page.
+    pub fn row_count(&self) -> usize {
+        unsafe { cass_result_row_count(self.inner()) }
+    }
+
+    /// Returns the number of columns in the result.
+    pub fn column_count(&self) -> usize {
+        unsafe { cass_result_column_count(self.inner()) }
+    }
+
+    /// Returns the name of the column at the given index.
+    ///
+    /// Returns [`None`] if the index is out of range.
+    pub fn column_name(&self, index: usize) -> Option<String> {
+        let mut name = std::ptr::null();
+        let mut name_len = 0;
+        let code = unsafe {
+            cass_result_column_name(
+                self.inner(),
+                index,
+                &mut name,
+                &mut name_len,
+            )
+        };
+        to_result::<()>(code).ok()?;
+
+        let name =
+            unsafe { slice::from_raw_parts(name as *const u8, name_len) };
+
+        Some(String::from_utf8_lossy(name).into_owned())
+    }
+
+    /// Returns `true` if there are more pages of rows to fetch.
+    pub fn has_more_pages(&self) -> bool {
+        let has_more_pages =
+            unsafe { cass_result_has_more_pages(self.inner()) };
+
+        has_more_pages != CASS_FALSE
+    }
+
+    /// Returns the paging state token to fetch the next page of rows.
+    ///
+    /// Returns [`None`] if there are no more pages.
+    ///
+    /// <div class="warning">
+    /// The token is not encrypted and must not be exposed to untrusted
+    /// clients.
+    /// </div>
+    pub fn paging_state_token(&self) -> Option<Vec<u8>> {
+        if !self.has_more_pages() {
+            return None;
+        }
+
+        let mut token: *const c_char = std::ptr::null();
+        let mut token_len = 0;
+        let code = unsafe {
+            cass_result_paging_state_token(
+                self.inner(),
+                &mut token,
+                &mut token_len,
+            )
+        };
+        to_result::<()>(code).ok()?;
+
+        let token =
+            unsafe { slice::from_raw_parts(token as *const u8, token_len) };
+
+        Some(token.to_vec())
+    }
+}
+
+impl Drop for QueryResult {
+    fn drop(&mut self) {
+        unsafe { cass_result_free(self.inner()) }
+    }
+}
+
+unsafe impl Send for QueryResult {}
+unsafe impl Sync for QueryResult {}
+
+impl DriverFutureResult for QueryResult {
+    /// Gets the successful result of a driver's future that returns a
+    /// [`QueryResult`].
+    fn get_driver_future_result(
+        _session: Session,
+        future: &DriverFuture<Self>,
+    ) -> Result<Self, DriverError> {
+        let result = unsafe { cass_future_get_result(future.inner()) };
+        if result.is_null() {
+            return Err(DriverError::with_message(
+                DriverErrorKind::LibUnexpectedResponse,
+                "the future has no query result",
+            ));
+        }
+
+        let mut result = Self::from_driver(result);
+        result.tracing_id = future.get_tracing_id().ok();
+        result.coordinator = future.get_coordinator();
+        result.custom_payload = future.get_custom_payload();
+        result.warnings = future.get_warnings();
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{
+        Read,
+        Write,
+    };
+    use std::net::{
+        TcpListener,
+        TcpStream,
+    };
+    use std::thread;
+
+    use futures::executor::block_on;
+
+    use crate::{
+        logging,
+        Cluster,
+        ProtocolVersion,
+        Statement,
+    };
+
+    const OPCODE_ERROR: u8 = 0x00;
+    const OPCODE_STARTUP: u8 = 0x01;
+    const OPCODE_READY: u8 = 0x02;
+    const OPCODE_OPTIONS: u8 = 0x05;
+    const OPCODE_SUPPORTED: u8 = 0x06;
+    const OPCODE_QUERY: u8 = 0x07;
+    const OPCODE_RESULT: u8 = 0x08;
+    const OPCODE_REGISTER: u8 = 0x0b;
+
+    const FLAG_WARNING: u8 = 0x08;
+
+    const TYPE_BIGINT: &[u8] = &[0x00, 0x02];
+    const TYPE_UUID: &[u8] = &[0x00, 0x0c];
+    const TYPE_VARCHAR: &[u8] = &[0x00, 0x0d];
+    const TYPE_INET: &[u8] = &[0x00, 0x10];
+    const TYPE_SET_OF_VARCHAR: &[u8] = &[0x00, 0x22, 0x00, 0x0d];
+
+    const WARNING: &str = "Aggregation query used without partition key";
+
+    /// Starts a stand-in server speaking just enough of the native protocol
+    /// v4 for the driver to connect and run queries, and returns its port.
+    ///
+    /// The server describes a single node cluster and returns a count with
+    /// [`WARNING`] for any query but the ones on the system tables.
+    fn start_stand_in_server() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                thread::spawn(move || serve(stream));
+            }
+        });
+
+        port
+    }
+
+    /// Answers the request frames of a connection.
+    fn serve(mut stream: TcpStream) {
+        let mut header = [0; 9];
+        while stream.read_exact(&mut header).is_ok() {
+            let length = u32::from_be_bytes([
+                header[5], header[6], header[7], header[8],
+            ]);
+            let mut body = vec![0; length as usize];
+            if stream.read_exact(&mut body).is_err() {
+                return;
+            }
+
+            let (flags, opcode, body) = if header[0] == 0x04 {
+                respond(header[4], &body)
+            } else {
+                error(0x000a, "only the protocol v4 is supported")
+            };
+            let mut frame = vec![0x84, flags, header[2], header[3], opcode];
+            frame.extend((body.len() as u32).to_be_bytes());
+            frame.extend(body);
+            if stream.write_all(&frame).is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Returns the flags, the opcode and the body of the response to a
+    /// request.
+    fn respond(opcode: u8, body: &[u8]) -> (u8, u8, Vec<u8>) {
+        match opcode {
+            OPCODE_OPTIONS => {
+                let mut supported = 2u16.to_be_bytes().to_vec();
+                supported.extend(string("CQL_VERSION"));
+                supported.extend(1u16.to_be_bytes());
+                supported.extend(string("3.4.4"));
+                supported.extend(string("COMPRESSION"));
+                supported.extend(0u16.to_be_bytes());
+                (0, OPCODE_SUPPORTED, supported)
+            }
+            OPCODE_STARTUP | OPCODE_REGISTER => (0, OPCODE_READY, Vec::new()),
+            OPCODE_QUERY => {
+                let length =
+                    u32::from_be_bytes([body[0], body[1], body[2], body[3]]);
+                let query =
+                    String::from_utf8_lossy(&body[4..4 + length as usize]);
+                if query.contains("system.local") {
+                    (0, OPCODE_RESULT, local_rows())
+                } else if query.contains("system.peers_v2") {
+                    error(0x2200, "unconfigured table peers_v2")
+                } else if query.contains("system.peers") {
+                    (0, OPCODE_RESULT, peers_rows())
+                } else {
+                    let mut body = 1u16.to_be_bytes().to_vec();
+                    body.extend(string(WARNING));
+                    body.extend(rows(
+                        "cassander_test",
+                        "events",
+                        &[("count", TYPE_BIGINT)],
+                        &[vec![0u64.to_be_bytes().to_vec()]],
+                    ));
+                    (FLAG_WARNING, OPCODE_RESULT, body)
+                }
+            }
+            _ => error(0x000a, "unexpected request"),
+        }
+    }
+
+    /// Returns the row of the node in `system.local`.
+    fn local_rows() -> Vec<u8> {
+        let address = vec![127, 0, 0, 1];
+        let mut tokens = 1u32.to_be_bytes().to_vec();
+        tokens.extend(bytes(b"0"));
+
+        rows(
+            "system",
+            "local",
+            &[
+                ("key", TYPE_VARCHAR),
+                ("data_center", TYPE_VARCHAR),
+                ("rack", TYPE_VARCHAR),
+                ("release_version", TYPE_VARCHAR),
+                ("partitioner", TYPE_VARCHAR),
+                ("cluster_name", TYPE_VARCHAR),
+                ("rpc_address", TYPE_INET),
+                ("broadcast_address", TYPE_INET),
+                ("listen_address", TYPE_INET),
+                ("host_id", TYPE_UUID),
+                ("schema_version", TYPE_UUID),
+                ("tokens", TYPE_SET_OF_VARCHAR),
+            ],
+            &[vec![
+                b"local".to_vec(),
+                b"dc1".to_vec(),
+                b"rack1".to_vec(),
+                b"3.11.10".to_vec(),
+                b"org.apache.cassandra.dht.Murmur3Partitioner".to_vec(),
+                b"stand-in".to_vec(),
+                address.clone(),
+                address.clone(),
+                address,
+                vec![1; 16],
+                vec![2; 16],
+                tokens,
+            ]],
+        )
+    }
+
+    /// Returns the rows of `system.peers`, the cluster has no other node.
+    fn peers_rows() -> Vec<u8> {
+        rows(
+            "system",
+            "peers",
+            &[
+                ("peer", TYPE_INET),
+                ("data_center", TYPE_VARCHAR),
+                ("rack", TYPE_VARCHAR),
+                ("release_version", TYPE_VARCHAR),
+                ("rpc_address", TYPE_INET),
+                ("host_id", TYPE_UUID),
+                ("schema_version", TYPE_UUID),
+                ("tokens", TYPE_SET_OF_VARCHAR),
+            ],
+            &[],
+        )
+    }
+
+    /// Encodes a `Rows` result of a single table.
+    fn rows(
+        keyspace: &str,
+        table: &str,
+        columns: &[(&str, &[u8])],
+        rows: &[Vec<Vec<u8>>],
+    ) -> Vec<u8> {
+        let mut body = 0x0002u32.to_be_bytes().to_vec();
+        // The global table spec flag.
+        body.extend(0x0001u32.to_be_bytes());
+        body.extend((columns.len() as u32).to_be_bytes());
+        body.extend(string(keyspace));
+        body.extend(string(table));
+        for (name, data_type) in columns {
+            body.extend(string(name));
+            body.extend(*data_type);
+        }
+        body.extend((rows.len() as u32).to_be_bytes());
+        for value in rows.iter().flatten() {
+            body.extend(bytes(value));
+        }
+
+        body
+    }
+
+    /// Encodes an `ERROR` response.
+    fn error(code: u32, message: &str) -> (u8, u8, Vec<u8>) {
+        let mut body = code.to_be_bytes().to_vec();
+        body.extend(string(message));
+
+        (0, OPCODE_ERROR, body)
+    }
+
+    /// Encodes a `[string]`.
+    fn string(value: &str) -> Vec<u8> {
+        let mut encoded = (value.len() as u16).to_be_bytes().to_vec();
+        encoded.extend(value.as_bytes());
+
+        encoded
+    }
+
+    /// Encodes a `[bytes]`.
+    fn bytes(value: &[u8]) -> Vec<u8> {
+        let mut encoded = (value.len() as u32).to_be_bytes().to_vec();
+        encoded.extend(value);
+
+        encoded
+    }
+
+    #[test]
+    #[ignore = "requires the DataStax C/C++ driver to connect to the stand-in \
+                server"]
+    fn test_warnings_from_stand_in_server() {
+        logging::use_tracing_logger();
+        let mut cluster = Cluster::new();
+        cluster.set_contact_points("127.0.0.1").unwrap();
+        cluster.set_port(start_stand_in_server()).unwrap();
+        cluster.set_protocol_version(ProtocolVersion::V4).unwrap();
+        cluster.set_use_schema(false).unwrap();
+        cluster.set_token_aware_routing(false).unwrap();
+        cluster.set_load_balance_round_robin().unwrap();
+        let session = block_on(cluster.connect()).unwrap();
+
+        let statement =
+            Statement::new("SELECT count(*) FROM cassander_test.events", 0);
+        let result = block_on(session.execute(&statement)).unwrap();
+
+        assert_eq!(result.warnings(), [WARNING]);
+    }
+}
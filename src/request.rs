@@ -0,0 +1,574 @@
+use std::borrow::Cow;
+use std::sync::atomic::AtomicBool;
+
+use tracing::field::Empty;
+use tracing::{
+    Level,
+    Span,
+};
+
+use crate::{
+    Consistency,
+    Coordinator,
+    DriverError,
+    Prepared,
+    QueryResult,
+};
+
+/// How the statement text is recorded in the request spans.
+///
+/// The statement text may contain sensitive data in its literals, e.g.
+/// `INSERT INTO users (name, password) VALUES ('alice', 's3cr3t')`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpanStatementMode {
+    /// The statement text is recorded as is.
+    Full,
+    /// The string, number, UUID and blob literals are replaced with `?`.
+    ///
+    /// The bind markers of prepared statements are not affected.
+    #[default]
+    Redacted,
+    /// The statement text is not recorded.
+    Omitted,
+}
+
+impl SpanStatementMode {
+    /// Returns the statement text to record in a span.
+    fn apply<'a>(&self, statement: &'a str) -> Option<Cow<'a, str>> {
+        match self {
+            SpanStatementMode::Full => Some(Cow::Borrowed(statement)),
+            SpanStatementMode::Redacted => {
+                Some(Cow::Owned(redact_literals(statement)))
+            }
+            SpanStatementMode::Omitted => None,
+        }
+    }
+}
+
+/// The kind of a request sent to the cluster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RequestKind {
+    /// A statement execution.
+    Execute,
+    /// A batch execution.
+    Batch,
+    /// A statement preparation.
+    Prepare,
+}
+
+impl RequestKind {
+    /// Returns the name of the span of the request.
+    fn span_name(&self) -> &'static str {
+        match self {
+            RequestKind::Execute => "cassandra.execute",
+            RequestKind::Batch => "cassandra.batch",
+            RequestKind::Prepare => "cassandra.prepare",
+        }
+    }
+}
+
+/// The description of a request used to count it and to trace it.
+pub(crate) struct RequestInfo<'a> {
+    /// The kind of the request.
+    pub(crate) kind:                RequestKind,
+    /// The statement text, the statements of a batch are separated by `;`.
+    pub(crate) statement:           Cow<'a, str>,
+    /// The keyspace set on the request.
+    pub(crate) keyspace:            Option<&'a str>,
+    /// The consistency level set on the request.
+    pub(crate) consistency:         Option<Consistency>,
+    /// The number of the page the request fetches, starting from 1.
+    pub(crate) page_number:         Option<u64>,
+    /// Whether the previous execution of the same request failed, updated
+    /// with the outcome of this one.
+    pub(crate) last_attempt_failed: Option<&'a AtomicBool>,
+}
+
+impl<'a> RequestInfo<'a> {
+    /// Returns the description of a prepare request.
+    pub(crate) fn prepare(query: &'a str) -> Self {
+        Self {
+            kind:                RequestKind::Prepare,
+            statement:           Cow::Borrowed(query),
+            keyspace:            None,
+            consistency:         None,
+            page_number:         None,
+            last_attempt_failed: None,
+        }
+    }
+
+    /// Creates the span of the request following the OpenTelemetry database
+    /// semantic conventions.
+    ///
+    /// The `keyspace` is used if the request does not set its own keyspace.
+    /// The outcome of the request is recorded with [`record_success`] and
+    /// [`record_error`].
+    pub(crate) fn span(
+        &self,
+        keyspace: Option<&str>,
+        mode: SpanStatementMode,
+    ) -> Span {
+        let span = tracing::info_span!(
+            target: "cassander::request",
+            "cassandra.request",
+            otel.name = self.kind.span_name(),
+            otel.kind = "client",
+            otel.status_code = Empty,
+            db.system = "cassandra",
+            db.operation = Empty,
+            db.name = Empty,
+            db.statement = Empty,
+            db.cassandra.consistency_level = Empty,
+            db.cassandra.page = Empty,
+            db.response.returned_rows = Empty,
+            "server.address" = Empty,
+            "server.port" = Empty,
+            "error.type" = Empty,
+        );
+
+        if span.is_disabled() {
+            return span;
+        }
+
+        match self.kind {
+            RequestKind::Execute => {
+                if let Some(operation) =
+                    self.statement.split_whitespace().next()
+                {
+                    span.record("db.operation", operation.to_uppercase());
+                }
+            }
+            RequestKind::Batch => {
+                span.record("db.operation", "BATCH");
+            }
+            RequestKind::Prepare => {
+                span.record("db.operation", "PREPARE");
+            }
+        }
+        if let Some(keyspace) = self.keyspace.or(keyspace) {
+            span.record("db.name", keyspace);
+        }
+        if let Some(statement) = mode.apply(&self.statement) {
+            span.record("db.statement", statement.as_ref());
+        }
+        if let Some(consistency) = self.consistency {
+            span.record(
+                "db.cassandra.consistency_level",
+                tracing::field::display(consistency),
+            );
+        }
+        if let Some(page_number) = self.page_number {
+            span.record("db.cassandra.page", page_number);
+        }
+
+        span
+    }
+
+    /// Emits the warnings returned by the server as `WARN` events within the
+    /// span of the request.
+    ///
+    /// The statement text is recorded according to the `mode`.
+    pub(crate) fn report_warnings(
+        &self,
+        span: &Span,
+        mode: SpanStatementMode,
+        warnings: &[String],
+    ) {
+        if warnings.is_empty() {
+            return;
+        }
+
+        let statement = mode.apply(&self.statement);
+        for warning in warnings {
+            tracing::event!(
+                target: "cassander::request",
+                parent: span,
+                Level::WARN,
+                { "db.statement" = statement.as_deref(), warning = warning.as_str() },
+                "the server returned a warning"
+            );
+        }
+    }
+}
+
+/// The successful outcome of a request.
+pub(crate) trait RequestOutcome {
+    /// Returns the number of rows returned by the request, [`None`] for the
+    /// requests that do not return rows.
+    fn row_count(&self) -> Option<usize>;
+
+    /// Returns the warnings returned by the server.
+    fn warnings(&self) -> &[String] {
+        &[]
+    }
+
+    /// Returns the node that coordinated the request.
+    fn coordinator(&self) -> Option<&Coordinator> {
+        None
+    }
+}
+
+impl RequestOutcome for QueryResult {
+    fn row_count(&self) -> Option<usize> {
+        Some(QueryResult::row_count(self))
+    }
+
+    fn warnings(&self) -> &[String] {
+        QueryResult::warnings(self)
+    }
+
+    fn coordinator(&self) -> Option<&Coordinator> {
+        QueryResult::coordinator(self)
+    }
+}
+
+impl RequestOutcome for Prepared {
+    fn row_count(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Records the successful outcome of a request in its span.
+pub(crate) fn record_success<R>(span: &Span, outcome: &R)
+where
+    R: RequestOutcome,
+{
+    if let Some(row_count) = outcome.row_count() {
+        span.record("db.response.returned_rows", row_count);
+    }
+    record_coordinator(span, outcome.coordinator());
+}
+
+/// Records the failure of a request in its span.
+pub(crate) fn record_error(span: &Span, error: &DriverError) {
+    span.record("otel.status_code", "ERROR");
+    span.record("error.type", tracing::field::debug(error.kind));
+    record_coordinator(span, error.coordinator.as_ref());
+}
+
+/// Records the address of the node that coordinated the request, if known.
+fn record_coordinator(span: &Span, coordinator: Option<&Coordinator>) {
+    if let Some(address) = coordinator.and_then(Coordinator::address) {
+        span.record("server.address", tracing::field::display(address.ip()));
+        span.record("server.port", address.port());
+    }
+}
+
+/// Replaces the literals of a CQL statement with `?`.
+///
+/// The quoted identifiers, keywords and bind markers are kept as is. The UUID,
+/// blob and number literals are matched as whole tokens, so a UUID starting
+/// with a letter is not mistaken for an identifier, and the sign of a number
+/// is redacted with it.
+fn redact_literals(statement: &str) -> String {
+    let mut redacted = String::with_capacity(statement.len());
+    let mut rest = statement;
+
+    while let Some(c) = rest.chars().next() {
+        let len = match c {
+            '\'' => {
+                // A string literal, the quote is escaped by doubling it.
+                redacted.push('?');
+                quoted_len(rest, "'")
+            }
+            '$' if rest.starts_with("$$") => {
+                // A dollar-quoted string literal.
+                redacted.push('?');
+                quoted_len(rest, "$$")
+            }
+            '"' => {
+                // A quoted identifier.
+                let len = quoted_len(rest, "\"");
+                redacted.push_str(&rest[..len]);
+                len
+            }
+            '-' if !follows_operand(&redacted)
+                && rest[1..].starts_with(|c: char| c.is_ascii_digit()) =>
+            {
+                // A negative number, e.g. `-4.5e+2`, not a subtraction.
+                redacted.push('?');
+                1 + number_len(&rest[1..])
+            }
+            c if is_word_char(c) => {
+                let literal_len = uuid_len(rest)
+                    .or_else(|| blob_len(rest))
+                    .or_else(|| c.is_ascii_digit().then(|| number_len(rest)));
+
+                match literal_len {
+                    Some(len) => {
+                        redacted.push('?');
+                        len
+                    }
+                    None => {
+                        // A keyword or an identifier, e.g. `table2`.
+                        let len = word_len(rest);
+                        redacted.push_str(&rest[..len]);
+                        len
+                    }
+                }
+            }
+            c => {
+                redacted.push(c);
+                c.len_utf8()
+            }
+        };
+        rest = &rest[len..];
+    }
+
+    redacted
+}
+
+/// Returns whether the character may be a part of an identifier or a literal
+/// that is not quoted.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Returns the length of the leading word of the text.
+fn word_len(text: &str) -> usize {
+    text.find(|c| !is_word_char(c)).unwrap_or(text.len())
+}
+
+/// Returns whether the end of the redacted text is an operand, so a `-`
+/// following it is a subtraction rather than the sign of a number.
+fn follows_operand(redacted: &str) -> bool {
+    redacted.trim_end().ends_with(|c| {
+        is_word_char(c) || matches!(c, ')' | ']' | '}' | '"' | '?')
+    })
+}
+
+/// Returns the length of the leading text quoted with the delimiter, a doubled
+/// delimiter inside the quotes is escaped.
+///
+/// An unterminated quote extends to the end of the text.
+fn quoted_len(text: &str, delimiter: &str) -> usize {
+    let mut offset = delimiter.len();
+
+    while let Some(position) = text[offset..].find(delimiter) {
+        offset += position + delimiter.len();
+        if delimiter != "$$" && text[offset..].starts_with(delimiter) {
+            offset += delimiter.len();
+        } else {
+            return offset;
+        }
+    }
+
+    text.len()
+}
+
+/// Returns the length of the leading UUID literal of the text, e.g.
+/// `f47ac10b-58cc-4372-a567-0e02b2c3d479`.
+fn uuid_len(text: &str) -> Option<usize> {
+    const GROUPS: [usize; 5] = [8, 4, 4, 4, 12];
+    let mut offset = 0;
+
+    for (index, group) in GROUPS.into_iter().enumerate() {
+        if index > 0 {
+            text[offset..].strip_prefix('-')?;
+            offset += 1;
+        }
+        let digits = text[offset..]
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(text.len() - offset);
+        if digits != group {
+            return None;
+        }
+        offset += group;
+    }
+
+    (word_len(&text[offset..]) == 0).then_some(offset)
+}
+
+/// Returns the length of the leading blob literal of the text, e.g. `0xcafe`.
+fn blob_len(text: &str) -> Option<usize> {
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))?;
+    let len = word_len(digits);
+
+    digits[..len]
+        .chars()
+        .all(|c| c.is_ascii_hexdigit())
+        .then_some(2 + len)
+}
+
+/// Returns the length of the leading number literal of the text, including the
+/// exponent and the duration units, e.g. `4.5e+2` or `1h30m`.
+fn number_len(text: &str) -> usize {
+    let mut previous = None;
+
+    for (offset, c) in text.char_indices() {
+        let is_exponent_sign =
+            matches!(c, '+' | '-') && matches!(previous, Some('e' | 'E'));
+        if !(is_word_char(c) || c == '.' || is_exponent_sign) {
+            return offset;
+        }
+        previous = Some(c);
+    }
+
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        Mutex,
+    };
+
+    use tracing::field::{
+        Field,
+        Visit,
+    };
+    use tracing::span::{
+        Attributes,
+        Id,
+        Record,
+    };
+    use tracing::{
+        Event,
+        Metadata,
+        Subscriber,
+    };
+
+    use super::*;
+
+    /// Collects the fields of the events as `name=value` strings.
+    #[derive(Default)]
+    struct EventCollector(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for EventCollector {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.lock().unwrap().push(format!(
+                "{}={:?}",
+                field.name(),
+                value
+            ));
+        }
+    }
+
+    impl Subscriber for EventCollector {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _span: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut visitor = EventCollector(self.0.clone());
+            visitor
+                .0
+                .lock()
+                .unwrap()
+                .push(format!("level={}", event.metadata().level()));
+            event.record(&mut visitor);
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[test]
+    fn test_report_warnings() {
+        let collector = EventCollector::default();
+        let fields = collector.0.clone();
+        let request = RequestInfo {
+            kind:                RequestKind::Execute,
+            statement:           Cow::Borrowed(
+                "SELECT * FROM events WHERE day = '2024-01-01' ALLOW FILTERING",
+            ),
+            keyspace:            None,
+            consistency:         None,
+            page_number:         Some(1),
+            last_attempt_failed: None,
+        };
+
+        tracing::subscriber::with_default(collector, || {
+            let span = request.span(None, SpanStatementMode::Redacted);
+            request.report_warnings(
+                &span,
+                SpanStatementMode::Redacted,
+                &["Read 1000 live rows and 5001 tombstone cells".to_string()],
+            );
+        });
+
+        assert_eq!(
+            *fields.lock().unwrap(),
+            vec![
+                "level=WARN",
+                "message=the server returned a warning",
+                "db.statement=\"SELECT * FROM events WHERE day = ? ALLOW \
+                 FILTERING\"",
+                "warning=\"Read 1000 live rows and 5001 tombstone cells\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_redact_literals() {
+        assert_eq!(
+            redact_literals(
+                "INSERT INTO users2 (id, name, age, \"Key1\") VALUES \
+                 (123e4567-e89b-12d3-a456-426614174000, 'O''Brien', -4.5e+2, \
+                 0xCAFE)"
+            ),
+            "INSERT INTO users2 (id, name, age, \"Key1\") VALUES (?, ?, ?, ?)"
+        );
+    }
+
+    #[test]
+    fn test_redact_literals_uuids() {
+        assert_eq!(
+            redact_literals(
+                "SELECT * FROM t WHERE id IN \
+                 (f47ac10b-e89b-12d3-a456-426614174000, \
+                 AB47AC10-E89B-12D3-A456-426614174000) AND deadbeef = 1"
+            ),
+            "SELECT * FROM t WHERE id IN (?, ?) AND deadbeef = ?"
+        );
+    }
+
+    #[test]
+    fn test_redact_literals_blobs() {
+        assert_eq!(
+            redact_literals(
+                "UPDATE t SET b = 0xCAFE, c = 0x WHERE k = 0xcafebabe"
+            ),
+            "UPDATE t SET b = ?, c = ? WHERE k = ?"
+        );
+    }
+
+    #[test]
+    fn test_redact_literals_signed_numbers() {
+        assert_eq!(
+            redact_literals(
+                "SELECT * FROM t WHERE a > -1 AND b IN (-2.5, -3e-2) AND c = \
+                 [-1]"
+            ),
+            "SELECT * FROM t WHERE a > ? AND b IN (?, ?) AND c = [?]"
+        );
+        assert_eq!(
+            redact_literals("UPDATE t SET c = c - 1, d = d-2 WHERE k = -0"),
+            "UPDATE t SET c = c - ?, d = d-? WHERE k = ?"
+        );
+    }
+
+    #[test]
+    fn test_redact_literals_keeps_bind_markers() {
+        assert_eq!(
+            redact_literals(
+                "SELECT * FROM t WHERE k = ? AND c = :c AND v = $$a$b$$ LIMIT \
+                 10"
+            ),
+            "SELECT * FROM t WHERE k = ? AND c = :c AND v = ? LIMIT ?"
+        );
+    }
+}
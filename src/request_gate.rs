@@ -0,0 +1,183 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use futures::channel::oneshot;
+use futures::FutureExt;
+use parking_lot::{
+    Condvar,
+    Mutex,
+};
+
+/// Admits the requests of a session until it is closed and tracks the ones
+/// in flight, so closing the session can wait for them to complete.
+#[derive(Default)]
+pub(crate) struct RequestGate {
+    /// The state shared with the guards of the requests.
+    shared: Arc<GateShared>,
+}
+
+/// The state of a request gate shared with the guards of the requests.
+#[derive(Default)]
+struct GateShared {
+    /// The state of the gate.
+    state:   Mutex<GateState>,
+    /// Notified when the requests in flight complete.
+    drained: Condvar,
+}
+
+/// The state of a request gate.
+#[derive(Default)]
+struct GateState {
+    /// Whether the gate admits no more requests.
+    closed:    bool,
+    /// The number of the requests in flight.
+    in_flight: usize,
+    /// The tasks waiting for the requests in flight to complete, the threads
+    /// wait on [`GateShared::drained`].
+    waiters:   Vec<oneshot::Sender<()>>,
+}
+
+impl RequestGate {
+    /// Admits a request, returns [`None`] if the gate is closed.
+    ///
+    /// The request is in flight until the returned guard is dropped.
+    pub(crate) fn enter(&self) -> Option<RequestGuard> {
+        let mut state = self.shared.state.lock();
+        if state.closed {
+            return None;
+        }
+        state.in_flight += 1;
+
+        Some(RequestGuard {
+            shared: self.shared.clone(),
+        })
+    }
+
+    /// Returns the number of the requests in flight.
+    pub(crate) fn in_flight(&self) -> usize {
+        self.shared.state.lock().in_flight
+    }
+
+    /// Closes the gate and returns a future that completes when the requests
+    /// in flight complete.
+    pub(crate) fn close(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut state = self.shared.state.lock();
+        state.closed = true;
+
+        let (sender, receiver) = oneshot::channel();
+        if state.in_flight == 0 {
+            let _ = sender.send(());
+        } else {
+            state.waiters.push(sender);
+        }
+
+        receiver.map(|_| ())
+    }
+
+    /// Closes the gate and blocks the current thread until the requests in
+    /// flight complete, returns `false` if they do not complete within the
+    /// timeout.
+    pub(crate) fn close_blocking(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now().checked_add(timeout);
+        let mut state = self.shared.state.lock();
+        state.closed = true;
+
+        while state.in_flight > 0 {
+            let Some(deadline) = deadline else {
+                self.shared.drained.wait(&mut state);
+                continue;
+            };
+            if self
+                .shared
+                .drained
+                .wait_until(&mut state, deadline)
+                .timed_out()
+            {
+                return state.in_flight == 0;
+            }
+        }
+
+        true
+    }
+}
+
+/// Marks a request as in flight until dropped.
+pub(crate) struct RequestGuard {
+    /// The state of the gate that admitted the request.
+    shared: Arc<GateShared>,
+}
+
+impl Drop for RequestGuard {
+    fn drop(&mut self) {
+        let mut state = self.shared.state.lock();
+        state.in_flight -= 1;
+        if state.in_flight == 0 {
+            self.shared.drained.notify_all();
+            for waiter in state.waiters.drain(..) {
+                let _ = waiter.send(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::poll;
+
+    use super::*;
+
+    #[test]
+    fn test_close_waits_for_requests_in_flight() {
+        let gate = RequestGate::default();
+        let first = gate.enter().unwrap();
+        let second = gate.enter().unwrap();
+
+        let mut drained = Box::pin(gate.close());
+
+        assert!(gate.enter().is_none());
+        assert_eq!(gate.in_flight(), 2);
+        block_on(async {
+            assert!(poll!(&mut drained).is_pending());
+            drop(first);
+            assert!(poll!(&mut drained).is_pending());
+            drop(second);
+            assert!(poll!(&mut drained).is_ready());
+        });
+        assert_eq!(gate.in_flight(), 0);
+        block_on(gate.close());
+    }
+
+    #[test]
+    fn test_close_blocking_waits_for_requests_in_flight() {
+        let gate = RequestGate::default();
+        let request = gate.enter().unwrap();
+
+        assert!(!gate.close_blocking(Duration::from_millis(10)));
+        assert!(gate.enter().is_none());
+
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            drop(request);
+        });
+        assert!(gate.close_blocking(Duration::from_secs(10)));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_close_blocking_without_timeout() {
+        let gate = RequestGate::default();
+        let request = gate.enter().unwrap();
+
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            drop(request);
+        });
+        assert!(gate.close_blocking(Duration::MAX));
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,95 @@
+use std::ffi::c_char;
+use std::marker::PhantomData;
+
+use crate::cql::CqlValue;
+use crate::ffi::{
+    cass_iterator_from_row,
+    cass_iterator_get_column,
+    cass_row_get_column,
+    cass_row_get_column_by_name_n,
+    struct_CassRow_,
+};
+use crate::iterator::DriverIterator;
+use crate::{
+    DriverError,
+    DriverErrorKind,
+};
+
+/// A row of a [`QueryResult`].
+///
+/// The row borrows the result it was read from.
+///
+/// [`QueryResult`]: crate::QueryResult
+#[derive(Clone, Copy)]
+pub struct Row<'a> {
+    /// The driver's row object.
+    inner:  *const struct_CassRow_,
+    /// The lifetime of the result the row belongs to.
+    _owner: PhantomData<&'a ()>,
+}
+
+impl Row<'_> {
+    /// Creates a new row from the driver object.
+    pub(crate) fn from_driver(inner: *const struct_CassRow_) -> Self {
+        assert!(!inner.is_null(), "the driver's row object must not be null");
+
+        Self {
+            inner,
+            _owner: PhantomData,
+        }
+    }
+
+    /// Returns the raw pointer to the row object.
+    pub(crate) fn inner(&self) -> *const struct_CassRow_ {
+        self.inner
+    }
+
+    /// Returns the value of the column at the given index.
+    pub fn get(&self, index: usize) -> Result<CqlValue, DriverError> {
+        let value = unsafe { cass_row_get_column(self.inner(), index) };
+        if value.is_null() {
+            return Err(DriverError::with_kind(
+                DriverErrorKind::LibIndexOutOfBounds,
+            ));
+        }
+
+        CqlValue::from_driver(value)
+    }
+
+    /// Returns the value of the column with the given name.
+    ///
+    /// The name is case-insensitive unless it is enclosed in double quotes.
+    pub fn get_by_name<T>(&self, name: T) -> Result<CqlValue, DriverError>
+    where
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+        let name_len = name.len();
+        let name_ptr = name.as_ptr() as *const c_char;
+        let value = unsafe {
+            cass_row_get_column_by_name_n(self.inner(), name_ptr, name_len)
+        };
+        if value.is_null() {
+            return Err(DriverError::with_message(
+                DriverErrorKind::LibNameDoesNotExist,
+                format!("the row has no column named {}", name),
+            ));
+        }
+
+        CqlValue::from_driver(value)
+    }
+
+    /// Returns the values of all the columns of the row.
+    pub fn values(&self) -> Result<Vec<CqlValue>, DriverError> {
+        let mut iterator = DriverIterator::from_driver(unsafe {
+            cass_iterator_from_row(self.inner())
+        });
+        let mut values = Vec::new();
+        while iterator.advance() {
+            let value = unsafe { cass_iterator_get_column(iterator.inner()) };
+            values.push(CqlValue::from_driver(value)?);
+        }
+
+        Ok(values)
+    }
+}
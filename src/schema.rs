@@ -0,0 +1,7 @@
+mod event;
+mod meta;
+mod snapshot;
+
+pub use event::*;
+pub use meta::*;
+pub(crate) use snapshot::*;
@@ -0,0 +1,267 @@
+use std::fmt::{
+    Display,
+    Formatter,
+};
+use std::pin::Pin;
+use std::task::{
+    Context,
+    Poll,
+};
+use std::thread;
+use std::time::Duration;
+
+use futures::channel::mpsc;
+use futures::executor::block_on;
+use futures::{
+    SinkExt,
+    Stream,
+    StreamExt,
+};
+
+use crate::schema::SchemaSnapshot;
+use crate::session::WeakSession;
+use crate::{
+    DriverError,
+    DriverErrorKind,
+    Session,
+};
+
+/// The size of the queue that stores schema events not yet consumed from a
+/// [`SchemaEventStream`].
+const SCHEMA_EVENT_QUEUE_SIZE: usize = 256;
+
+/// The shortest interval between the checks of the schema metadata snapshot.
+const MIN_SCHEMA_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The kind of a schema change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SchemaChangeKind {
+    /// The schema element has been created.
+    Created,
+    /// The definition of the schema element has been changed.
+    Updated,
+    /// The schema element has been dropped.
+    Dropped,
+}
+
+impl Display for SchemaChangeKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            SchemaChangeKind::Created => "CREATED",
+            SchemaChangeKind::Updated => "UPDATED",
+            SchemaChangeKind::Dropped => "DROPPED",
+        };
+
+        write!(f, "{}", kind)
+    }
+}
+
+/// The schema element affected by a schema change.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SchemaElement {
+    /// A keyspace.
+    Keyspace {
+        /// The name of the keyspace.
+        keyspace: String,
+    },
+    /// A table.
+    Table {
+        /// The keyspace of the table.
+        keyspace: String,
+        /// The name of the table.
+        name:     String,
+    },
+    /// A user defined type.
+    UserType {
+        /// The keyspace of the type.
+        keyspace: String,
+        /// The name of the type.
+        name:     String,
+    },
+    /// A user defined function.
+    Function {
+        /// The keyspace of the function.
+        keyspace: String,
+        /// The full name of the function including its argument types, e.g.
+        /// `avg(int)`.
+        name:     String,
+    },
+    /// A user defined aggregate.
+    Aggregate {
+        /// The keyspace of the aggregate.
+        keyspace: String,
+        /// The full name of the aggregate including its argument types.
+        name:     String,
+    },
+}
+
+impl SchemaElement {
+    /// Returns the keyspace of the schema element.
+    pub fn keyspace(&self) -> &str {
+        match self {
+            SchemaElement::Keyspace {
+                keyspace,
+            }
+            | SchemaElement::Table {
+                keyspace, ..
+            }
+            | SchemaElement::UserType {
+                keyspace, ..
+            }
+            | SchemaElement::Function {
+                keyspace, ..
+            }
+            | SchemaElement::Aggregate {
+                keyspace, ..
+            } => keyspace,
+        }
+    }
+}
+
+impl Display for SchemaElement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaElement::Keyspace {
+                keyspace,
+            } => write!(f, "keyspace {}", keyspace),
+            SchemaElement::Table {
+                keyspace,
+                name,
+            } => write!(f, "table {}.{}", keyspace, name),
+            SchemaElement::UserType {
+                keyspace,
+                name,
+            } => write!(f, "type {}.{}", keyspace, name),
+            SchemaElement::Function {
+                keyspace,
+                name,
+            } => write!(f, "function {}.{}", keyspace, name),
+            SchemaElement::Aggregate {
+                keyspace,
+                name,
+            } => write!(f, "aggregate {}.{}", keyspace, name),
+        }
+    }
+}
+
+/// A schema change detected by the control connection.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SchemaEvent {
+    /// The kind of the change.
+    pub kind:    SchemaChangeKind,
+    /// The changed schema element.
+    pub element: SchemaElement,
+}
+
+impl SchemaEvent {
+    /// Creates a new schema event.
+    pub fn new(kind: SchemaChangeKind, element: SchemaElement) -> Self {
+        Self {
+            kind,
+            element,
+        }
+    }
+}
+
+impl Display for SchemaEvent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.kind, self.element)
+    }
+}
+
+/// A stream of schema changes.
+///
+/// The stream is backed by a background thread that polls the schema metadata
+/// snapshot of the session and compares it to the previous one every time the
+/// snapshot version changes. The thread stops shortly after the stream is
+/// dropped.
+///
+/// The thread does not keep the session alive, the stream ends once the
+/// session is dropped.
+#[must_use = "streams do nothing unless polled"]
+pub struct SchemaEventStream {
+    receiver: mpsc::Receiver<SchemaEvent>,
+}
+
+impl SchemaEventStream {
+    /// Starts watching the schema of the given session.
+    ///
+    /// The interval is at least [`MIN_SCHEMA_POLL_INTERVAL`].
+    pub(crate) fn new(
+        session: &Session,
+        interval: Duration,
+    ) -> Result<Self, DriverError> {
+        let (sender, receiver) = mpsc::channel(SCHEMA_EVENT_QUEUE_SIZE);
+        let session = session.downgrade();
+        let interval = interval.max(MIN_SCHEMA_POLL_INTERVAL);
+
+        thread::Builder::new()
+            .name("cassander-schema-events".to_string())
+            .spawn(move || watch_schema(session, interval, sender))
+            .map_err(|error| {
+                DriverError::with_message(
+                    DriverErrorKind::LibUnableToInit,
+                    format!(
+                        "failed to spawn the schema watcher thread: {}",
+                        error
+                    ),
+                )
+            })?;
+
+        Ok(Self {
+            receiver,
+        })
+    }
+}
+
+impl Stream for SchemaEventStream {
+    type Item = SchemaEvent;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+/// Polls the schema metadata of the session and sends the schema changes to
+/// the given channel until the receiving side or the session is dropped.
+///
+/// The first snapshot is the baseline: no events are produced for the schema
+/// elements that existed when the watcher started.
+fn watch_schema(
+    session: WeakSession,
+    interval: Duration,
+    mut sender: mpsc::Sender<SchemaEvent>,
+) {
+    let mut version = None;
+    let mut snapshot: Option<SchemaSnapshot> = None;
+
+    while !sender.is_closed() {
+        // Do not keep the session alive between the checks.
+        let Some(meta) = session.upgrade().map(|s| s.get_schema_meta()) else {
+            return;
+        };
+        let current_version = meta.snapshot_version();
+
+        if version != Some(current_version) {
+            let current = SchemaSnapshot::from_meta(&meta);
+            if let Some(previous) = &snapshot {
+                for event in previous.diff(&current) {
+                    if block_on(sender.send(event)).is_err() {
+                        // The stream has been dropped.
+                        return;
+                    }
+                }
+            }
+
+            version = Some(current_version);
+            snapshot = Some(current);
+        }
+
+        // Do not hold the snapshot while sleeping.
+        drop(meta);
+        thread::sleep(interval);
+    }
+}
@@ -0,0 +1,667 @@
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::c_char;
+use std::hash::{
+    Hash,
+    Hasher,
+};
+use std::marker::PhantomData;
+use std::slice;
+
+use crate::cql::CqlType;
+use crate::ffi::{
+    cass_aggregate_meta_full_name,
+    cass_aggregate_meta_name,
+    cass_column_meta_data_type,
+    cass_column_meta_name,
+    cass_column_meta_type,
+    cass_data_type_sub_data_type,
+    cass_data_type_sub_type_count,
+    cass_data_type_sub_type_name,
+    cass_data_type_type,
+    cass_data_type_type_name,
+    cass_function_meta_full_name,
+    cass_function_meta_name,
+    cass_iterator_aggregates_from_keyspace_meta,
+    cass_iterator_columns_from_table_meta,
+    cass_iterator_fields_from_aggregate_meta,
+    cass_iterator_fields_from_column_meta,
+    cass_iterator_fields_from_function_meta,
+    cass_iterator_fields_from_index_meta,
+    cass_iterator_fields_from_keyspace_meta,
+    cass_iterator_fields_from_table_meta,
+    cass_iterator_functions_from_keyspace_meta,
+    cass_iterator_get_aggregate_meta,
+    cass_iterator_get_column_meta,
+    cass_iterator_get_function_meta,
+    cass_iterator_get_index_meta,
+    cass_iterator_get_keyspace_meta,
+    cass_iterator_get_meta_field_name,
+    cass_iterator_get_meta_field_value,
+    cass_iterator_get_table_meta,
+    cass_iterator_get_user_type,
+    cass_iterator_indexes_from_table_meta,
+    cass_iterator_keyspaces_from_schema_meta,
+    cass_iterator_tables_from_keyspace_meta,
+    cass_iterator_user_types_from_keyspace_meta,
+    cass_keyspace_meta_is_virtual,
+    cass_keyspace_meta_name,
+    cass_keyspace_meta_table_by_name_n,
+    cass_schema_meta_free,
+    cass_schema_meta_keyspace_by_name_n,
+    cass_schema_meta_snapshot_version,
+    cass_schema_meta_version,
+    cass_table_meta_clustering_key,
+    cass_table_meta_clustering_key_count,
+    cass_table_meta_column,
+    cass_table_meta_column_by_name_n,
+    cass_table_meta_column_count,
+    cass_table_meta_is_virtual,
+    cass_table_meta_name,
+    cass_table_meta_partition_key,
+    cass_table_meta_partition_key_count,
+    cass_value_get_bytes,
+    enum_CassColumnType__CASS_COLUMN_TYPE_CLUSTERING_KEY as CLUSTERING_KEY,
+    enum_CassColumnType__CASS_COLUMN_TYPE_PARTITION_KEY as PARTITION_KEY,
+    enum_CassColumnType__CASS_COLUMN_TYPE_STATIC as STATIC,
+    enum_CassError_,
+    enum_CassError__CASS_OK as CASS_OK,
+    enum_cass_bool_t_cass_false as CASS_FALSE,
+    struct_CassAggregateMeta_,
+    struct_CassColumnMeta_,
+    struct_CassDataType_,
+    struct_CassFunctionMeta_,
+    struct_CassIterator_,
+    struct_CassKeyspaceMeta_,
+    struct_CassSchemaMeta_,
+    struct_CassTableMeta_,
+    struct_CassValue_,
+};
+use crate::iterator::{
+    DriverIter,
+    DriverIterator,
+};
+use crate::ClusterVersion;
+
+/// A snapshot of the schema metadata.
+///
+/// The snapshot is immutable: it does not change when the control connection
+/// receives schema updates. Request a new snapshot with
+/// [`Session::get_schema_meta`](crate::Session::get_schema_meta) to see them.
+pub struct SchemaMeta(*const struct_CassSchemaMeta_);
+
+impl SchemaMeta {
+    /// Creates a new schema metadata snapshot from the driver object.
+    pub(crate) fn from_driver(meta: *const struct_CassSchemaMeta_) -> Self {
+        assert!(
+            !meta.is_null(),
+            "the driver's schema metadata object must not be null"
+        );
+
+        Self(meta)
+    }
+
+    /// Returns the raw pointer to the schema metadata object.
+    pub(crate) fn inner(&self) -> *const struct_CassSchemaMeta_ {
+        self.0
+    }
+
+    /// Returns the version of the snapshot.
+    ///
+    /// The version is incremented every time the driver refreshes the schema
+    /// metadata.
+    pub fn snapshot_version(&self) -> u32 {
+        unsafe { cass_schema_meta_snapshot_version(self.inner()) }
+    }
+
+    /// Returns the version of the connected Cassandra cluster.
+    pub fn version(&self) -> ClusterVersion {
+        let version = unsafe { cass_schema_meta_version(self.inner()) };
+
+        ClusterVersion::from_driver(version)
+    }
+
+    /// Returns the metadata of the keyspace with the given name.
+    ///
+    /// Returns [`None`] if the keyspace does not exist.
+    pub fn keyspace<T>(&self, name: T) -> Option<KeyspaceMeta<'_>>
+    where
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+        let len = name.len();
+        let ptr = name.as_ptr() as *const c_char;
+        let keyspace = unsafe {
+            cass_schema_meta_keyspace_by_name_n(self.inner(), ptr, len)
+        };
+
+        KeyspaceMeta::from_driver(keyspace)
+    }
+
+    /// Returns an iterator over the metadata of all keyspaces.
+    pub fn keyspaces(&self) -> impl Iterator<Item = KeyspaceMeta<'_>> {
+        let iterator =
+            unsafe { cass_iterator_keyspaces_from_schema_meta(self.inner()) };
+
+        DriverIter::new(iterator, |iterator| {
+            let keyspace = unsafe { cass_iterator_get_keyspace_meta(iterator) };
+
+            KeyspaceMeta::from_driver(keyspace)
+        })
+        .flatten()
+    }
+}
+
+impl Drop for SchemaMeta {
+    fn drop(&mut self) {
+        unsafe { cass_schema_meta_free(self.inner()) }
+    }
+}
+
+// The snapshot is immutable and can be safely shared between threads.
+unsafe impl Send for SchemaMeta {}
+unsafe impl Sync for SchemaMeta {}
+
+/// The metadata of a keyspace.
+#[derive(Clone, Copy)]
+pub struct KeyspaceMeta<'a> {
+    inner:  *const struct_CassKeyspaceMeta_,
+    _owner: PhantomData<&'a SchemaMeta>,
+}
+
+impl<'a> KeyspaceMeta<'a> {
+    /// Creates a new keyspace metadata from the driver object.
+    ///
+    /// Returns [`None`] if the driver object is null.
+    fn from_driver(inner: *const struct_CassKeyspaceMeta_) -> Option<Self> {
+        if inner.is_null() {
+            None
+        } else {
+            Some(Self {
+                inner,
+                _owner: PhantomData,
+            })
+        }
+    }
+
+    /// Returns the name of the keyspace.
+    pub fn name(&self) -> String {
+        get_name(|s, l| unsafe { cass_keyspace_meta_name(self.inner, s, l) })
+    }
+
+    /// Returns `true` if the keyspace is virtual.
+    pub fn is_virtual(&self) -> bool {
+        let is_virtual = unsafe { cass_keyspace_meta_is_virtual(self.inner) };
+
+        is_virtual != CASS_FALSE
+    }
+
+    /// Returns the metadata of the table with the given name.
+    ///
+    /// Returns [`None`] if the table does not exist.
+    pub fn table<T>(&self, name: T) -> Option<TableMeta<'a>>
+    where
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+        let len = name.len();
+        let ptr = name.as_ptr() as *const c_char;
+        let table =
+            unsafe { cass_keyspace_meta_table_by_name_n(self.inner, ptr, len) };
+
+        TableMeta::from_driver(table)
+    }
+
+    /// Returns an iterator over the metadata of all tables of the keyspace.
+    pub fn tables(&self) -> impl Iterator<Item = TableMeta<'a>> {
+        let iterator =
+            unsafe { cass_iterator_tables_from_keyspace_meta(self.inner) };
+
+        DriverIter::new(iterator, |iterator| {
+            let table = unsafe { cass_iterator_get_table_meta(iterator) };
+
+            TableMeta::from_driver(table)
+        })
+        .flatten()
+    }
+
+    /// Returns an iterator over the user defined types of the keyspace.
+    pub fn user_types(&self) -> impl Iterator<Item = UserTypeMeta<'a>> {
+        let iterator =
+            unsafe { cass_iterator_user_types_from_keyspace_meta(self.inner) };
+
+        DriverIter::new(iterator, |iterator| UserTypeMeta {
+            inner:  unsafe { cass_iterator_get_user_type(iterator) },
+            _owner: PhantomData,
+        })
+    }
+
+    /// Returns an iterator over the user defined functions of the keyspace.
+    pub fn functions(&self) -> impl Iterator<Item = FunctionMeta<'a>> {
+        let iterator =
+            unsafe { cass_iterator_functions_from_keyspace_meta(self.inner) };
+
+        DriverIter::new(iterator, |iterator| FunctionMeta {
+            inner:  unsafe { cass_iterator_get_function_meta(iterator) },
+            _owner: PhantomData,
+        })
+    }
+
+    /// Returns an iterator over the user defined aggregates of the keyspace.
+    pub fn aggregates(&self) -> impl Iterator<Item = AggregateMeta<'a>> {
+        let iterator =
+            unsafe { cass_iterator_aggregates_from_keyspace_meta(self.inner) };
+
+        DriverIter::new(iterator, |iterator| AggregateMeta {
+            inner:  unsafe { cass_iterator_get_aggregate_meta(iterator) },
+            _owner: PhantomData,
+        })
+    }
+
+    /// Returns the fingerprint of the keyspace options, e.g. the replication
+    /// strategy.
+    ///
+    /// The fingerprint does not include the keyspace elements like tables.
+    pub(crate) fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash_fields(
+            unsafe { cass_iterator_fields_from_keyspace_meta(self.inner) },
+            &mut hasher,
+        );
+
+        hasher.finish()
+    }
+}
+
+/// The metadata of a table.
+#[derive(Clone, Copy)]
+pub struct TableMeta<'a> {
+    inner:  *const struct_CassTableMeta_,
+    _owner: PhantomData<&'a SchemaMeta>,
+}
+
+impl<'a> TableMeta<'a> {
+    /// Creates a new table metadata from the driver object.
+    ///
+    /// Returns [`None`] if the driver object is null.
+    fn from_driver(inner: *const struct_CassTableMeta_) -> Option<Self> {
+        if inner.is_null() {
+            None
+        } else {
+            Some(Self {
+                inner,
+                _owner: PhantomData,
+            })
+        }
+    }
+
+    /// Returns the name of the table.
+    pub fn name(&self) -> String {
+        get_name(|s, l| unsafe { cass_table_meta_name(self.inner, s, l) })
+    }
+
+    /// Returns `true` if the table is virtual.
+    pub fn is_virtual(&self) -> bool {
+        let is_virtual = unsafe { cass_table_meta_is_virtual(self.inner) };
+
+        is_virtual != CASS_FALSE
+    }
+
+    /// Returns the metadata of the column with the given name.
+    ///
+    /// Returns [`None`] if the column does not exist.
+    pub fn column<T>(&self, name: T) -> Option<ColumnMeta<'a>>
+    where
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+        let len = name.len();
+        let ptr = name.as_ptr() as *const c_char;
+        let column =
+            unsafe { cass_table_meta_column_by_name_n(self.inner, ptr, len) };
+
+        ColumnMeta::from_driver(column)
+    }
+
+    /// Returns the metadata of all columns of the table: the partition key
+    /// columns, the clustering columns and the other columns.
+    pub fn columns(&self) -> impl Iterator<Item = ColumnMeta<'a>> {
+        let inner = self.inner;
+        let count = unsafe { cass_table_meta_column_count(inner) };
+
+        (0..count).filter_map(move |index| {
+            ColumnMeta::from_driver(unsafe {
+                cass_table_meta_column(inner, index)
+            })
+        })
+    }
+
+    /// Returns the metadata of the partition key columns in the order of the
+    /// key.
+    pub fn partition_key(&self) -> Vec<ColumnMeta<'a>> {
+        let count = unsafe { cass_table_meta_partition_key_count(self.inner) };
+
+        (0..count)
+            .filter_map(|index| {
+                ColumnMeta::from_driver(unsafe {
+                    cass_table_meta_partition_key(self.inner, index)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the metadata of the clustering columns in the order of the
+    /// key.
+    pub fn clustering_key(&self) -> Vec<ColumnMeta<'a>> {
+        let count = unsafe { cass_table_meta_clustering_key_count(self.inner) };
+
+        (0..count)
+            .filter_map(|index| {
+                ColumnMeta::from_driver(unsafe {
+                    cass_table_meta_clustering_key(self.inner, index)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the fingerprint of the table definition: the table options,
+    /// its columns and indexes.
+    pub(crate) fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash_fields(
+            unsafe { cass_iterator_fields_from_table_meta(self.inner) },
+            &mut hasher,
+        );
+
+        let columns =
+            unsafe { cass_iterator_columns_from_table_meta(self.inner) };
+        let columns: DriverIter<'_, _> =
+            DriverIter::new(columns, |iterator| unsafe {
+                cass_iterator_get_column_meta(iterator)
+            });
+        for column in columns {
+            hash_fields(
+                unsafe { cass_iterator_fields_from_column_meta(column) },
+                &mut hasher,
+            );
+        }
+
+        let indexes =
+            unsafe { cass_iterator_indexes_from_table_meta(self.inner) };
+        let indexes: DriverIter<'_, _> =
+            DriverIter::new(indexes, |iterator| unsafe {
+                cass_iterator_get_index_meta(iterator)
+            });
+        for index in indexes {
+            hash_fields(
+                unsafe { cass_iterator_fields_from_index_meta(index) },
+                &mut hasher,
+            );
+        }
+
+        hasher.finish()
+    }
+}
+
+/// The kind of a table column.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColumnKind {
+    /// A column of the partition key.
+    PartitionKey,
+    /// A clustering column.
+    ClusteringKey,
+    /// A static column.
+    Static,
+    /// Any other column.
+    Regular,
+}
+
+/// The metadata of a table column.
+#[derive(Clone, Copy)]
+pub struct ColumnMeta<'a> {
+    inner:  *const struct_CassColumnMeta_,
+    _owner: PhantomData<&'a SchemaMeta>,
+}
+
+impl ColumnMeta<'_> {
+    /// Creates a new column metadata from the driver object.
+    ///
+    /// Returns [`None`] if the driver object is null.
+    fn from_driver(inner: *const struct_CassColumnMeta_) -> Option<Self> {
+        if inner.is_null() {
+            None
+        } else {
+            Some(Self {
+                inner,
+                _owner: PhantomData,
+            })
+        }
+    }
+
+    /// Returns the name of the column.
+    pub fn name(&self) -> String {
+        get_name(|s, l| unsafe { cass_column_meta_name(self.inner, s, l) })
+    }
+
+    /// Returns the kind of the column.
+    pub fn kind(&self) -> ColumnKind {
+        match unsafe { cass_column_meta_type(self.inner) } {
+            PARTITION_KEY => ColumnKind::PartitionKey,
+            CLUSTERING_KEY => ColumnKind::ClusteringKey,
+            STATIC => ColumnKind::Static,
+            _ => ColumnKind::Regular,
+        }
+    }
+
+    /// Returns the data type of the column.
+    pub fn data_type(&self) -> CqlType {
+        CqlType::from_driver(unsafe { cass_column_meta_data_type(self.inner) })
+    }
+}
+
+/// The metadata of a user defined type.
+#[derive(Clone, Copy)]
+pub struct UserTypeMeta<'a> {
+    inner:  *const struct_CassDataType_,
+    _owner: PhantomData<&'a SchemaMeta>,
+}
+
+impl UserTypeMeta<'_> {
+    /// Returns the name of the user defined type.
+    pub fn name(&self) -> String {
+        get_name(|s, l| unsafe {
+            let _ = cass_data_type_type_name(self.inner, s, l);
+        })
+    }
+
+    /// Returns the names of the fields of the user defined type.
+    pub fn field_names(&self) -> Vec<String> {
+        let count = unsafe { cass_data_type_sub_type_count(self.inner) };
+
+        (0..count)
+            .map(|index| {
+                get_name(|s, l| unsafe {
+                    let _ =
+                        cass_data_type_sub_type_name(self.inner, index, s, l);
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the fingerprint of the type definition: the names and the types
+    /// of its fields.
+    pub(crate) fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.field_names().hash(&mut hasher);
+        hash_data_type(self.inner, &mut hasher);
+
+        hasher.finish()
+    }
+}
+
+/// The metadata of a user defined function.
+#[derive(Clone, Copy)]
+pub struct FunctionMeta<'a> {
+    inner:  *const struct_CassFunctionMeta_,
+    _owner: PhantomData<&'a SchemaMeta>,
+}
+
+impl FunctionMeta<'_> {
+    /// Returns the name of the function.
+    pub fn name(&self) -> String {
+        get_name(|s, l| unsafe { cass_function_meta_name(self.inner, s, l) })
+    }
+
+    /// Returns the full name of the function.
+    ///
+    /// The full name includes the function name and its argument types, e.g.
+    /// `avg(int)`, and identifies the function among its overloads.
+    pub fn full_name(&self) -> String {
+        get_name(|s, l| unsafe {
+            cass_function_meta_full_name(self.inner, s, l)
+        })
+    }
+
+    /// Returns the fingerprint of the function definition.
+    pub(crate) fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash_fields(
+            unsafe { cass_iterator_fields_from_function_meta(self.inner) },
+            &mut hasher,
+        );
+
+        hasher.finish()
+    }
+}
+
+/// The metadata of a user defined aggregate.
+#[derive(Clone, Copy)]
+pub struct AggregateMeta<'a> {
+    inner:  *const struct_CassAggregateMeta_,
+    _owner: PhantomData<&'a SchemaMeta>,
+}
+
+impl AggregateMeta<'_> {
+    /// Returns the name of the aggregate.
+    pub fn name(&self) -> String {
+        get_name(|s, l| unsafe { cass_aggregate_meta_name(self.inner, s, l) })
+    }
+
+    /// Returns the full name of the aggregate.
+    ///
+    /// The full name includes the aggregate name and its argument types and
+    /// identifies the aggregate among its overloads.
+    pub fn full_name(&self) -> String {
+        get_name(|s, l| unsafe {
+            cass_aggregate_meta_full_name(self.inner, s, l)
+        })
+    }
+
+    /// Returns the fingerprint of the aggregate definition.
+    pub(crate) fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hash_fields(
+            unsafe { cass_iterator_fields_from_aggregate_meta(self.inner) },
+            &mut hasher,
+        );
+
+        hasher.finish()
+    }
+}
+
+/// Hashes the names and the raw values of the metadata fields.
+///
+/// The metadata fields are the columns of the corresponding `system_schema`
+/// table row, so any change to the schema element definition changes the hash.
+fn hash_fields<H>(iterator: *mut struct_CassIterator_, hasher: &mut H)
+where
+    H: Hasher,
+{
+    let mut iterator = DriverIterator::from_driver(iterator);
+    while iterator.advance() {
+        let name = get_string(|s, l| unsafe {
+            cass_iterator_get_meta_field_name(iterator.inner(), s, l)
+        });
+        let value =
+            unsafe { cass_iterator_get_meta_field_value(iterator.inner()) };
+
+        name.hash(hasher);
+        value_bytes(value).hash(hasher);
+    }
+}
+
+/// Hashes the data type and all of its sub-types.
+fn hash_data_type<H>(data_type: *const struct_CassDataType_, hasher: &mut H)
+where
+    H: Hasher,
+{
+    if data_type.is_null() {
+        return;
+    }
+
+    unsafe { cass_data_type_type(data_type) }.hash(hasher);
+
+    let count = unsafe { cass_data_type_sub_type_count(data_type) };
+    for index in 0..count {
+        let sub_type =
+            unsafe { cass_data_type_sub_data_type(data_type, index) };
+        hash_data_type(sub_type, hasher);
+    }
+}
+
+/// Returns the raw bytes of the value.
+///
+/// Returns [`None`] if the value is null.
+fn value_bytes<'a>(value: *const struct_CassValue_) -> Option<&'a [u8]> {
+    if value.is_null() {
+        return None;
+    }
+
+    let mut bytes = std::ptr::null();
+    let mut len = 0;
+    let code = unsafe { cass_value_get_bytes(value, &mut bytes, &mut len) };
+    if code != CASS_OK || bytes.is_null() {
+        return None;
+    }
+
+    Some(unsafe { slice::from_raw_parts(bytes, len) })
+}
+
+/// Copies a name returned by the driver.
+fn get_name<F>(getter: F) -> String
+where
+    F: FnOnce(*mut *const c_char, *mut usize),
+{
+    let mut string = std::ptr::null();
+    let mut string_len = 0;
+    getter(&mut string, &mut string_len);
+
+    to_string_lossy(string, string_len)
+}
+
+/// Same as [`get_name`] but for the getters that can fail.
+///
+/// Returns an empty string if the getter fails.
+fn get_string<F>(getter: F) -> String
+where
+    F: FnOnce(*mut *const c_char, *mut usize) -> enum_CassError_,
+{
+    let mut string = std::ptr::null();
+    let mut string_len = 0;
+    if getter(&mut string, &mut string_len) != CASS_OK {
+        return String::new();
+    }
+
+    to_string_lossy(string, string_len)
+}
+
+fn to_string_lossy(string: *const c_char, len: usize) -> String {
+    if string.is_null() {
+        return String::new();
+    }
+
+    // The driver returns a pointer to its internal data. We should not free it.
+    let ptr = string as *const u8;
+    let slice = unsafe { slice::from_raw_parts(ptr, len) };
+
+    String::from_utf8_lossy(slice).into_owned()
+}
@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+
+use crate::schema::{
+    SchemaChangeKind,
+    SchemaElement,
+    SchemaEvent,
+    SchemaMeta,
+};
+
+/// A comparable summary of a schema metadata snapshot.
+///
+/// Every schema element is represented by its name and the fingerprint of its
+/// definition, which is enough to tell whether the element has been created,
+/// updated or dropped between two snapshots.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SchemaSnapshot {
+    keyspaces: BTreeMap<String, KeyspaceSnapshot>,
+}
+
+/// A comparable summary of a keyspace.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct KeyspaceSnapshot {
+    /// The fingerprint of the keyspace options.
+    fingerprint: u64,
+    /// The fingerprints of the tables by name.
+    tables:      BTreeMap<String, u64>,
+    /// The fingerprints of the user defined types by name.
+    user_types:  BTreeMap<String, u64>,
+    /// The fingerprints of the user defined functions by full name.
+    functions:   BTreeMap<String, u64>,
+    /// The fingerprints of the user defined aggregates by full name.
+    aggregates:  BTreeMap<String, u64>,
+}
+
+impl SchemaSnapshot {
+    /// Creates a summary of the given schema metadata snapshot.
+    pub(crate) fn from_meta(meta: &SchemaMeta) -> Self {
+        let keyspaces = meta
+            .keyspaces()
+            .map(|keyspace| {
+                let snapshot = KeyspaceSnapshot {
+                    fingerprint: keyspace.fingerprint(),
+                    tables:      keyspace
+                        .tables()
+                        .map(|table| (table.name(), table.fingerprint()))
+                        .collect(),
+                    user_types:  keyspace
+                        .user_types()
+                        .map(|user_type| {
+                            (user_type.name(), user_type.fingerprint())
+                        })
+                        .collect(),
+                    functions:   keyspace
+                        .functions()
+                        .map(|function| {
+                            (function.full_name(), function.fingerprint())
+                        })
+                        .collect(),
+                    aggregates:  keyspace
+                        .aggregates()
+                        .map(|aggregate| {
+                            (aggregate.full_name(), aggregate.fingerprint())
+                        })
+                        .collect(),
+                };
+
+                (keyspace.name(), snapshot)
+            })
+            .collect();
+
+        Self {
+            keyspaces,
+        }
+    }
+
+    /// Returns the changes that turn this snapshot into the `other` one.
+    ///
+    /// A created keyspace is reported before its elements and a dropped
+    /// keyspace is reported after its elements, so the consumers can rely on
+    /// the keyspace to exist while they process the element events.
+    pub(crate) fn diff(&self, other: &SchemaSnapshot) -> Vec<SchemaEvent> {
+        let empty = KeyspaceSnapshot::default();
+        let mut events = Vec::new();
+
+        for (name, current) in &other.keyspaces {
+            match self.keyspaces.get(name) {
+                None => {
+                    events.push(SchemaEvent::new(
+                        SchemaChangeKind::Created,
+                        SchemaElement::Keyspace {
+                            keyspace: name.clone(),
+                        },
+                    ));
+                    empty.diff_elements(current, name, &mut events);
+                }
+                Some(previous) => {
+                    if previous.fingerprint != current.fingerprint {
+                        events.push(SchemaEvent::new(
+                            SchemaChangeKind::Updated,
+                            SchemaElement::Keyspace {
+                                keyspace: name.clone(),
+                            },
+                        ));
+                    }
+                    previous.diff_elements(current, name, &mut events);
+                }
+            }
+        }
+
+        for (name, previous) in &self.keyspaces {
+            if !other.keyspaces.contains_key(name) {
+                previous.diff_elements(&empty, name, &mut events);
+                events.push(SchemaEvent::new(
+                    SchemaChangeKind::Dropped,
+                    SchemaElement::Keyspace {
+                        keyspace: name.clone(),
+                    },
+                ));
+            }
+        }
+
+        events
+    }
+}
+
+impl KeyspaceSnapshot {
+    /// Appends the changes of the keyspace elements to `events`.
+    fn diff_elements(
+        &self,
+        other: &KeyspaceSnapshot,
+        keyspace: &str,
+        events: &mut Vec<SchemaEvent>,
+    ) {
+        let element = |constructor: fn(String, String) -> SchemaElement| {
+            move |name: &str| {
+                constructor(keyspace.to_string(), name.to_string())
+            }
+        };
+
+        diff_maps(
+            &self.user_types,
+            &other.user_types,
+            element(|keyspace, name| SchemaElement::UserType {
+                keyspace,
+                name,
+            }),
+            events,
+        );
+        diff_maps(
+            &self.tables,
+            &other.tables,
+            element(|keyspace, name| SchemaElement::Table {
+                keyspace,
+                name,
+            }),
+            events,
+        );
+        diff_maps(
+            &self.functions,
+            &other.functions,
+            element(|keyspace, name| SchemaElement::Function {
+                keyspace,
+                name,
+            }),
+            events,
+        );
+        diff_maps(
+            &self.aggregates,
+            &other.aggregates,
+            element(|keyspace, name| SchemaElement::Aggregate {
+                keyspace,
+                name,
+            }),
+            events,
+        );
+    }
+}
+
+/// Appends the differences between two maps of fingerprints to `events`.
+fn diff_maps<F>(
+    previous: &BTreeMap<String, u64>,
+    current: &BTreeMap<String, u64>,
+    element: F,
+    events: &mut Vec<SchemaEvent>,
+) where
+    F: Fn(&str) -> SchemaElement,
+{
+    for (name, fingerprint) in current {
+        let kind = match previous.get(name) {
+            None => SchemaChangeKind::Created,
+            Some(previous) if previous != fingerprint => {
+                SchemaChangeKind::Updated
+            }
+            Some(_) => continue,
+        };
+
+        events.push(SchemaEvent::new(kind, element(name)));
+    }
+
+    for name in previous.keys() {
+        if !current.contains_key(name) {
+            events.push(SchemaEvent::new(
+                SchemaChangeKind::Dropped,
+                element(name),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyspace(fingerprint: u64, tables: &[(&str, u64)]) -> KeyspaceSnapshot {
+        KeyspaceSnapshot {
+            fingerprint,
+            tables: tables
+                .iter()
+                .map(|(name, fingerprint)| (name.to_string(), *fingerprint))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn snapshot(keyspaces: Vec<(&str, KeyspaceSnapshot)>) -> SchemaSnapshot {
+        SchemaSnapshot {
+            keyspaces: keyspaces
+                .into_iter()
+                .map(|(name, keyspace)| (name.to_string(), keyspace))
+                .collect(),
+        }
+    }
+
+    fn table_event(kind: SchemaChangeKind, name: &str) -> SchemaEvent {
+        SchemaEvent::new(
+            kind,
+            SchemaElement::Table {
+                keyspace: "kyiv".to_string(),
+                name:     name.to_string(),
+            },
+        )
+    }
+
+    fn keyspace_event(kind: SchemaChangeKind) -> SchemaEvent {
+        SchemaEvent::new(
+            kind,
+            SchemaElement::Keyspace {
+                keyspace: "kyiv".to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn test_diff_same_snapshot() {
+        let a = snapshot(vec![("kyiv", keyspace(1, &[("streets", 2)]))]);
+
+        assert!(a.diff(&a.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_tables() {
+        let a = snapshot(vec![(
+            "kyiv",
+            keyspace(1, &[("streets", 2), ("parks", 3)]),
+        )]);
+        let b = snapshot(vec![(
+            "kyiv",
+            keyspace(1, &[("streets", 20), ("metro", 4)]),
+        )]);
+
+        assert_eq!(
+            a.diff(&b),
+            vec![
+                table_event(SchemaChangeKind::Created, "metro"),
+                table_event(SchemaChangeKind::Updated, "streets"),
+                table_event(SchemaChangeKind::Dropped, "parks"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_keyspace_options() {
+        let a = snapshot(vec![("kyiv", keyspace(1, &[]))]);
+        let b = snapshot(vec![("kyiv", keyspace(2, &[]))]);
+
+        assert_eq!(a.diff(&b), vec![keyspace_event(SchemaChangeKind::Updated)]);
+    }
+
+    #[test]
+    fn test_diff_created_keyspace_goes_first() {
+        let a = snapshot(vec![]);
+        let b = snapshot(vec![("kyiv", keyspace(1, &[("streets", 2)]))]);
+
+        assert_eq!(
+            a.diff(&b),
+            vec![
+                keyspace_event(SchemaChangeKind::Created),
+                table_event(SchemaChangeKind::Created, "streets"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_dropped_keyspace_goes_last() {
+        let a = snapshot(vec![("kyiv", keyspace(1, &[("streets", 2)]))]);
+        let b = snapshot(vec![]);
+
+        assert_eq!(
+            a.diff(&b),
+            vec![
+                table_event(SchemaChangeKind::Dropped, "streets"),
+                keyspace_event(SchemaChangeKind::Dropped),
+            ]
+        );
+    }
+}
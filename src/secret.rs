@@ -0,0 +1,73 @@
+use std::fmt::{
+    Debug,
+    Formatter,
+};
+
+use zeroize::Zeroize;
+
+/// A sensitive string, e.g. a password or an authentication token.
+///
+/// The secret is wiped from memory when dropped and is never written to
+/// [`Debug`] output. It deliberately does not implement [`PartialEq`], so
+/// that it cannot be compared in variable time by accident.
+#[derive(Clone, Default)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Creates a new secret.
+    pub fn new<T>(secret: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self(secret.into())
+    }
+
+    /// Returns the secret value.
+    ///
+    /// Take care not to copy the value into memory that is not wiped.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns `true` if the secret is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for Secret {
+    fn from(secret: String) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(secret: &str) -> Self {
+        Self::new(secret)
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Secret(\"***\")")
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_does_not_expose_secret() {
+        let secret = Secret::new("p@ssw0rd");
+
+        assert!(!format!("{:?}", secret).contains("p@ssw0rd"));
+        assert_eq!(secret.expose(), "p@ssw0rd");
+    }
+}
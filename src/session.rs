@@ -1,10 +1,93 @@
-use std::sync::Arc;
+use std::ffi::c_char;
+use std::future::Future;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{
+    AtomicBool,
+    AtomicU64,
+    Ordering,
+};
+use std::sync::{
+    Arc,
+    Weak,
+};
+use std::thread;
+use std::time::Duration;
 
+use futures::future::{
+    select,
+    Either,
+};
+use futures::stream::{
+    self,
+    Stream,
+    StreamExt,
+};
+use tracing::{
+    Instrument,
+    Span,
+};
+
+use crate::concurrent::{
+    retry_on_queue_full,
+    DEFAULT_QUEUE_SIZE_IO,
+};
+use crate::cql::CqlUuid;
 use crate::ffi::{
+    cass_session_close,
+    cass_session_execute,
+    cass_session_execute_batch,
     cass_session_free,
+    cass_session_get_metrics,
+    cass_session_get_schema_meta,
+    cass_session_get_speculative_execution_metrics,
     cass_session_new,
+    cass_session_prepare_n,
+    struct_CassMetrics_,
     struct_CassSession_,
+    struct_CassSpeculativeExecutionMetrics_,
+};
+use crate::future::{
+    DriverFuture,
+    DriverFutureResult,
+};
+use crate::metrics::RequestCounterSet;
+use crate::request::{
+    record_error,
+    record_success,
+    RequestInfo,
+    RequestKind,
+    RequestOutcome,
 };
+use crate::request_gate::{
+    RequestGate,
+    RequestGuard,
+};
+use crate::timer::sleep;
+use crate::trace::{
+    fetch_trace,
+    TraceSettings,
+};
+use crate::{
+    Batch,
+    CallbackData,
+    Cluster,
+    DriverError,
+    DriverErrorKind,
+    Prepared,
+    QueryResult,
+    QueryTrace,
+    RequestCounters,
+    SchemaEventStream,
+    SchemaMeta,
+    SessionMetrics,
+    SpanStatementMode,
+    Statement,
+    TableScan,
+    WakeDispatcher,
+};
+
+/// The identifier of the next session created.
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
 
 /// A session object is used to execute queries and maintains cluster state
 /// through the control connection.
@@ -12,21 +95,585 @@ use crate::ffi::{
 /// The control connection is used to auto-discover nodes and monitor cluster
 /// changes (topology and schema). Each session also maintains multiple pools of
 /// connections to cluster nodes which are used to query the cluster.
+///
+/// The session should be closed with [`Session::close`]. Otherwise, it is
+/// closed when the last handle is dropped, on a background thread since
+/// closing blocks until the connections are closed.
 #[derive(Clone)]
 pub struct Session(Arc<SessionWrapper>);
 
 impl Session {
     /// Creates a new Cassandra session.
     pub(crate) fn new() -> Self {
-        let session = unsafe { cass_session_new() };
+        Self::with_settings(SessionSettings::default())
+    }
 
-        Self(Arc::new(SessionWrapper(session)))
+    /// Creates a new Cassandra session to be connected to the given cluster
+    /// with the given keyspace.
+    ///
+    /// The session keeps alive the targets of the callbacks installed on the
+    /// cluster.
+    pub(crate) fn for_cluster(
+        cluster: &Cluster,
+        keyspace: Option<&str>,
+    ) -> Self {
+        Self::with_settings(SessionSettings {
+            _callback_data:      cluster.callback_data().to_vec(),
+            application_name:    cluster.application_name().map(str::to_string),
+            keyspace:            keyspace.map(str::to_string),
+            span_statement_mode: cluster.span_statement_mode(),
+            trace_settings:      cluster.trace_settings(),
+            wake_dispatcher:     cluster.wake_dispatcher().cloned(),
+            queue_size_io:       cluster.queue_size_io(),
+        })
+    }
+
+    /// Creates a new Cassandra session with the given settings.
+    fn with_settings(settings: SessionSettings) -> Self {
+        let inner = unsafe { cass_session_new() };
+
+        Self(Arc::new(SessionWrapper {
+            inner,
+            id: NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed),
+            settings,
+            counters: RequestCounterSet::new(),
+            gate: RequestGate::default(),
+            closed: AtomicBool::new(false),
+            started: AtomicBool::new(false),
+        }))
+    }
+
+    /// Marks the session as asked to connect to a cluster, its connections
+    /// are then closed on a background thread when it is dropped.
+    pub(crate) fn set_started(&self) {
+        self.0.started.store(true, Ordering::Release);
+    }
+
+    /// Returns a weak reference to the session.
+    pub(crate) fn downgrade(&self) -> WeakSession {
+        WeakSession(Arc::downgrade(&self.0))
     }
 
     /// Returns the raw pointer to the session object.
     pub(crate) fn inner(&self) -> *mut struct_CassSession_ {
         self.0.inner()
     }
+
+    /// Returns the dispatcher of the wake-ups set on the cluster the session
+    /// was connected to, if any.
+    pub(crate) fn wake_dispatcher(&self) -> Option<&Arc<dyn WakeDispatcher>> {
+        self.0.settings.wake_dispatcher.as_ref()
+    }
+
+    /// Returns the identifier of the session, unique within the process.
+    ///
+    /// The identifier tells apart the sessions of the same application, e.g.
+    /// in the metrics.
+    pub fn id(&self) -> u64 {
+        self.0.id
+    }
+
+    /// Returns the application name set on the cluster the session was
+    /// connected to.
+    pub fn application_name(&self) -> Option<&str> {
+        self.0.settings.application_name.as_deref()
+    }
+
+    /// Executes the statement and returns its result.
+    ///
+    /// The statement is copied by the driver, so it can be modified or reused
+    /// once this method is called.
+    pub async fn execute(
+        &self,
+        statement: &Statement,
+    ) -> Result<QueryResult, DriverError> {
+        let _guard = self.enter()?;
+        let future = self.execute_future(statement);
+
+        self.track_request(future, statement.request_info()).await
+    }
+
+    /// Executes the statement blocking the current thread, see
+    /// [`Session::execute`].
+    pub(crate) fn execute_blocking(
+        &self,
+        statement: &Statement,
+        timeout: Option<Duration>,
+    ) -> Result<QueryResult, DriverError> {
+        let _guard = self.enter()?;
+        let future = self.execute_future(statement);
+
+        self.track_request_blocking(future, statement.request_info(), timeout)
+    }
+
+    /// Sends the statement to the cluster.
+    fn execute_future(
+        &self,
+        statement: &Statement,
+    ) -> DriverFuture<QueryResult> {
+        let future =
+            unsafe { cass_session_execute(self.inner(), statement.inner()) };
+
+        DriverFuture::new(future, self.clone())
+    }
+
+    /// Executes the statements concurrently and returns a stream of their
+    /// results in completion order, paired with the indexes of the
+    /// statements.
+    ///
+    /// At most `max_in_flight` statements are executed at a time, the next
+    /// statements are taken from the iterator as the previous ones complete.
+    /// The limit is capped by the size of the request queue, see
+    /// [`Cluster::set_queue_size_io`]. The statements rejected with
+    /// [`DriverErrorKind::LibRequestQueueFull`] are retried with an
+    /// exponential backoff instead of failing. The other errors are returned
+    /// in the stream and do not stop the execution of the other statements.
+    ///
+    /// See [`Session::execute_concurrent_ordered`] to get the results in the
+    /// order of the statements.
+    pub fn execute_concurrent<I>(
+        &self,
+        statements: I,
+        max_in_flight: usize,
+    ) -> impl Stream<Item = (usize, Result<QueryResult, DriverError>)>
+    where
+        I: IntoIterator<Item = Statement>,
+    {
+        let max_in_flight = self.max_in_flight(max_in_flight);
+
+        self.concurrent_requests(statements)
+            .buffer_unordered(max_in_flight)
+    }
+
+    /// Executes the statements concurrently and returns a stream of their
+    /// results in the order of the statements.
+    ///
+    /// The same as [`Session::execute_concurrent`], but a slow statement holds
+    /// back the results of the statements after it.
+    pub fn execute_concurrent_ordered<I>(
+        &self,
+        statements: I,
+        max_in_flight: usize,
+    ) -> impl Stream<Item = Result<QueryResult, DriverError>>
+    where
+        I: IntoIterator<Item = Statement>,
+    {
+        let max_in_flight = self.max_in_flight(max_in_flight);
+
+        self.concurrent_requests(statements)
+            .buffered(max_in_flight)
+            .map(|(_, result)| result)
+    }
+
+    /// Returns the requests executing the statements, retrying the ones
+    /// rejected because the request queue is full.
+    fn concurrent_requests<I>(
+        &self,
+        statements: I,
+    ) -> impl Stream<
+        Item = impl Future<Output = (usize, Result<QueryResult, DriverError>)>,
+    >
+    where
+        I: IntoIterator<Item = Statement>,
+    {
+        let session = self.clone();
+
+        stream::iter(statements.into_iter().enumerate()).map(
+            move |(index, statement)| {
+                let session = session.clone();
+                async move {
+                    let result =
+                        retry_on_queue_full(|| session.execute(&statement))
+                            .await;
+
+                    (index, result)
+                }
+            },
+        )
+    }
+
+    /// Returns the number of the concurrent requests capped by the size of
+    /// the request queue.
+    pub(crate) fn max_in_flight(&self, max_in_flight: usize) -> usize {
+        let queue_size = self
+            .0
+            .settings
+            .queue_size_io
+            .unwrap_or(DEFAULT_QUEUE_SIZE_IO);
+
+        max_in_flight.min(queue_size).max(1)
+    }
+
+    /// Executes the batch and returns its result.
+    ///
+    /// The batch is copied by the driver, so it can be modified or reused once
+    /// this method is called.
+    pub async fn execute_batch(
+        &self,
+        batch: &Batch,
+    ) -> Result<QueryResult, DriverError> {
+        let _guard = self.enter()?;
+        let future = self.execute_batch_future(batch);
+
+        self.track_request(future, batch.request_info()).await
+    }
+
+    /// Executes the batch blocking the current thread, see
+    /// [`Session::execute_batch`].
+    pub(crate) fn execute_batch_blocking(
+        &self,
+        batch: &Batch,
+        timeout: Option<Duration>,
+    ) -> Result<QueryResult, DriverError> {
+        let _guard = self.enter()?;
+        let future = self.execute_batch_future(batch);
+
+        self.track_request_blocking(future, batch.request_info(), timeout)
+    }
+
+    /// Sends the batch to the cluster.
+    fn execute_batch_future(&self, batch: &Batch) -> DriverFuture<QueryResult> {
+        let future =
+            unsafe { cass_session_execute_batch(self.inner(), batch.inner()) };
+
+        DriverFuture::new(future, self.clone())
+    }
+
+    /// Prepares the query on the cluster.
+    pub async fn prepare<T>(&self, query: T) -> Result<Prepared, DriverError>
+    where
+        T: Into<String>,
+    {
+        let _guard = self.enter()?;
+        let query = query.into();
+        let future = self.prepare_future(&query);
+
+        let prepared = self
+            .track_request(future, RequestInfo::prepare(&query))
+            .await?;
+
+        Ok(prepared.with_query(query))
+    }
+
+    /// Prepares the query blocking the current thread, see
+    /// [`Session::prepare`].
+    pub(crate) fn prepare_blocking<T>(
+        &self,
+        query: T,
+        timeout: Option<Duration>,
+    ) -> Result<Prepared, DriverError>
+    where
+        T: Into<String>,
+    {
+        let _guard = self.enter()?;
+        let query = query.into();
+        let future = self.prepare_future(&query);
+
+        let prepared = self.track_request_blocking(
+            future,
+            RequestInfo::prepare(&query),
+            timeout,
+        )?;
+
+        Ok(prepared.with_query(query))
+    }
+
+    /// Sends the query to be prepared to the cluster.
+    fn prepare_future(&self, query: &str) -> DriverFuture<Prepared> {
+        let query_len = query.len();
+        let query_ptr = query.as_ptr() as *const c_char;
+        let future = unsafe {
+            cass_session_prepare_n(self.inner(), query_ptr, query_len)
+        };
+
+        DriverFuture::new(future, self.clone())
+    }
+
+    /// Reads the server-side trace of a request.
+    ///
+    /// The request must be traced with [`Statement::set_tracing`] or
+    /// [`Batch::set_tracing`], its tracing identifier is returned with
+    /// [`QueryResult::tracing_id`].
+    ///
+    /// The cluster writes the traces asynchronously, so the trace is polled
+    /// according to [`Cluster::set_tracing_max_wait_time`],
+    /// [`Cluster::set_tracing_retry_wait_time`] and
+    /// [`Cluster::set_tracing_consistency`]. Fails with
+    /// [`DriverErrorKind::LibRequestTimedOut`] if the trace is not complete
+    /// within the maximum wait time.
+    ///
+    /// [`DriverErrorKind::LibRequestTimedOut`]: crate::DriverErrorKind::LibRequestTimedOut
+    pub async fn fetch_trace(
+        &self,
+        id: CqlUuid,
+    ) -> Result<QueryTrace, DriverError> {
+        fetch_trace(self, id, self.0.settings.trace_settings).await
+    }
+
+    /// Closes the session.
+    ///
+    /// The session stops accepting new requests, they fail with
+    /// [`DriverErrorKind::LibNoHostsAvailable`]. The requests in flight are
+    /// given up to `timeout` to complete, then the connections are closed and
+    /// the requests still in flight fail. They are not waited for if the
+    /// timer thread of the crate cannot be started.
+    ///
+    /// Fails with [`DriverErrorKind::LibUnableToClose`] if the session is not
+    /// connected or the driver fails to close it.
+    pub async fn close(&self, timeout: Duration) -> Result<(), DriverError> {
+        let drained = Box::pin(self.0.gate.close());
+        if let Either::Right(_) =
+            select(drained, Box::pin(sleep(timeout))).await
+        {
+            self.warn_in_flight();
+        }
+
+        self.close_future().await.map_err(close_error)?;
+        self.0.closed.store(true, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Closes the session blocking the current thread, see
+    /// [`Session::close`].
+    pub(crate) fn close_blocking(
+        &self,
+        timeout: Duration,
+    ) -> Result<(), DriverError> {
+        if !self.0.gate.close_blocking(timeout) {
+            self.warn_in_flight();
+        }
+
+        self.close_future().wait_for_result().map_err(close_error)?;
+        self.0.closed.store(true, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Starts closing the connections of the session.
+    fn close_future(&self) -> DriverFuture<()> {
+        let future = unsafe { cass_session_close(self.inner()) };
+
+        DriverFuture::new(future, self.clone())
+    }
+
+    /// Reports the requests that are still in flight when the session is
+    /// closed.
+    fn warn_in_flight(&self) {
+        tracing::warn!(
+            target: "cassander::session",
+            in_flight = self.0.gate.in_flight(),
+            "closing the session with requests in flight"
+        );
+    }
+
+    /// Admits a new request, fails if the session is closed.
+    fn enter(&self) -> Result<RequestGuard, DriverError> {
+        self.0.gate.enter().ok_or_else(|| {
+            DriverError::with_message(
+                DriverErrorKind::LibNoHostsAvailable,
+                "the session is closed",
+            )
+        })
+    }
+
+    /// Waits for the request to complete within its span and updates the
+    /// request counters.
+    async fn track_request<R>(
+        &self,
+        future: DriverFuture<R>,
+        request: RequestInfo<'_>,
+    ) -> Result<R, DriverError>
+    where
+        R: DriverFutureResult + RequestOutcome,
+    {
+        let span = self.start_request(&request);
+        let result = future.instrument(span.clone()).await;
+        self.finish_request(&span, request, &result);
+
+        result
+    }
+
+    /// Blocks the current thread until the request completes within its span
+    /// and updates the request counters.
+    ///
+    /// Fails with [`DriverErrorKind::LibRequestTimedOut`] if the request does
+    /// not complete within the timeout.
+    fn track_request_blocking<R>(
+        &self,
+        future: DriverFuture<R>,
+        request: RequestInfo<'_>,
+        timeout: Option<Duration>,
+    ) -> Result<R, DriverError>
+    where
+        R: DriverFutureResult + RequestOutcome,
+    {
+        let span = self.start_request(&request);
+        let future = match timeout {
+            Some(timeout) => future.with_timeout(timeout),
+            None => future,
+        };
+        let result = span.in_scope(|| future.wait_for_result());
+        self.finish_request(&span, request, &result);
+
+        result
+    }
+
+    /// Creates the span of the request and counts the request.
+    ///
+    /// The executions of statements and batches are counted, the prepare
+    /// requests are only traced.
+    fn start_request(&self, request: &RequestInfo<'_>) -> Span {
+        let settings = &self.0.settings;
+        let span = request
+            .span(settings.keyspace.as_deref(), settings.span_statement_mode);
+
+        if request.kind != RequestKind::Prepare {
+            let after_failure = request
+                .last_attempt_failed
+                .is_some_and(|failed| failed.load(Ordering::Relaxed));
+            let is_next_page = request.page_number.is_some_and(|page| page > 1);
+            self.0.counters.record_request(
+                request.consistency,
+                after_failure,
+                is_next_page,
+            );
+        }
+
+        span
+    }
+
+    /// Records the outcome of the request in its span and counts the errors.
+    fn finish_request<R>(
+        &self,
+        span: &Span,
+        request: RequestInfo<'_>,
+        result: &Result<R, DriverError>,
+    ) where
+        R: RequestOutcome,
+    {
+        match result {
+            Ok(outcome) => {
+                record_success(span, outcome);
+                request.report_warnings(
+                    span,
+                    self.0.settings.span_statement_mode,
+                    outcome.warnings(),
+                );
+            }
+            Err(error) => {
+                record_error(span, error);
+                if request.kind != RequestKind::Prepare {
+                    self.0.counters.record_error(error.kind);
+                }
+            }
+        }
+        if let Some(failed) = request.last_attempt_failed {
+            failed.store(result.is_err(), Ordering::Relaxed);
+        }
+    }
+
+    /// Returns a snapshot of the request counters maintained by this crate.
+    ///
+    /// The counters complement the driver metrics returned by
+    /// [`Session::metrics`].
+    pub fn request_counters(&self) -> RequestCounters {
+        self.0.counters.snapshot()
+    }
+
+    /// Gets a snapshot of the session performance metrics.
+    ///
+    /// The metrics are collected across all the connections of the session
+    /// since it was connected.
+    pub fn metrics(&self) -> SessionMetrics {
+        let mut metrics = MaybeUninit::<struct_CassMetrics_>::zeroed();
+        let mut speculative_executions =
+            MaybeUninit::<struct_CassSpeculativeExecutionMetrics_>::zeroed();
+
+        let (metrics, speculative_executions) = unsafe {
+            cass_session_get_metrics(self.inner(), metrics.as_mut_ptr());
+            cass_session_get_speculative_execution_metrics(
+                self.inner(),
+                speculative_executions.as_mut_ptr(),
+            );
+
+            (metrics.assume_init(), speculative_executions.assume_init())
+        };
+
+        SessionMetrics::from_driver(&metrics, &speculative_executions)
+    }
+
+    /// Gets a snapshot of the schema metadata.
+    ///
+    /// The returned snapshot is immutable: the changes made to the schema after
+    /// this call are not visible through it.
+    ///
+    /// The snapshot is empty if the session is not connected or if retrieving
+    /// the schema metadata was disabled with [`Cluster::set_use_schema`].
+    ///
+    /// [`Cluster::set_use_schema`]: crate::Cluster::set_use_schema
+    pub fn get_schema_meta(&self) -> SchemaMeta {
+        let meta = unsafe { cass_session_get_schema_meta(self.inner()) };
+
+        SchemaMeta::from_driver(meta)
+    }
+
+    /// Returns a stream of the schema changes detected by the control
+    /// connection.
+    ///
+    /// The schema metadata snapshot is checked for changes every `interval`.
+    /// The changes are detected by comparing the snapshots, so only the
+    /// changes made after this call are reported. Several changes made to the
+    /// same schema element within one interval may be reported as a single
+    /// event.
+    ///
+    /// The schema metadata must be enabled with [`Cluster::set_use_schema`]
+    /// (this is the default) for the stream to produce any events.
+    ///
+    /// The stream does not keep the session alive, it ends once the session
+    /// is dropped. An interval shorter than 10 milliseconds is raised to 10
+    /// milliseconds. Fails if the thread that watches the schema cannot be
+    /// spawned.
+    ///
+    /// [`Cluster::set_use_schema`]: crate::Cluster::set_use_schema
+    pub fn schema_events(
+        &self,
+        interval: Duration,
+    ) -> Result<SchemaEventStream, DriverError> {
+        SchemaEventStream::new(self, interval)
+    }
+
+    /// Returns a scan of all the rows of the table by ranges of the token
+    /// ring, see [`TableScan`].
+    ///
+    /// ```rust,ignore
+    /// let scan = session.scan_table("ks", "events").with_max_in_flight(16);
+    /// let mut rows = pin!(scan.rows());
+    /// while let Some(row) = rows.next().await {
+    ///     process(row?);
+    /// }
+    /// save(scan.checkpoint());
+    /// ```
+    pub fn scan_table<K, T>(&self, keyspace: K, table: T) -> TableScan
+    where
+        K: Into<String>,
+        T: Into<String>,
+    {
+        TableScan::new(self.clone(), keyspace.into(), table.into())
+    }
+}
+
+/// Reports the failure to close a session as
+/// [`DriverErrorKind::LibUnableToClose`].
+fn close_error(error: DriverError) -> DriverError {
+    match error.kind {
+        DriverErrorKind::LibUnableToClose => error,
+        _ => DriverError {
+            kind: DriverErrorKind::LibUnableToClose,
+            message: format!("failed to close the session: {}", error.message),
+            ..error
+        },
+    }
 }
 
 impl Default for Session {
@@ -36,21 +683,112 @@ impl Default for Session {
     }
 }
 
-#[repr(transparent)]
-struct SessionWrapper(*mut struct_CassSession_);
+struct SessionWrapper {
+    /// The driver's session object.
+    inner:    *mut struct_CassSession_,
+    /// The identifier of the session, unique within the process.
+    id:       u64,
+    /// The settings copied from the cluster.
+    ///
+    /// The targets of the cluster callbacks are dropped after the session is
+    /// freed.
+    settings: SessionSettings,
+    /// The request counters maintained by this crate.
+    counters: RequestCounterSet,
+    /// Admits the requests until the session is closed.
+    gate:     RequestGate,
+    /// Whether the session was closed with [`Session::close`].
+    closed:   AtomicBool,
+    /// Whether the session was asked to connect to a cluster.
+    started:  AtomicBool,
+}
+
+/// The settings of a session copied from the cluster it is connected to.
+#[derive(Default)]
+struct SessionSettings {
+    /// The targets of the cluster callbacks the session may call.
+    _callback_data:      Vec<CallbackData>,
+    /// The application name set on the cluster.
+    application_name:    Option<String>,
+    /// The keyspace the session was connected with.
+    keyspace:            Option<String>,
+    /// How the statement text is recorded in the request spans.
+    span_statement_mode: SpanStatementMode,
+    /// How the query traces are read.
+    trace_settings:      TraceSettings,
+    /// Wakes the tasks waiting for the requests.
+    wake_dispatcher:     Option<Arc<dyn WakeDispatcher>>,
+    /// The size of the request queue, [`None`] if it is the default one.
+    queue_size_io:       Option<usize>,
+}
 
 impl SessionWrapper {
     /// Returns the raw pointer to the session object.
     pub fn inner(&self) -> *mut struct_CassSession_ {
-        self.0
+        self.inner
     }
 }
 
 impl Drop for SessionWrapper {
     fn drop(&mut self) {
-        unsafe { cass_session_free(self.inner()) }
+        let session = FreeOnDrop {
+            inner:     self.inner,
+            _settings: std::mem::take(&mut self.settings),
+        };
+        // A session that is closed or was never asked to connect has no
+        // connections to wait for.
+        if self.closed.load(Ordering::Acquire)
+            || !self.started.load(Ordering::Acquire)
+        {
+            drop(session);
+            return;
+        }
+
+        // Freeing a session that is not closed blocks until the connections
+        // are closed, which must not happen on an executor thread. If the
+        // thread cannot be spawned, the closure is dropped with the session,
+        // which frees it on the current thread.
+        let spawned = thread::Builder::new()
+            .name("cassander-session-free".to_string())
+            .spawn(move || drop(session));
+        if let Err(error) = spawned {
+            tracing::warn!(
+                target: "cassander::session",
+                %error,
+                "failed to spawn the thread that frees the session, freed it \
+                 on the current thread"
+            );
+        }
+    }
+}
+
+/// Frees the session, then drops the targets of the cluster callbacks it may
+/// call.
+struct FreeOnDrop {
+    /// The driver's session object.
+    inner:     *mut struct_CassSession_,
+    /// The settings copied from the cluster.
+    _settings: SessionSettings,
+}
+
+impl Drop for FreeOnDrop {
+    fn drop(&mut self) {
+        unsafe { cass_session_free(self.inner) }
     }
 }
 
+unsafe impl Send for FreeOnDrop {}
+
 unsafe impl Send for SessionWrapper {}
 unsafe impl Sync for SessionWrapper {}
+
+/// A weak reference to a session that does not keep the session alive.
+#[derive(Clone)]
+pub(crate) struct WeakSession(Weak<SessionWrapper>);
+
+impl WeakSession {
+    /// Returns the session if it is still alive.
+    pub(crate) fn upgrade(&self) -> Option<Session> {
+        self.0.upgrade().map(Session)
+    }
+}
@@ -1,6 +1,7 @@
 use std::ffi::c_char;
 
 use crate::ffi::{
+    cass_ssl_add_trusted_cert_n,
     cass_ssl_free,
     cass_ssl_new,
     cass_ssl_new_no_lib_init,
@@ -57,7 +58,9 @@ impl Ssl {
 
     /// Adds a trusted certificate in the PEM format.
     ///
-    /// This is used to verify the peer's certificate.
+    /// This is used to verify the peer's certificate. Only the first
+    /// certificate of a PEM bundle is added, add the certificates of a bundle
+    /// one by one.
     pub fn add_trusted_cert<T>(&mut self, cert: T) -> Result<(), DriverError>
     where
         T: AsRef<str>,
@@ -65,7 +68,8 @@ impl Ssl {
         let cert = cert.as_ref();
         let len = cert.len();
         let ptr = cert.as_ptr() as *const c_char;
-        let code = unsafe { cass_ssl_set_cert_n(self.inner(), ptr, len) };
+        let code =
+            unsafe { cass_ssl_add_trusted_cert_n(self.inner(), ptr, len) };
 
         to_result(code)
     }
@@ -5,8 +5,25 @@ use std::ops::{
 
 use crate::ffi::enum_CassSslVerifyFlags_;
 
+/// The names of the flags, see [`SslVerifyFlags::from_names`].
+const FLAG_NAMES: [(&str, SslVerifyFlags); 4] = [
+    ("NONE", SslVerifyFlags::NONE),
+    ("PEER_CERT", SslVerifyFlags::PEER_CERT),
+    ("PEER_IDENTITY", SslVerifyFlags::PEER_IDENTITY),
+    ("PEER_IDENTITY_DNS", SslVerifyFlags::PEER_IDENTITY_DNS),
+];
+
 /// SSL verification flags.
+///
+/// When the `serde` feature is enabled, the flags are serialized into the list
+/// of their names, e.g. `["PEER_CERT", "PEER_IDENTITY"]`, and deserialized
+/// from it.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "Vec<String>", try_from = "Vec<String>")
+)]
 pub struct SslVerifyFlags(u32);
 
 impl SslVerifyFlags {
@@ -24,6 +41,42 @@ impl SslVerifyFlags {
     /// hostname resolution is enabled.
     pub const PEER_IDENTITY_DNS: SslVerifyFlags = SslVerifyFlags(0x4);
 
+    /// Combines the flags with the given names, e.g. `PEER_CERT`.
+    ///
+    /// The names are case-insensitive. Returns [`None`] if a name is unknown.
+    pub fn from_names<I, T>(names: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        names
+            .into_iter()
+            .try_fold(SslVerifyFlags::NONE, |flags, name| {
+                let name = name.as_ref().trim();
+                FLAG_NAMES
+                    .iter()
+                    .find(|(flag_name, _)| flag_name.eq_ignore_ascii_case(name))
+                    .map(|(_, flag)| flags | *flag)
+            })
+    }
+
+    /// Returns the names of the flags that are set.
+    ///
+    /// The name of empty flags is `NONE`.
+    pub fn names(self) -> Vec<&'static str> {
+        if self == SslVerifyFlags::NONE {
+            return vec!["NONE"];
+        }
+
+        FLAG_NAMES
+            .iter()
+            .filter(|(_, flag)| {
+                *flag != SslVerifyFlags::NONE && self & *flag == *flag
+            })
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
     /// Returns the inner value of the flags.
     pub(crate) fn inner(self) -> u32 {
         self.0
@@ -57,3 +110,21 @@ impl Default for SslVerifyFlags {
         SslVerifyFlags::PEER_CERT
     }
 }
+
+impl TryFrom<Vec<String>> for SslVerifyFlags {
+    type Error = String;
+
+    /// Combines the flags with the given names.
+    fn try_from(names: Vec<String>) -> Result<Self, Self::Error> {
+        SslVerifyFlags::from_names(&names).ok_or_else(|| {
+            format!("unknown SSL verify flags in {}", names.join(", "))
+        })
+    }
+}
+
+impl From<SslVerifyFlags> for Vec<String> {
+    /// Returns the names of the flags that are set.
+    fn from(flags: SslVerifyFlags) -> Self {
+        flags.names().into_iter().map(str::to_string).collect()
+    }
+}
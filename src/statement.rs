@@ -0,0 +1,439 @@
+use std::borrow::Cow;
+use std::ffi::c_char;
+use std::sync::atomic::AtomicBool;
+
+use crate::cql::{
+    CqlValue,
+    ValueSink,
+};
+use crate::ffi::{
+    cass_prepared_parameter_data_type,
+    cass_prepared_parameter_data_type_by_name_n,
+    cass_statement_free,
+    cass_statement_new_n,
+    cass_statement_set_consistency,
+    cass_statement_set_custom_payload,
+    cass_statement_set_execution_profile_n,
+    cass_statement_set_is_idempotent,
+    cass_statement_set_keyspace_n,
+    cass_statement_set_node,
+    cass_statement_set_paging_size,
+    cass_statement_set_paging_state_token,
+    cass_statement_set_request_timeout,
+    cass_statement_set_retry_policy,
+    cass_statement_set_serial_consistency,
+    cass_statement_set_timestamp,
+    cass_statement_set_tracing,
+    struct_CassStatement_,
+};
+use crate::request::{
+    RequestInfo,
+    RequestKind,
+};
+use crate::{
+    to_result,
+    Consistency,
+    Coordinator,
+    CustomPayload,
+    DriverError,
+    DriverErrorKind,
+    Prepared,
+    RetryPolicy,
+};
+
+/// A statement object is an executable query.
+///
+/// The statement settings override the settings of the execution profile and
+/// the cluster.
+pub struct Statement {
+    /// The driver's statement object.
+    inner:               *mut struct_CassStatement_,
+    /// The query string of the statement.
+    query:               String,
+    /// The consistency level set on the statement.
+    consistency:         Option<Consistency>,
+    /// The keyspace set on the statement.
+    keyspace:            Option<String>,
+    /// The number of the page the statement fetches, starting from 1.
+    ///
+    /// The number is increased every time a paging state is set.
+    page_number:         u64,
+    /// Whether the last execution of the statement failed.
+    last_attempt_failed: AtomicBool,
+    /// The prepared statement the statement was bound from, it provides the
+    /// data types of the bind variables.
+    prepared:            Option<Prepared>,
+}
+
+impl Statement {
+    /// Creates a new query statement.
+    ///
+    /// The `parameter_count` is the number of bind variables in the query.
+    pub fn new<T>(query: T, parameter_count: usize) -> Self
+    where
+        T: Into<String>,
+    {
+        let query = query.into();
+        let query_len = query.len();
+        let query_ptr = query.as_ptr() as *const c_char;
+        let inner = unsafe {
+            cass_statement_new_n(query_ptr, query_len, parameter_count)
+        };
+
+        Self::from_driver(inner, query)
+    }
+
+    /// Creates a new statement from the driver object and its query string.
+    pub(crate) fn from_driver(
+        inner: *mut struct_CassStatement_,
+        query: String,
+    ) -> Self {
+        assert!(
+            !inner.is_null(),
+            "the driver's statement object must not be null"
+        );
+
+        Self {
+            inner,
+            query,
+            consistency: None,
+            keyspace: None,
+            page_number: 1,
+            last_attempt_failed: AtomicBool::new(false),
+            prepared: None,
+        }
+    }
+
+    /// Creates a new bound statement from the driver object and the prepared
+    /// statement it was bound from.
+    pub(crate) fn from_prepared(
+        inner: *mut struct_CassStatement_,
+        prepared: &Prepared,
+    ) -> Self {
+        let mut statement =
+            Self::from_driver(inner, prepared.query().to_string());
+        statement.prepared = Some(prepared.clone());
+
+        statement
+    }
+
+    /// Returns the raw pointer to the statement object.
+    pub(crate) fn inner(&self) -> *mut struct_CassStatement_ {
+        self.inner
+    }
+
+    /// Returns the query string of the statement.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the description of the statement execution request.
+    pub(crate) fn request_info(&self) -> RequestInfo<'_> {
+        RequestInfo {
+            kind:                RequestKind::Execute,
+            statement:           Cow::Borrowed(&self.query),
+            keyspace:            self.keyspace.as_deref(),
+            consistency:         self.consistency,
+            page_number:         Some(self.page_number),
+            last_attempt_failed: Some(&self.last_attempt_failed),
+        }
+    }
+
+    /// Binds the value to the bind variable at the given index.
+    ///
+    /// The user-defined type values can only be bound to the statements
+    /// created with [`Prepared::bind`], since the driver needs the data type
+    /// of the bind variable.
+    pub fn bind<T>(&mut self, index: usize, value: T) -> Result<(), DriverError>
+    where
+        T: Into<CqlValue>,
+    {
+        let data_type = match &self.prepared {
+            Some(prepared) => unsafe {
+                cass_prepared_parameter_data_type(prepared.inner(), index)
+            },
+            None => std::ptr::null(),
+        };
+
+        value
+            .into()
+            .write_to(ValueSink::Index(self.inner(), index), data_type)
+    }
+
+    /// Binds the value to the bind variable with the given name.
+    ///
+    /// The names of the bind variables are known only for prepared statements
+    /// and for the named bind markers, e.g. `:name`, of the simple statements.
+    /// The user-defined type values can only be bound to the statements
+    /// created with [`Prepared::bind`].
+    pub fn bind_by_name<N, T>(
+        &mut self,
+        name: N,
+        value: T,
+    ) -> Result<(), DriverError>
+    where
+        N: AsRef<str>,
+        T: Into<CqlValue>,
+    {
+        let name = name.as_ref();
+        let data_type = match &self.prepared {
+            Some(prepared) => unsafe {
+                cass_prepared_parameter_data_type_by_name_n(
+                    prepared.inner(),
+                    name.as_ptr() as *const c_char,
+                    name.len(),
+                )
+            },
+            None => std::ptr::null(),
+        };
+
+        value
+            .into()
+            .write_to(ValueSink::Name(self.inner(), name), data_type)
+    }
+
+    /// Sets the statement's keyspace.
+    ///
+    /// This is used for token-aware routing and when using protocol v5 or DSE
+    /// protocol v2 to execute the statement in a keyspace other than the
+    /// session's keyspace.
+    pub fn set_keyspace<T>(&mut self, keyspace: T) -> Result<(), DriverError>
+    where
+        T: AsRef<str>,
+    {
+        let keyspace = keyspace.as_ref();
+        let keyspace_len = keyspace.len();
+        let keyspace_ptr = keyspace.as_ptr() as *const c_char;
+        let code = unsafe {
+            cass_statement_set_keyspace_n(
+                self.inner(),
+                keyspace_ptr,
+                keyspace_len,
+            )
+        };
+        to_result::<()>(code)?;
+
+        self.keyspace = Some(keyspace.to_string());
+
+        Ok(())
+    }
+
+    /// Sets the statement's consistency level.
+    pub fn set_consistency(
+        &mut self,
+        consistency: Consistency,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_statement_set_consistency(
+                self.inner(),
+                consistency.to_driver(),
+            )
+        };
+        to_result::<()>(code)?;
+
+        self.consistency = Some(consistency);
+
+        Ok(())
+    }
+
+    /// Sets the statement's serial consistency level.
+    pub fn set_serial_consistency(
+        &mut self,
+        consistency: Consistency,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_statement_set_serial_consistency(
+                self.inner(),
+                consistency.to_driver(),
+            )
+        };
+
+        to_result(code)
+    }
+
+    /// Sets the statement's page size.
+    ///
+    /// The default value is 5000 rows, `None` disables paging.
+    pub fn set_paging_size(
+        &mut self,
+        page_size: Option<usize>,
+    ) -> Result<(), DriverError> {
+        let page_size = match page_size {
+            Some(page_size) => page_size.try_into().map_err(|_| {
+                DriverError::with_kind(DriverErrorKind::LibBadParams)
+            })?,
+            None => -1,
+        };
+
+        let code =
+            unsafe { cass_statement_set_paging_size(self.inner(), page_size) };
+
+        to_result(code)
+    }
+
+    /// Sets the statement's paging state to continue from the page the token
+    /// was returned with.
+    ///
+    /// See [`QueryResult::paging_state_token`].
+    ///
+    /// [`QueryResult::paging_state_token`]: crate::QueryResult::paging_state_token
+    pub fn set_paging_state_token(
+        &mut self,
+        token: &[u8],
+    ) -> Result<(), DriverError> {
+        let ptr = token.as_ptr() as *const c_char;
+        let code = unsafe {
+            cass_statement_set_paging_state_token(
+                self.inner(),
+                ptr,
+                token.len(),
+            )
+        };
+        to_result::<()>(code)?;
+
+        self.page_number = if token.is_empty() {
+            1
+        } else {
+            self.page_number + 1
+        };
+
+        Ok(())
+    }
+
+    /// Sets the statement's timestamp in microseconds since the Unix epoch.
+    pub fn set_timestamp(&mut self, timestamp: i64) -> Result<(), DriverError> {
+        let code =
+            unsafe { cass_statement_set_timestamp(self.inner(), timestamp) };
+
+        to_result(code)
+    }
+
+    /// Sets the statement's timeout in milliseconds for waiting for a response
+    /// from a node.
+    ///
+    /// A zero timeout disables it.
+    pub fn set_request_timeout(
+        &mut self,
+        timeout: i64,
+    ) -> Result<(), DriverError> {
+        let timeout = timeout.try_into().map_err(|_| {
+            DriverError::with_kind(DriverErrorKind::LibBadParams)
+        })?;
+
+        let code = unsafe {
+            cass_statement_set_request_timeout(self.inner(), timeout)
+        };
+
+        to_result(code)
+    }
+
+    /// Sets whether the statement is idempotent.
+    ///
+    /// Idempotent statements are able to be automatically retried after
+    /// timeouts/errors and can be speculatively executed.
+    ///
+    /// The default value is `false`.
+    pub fn set_is_idempotent(
+        &mut self,
+        is_idempotent: bool,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_statement_set_is_idempotent(self.inner(), is_idempotent.into())
+        };
+
+        to_result(code)
+    }
+
+    /// Sets the statement's retry policy.
+    pub fn set_retry_policy(
+        &mut self,
+        policy: &RetryPolicy,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_statement_set_retry_policy(self.inner(), policy.inner())
+        };
+
+        to_result(code)
+    }
+
+    /// Sets the custom payload sent with the statement.
+    ///
+    /// The payload is copied, so it can be modified or reused once this
+    /// method is called.
+    pub fn set_custom_payload(
+        &mut self,
+        payload: &CustomPayload,
+    ) -> Result<(), DriverError> {
+        let code = unsafe {
+            cass_statement_set_custom_payload(self.inner(), payload.inner())
+        };
+
+        to_result(code)
+    }
+
+    /// Sets the node the statement is sent to.
+    ///
+    /// The load balancing policy is bypassed. If the node is down or
+    /// unavailable the execution fails, the statement is not sent to another
+    /// node.
+    pub fn set_node(&mut self, node: &Coordinator) -> Result<(), DriverError> {
+        let code =
+            unsafe { cass_statement_set_node(self.inner(), node.inner()) };
+
+        to_result(code)
+    }
+
+    /// Sets whether the statement is traced by the cluster.
+    ///
+    /// The tracing identifier of a traced statement is returned with
+    /// [`QueryResult::tracing_id`], use it with [`Session::fetch_trace`] to
+    /// read the trace.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`QueryResult::tracing_id`]: crate::QueryResult::tracing_id
+    /// [`Session::fetch_trace`]: crate::Session::fetch_trace
+    pub fn set_tracing(&mut self, enabled: bool) -> Result<(), DriverError> {
+        let code =
+            unsafe { cass_statement_set_tracing(self.inner(), enabled.into()) };
+
+        to_result(code)
+    }
+
+    /// Sets the execution profile to execute the statement with.
+    ///
+    /// The profile must be registered on the cluster with
+    /// [`Cluster::set_execution_profile`], otherwise the execution fails.
+    /// Passing an empty name resets the statement to the default profile.
+    ///
+    /// [`Cluster::set_execution_profile`]: crate::Cluster::set_execution_profile
+    pub fn set_execution_profile<T>(
+        &mut self,
+        name: T,
+    ) -> Result<(), DriverError>
+    where
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+        let name_len = name.len();
+        let name_ptr = name.as_ptr() as *const c_char;
+        let code = unsafe {
+            cass_statement_set_execution_profile_n(
+                self.inner(),
+                name_ptr,
+                name_len,
+            )
+        };
+
+        to_result(code)
+    }
+}
+
+unsafe impl Send for Statement {}
+unsafe impl Sync for Statement {}
+
+impl Drop for Statement {
+    fn drop(&mut self) {
+        unsafe { cass_statement_free(self.inner()) }
+    }
+}
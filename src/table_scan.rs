@@ -0,0 +1,834 @@
+use std::fmt::{
+    self,
+    Display,
+    Formatter,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::future;
+use futures::stream::{
+    self,
+    Stream,
+    StreamExt,
+};
+use parking_lot::Mutex;
+use thiserror::Error;
+
+use crate::concurrent::{
+    is_transient,
+    next_retry_delay,
+    retry_on_queue_full,
+};
+use crate::cql::{
+    quote_identifier,
+    CqlValue,
+};
+use crate::timer::sleep;
+use crate::{
+    Consistency,
+    DriverError,
+    DriverErrorKind,
+    Prepared,
+    QueryResult,
+    Session,
+    Statement,
+};
+
+/// A range of the Murmur3 token ring, from `start` exclusive to `end`
+/// inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TokenRange {
+    /// The token before the first token of the range.
+    pub start: i64,
+    /// The last token of the range.
+    pub end:   i64,
+}
+
+impl TokenRange {
+    /// Returns the range of the whole ring.
+    ///
+    /// The minimum token, `i64::MIN`, is never assigned to a partition.
+    pub fn full_ring() -> Self {
+        Self {
+            start: i64::MIN,
+            end:   i64::MAX,
+        }
+    }
+
+    /// Splits the range into at most `count` contiguous ranges of about the
+    /// same size.
+    pub fn split(&self, count: usize) -> Vec<TokenRange> {
+        let start = i128::from(self.start);
+        let width = i128::from(self.end) - start;
+        let count = (count.max(1) as i128).min(width.max(1));
+
+        let bounds: Vec<i64> = (0..=count)
+            .map(|index| (start + width * index / count) as i64)
+            .collect();
+
+        bounds
+            .windows(2)
+            .map(|pair| TokenRange {
+                start: pair[0],
+                end:   pair[1],
+            })
+            .collect()
+    }
+}
+
+impl Display for TokenRange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}]", self.start, self.end)
+    }
+}
+
+/// Splits the ring into at least `count` ranges, each of them owned by a
+/// single token of a node.
+///
+/// The ranges between the tokens are split evenly, and the range that wraps
+/// around the ring is split at the minimum token.
+fn align_ranges(mut tokens: Vec<i64>, count: usize) -> Vec<TokenRange> {
+    tokens.sort_unstable();
+    tokens.dedup();
+    let (Some(&first), Some(&last)) = (tokens.first(), tokens.last()) else {
+        return TokenRange::full_ring().split(count);
+    };
+
+    let mut ranges = Vec::with_capacity(tokens.len() + 1);
+    if first != i64::MIN {
+        ranges.push(TokenRange {
+            start: i64::MIN,
+            end:   first,
+        });
+    }
+    ranges.extend(tokens.windows(2).map(|pair| TokenRange {
+        start: pair[0],
+        end:   pair[1],
+    }));
+    if last != i64::MAX {
+        ranges.push(TokenRange {
+            start: last,
+            end:   i64::MAX,
+        });
+    }
+
+    let per_range = count.div_ceil(ranges.len()).max(1);
+    ranges
+        .iter()
+        .flat_map(|range| range.split(per_range))
+        .collect()
+}
+
+/// The progress of the scan of a range, see [`ScanCheckpoint`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RangeProgress {
+    /// The range.
+    pub range:      TokenRange,
+    /// The token of the last row yielded from the range.
+    pub last_token: Option<i64>,
+    /// Whether all the rows of the range were yielded.
+    pub done:       bool,
+}
+
+impl RangeProgress {
+    /// Creates the progress of a range not scanned yet.
+    pub fn new(range: TokenRange) -> Self {
+        Self {
+            range,
+            last_token: None,
+            done: false,
+        }
+    }
+}
+
+/// The progress of a [`TableScan`], used to resume the scan.
+///
+/// A range is resumed from the token of the last row yielded from it
+/// inclusive, so the rows of the partition the scan stopped in are yielded
+/// again.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScanCheckpoint {
+    /// The progress of the ranges, in the order of the ring.
+    ranges: Vec<RangeProgress>,
+}
+
+impl ScanCheckpoint {
+    /// Returns the progress of the ranges, empty if the scan has not started.
+    pub fn ranges(&self) -> &[RangeProgress] {
+        &self.ranges
+    }
+
+    /// Returns whether all the ranges were scanned.
+    pub fn is_complete(&self) -> bool {
+        !self.ranges.is_empty() && self.ranges.iter().all(|range| range.done)
+    }
+
+    /// Returns the ranges that are not scanned yet.
+    fn pending(&self) -> Vec<PendingRange> {
+        self.ranges
+            .iter()
+            .enumerate()
+            .filter(|(_, progress)| !progress.done)
+            .map(|(index, progress)| PendingRange {
+                index,
+                range: progress.range,
+                start: progress
+                    .last_token
+                    .map_or(progress.range.start, |token| {
+                        token.saturating_sub(1).max(progress.range.start)
+                    }),
+            })
+            .collect()
+    }
+
+    /// Updates the progress with the event of a range.
+    fn update(&mut self, event: &ScanEvent) {
+        let (index, last_token, done) = match event {
+            ScanEvent::Row(row) => (row.index, Some(row.token), false),
+            ScanEvent::Done(index) => (*index, None, true),
+            ScanEvent::Failed(_) => return,
+        };
+
+        if let Some(progress) = self.ranges.get_mut(index) {
+            progress.last_token = last_token.or(progress.last_token);
+            progress.done |= done;
+        }
+    }
+}
+
+impl From<Vec<RangeProgress>> for ScanCheckpoint {
+    fn from(ranges: Vec<RangeProgress>) -> Self {
+        Self {
+            ranges,
+        }
+    }
+}
+
+/// A row yielded by a [`TableScan`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanRow {
+    /// The index of the range in the checkpoint.
+    index:  usize,
+    /// The range the row belongs to.
+    range:  TokenRange,
+    /// The token of the partition key of the row.
+    token:  i64,
+    /// The values of the columns.
+    values: Vec<CqlValue>,
+}
+
+impl ScanRow {
+    /// Returns the range the row belongs to.
+    pub fn range(&self) -> TokenRange {
+        self.range
+    }
+
+    /// Returns the token of the partition key of the row.
+    pub fn token(&self) -> i64 {
+        self.token
+    }
+
+    /// Returns the values of the columns, in the order of the columns of the
+    /// scan.
+    pub fn values(&self) -> &[CqlValue] {
+        &self.values
+    }
+
+    /// Returns the values of the columns, in the order of the columns of the
+    /// scan.
+    pub fn into_values(self) -> Vec<CqlValue> {
+        self.values
+    }
+}
+
+/// An error of a [`TableScan`].
+#[derive(Error, Debug, Clone)]
+#[error(
+    "failed to scan {}: {error}",
+    .range.map_or("the table".to_string(), |range| format!("the token range {}", range))
+)]
+pub struct ScanError {
+    /// The range that failed after the retries, none if the scan failed to
+    /// start.
+    pub range: Option<TokenRange>,
+    /// The error of the last attempt.
+    pub error: DriverError,
+}
+
+/// A scan of all the rows of a table by ranges of the token ring, see
+/// [`Session::scan_table`].
+///
+/// The ring is split into ranges that are scanned concurrently with
+/// `SELECT ... WHERE token(pk) > ? AND token(pk) <= ?` queries, page by page.
+/// The rows are yielded as they arrive, the rows of a range in the token
+/// order and the ranges interleaved. A page that fails with a transient
+/// error, e.g. a timeout, is retried with an exponential backoff, a range
+/// that still fails yields a [`ScanError`] and the other ranges go on.
+///
+/// The progress of the scan is tracked in a [`ScanCheckpoint`], which can be
+/// saved to resume the scan later with [`TableScan::with_checkpoint`]. The
+/// rows are yielded at least once: when a scan is resumed, the rows of the
+/// partition a range stopped in are yielded again.
+///
+/// The table must be known to the schema metadata of the session, and the
+/// cluster must use the `Murmur3Partitioner`, the ranges of the other
+/// partitioners are not ranges of 64-bit integers.
+pub struct TableScan {
+    session:       Session,
+    keyspace:      String,
+    table:         String,
+    /// The columns to select, all the columns of the table if none.
+    columns:       Option<Vec<String>>,
+    /// The number of the ranges the ring is split into.
+    ranges:        usize,
+    /// Whether the ranges are aligned with the tokens of the nodes.
+    align:         bool,
+    /// The maximum number of the ranges scanned at a time.
+    max_in_flight: usize,
+    /// The number of the rows fetched per page.
+    page_size:     Option<usize>,
+    /// The consistency level of the queries.
+    consistency:   Option<Consistency>,
+    /// The maximum number of the retries of a failed page.
+    max_retries:   u32,
+    /// The delay before the first retry, doubled for every next one, up to a
+    /// minute.
+    retry_delay:   Duration,
+    /// The progress of the scan.
+    checkpoint:    Arc<Mutex<ScanCheckpoint>>,
+}
+
+impl TableScan {
+    /// Creates a scan of the table.
+    pub(crate) fn new(
+        session: Session,
+        keyspace: String,
+        table: String,
+    ) -> Self {
+        Self {
+            session,
+            keyspace,
+            table,
+            columns: None,
+            ranges: 256,
+            align: false,
+            max_in_flight: 8,
+            page_size: None,
+            consistency: None,
+            max_retries: 3,
+            retry_delay: Duration::from_millis(100),
+            checkpoint: Arc::new(Mutex::new(ScanCheckpoint::default())),
+        }
+    }
+
+    /// Sets the columns to select.
+    ///
+    /// By default, all the columns of the table are selected in the order of
+    /// [`TableMeta::columns`].
+    ///
+    /// [`TableMeta::columns`]: crate::TableMeta::columns
+    pub fn with_columns<I, T>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.columns = Some(columns.into_iter().map(Into::into).collect());
+
+        self
+    }
+
+    /// Sets the number of the ranges the ring is split into.
+    ///
+    /// The default value is 256. The ranges are the units of the concurrency,
+    /// the retries and the checkpoints.
+    pub fn with_ranges(mut self, ranges: usize) -> Self {
+        self.ranges = ranges.max(1);
+
+        self
+    }
+
+    /// Sets whether the ranges are aligned with the tokens of the nodes, so
+    /// that every range is owned by a single set of replicas.
+    ///
+    /// The tokens are read from the `system.local` and `system.peers` tables
+    /// when the scan starts, and the ring is split at them into at least the
+    /// number of the ranges set with [`TableScan::with_ranges`]. The default
+    /// value is `false`.
+    pub fn with_replica_alignment(mut self, align: bool) -> Self {
+        self.align = align;
+
+        self
+    }
+
+    /// Sets the maximum number of the ranges scanned at a time.
+    ///
+    /// Every range has at most one query in flight. The default value is 8.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+
+        self
+    }
+
+    /// Sets the number of the rows fetched per page.
+    ///
+    /// By default, the page size of the execution profile is used.
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = Some(page_size);
+
+        self
+    }
+
+    /// Sets the consistency level of the queries.
+    pub fn with_consistency(mut self, consistency: Consistency) -> Self {
+        self.consistency = Some(consistency);
+
+        self
+    }
+
+    /// Sets the maximum number of the retries of a failed page and the delay
+    /// before the first retry, doubled for every next one, up to a minute.
+    ///
+    /// The default values are 3 retries and 100 milliseconds.
+    pub fn with_retries(mut self, max_retries: u32, delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_delay = delay;
+
+        self
+    }
+
+    /// Resumes the scan from the checkpoint of a previous scan of the same
+    /// table, the ranges set with [`TableScan::with_ranges`] are ignored.
+    pub fn with_checkpoint(self, checkpoint: ScanCheckpoint) -> Self {
+        *self.checkpoint.lock() = checkpoint;
+
+        self
+    }
+
+    /// Returns the progress of the scan.
+    ///
+    /// The checkpoint is up to date with the rows yielded so far, it is
+    /// empty until the scan starts.
+    pub fn checkpoint(&self) -> ScanCheckpoint {
+        self.checkpoint.lock().clone()
+    }
+
+    /// Returns the stream of the rows of the table.
+    ///
+    /// The scan starts when the stream is polled, and continues from the
+    /// checkpoint if the stream is created again, e.g. after an error.
+    pub fn rows(&self) -> impl Stream<Item = Result<ScanRow, ScanError>> + '_ {
+        stream::once(self.plan())
+            .flat_map(move |plan| match plan {
+                Ok((prepared, pending)) => stream::iter(pending)
+                    .map(move |pending| {
+                        Box::pin(self.scan_range(prepared.clone(), pending))
+                    })
+                    .flatten_unordered(
+                        self.session.max_in_flight(self.max_in_flight),
+                    )
+                    .right_stream(),
+                Err(error) => stream::iter([ScanEvent::Failed(ScanError {
+                    range: None,
+                    error,
+                })])
+                .left_stream(),
+            })
+            .filter_map(move |event| {
+                self.checkpoint.lock().update(&event);
+
+                future::ready(match event {
+                    ScanEvent::Row(row) => Some(Ok(row)),
+                    ScanEvent::Done(_) => None,
+                    ScanEvent::Failed(error) => Some(Err(error)),
+                })
+            })
+    }
+
+    /// Returns the rows of the table blocking the current thread, see
+    /// [`TableScan::rows`].
+    pub fn rows_blocking(
+        &self,
+    ) -> impl Iterator<Item = Result<ScanRow, ScanError>> + '_ {
+        futures::executor::block_on_stream(Box::pin(self.rows()))
+    }
+
+    /// Prepares the query of the ranges and returns it with the ranges to
+    /// scan, splitting the ring if the scan has not started.
+    async fn plan(&self) -> Result<(Prepared, Vec<PendingRange>), DriverError> {
+        let prepared = self.session.prepare(self.query()?).await?;
+        self.check_partitioner().await?;
+
+        if self.checkpoint.lock().ranges.is_empty() {
+            let ranges = if self.align {
+                align_ranges(self.ring_tokens().await?, self.ranges)
+            } else {
+                TokenRange::full_ring().split(self.ranges)
+            };
+
+            let mut checkpoint = self.checkpoint.lock();
+            if checkpoint.ranges.is_empty() {
+                checkpoint.ranges =
+                    ranges.into_iter().map(RangeProgress::new).collect();
+            }
+        }
+
+        Ok((prepared, self.checkpoint.lock().pending()))
+    }
+
+    /// Returns the query of a range, which selects the token of the partition
+    /// key followed by the columns.
+    fn query(&self) -> Result<String, DriverError> {
+        let schema = self.session.get_schema_meta();
+        let table = schema
+            .keyspace(&self.keyspace)
+            .and_then(|keyspace| keyspace.table(&self.table))
+            .ok_or_else(|| {
+                DriverError::with_message(
+                    DriverErrorKind::LibBadParams,
+                    format!(
+                        "the table {}.{} does not exist",
+                        self.keyspace, self.table
+                    ),
+                )
+            })?;
+
+        let partition_key: Vec<_> = table
+            .partition_key()
+            .iter()
+            .map(|column| quote_identifier(&column.name()))
+            .collect();
+        if partition_key.is_empty() {
+            return Err(DriverError::with_message(
+                DriverErrorKind::LibBadParams,
+                format!(
+                    "the partition key of the table {}.{} is unknown",
+                    self.keyspace, self.table
+                ),
+            ));
+        }
+
+        let columns: Vec<_> = match &self.columns {
+            Some(columns) => columns
+                .iter()
+                .map(|column| quote_identifier(column))
+                .collect(),
+            None => table
+                .columns()
+                .map(|column| quote_identifier(&column.name()))
+                .collect(),
+        };
+        let token = format!("token({})", partition_key.join(", "));
+
+        Ok(format!(
+            "SELECT {token}, {} FROM {}.{} WHERE {token} > ? AND {token} <= ?",
+            columns.join(", "),
+            quote_identifier(&self.keyspace),
+            quote_identifier(&self.table),
+        ))
+    }
+
+    /// Fails if the cluster does not use the `Murmur3Partitioner`.
+    async fn check_partitioner(&self) -> Result<(), DriverError> {
+        let statement =
+            Statement::new("SELECT partitioner FROM system.local", 0);
+        let result = self.execute(&statement).await?;
+        match result.first_row().map(|row| row.get(0)).transpose()? {
+            Some(CqlValue::Text(partitioner)) => {
+                check_partitioner(&partitioner)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the tokens of all the nodes of the cluster.
+    ///
+    /// The rows whose tokens cannot be read are skipped: the ranges still
+    /// cover the whole ring, only fewer of them are aligned with the tokens
+    /// of the nodes.
+    async fn ring_tokens(&self) -> Result<Vec<i64>, DriverError> {
+        let mut tokens = Vec::new();
+        for query in [
+            "SELECT tokens FROM system.local",
+            "SELECT tokens FROM system.peers",
+        ] {
+            let result = self.execute(&Statement::new(query, 0)).await?;
+            for row in result.rows() {
+                let Ok(CqlValue::Set(values) | CqlValue::List(values)) =
+                    row.get(0)
+                else {
+                    continue;
+                };
+                tokens.extend(values.iter().filter_map(|value| match value {
+                    CqlValue::Text(token) => token.parse::<i64>().ok(),
+                    _ => None,
+                }));
+            }
+        }
+
+        Ok(tokens)
+    }
+
+    /// Returns the stream of the events of the scan of a range.
+    fn scan_range(
+        &self,
+        prepared: Prepared,
+        pending: PendingRange,
+    ) -> impl Stream<Item = ScanEvent> + '_ {
+        let failed = move |error| {
+            ScanEvent::Failed(ScanError {
+                range: Some(pending.range),
+                error,
+            })
+        };
+
+        stream::unfold(
+            Some(self.statement(&prepared, &pending)),
+            move |statement| async move {
+                let mut statement = match statement? {
+                    Ok(statement) => statement,
+                    Err(error) => return Some((vec![failed(error)], None)),
+                };
+
+                match self.fetch_page(&pending, &mut statement).await {
+                    Ok((rows, more)) => {
+                        let mut events: Vec<_> =
+                            rows.into_iter().map(ScanEvent::Row).collect();
+                        if more {
+                            Some((events, Some(Ok(statement))))
+                        } else {
+                            events.push(ScanEvent::Done(pending.index));
+                            Some((events, None))
+                        }
+                    }
+                    Err(error) => Some((vec![failed(error)], None)),
+                }
+            },
+        )
+        .flat_map(stream::iter)
+    }
+
+    /// Returns the statement of the first page of a range.
+    fn statement(
+        &self,
+        prepared: &Prepared,
+        pending: &PendingRange,
+    ) -> Result<Statement, DriverError> {
+        let mut statement = prepared.bind();
+        statement.bind(0, pending.start)?;
+        statement.bind(1, pending.range.end)?;
+        statement.set_is_idempotent(true)?;
+        if let Some(page_size) = self.page_size {
+            statement.set_paging_size(Some(page_size))?;
+        }
+        if let Some(consistency) = self.consistency {
+            statement.set_consistency(consistency)?;
+        }
+
+        Ok(statement)
+    }
+
+    /// Fetches the next page of a range and returns its rows and whether
+    /// there are more pages, advancing the statement to the next page.
+    async fn fetch_page(
+        &self,
+        pending: &PendingRange,
+        statement: &mut Statement,
+    ) -> Result<(Vec<ScanRow>, bool), DriverError> {
+        let page = self.execute(statement).await?;
+        let rows = page
+            .rows()
+            .map(|row| {
+                let mut values = row.values()?;
+                let token = match values.first() {
+                    Some(CqlValue::BigInt(token)) => *token,
+                    _ => {
+                        return Err(DriverError::with_message(
+                            DriverErrorKind::LibUnexpectedResponse,
+                            "the token of a row is missing",
+                        ))
+                    }
+                };
+                values.remove(0);
+
+                Ok(ScanRow {
+                    index: pending.index,
+                    range: pending.range,
+                    token,
+                    values,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match page.paging_state_token() {
+            Some(token) => {
+                statement.set_paging_state_token(&token)?;
+                Ok((rows, true))
+            }
+            None => Ok((rows, false)),
+        }
+    }
+
+    /// Executes the statement, retrying it with an exponential backoff if it
+    /// failed with a transient error.
+    async fn execute(
+        &self,
+        statement: &Statement,
+    ) -> Result<QueryResult, DriverError> {
+        let mut delay = self.retry_delay;
+        let mut retries = 0;
+        loop {
+            match retry_on_queue_full(|| self.session.execute(statement)).await
+            {
+                Err(error)
+                    if retries < self.max_retries
+                        && is_transient(error.kind) =>
+                {
+                    retries += 1;
+                    sleep(delay).await?;
+                    delay = next_retry_delay(delay);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Fails if the partitioner is not the `Murmur3Partitioner`, e.g. the
+/// `RandomPartitioner` or the `ByteOrderedPartitioner`.
+fn check_partitioner(partitioner: &str) -> Result<(), DriverError> {
+    if partitioner.rsplit('.').next() == Some("Murmur3Partitioner") {
+        return Ok(());
+    }
+
+    Err(DriverError::with_message(
+        DriverErrorKind::LibBadParams,
+        format!(
+            "the table scan requires the Murmur3Partitioner, the cluster uses \
+             the {}",
+            partitioner
+        ),
+    ))
+}
+
+/// A range yet to be scanned.
+#[derive(Debug, Clone, Copy)]
+struct PendingRange {
+    /// The index of the range in the checkpoint.
+    index: usize,
+    /// The range.
+    range: TokenRange,
+    /// The token the scan of the range starts after.
+    start: i64,
+}
+
+/// An event of the scan of a range.
+enum ScanEvent {
+    /// A row of the range.
+    Row(ScanRow),
+    /// All the rows of the range were yielded.
+    Done(usize),
+    /// The scan of the range failed.
+    Failed(ScanError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_ring() {
+        let ranges = TokenRange::full_ring().split(7);
+        assert_eq!(ranges.len(), 7);
+        assert_eq!(ranges[0].start, i64::MIN);
+        assert_eq!(ranges[6].end, i64::MAX);
+        assert!(ranges.windows(2).all(|pair| pair[0].end == pair[1].start));
+
+        let small = TokenRange {
+            start: 0, end: 3
+        };
+        assert_eq!(small.split(10).len(), 3);
+        assert_eq!(small.split(0), vec![small]);
+    }
+
+    #[test]
+    fn test_align_ranges() {
+        let ranges = align_ranges(vec![100, -100, 0, 100], 4);
+        assert_eq!(
+            ranges,
+            vec![
+                TokenRange {
+                    start: i64::MIN,
+                    end:   -100,
+                },
+                TokenRange {
+                    start: -100,
+                    end:   0,
+                },
+                TokenRange {
+                    start: 0,
+                    end:   100,
+                },
+                TokenRange {
+                    start: 100,
+                    end:   i64::MAX,
+                },
+            ]
+        );
+
+        let ranges = align_ranges(vec![0], 4);
+        assert_eq!(ranges.len(), 4);
+        assert!(ranges.iter().any(|range| range.end == 0));
+        assert_eq!(align_ranges(Vec::new(), 3).len(), 3);
+    }
+
+    #[test]
+    fn test_check_partitioner() {
+        assert!(check_partitioner(
+            "org.apache.cassandra.dht.Murmur3Partitioner"
+        )
+        .is_ok());
+        for partitioner in [
+            "org.apache.cassandra.dht.RandomPartitioner",
+            "org.apache.cassandra.dht.ByteOrderedPartitioner",
+        ] {
+            let error = check_partitioner(partitioner).unwrap_err();
+            assert_eq!(error.kind, DriverErrorKind::LibBadParams);
+            assert!(error.to_string().contains(partitioner), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_checkpoint_resume() {
+        let ranges = TokenRange::full_ring().split(3);
+        let mut checkpoint = ScanCheckpoint::from(
+            ranges
+                .iter()
+                .copied()
+                .map(RangeProgress::new)
+                .collect::<Vec<_>>(),
+        );
+        assert!(!checkpoint.is_complete());
+
+        checkpoint.update(&ScanEvent::Done(0));
+        checkpoint.update(&ScanEvent::Row(ScanRow {
+            index:  1,
+            range:  ranges[1],
+            token:  42,
+            values: Vec::new(),
+        }));
+
+        let pending = checkpoint.pending();
+        assert_eq!(pending.len(), 2);
+        assert_eq!((pending[0].index, pending[0].start), (1, 41));
+        assert_eq!((pending[1].index, pending[1].start), (2, ranges[2].start));
+
+        checkpoint.update(&ScanEvent::Done(1));
+        checkpoint.update(&ScanEvent::Done(2));
+        assert!(checkpoint.is_complete());
+        assert!(checkpoint.pending().is_empty());
+    }
+}
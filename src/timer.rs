@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{
+    Arc,
+    Weak,
+};
+use std::task::{
+    Context,
+    Poll,
+    Waker,
+};
+use std::thread;
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use parking_lot::{
+    Condvar,
+    Mutex,
+    MutexGuard,
+};
+
+use crate::{
+    DriverError,
+    DriverErrorKind,
+};
+
+/// The timer serving all the delays.
+static TIMER: Timer = Timer {
+    entries: Mutex::new(Entries {
+        deadlines: BTreeMap::new(),
+        next_id:   0,
+        started:   false,
+    }),
+    changed: Condvar::new(),
+};
+
+/// Returns a future that completes after the given duration.
+///
+/// The crate does not depend on an async runtime, so the delays are served by
+/// a single background thread shared by all of them. The thread is started by
+/// the first delay polled, the delay fails with
+/// [`DriverErrorKind::LibUnableToInit`] if the thread cannot be spawned.
+///
+/// A duration too long to be represented as an instant, e.g.
+/// [`Duration::MAX`], never completes.
+pub(crate) fn sleep(duration: Duration) -> Delay {
+    Delay {
+        deadline: Instant::now().checked_add(duration),
+        state:    None,
+    }
+}
+
+/// A future that completes at a deadline.
+pub(crate) struct Delay {
+    /// The instant the future completes at, none if it never completes.
+    deadline: Option<Instant>,
+    /// The key of the delay on the timer and the state shared with the timer
+    /// thread, created on the first poll.
+    state:    Option<(Key, Arc<Mutex<DelayState>>)>,
+}
+
+impl Future for Delay {
+    type Output = Result<(), DriverError>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), DriverError>> {
+        let Some(deadline) = self.deadline else {
+            return Poll::Pending;
+        };
+        if Instant::now() >= deadline {
+            return Poll::Ready(Ok(()));
+        }
+
+        match &self.state {
+            Some((_, state)) => {
+                let mut state = state.lock();
+                if state.fired {
+                    return Poll::Ready(Ok(()));
+                }
+                state.waker = Some(cx.waker().clone());
+            }
+            None => {
+                let state = Arc::new(Mutex::new(DelayState {
+                    fired: false,
+                    waker: Some(cx.waker().clone()),
+                }));
+                let key = TIMER.schedule(deadline, &state)?;
+                self.state = Some((key, state));
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Delay {
+    fn drop(&mut self) {
+        if let Some((key, _)) = &self.state {
+            TIMER.cancel(key);
+        }
+    }
+}
+
+/// The state of a delay shared with the timer thread.
+struct DelayState {
+    /// Whether the deadline has passed.
+    fired: bool,
+    /// The waker of the task waiting for the delay.
+    waker: Option<Waker>,
+}
+
+/// The key of a delay on the timer: its deadline and a sequence number that
+/// tells apart the delays with the same deadline.
+type Key = (Instant, u64);
+
+/// The deadlines scheduled on the timer thread.
+struct Timer {
+    /// The pending deadlines.
+    entries: Mutex<Entries>,
+    /// Notifies the timer thread about a new deadline.
+    changed: Condvar,
+}
+
+/// The pending deadlines of the timer.
+struct Entries {
+    /// The pending delays by their keys, the earliest deadline first.
+    ///
+    /// A delay is removed when it fires or when it is dropped.
+    deadlines: BTreeMap<Key, Weak<Mutex<DelayState>>>,
+    /// The sequence number of the next delay.
+    next_id:   u64,
+    /// Whether the timer thread has been started.
+    started:   bool,
+}
+
+impl Timer {
+    /// Schedules the delay to fire at the deadline, starting the timer thread
+    /// if needed, and returns the key of the delay.
+    fn schedule(
+        &'static self,
+        deadline: Instant,
+        state: &Arc<Mutex<DelayState>>,
+    ) -> Result<Key, DriverError> {
+        let mut entries = self.entries.lock();
+        if !entries.started {
+            thread::Builder::new()
+                .name("cassander-timer".to_string())
+                .spawn(move || self.run())
+                .map_err(|error| {
+                    DriverError::with_message(
+                        DriverErrorKind::LibUnableToInit,
+                        format!("failed to spawn the timer thread: {}", error),
+                    )
+                })?;
+            entries.started = true;
+        }
+
+        let key = (deadline, entries.next_id);
+        entries.next_id += 1;
+        let is_earliest = entries
+            .deadlines
+            .first_key_value()
+            .is_none_or(|(earliest, _)| key < *earliest);
+        entries.deadlines.insert(key, Arc::downgrade(state));
+
+        if is_earliest {
+            self.changed.notify_one();
+        }
+
+        Ok(key)
+    }
+
+    /// Removes the delay, if it has not fired yet.
+    fn cancel(&self, key: &Key) {
+        self.entries.lock().deadlines.remove(key);
+    }
+
+    /// Fires the delays as their deadlines pass.
+    fn run(&self) {
+        let mut entries = self.entries.lock();
+        loop {
+            let now = Instant::now();
+            let mut wakers = Vec::new();
+            while let Some(entry) = entries.deadlines.first_entry() {
+                if entry.key().0 > now {
+                    break;
+                }
+                if let Some(state) = entry.remove().upgrade() {
+                    let mut state = state.lock();
+                    state.fired = true;
+                    wakers.extend(state.waker.take());
+                }
+            }
+
+            if !wakers.is_empty() {
+                // The woken tasks may poll or drop their delays right away,
+                // so they are woken without holding any lock.
+                MutexGuard::unlocked(&mut entries, || {
+                    wakers.into_iter().for_each(Waker::wake);
+                });
+                continue;
+            }
+
+            match entries.deadlines.first_key_value() {
+                Some(((deadline, _), _)) => {
+                    let deadline = *deadline;
+                    self.changed.wait_until(&mut entries, deadline);
+                }
+                None => self.changed.wait(&mut entries),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::stream::{
+        FuturesUnordered,
+        StreamExt,
+    };
+    use futures::task::noop_waker;
+
+    use super::*;
+
+    #[test]
+    fn test_delays_complete_in_deadline_order() {
+        let delays: FuturesUnordered<_> = [30, 10, 20, 0]
+            .into_iter()
+            .map(|millis| async move {
+                sleep(Duration::from_millis(millis)).await.unwrap();
+                millis
+            })
+            .collect();
+
+        let completed: Vec<_> = block_on(delays.collect());
+
+        assert_eq!(completed, vec![0, 10, 20, 30]);
+    }
+
+    #[test]
+    fn test_dropped_delay_is_removed() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut delay = sleep(Duration::from_secs(60));
+
+        assert!(Pin::new(&mut delay).poll(&mut cx).is_pending());
+        let (key, _) = delay.state.clone().unwrap();
+        assert!(TIMER.entries.lock().deadlines.contains_key(&key));
+
+        drop(delay);
+        assert!(!TIMER.entries.lock().deadlines.contains_key(&key));
+    }
+
+    #[test]
+    fn test_fired_delay_is_ready() {
+        let mut delay = sleep(Duration::from_millis(10));
+
+        block_on(&mut delay).unwrap();
+        let (key, state) = delay.state.clone().unwrap();
+
+        assert!(state.lock().fired);
+        assert!(!TIMER.entries.lock().deadlines.contains_key(&key));
+    }
+
+    #[test]
+    fn test_endless_delay_is_not_scheduled() {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut delay = sleep(Duration::MAX);
+
+        assert!(Pin::new(&mut delay).poll(&mut cx).is_pending());
+        assert!(delay.state.is_none());
+    }
+}
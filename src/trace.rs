@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+use std::time::{
+    Duration,
+    Instant,
+};
+
+use crate::cql::{
+    CqlInet,
+    CqlUuid,
+    CqlValue,
+};
+use crate::timer::sleep;
+use crate::{
+    Consistency,
+    DriverError,
+    DriverErrorKind,
+    QueryResult,
+    Row,
+    Session,
+    Statement,
+};
+
+/// The query that reads the trace session.
+const SESSION_QUERY: &str =
+    "SELECT * FROM system_traces.sessions WHERE session_id = ?";
+
+/// The query that reads the trace events.
+const EVENTS_QUERY: &str =
+    "SELECT * FROM system_traces.events WHERE session_id = ?";
+
+/// The server-side trace of a request.
+///
+/// See [`Session::fetch_trace`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryTrace {
+    /// The tracing identifier of the request.
+    pub id:          CqlUuid,
+    /// The node that coordinated the request.
+    pub coordinator: CqlInet,
+    /// The address of the client that sent the request, [`None`] if the
+    /// cluster does not record it.
+    pub client:      Option<CqlInet>,
+    /// The kind of the request, e.g. `Execute CQL3 query`.
+    pub request:     String,
+    /// The parameters of the request, e.g. the query and the consistency
+    /// level.
+    pub parameters:  HashMap<String, String>,
+    /// The number of milliseconds since the Unix epoch the request started
+    /// at.
+    pub started_at:  i64,
+    /// The time the coordinator spent on the request.
+    pub duration:    Duration,
+    /// The events recorded by the nodes involved in the request.
+    ///
+    /// The nodes are listed in the order they recorded their first event.
+    pub nodes:       Vec<NodeTrace>,
+}
+
+/// The events recorded by a single node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeTrace {
+    /// The address of the node.
+    pub source: CqlInet,
+    /// The events in the order they were recorded.
+    pub events: Vec<TraceEvent>,
+}
+
+/// An event of a trace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    /// The time-based identifier of the event.
+    pub id:             CqlUuid,
+    /// The description of the event.
+    pub activity:       String,
+    /// The address of the node that recorded the event.
+    pub source:         CqlInet,
+    /// The time elapsed on the node since it started handling the request.
+    pub source_elapsed: Duration,
+    /// The name of the thread that recorded the event.
+    pub thread:         String,
+}
+
+/// How the traces are read, see [`Cluster::set_tracing_max_wait_time`].
+///
+/// [`Cluster::set_tracing_max_wait_time`]: crate::Cluster::set_tracing_max_wait_time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TraceSettings {
+    /// The maximum time to wait for a trace to become available.
+    pub(crate) max_wait_time:   Duration,
+    /// The time to wait between the attempts to read a trace.
+    pub(crate) retry_wait_time: Duration,
+    /// The consistency level the traces are read with.
+    pub(crate) consistency:     Consistency,
+}
+
+impl Default for TraceSettings {
+    /// Returns the driver's default settings.
+    fn default() -> Self {
+        Self {
+            max_wait_time:   Duration::from_millis(15),
+            retry_wait_time: Duration::from_millis(3),
+            consistency:     Consistency::One,
+        }
+    }
+}
+
+/// Reads the trace with the given identifier.
+///
+/// The trace session is polled until the coordinator records the duration of
+/// the request or the maximum wait time elapses, then all the events of the
+/// trace are read.
+pub(crate) async fn fetch_trace(
+    session: &Session,
+    id: CqlUuid,
+    settings: TraceSettings,
+) -> Result<QueryTrace, DriverError> {
+    let started = Instant::now();
+    let mut statement = trace_statement(SESSION_QUERY, id, settings)?;
+    let mut trace = loop {
+        let result = session.execute(&statement).await?;
+        if let Some(trace) = read_session(id, &result)? {
+            break trace;
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed >= settings.max_wait_time {
+            return Err(DriverError::with_message(
+                DriverErrorKind::LibRequestTimedOut,
+                format!(
+                    "the trace {} is not available after {:?}",
+                    id, settings.max_wait_time
+                ),
+            ));
+        }
+
+        sleep(
+            settings
+                .retry_wait_time
+                .min(settings.max_wait_time - elapsed),
+        )
+        .await?;
+    };
+
+    statement = trace_statement(EVENTS_QUERY, id, settings)?;
+    let mut events = Vec::new();
+    loop {
+        let result = session.execute(&statement).await?;
+        for row in result.rows() {
+            events.push(read_event(&row)?);
+        }
+
+        match result.paging_state_token() {
+            Some(token) => statement.set_paging_state_token(&token)?,
+            None => break,
+        }
+    }
+    trace.nodes = group_events(events);
+
+    Ok(trace)
+}
+
+/// Creates a statement that reads the trace with the given identifier.
+fn trace_statement(
+    query: &str,
+    id: CqlUuid,
+    settings: TraceSettings,
+) -> Result<Statement, DriverError> {
+    let mut statement = Statement::new(query, 1);
+    statement.bind(0, id)?;
+    statement.set_consistency(settings.consistency)?;
+
+    Ok(statement)
+}
+
+/// Reads the trace session.
+///
+/// Returns [`None`] if the session is not recorded or not completed yet.
+fn read_session(
+    id: CqlUuid,
+    result: &QueryResult,
+) -> Result<Option<QueryTrace>, DriverError> {
+    let Some(row) = result.first_row() else {
+        return Ok(None);
+    };
+    let Some(duration) = row.get_by_name("duration")?.as_i64() else {
+        return Ok(None);
+    };
+
+    let parameters = match row.get_by_name("parameters")? {
+        CqlValue::Map(entries) => entries
+            .into_iter()
+            .filter_map(|(key, value)| {
+                Some((key.as_str()?.to_string(), value.as_str()?.to_string()))
+            })
+            .collect(),
+        _ => HashMap::new(),
+    };
+    let started_at = match row.get_by_name("started_at")? {
+        CqlValue::Timestamp(started_at) => started_at,
+        _ => 0,
+    };
+
+    Ok(Some(QueryTrace {
+        id,
+        coordinator: required_inet(&row, "coordinator")?,
+        client: optional(&row, "client")?.and_then(|v| v.as_inet()),
+        request: optional(&row, "request")?
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default(),
+        parameters,
+        started_at,
+        duration: micros(duration),
+        nodes: Vec::new(),
+    }))
+}
+
+/// Reads a trace event.
+fn read_event(row: &Row<'_>) -> Result<TraceEvent, DriverError> {
+    let id = row.get_by_name("event_id")?.as_uuid().ok_or_else(|| {
+        DriverError::with_message(
+            DriverErrorKind::LibInvalidValueType,
+            "the trace event has no identifier",
+        )
+    })?;
+    let text = |name| -> Result<String, DriverError> {
+        Ok(optional(row, name)?
+            .and_then(|value| value.as_str().map(str::to_string))
+            .unwrap_or_default())
+    };
+
+    Ok(TraceEvent {
+        id,
+        activity: text("activity")?,
+        source: required_inet(row, "source")?,
+        source_elapsed: micros(
+            optional(row, "source_elapsed")?
+                .and_then(|value| value.as_i64())
+                .unwrap_or_default(),
+        ),
+        thread: text("thread")?,
+    })
+}
+
+/// Reads a column that may be missing in older versions of the cluster.
+fn optional(
+    row: &Row<'_>,
+    name: &str,
+) -> Result<Option<CqlValue>, DriverError> {
+    match row.get_by_name(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(error) if error.kind == DriverErrorKind::LibNameDoesNotExist => {
+            Ok(None)
+        }
+        Err(error) => Err(error),
+    }
+}
+
+/// Reads an address column that must be set.
+fn required_inet(row: &Row<'_>, name: &str) -> Result<CqlInet, DriverError> {
+    row.get_by_name(name)?.as_inet().ok_or_else(|| {
+        DriverError::with_message(
+            DriverErrorKind::LibInvalidValueType,
+            format!("the trace has no {} address", name),
+        )
+    })
+}
+
+/// Converts a non-negative number of microseconds to a duration.
+fn micros(value: i64) -> Duration {
+    Duration::from_micros(value.try_into().unwrap_or_default())
+}
+
+/// Groups the events by the node that recorded them.
+///
+/// The events are ordered by their time-based identifiers and the nodes by
+/// their first event.
+fn group_events(mut events: Vec<TraceEvent>) -> Vec<NodeTrace> {
+    events.sort_by_key(|event| event.id);
+
+    let mut nodes: Vec<NodeTrace> = Vec::new();
+    for event in events {
+        match nodes.iter_mut().find(|node| node.source == event.source) {
+            Some(node) => node.events.push(event),
+            None => nodes.push(NodeTrace {
+                source: event.source,
+                events: vec![event],
+            }),
+        }
+    }
+
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use super::*;
+
+    fn event(time: u64, source: [u8; 4], activity: &str) -> TraceEvent {
+        TraceEvent {
+            id:             CqlUuid::from_components(
+                (1 << 60) | time,
+                0x8000_0000_0000_0000,
+            ),
+            activity:       activity.to_string(),
+            source:         Ipv4Addr::from(source).into(),
+            source_elapsed: Duration::from_micros(time),
+            thread:         String::new(),
+        }
+    }
+
+    #[test]
+    fn test_group_events() {
+        let nodes = group_events(vec![
+            event(3, [10, 0, 0, 2], "Read 1 live rows"),
+            event(1, [10, 0, 0, 1], "Parsing SELECT"),
+            event(4, [10, 0, 0, 1], "Read-repair DC_LOCAL"),
+            event(2, [10, 0, 0, 2], "READ message received"),
+        ]);
+
+        let activities: Vec<(String, Vec<&str>)> = nodes
+            .iter()
+            .map(|node| {
+                (
+                    node.source.to_string(),
+                    node.events
+                        .iter()
+                        .map(|event| event.activity.as_str())
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            activities,
+            vec![
+                (
+                    "10.0.0.1".to_string(),
+                    vec!["Parsing SELECT", "Read-repair DC_LOCAL"]
+                ),
+                (
+                    "10.0.0.2".to_string(),
+                    vec!["READ message received", "Read 1 live rows"]
+                ),
+            ]
+        );
+    }
+}
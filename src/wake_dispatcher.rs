@@ -0,0 +1,134 @@
+use std::sync::mpsc;
+use std::task::Waker;
+use std::thread;
+
+/// Wakes the tasks waiting for the driver futures.
+///
+/// By default, the task awaiting a request is woken on the driver I/O thread
+/// that completed the request. Depending on the executor, waking a task may be
+/// expensive, e.g. it may poll the task inline, and a slow wake-up stalls every
+/// connection served by that I/O thread. A dispatcher set with
+/// [`Cluster::set_wake_dispatcher`] takes the wake-ups off the I/O threads.
+///
+/// Any `Fn(Waker)` closure is a dispatcher, so the wake-ups can be handed to
+/// the executor the application runs on, e.g. with tokio:
+///
+/// ```rust,ignore
+/// let handle = tokio::runtime::Handle::current();
+/// cluster.set_wake_dispatcher(move |waker: Waker| {
+///     handle.spawn(async move { waker.wake() });
+/// });
+/// ```
+///
+/// or with smol:
+///
+/// ```rust,ignore
+/// cluster.set_wake_dispatcher(|waker: Waker| {
+///     smol::spawn(async move { waker.wake() }).detach();
+/// });
+/// ```
+///
+/// [`ThreadDispatcher`] wakes the tasks on a dedicated thread and does not
+/// depend on any executor.
+///
+/// [`Cluster::set_wake_dispatcher`]: crate::Cluster::set_wake_dispatcher
+pub trait WakeDispatcher: Send + Sync {
+    /// Wakes the task.
+    ///
+    /// The method is called on a driver I/O thread, so it must return quickly
+    /// and must not block.
+    fn dispatch(&self, waker: Waker);
+}
+
+impl<F> WakeDispatcher for F
+where
+    F: Fn(Waker) + Send + Sync,
+{
+    fn dispatch(&self, waker: Waker) {
+        self(waker)
+    }
+}
+
+/// A [`WakeDispatcher`] that wakes the tasks on a dedicated thread.
+///
+/// The thread exits when the dispatcher is dropped, i.e. when the cluster and
+/// all of its sessions are dropped.
+pub struct ThreadDispatcher {
+    /// Sends the wakers to the thread.
+    sender: mpsc::Sender<Waker>,
+}
+
+impl ThreadDispatcher {
+    /// Creates a dispatcher and starts its thread.
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel::<Waker>();
+        thread::Builder::new()
+            .name("cassander-waker".to_string())
+            .spawn(move || {
+                for waker in receiver {
+                    waker.wake();
+                }
+            })
+            .expect("failed to spawn the waker thread");
+
+        Self {
+            sender,
+        }
+    }
+}
+
+impl Default for ThreadDispatcher {
+    /// Creates a dispatcher and starts its thread.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WakeDispatcher for ThreadDispatcher {
+    fn dispatch(&self, waker: Waker) {
+        // The thread only exits if it panicked, the task must be woken anyway.
+        if let Err(error) = self.sender.send(waker) {
+            error.0.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::task::{
+        waker,
+        ArcWake,
+    };
+    use parking_lot::Mutex;
+
+    use super::*;
+
+    /// Records the threads the task is woken on.
+    struct ThreadRecorder(Mutex<Vec<thread::ThreadId>>, mpsc::SyncSender<()>);
+
+    impl ArcWake for ThreadRecorder {
+        fn wake_by_ref(arc_self: &Arc<Self>) {
+            arc_self.0.lock().push(thread::current().id());
+            let _ = arc_self.1.send(());
+        }
+    }
+
+    #[test]
+    fn test_thread_dispatcher_wakes_on_its_thread() {
+        let (sender, receiver) = mpsc::sync_channel(2);
+        let recorder = Arc::new(ThreadRecorder(Mutex::default(), sender));
+        let dispatcher = ThreadDispatcher::new();
+
+        dispatcher.dispatch(waker(recorder.clone()));
+        dispatcher.dispatch(waker(recorder.clone()));
+        receiver.recv().unwrap();
+        receiver.recv().unwrap();
+
+        let threads = recorder.0.lock();
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0], threads[1]);
+        assert_ne!(threads[0], thread::current().id());
+    }
+}